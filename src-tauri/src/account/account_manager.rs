@@ -1,82 +1,437 @@
 use anyhow::{anyhow, Result};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::fmt::Write as _;
+use std::io::Write as _;
 use std::path::PathBuf;
 use uuid::Uuid;
 
 use super::types::*;
-use crate::api::{TraeApiClient, UsageSummary, UsageQueryResponse, login_with_email};
+use crate::error::{coded, ErrorCode};
+use crate::api::{request_log, TraeApiClient, UsageSummary, UsageQueryResponse, login_with_email};
+use crate::integrity::{self, IntegrityIssue, IntegrityState};
+
+/// 批量操作时同时进行的网络请求数
+const BATCH_CONCURRENCY: usize = 3;
+/// 统计数据缓存有效期（秒），接口数据按天更新
+const STATISTICS_CACHE_TTL: i64 = 3600;
 
 /// 账号管理器
 pub struct AccountManager {
     store: AccountStore,
     data_path: PathBuf,
+    /// 加载时发现的完整性问题（等待用户处理）
+    integrity_issue: Option<IntegrityIssue>,
+    /// 数据文件锁（持有期间其他实例只能只读），为 None 时处于只读模式
+    store_lock: Option<fs::File>,
+    /// 最近一次加载或保存后 accounts.json 的修改时间和大小，用于发现外部修改
+    disk_fingerprint: std::sync::Mutex<Option<(std::time::SystemTime, u64)>>,
+    /// 切换账号时写入 Trae 权益信息的方式（来自设置）
+    entitlement_mode: crate::machine::EntitlementMode,
 }
 
 impl AccountManager {
     /// 创建账号管理器
     pub fn new() -> Result<Self> {
         let data_path = Self::get_data_path()?;
-        let mut store = Self::load_store(&data_path)?;
+        let store_lock = Self::acquire_store_lock(&data_path);
+        if store_lock.is_none() {
+            println!("[WARN] 账号数据正被另一个实例使用，当前以只读模式运行");
+        }
+        let (mut store, integrity_issue) = Self::load_store(&data_path, store_lock.is_none())?;
+
+        let changed = Self::migrate_store(&mut store);
+
+        let disk_fingerprint = std::sync::Mutex::new(Self::file_fingerprint(&data_path));
+        let manager = Self {
+            store,
+            data_path,
+            integrity_issue,
+            store_lock,
+            disk_fingerprint,
+            entitlement_mode: Default::default(),
+        };
+
+        if changed && manager.integrity_issue.is_none() && !manager.is_read_only() {
+            manager.save_store()?;
+        }
+
+        Ok(manager)
+    }
 
-        // 确保每个账号都有机器码
+    /// 迁移旧版本数据，返回是否有改动
+    fn migrate_store(store: &mut AccountStore) -> bool {
         let mut changed = false;
+        let mut seen_ids = HashSet::new();
         for account in &mut store.accounts {
+            // 旧版本的 ID 基于时间戳生成，批量创建时可能重复：保留第一个，其余重新分配 UUID
+            // 不重复的旧 ID 保持不变，前端和工作区映射中的引用仍然有效
+            if !seen_ids.insert(account.id.clone()) {
+                let new_id = Uuid::new_v4().to_string();
+                println!("[WARN] 账号 {} 的 ID 重复，已重新分配: {} -> {}", account.email, account.id, new_id);
+                account.id = new_id;
+                changed = true;
+            }
+
+            // 确保每个账号都有机器码
             if account.machine_id.is_none() {
                 account.machine_id = Some(Uuid::new_v4().to_string());
                 changed = true;
             }
         }
+        changed
+    }
 
-        let manager = Self { store, data_path };
-
-        if changed {
-            manager.save_store()?;
-        }
-
-        Ok(manager)
+    /// 设置写入 Trae 权益信息的方式
+    pub fn set_entitlement_mode(&mut self, mode: crate::machine::EntitlementMode) {
+        self.entitlement_mode = mode;
     }
 
-    /// 获取数据存储路径
-    fn get_data_path() -> Result<PathBuf> {
+    /// 获取应用数据目录
+    pub fn data_dir() -> Result<PathBuf> {
         let proj_dirs = directories::ProjectDirs::from("com", "sauce", "trae-auto")
             .ok_or_else(|| anyhow!("无法获取应用数据目录"))?;
 
         let data_dir = proj_dirs.data_dir();
         fs::create_dir_all(data_dir)?;
 
-        Ok(data_dir.join("accounts.json"))
+        Ok(data_dir.to_path_buf())
+    }
+
+    /// 获取数据存储路径
+    fn get_data_path() -> Result<PathBuf> {
+        Ok(Self::data_dir()?.join("accounts.json"))
     }
 
-    /// 加载账号存储
-    fn load_store(path: &PathBuf) -> Result<AccountStore> {
-        if path.exists() {
-            let content = fs::read_to_string(path)?;
-            let cleaned = content.trim_start_matches('\u{feff}');
-            let trimmed = cleaned.trim();
-            if trimmed.is_empty() {
-                return Ok(AccountStore::default());
+    /// 获取数据文件锁（accounts.lock，进程退出时自动释放），已被占用时返回 None
+    fn acquire_store_lock(data_path: &std::path::Path) -> Option<fs::File> {
+        let lock_path = data_path.with_extension("lock");
+        let file = match fs::OpenOptions::new().create(true).truncate(false).write(true).open(&lock_path) {
+            Ok(file) => file,
+            Err(e) => {
+                println!("[WARN] 无法打开数据锁文件 {}: {}", lock_path.display(), e);
+                return None;
             }
-            match serde_json::from_str::<AccountStore>(trimmed) {
-                Ok(store) => Ok(store),
-                Err(_) => {
-                    let store = AccountStore::default();
-                    let content = serde_json::to_string_pretty(&store)?;
-                    fs::write(path, content)?;
-                    Ok(store)
+        };
+        fs2::FileExt::try_lock_exclusive(&file).ok()?;
+        // 记录持有锁的进程，便于排查
+        let _ = file.set_len(0);
+        let _ = write!(&file, "{}", std::process::id());
+        Some(file)
+    }
+
+    /// 是否处于只读模式（另一个实例持有数据文件锁）
+    pub fn is_read_only(&self) -> bool {
+        self.store_lock.is_none()
+    }
+
+    /// 只读模式下重新尝试获取数据文件锁，成功后从磁盘重新加载数据
+    pub fn try_reacquire_store_lock(&mut self) -> Result<bool> {
+        if self.store_lock.is_some() {
+            return Ok(true);
+        }
+        let Some(lock) = Self::acquire_store_lock(&self.data_path) else {
+            return Ok(false);
+        };
+        self.reload_store()?;
+        self.store_lock = Some(lock);
+        println!("[INFO] 已获取账号数据锁，退出只读模式");
+        Ok(true)
+    }
+
+    fn file_fingerprint(path: &std::path::Path) -> Option<(std::time::SystemTime, u64)> {
+        let metadata = fs::metadata(path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// 从磁盘重新加载账号数据
+    fn reload_store(&mut self) -> Result<()> {
+        let (mut store, integrity_issue) = Self::load_store(&self.data_path, self.is_read_only())?;
+        // 外部写入的数据也可能包含重复 ID，迁移结果随下一次保存写回
+        Self::migrate_store(&mut store);
+        self.store = store;
+        self.integrity_issue = integrity_issue;
+        self.update_disk_fingerprint();
+        Ok(())
+    }
+
+    /// 记录 accounts.json 当前的修改时间和大小
+    fn update_disk_fingerprint(&self) {
+        *self.disk_fingerprint.lock().unwrap_or_else(|e| e.into_inner()) = Self::file_fingerprint(&self.data_path);
+    }
+
+    /// accounts.json 被外部修改（同步工具、手动编辑）时重新加载，返回是否发生了重新加载
+    pub fn reload_if_changed_externally(&mut self) -> Result<bool> {
+        let current = Self::file_fingerprint(&self.data_path);
+        let known = *self.disk_fingerprint.lock().unwrap_or_else(|e| e.into_inner());
+        if current.is_none() || current == known {
+            return Ok(false);
+        }
+        self.reload_store()?;
+        println!("[INFO] 检测到 accounts.json 被外部修改，已重新加载 {} 个账号", self.store.accounts.len());
+        Ok(true)
+    }
+
+    /// 加载账号存储（同时校验 HMAC 签名，异常时保留原文件并提示从备份恢复）
+    ///
+    /// read_only 为 true 时（其他实例持有数据文件锁）不补签名
+    fn load_store(path: &PathBuf, read_only: bool) -> Result<(AccountStore, Option<IntegrityIssue>)> {
+        if !path.exists() {
+            return Ok((AccountStore::default(), None));
+        }
+
+        let content = fs::read_to_string(path)?;
+        let cleaned = content.trim_start_matches('\u{feff}');
+        let trimmed = cleaned.trim();
+        if trimmed.is_empty() {
+            return Ok((AccountStore::default(), None));
+        }
+
+        let state = integrity::verify_store(path);
+        match serde_json::from_str::<AccountStore>(trimmed) {
+            Ok(store) => {
+                if state == IntegrityState::Unsigned && !read_only {
+                    // 旧版本数据没有签名，首次加载时补签
+                    integrity::sign_store(path)?;
                 }
+                let issue = (state == IntegrityState::Invalid).then(|| {
+                    println!("[WARN] accounts.json 签名校验失败，数据可能被篡改或损坏");
+                    Self::integrity_issue_for(path, "签名校验失败，数据可能被篡改或损坏", true)
+                });
+                Ok((store, issue))
+            }
+            Err(e) => {
+                println!("[ERROR] accounts.json 解析失败: {}", e);
+                Ok((
+                    AccountStore::default(),
+                    Some(Self::integrity_issue_for(path, &format!("文件无法解析: {}", e), false)),
+                ))
+            }
+        }
+    }
+
+    fn integrity_issue_for(path: &std::path::Path, reason: &str, trustable: bool) -> IntegrityIssue {
+        // 留存异常文件，避免后续保存时被覆盖
+        let quarantine = path.with_extension(format!("json.corrupt-{}", chrono::Utc::now().timestamp()));
+        if let Err(e) = fs::copy(path, &quarantine) {
+            println!("[WARN] 留存异常数据文件失败: {}", e);
+        }
+        IntegrityIssue {
+            path: path.to_string_lossy().to_string(),
+            reason: reason.to_string(),
+            detected_at: chrono::Utc::now().timestamp(),
+            latest_valid_backup: integrity::latest_valid_backup(Self::backup_candidates(path))
+                .map(|p| p.to_string_lossy().to_string()),
+            trustable,
+        }
+    }
+
+    /// 可用于恢复的备份文件
+    fn backup_candidates(path: &std::path::Path) -> Vec<PathBuf> {
+        let mut candidates = vec![path.with_extension("json.bak")];
+        if let Some(dir) = path.parent() {
+            if let Ok(entries) = fs::read_dir(dir.join("backups")) {
+                candidates.extend(
+                    entries
+                        .flatten()
+                        .map(|e| e.path())
+                        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                        .filter(|p| p.file_name().is_some_and(|name| name.to_string_lossy().starts_with("accounts"))),
+                );
             }
-        } else {
-            Ok(AccountStore::default())
         }
+        candidates
     }
 
-    /// 保存账号存储
+    /// 保存账号存储；存在未处理的完整性问题时拒绝保存，避免覆盖异常文件并重新签名
     fn save_store(&self) -> Result<()> {
+        if self.integrity_issue.is_some() {
+            return Err(coded(ErrorCode::ReadOnly, "账号数据完整性异常，请先从备份恢复或忽略该问题后再修改"));
+        }
+        self.write_store()
+    }
+
+    /// 写入账号存储并重新签名（不检查完整性问题，仅用于恢复备份和忽略完整性问题）
+    fn write_store(&self) -> Result<()> {
+        if self.is_read_only() {
+            return Err(coded(ErrorCode::ReadOnly, "账号数据正被另一个实例使用，当前为只读模式，请关闭其他实例后重试"));
+        }
+        if self.data_path.exists() && integrity::verify_store(&self.data_path) != IntegrityState::Invalid {
+            // 保留上一个校验通过的版本，作为最近的有效备份
+            let _ = integrity::copy_signed(&self.data_path, &self.data_path.with_extension("json.bak"));
+        }
         let content = serde_json::to_string_pretty(&self.store)?;
         fs::write(&self.data_path, content)?;
+        self.update_disk_fingerprint();
+        integrity::sign_store(&self.data_path)?;
+        Ok(())
+    }
+
+    /// 获取加载时发现的完整性问题
+    pub fn integrity_issue(&self) -> Option<IntegrityIssue> {
+        self.integrity_issue.clone()
+    }
+
+    /// 从备份恢复账号数据（未指定时使用最近一个校验通过的备份）
+    pub fn restore_from_backup(&mut self, backup_path: Option<String>) -> Result<usize> {
+        let path = match backup_path {
+            Some(p) => PathBuf::from(p),
+            None => integrity::latest_valid_backup(Self::backup_candidates(&self.data_path))
+                .ok_or_else(|| anyhow!("没有找到校验通过的备份"))?,
+        };
+        if integrity::verify_file(&path) == IntegrityState::Invalid {
+            return Err(anyhow!("备份文件签名校验失败: {}", path.display()));
+        }
+        let content = fs::read_to_string(&path)?;
+        let store: AccountStore = serde_json::from_str(content.trim_start_matches('\u{feff}').trim())
+            .map_err(|e| anyhow!("备份文件解析失败: {}", e))?;
+
+        let count = store.accounts.len();
+        self.store = store;
+        self.write_store()?;
+        self.integrity_issue = None;
+        println!("[INFO] 已从备份恢复 {} 个账号: {}", count, path.display());
+        Ok(count)
+    }
+
+    fn backups_dir(&self) -> PathBuf {
+        self.data_path.with_file_name("backups")
+    }
+
+    /// 创建一份账号数据备份（可同时备份设置），retention 不为 None 时只保留最近 retention 份
+    pub fn create_backup(&self, settings_json: Option<&str>, retention: Option<usize>) -> Result<BackupEntry> {
+        if self.integrity_issue.is_some() {
+            return Err(anyhow!("账号数据完整性异常，请先处理后再备份"));
+        }
+        if !self.data_path.exists() {
+            self.save_store()?;
+        }
+
+        let dir = self.backups_dir();
+        fs::create_dir_all(&dir)?;
+        // 精确到毫秒，同一毫秒内的多次备份追加序号，避免覆盖已有备份
+        let base_id = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+        let mut id = base_id.clone();
+        let mut suffix = 1;
+        while dir.join(format!("accounts-{}.json", id)).exists() {
+            id = format!("{}-{}", base_id, suffix);
+            suffix += 1;
+        }
+        integrity::copy_signed(&self.data_path, &dir.join(format!("accounts-{}.json", id)))?;
+        if let Some(settings) = settings_json {
+            fs::write(dir.join(format!("settings-{}.json", id)), settings)?;
+        }
+        if let Some(retention) = retention {
+            self.prune_backups(retention);
+        }
+
+        println!("[INFO] 已创建备份: {}", id);
+        self.list_backups()
+            .into_iter()
+            .find(|b| b.id == id)
+            .ok_or_else(|| anyhow!("备份创建失败"))
+    }
+
+    /// 列出所有备份（按时间倒序）
+    pub fn list_backups(&self) -> Vec<BackupEntry> {
+        let dir = self.backups_dir();
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Vec::new();
+        };
+        let mut backups: Vec<BackupEntry> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let name = path.file_name()?.to_string_lossy().to_string();
+                let id = name.strip_prefix("accounts-")?.strip_suffix(".json")?.to_string();
+                let metadata = entry.metadata().ok()?;
+                let account_count = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|c| serde_json::from_str::<AccountStore>(c.trim_start_matches('\u{feff}').trim()).ok())
+                    .map(|store| store.accounts.len())
+                    .unwrap_or(0);
+                let created_at = metadata
+                    .modified()
+                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp())
+                    .unwrap_or(0);
+                Some(BackupEntry {
+                    has_settings: dir.join(format!("settings-{}.json", id)).exists(),
+                    valid: integrity::verify_file(&path) != IntegrityState::Invalid,
+                    path: path.to_string_lossy().to_string(),
+                    size: metadata.len(),
+                    created_at,
+                    account_count,
+                    id,
+                })
+            })
+            .collect();
+        backups.sort_by(|a, b| b.id.cmp(&a.id));
+        backups
+    }
+
+    /// 删除超出保留数量的旧备份，返回删除的备份数和释放的字节数
+    pub fn prune_backups(&self, retention: usize) -> (usize, u64) {
+        let mut removed = 0;
+        let mut reclaimed = 0;
+        for backup in self.list_backups().into_iter().skip(retention.max(1)) {
+            let path = PathBuf::from(&backup.path);
+            let mut sig = path.as_os_str().to_os_string();
+            sig.push(".sig");
+            let settings_path = self.backups_dir().join(format!("settings-{}.json", backup.id));
+            reclaimed += backup.size + fs::metadata(&settings_path).map(|m| m.len()).unwrap_or(0);
+            let _ = fs::remove_file(&path);
+            let _ = fs::remove_file(PathBuf::from(sig));
+            let _ = fs::remove_file(settings_path);
+            removed += 1;
+            println!("[INFO] 已删除过期备份: {}", backup.id);
+        }
+        (removed, reclaimed)
+    }
+
+    /// 备份对应的设置文件
+    pub fn backup_settings_path(&self, backup_id: &str) -> Option<PathBuf> {
+        let path = self.backups_dir().join(format!("settings-{}.json", backup_id));
+        path.exists().then_some(path)
+    }
+
+    /// 从指定备份恢复账号数据
+    pub fn restore_backup(&mut self, backup_id: &str) -> Result<usize> {
+        if backup_id.contains(['/', '\\']) || backup_id.contains("..") {
+            return Err(anyhow!("备份标识无效"));
+        }
+        let path = self.backups_dir().join(format!("accounts-{}.json", backup_id));
+        if !path.exists() {
+            return Err(anyhow!("备份不存在: {}", backup_id));
+        }
+        self.restore_from_backup(Some(path.to_string_lossy().to_string()))
+    }
+
+    /// 忽略完整性问题，信任当前数据并重新签名
+    pub fn dismiss_integrity_issue(&mut self) -> Result<()> {
+        if self.integrity_issue.is_none() {
+            return Ok(());
+        }
+        self.write_store()?;
+        self.integrity_issue = None;
         Ok(())
     }
 
+    /// 信任外部对 accounts.json 的修改（同步工具、手动编辑），按当前内容重新签名并解除只读
+    ///
+    /// 文件无法解析时内存中是空数据，重新签名会覆盖原文件，只能从备份恢复
+    pub fn trust_external_change(&mut self) -> Result<usize> {
+        let Some(issue) = &self.integrity_issue else {
+            return Ok(self.store.accounts.len());
+        };
+        if !issue.trustable {
+            return Err(coded(ErrorCode::InvalidInput, "账号数据文件无法解析，请从备份恢复"));
+        }
+        self.write_store()?;
+        self.integrity_issue = None;
+        println!("[INFO] 已信任外部修改并重新签名 accounts.json（{} 个账号）", self.store.accounts.len());
+        Ok(self.store.accounts.len())
+    }
+
     pub fn update_account_email(&mut self, account_id: &str, email: String) -> Result<()> {
         let email = email.trim();
         if email.is_empty() {
@@ -85,7 +440,7 @@ impl AccountManager {
 
         let account = self.store.accounts.iter_mut()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?;
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
 
         account.email = email.to_string();
         account.updated_at = chrono::Utc::now().timestamp();
@@ -93,16 +448,49 @@ impl AccountManager {
         Ok(())
     }
 
+    /// 保存登录页捕获的刷新 Token（为空时忽略）
+    pub fn set_refresh_token(&mut self, account_id: &str, refresh_token: Option<String>) -> Result<()> {
+        let Some(refresh_token) = refresh_token.filter(|t| !t.trim().is_empty()) else {
+            return Ok(());
+        };
+        let account = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+
+        account.refresh_token = Some(refresh_token);
+        account.updated_at = chrono::Utc::now().timestamp();
+        self.save_store()?;
+        Ok(())
+    }
+
+    /// 记录账号的添加方式（已有记录时保留，避免重新登录覆盖最初的来源）
+    pub fn set_registration(&mut self, account_id: &str, registration: AccountRegistration) -> Result<Account> {
+        let account = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+
+        if account.registration.source == AccountSource::Unknown {
+            account.registration = registration;
+            let updated = account.clone();
+            self.save_store()?;
+            return Ok(updated);
+        }
+        Ok(account.clone())
+    }
+
     pub fn update_account_profile(
         &mut self,
         account_id: &str,
         email: Option<String>,
         password: Option<String>,
+        region: Option<String>,
+        api_host: Option<String>,
+        locale: Option<String>,
     ) -> Result<Account> {
         let account_index = self.store.accounts
             .iter()
             .position(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?;
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
         let mut changed = false;
         let account_snapshot = {
             let account = &mut self.store.accounts[account_index];
@@ -123,6 +511,45 @@ impl AccountManager {
                 }
             }
 
+            // 传入空字符串表示清除覆盖，恢复自动检测
+            if let Some(next_region) = region {
+                let trimmed = next_region.trim().to_uppercase();
+                let next_value = match trimmed.as_str() {
+                    "" => None,
+                    "US" | "SG" | "CN" => Some(trimmed),
+                    _ => return Err(anyhow!("不支持的区域: {}（可选 US / SG / CN）", next_region.trim())),
+                };
+                if next_value != account.region_override {
+                    account.region_override = next_value;
+                    changed = true;
+                }
+            }
+
+            if let Some(next_host) = api_host {
+                let trimmed = next_host.trim().trim_end_matches('/');
+                if !trimmed.is_empty() && !trimmed.starts_with("http://") && !trimmed.starts_with("https://") {
+                    return Err(anyhow!("API 地址必须以 http:// 或 https:// 开头"));
+                }
+                let next_value = if trimmed.is_empty() { None } else { Some(trimmed.to_string()) };
+                if next_value != account.api_host {
+                    account.api_host = next_value;
+                    changed = true;
+                }
+            }
+
+            // 传入空字符串表示不修改 Trae 的界面语言
+            if let Some(next_locale) = locale {
+                let trimmed = next_locale.trim().to_lowercase();
+                if !trimmed.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') || trimmed.len() > 16 {
+                    return Err(anyhow!("无效的语言代码: {}", next_locale.trim()));
+                }
+                let next_value = if trimmed.is_empty() { None } else { Some(trimmed) };
+                if next_value != account.locale {
+                    account.locale = next_value;
+                    changed = true;
+                }
+            }
+
             if changed {
                 account.updated_at = chrono::Utc::now().timestamp();
             }
@@ -167,8 +594,7 @@ impl AccountManager {
 
         account.avatar_url = user_info.avatar_url;
         account.region = user_info.region;
-        account.jwt_token = Some(token_result.token);
-        account.token_expired_at = Some(token_result.expired_at);
+        account.set_token(token_result.token, Some(token_result.expired_at), token_result.refresh_token);
         account.password = password;
 
         self.store.accounts.push(account.clone());
@@ -245,6 +671,49 @@ impl AccountManager {
         Ok(account)
     }
 
+    /// 添加未验证账号（不调用 API，仅保存 Token/Cookies，稍后再校验）
+    pub fn add_account_unverified(
+        &mut self,
+        token: Option<String>,
+        cookies: Option<String>,
+        password: Option<String>,
+    ) -> Result<Account> {
+        let token = token.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let cookies = cookies.map(|v| v.trim().to_string()).unwrap_or_default();
+        if token.is_none() && cookies.is_empty() {
+            return Err(anyhow!("请提供 Token 或 Cookies"));
+        }
+
+        // Token 可在本地解析出 user_id，用于离线去重
+        let jwt = token.as_deref().and_then(|t| TraeApiClient::parse_jwt_token(t).ok());
+        let duplicated = self.store.accounts.iter().any(|a| {
+            jwt.as_ref().map(|j| a.user_id == j.user_id).unwrap_or(false)
+                || (!cookies.is_empty() && a.cookies == cookies)
+                || (token.is_some() && a.jwt_token == token)
+        });
+        if duplicated {
+            return Err(anyhow!("该账号已存在"));
+        }
+
+        let mut account = Account::new_unverified(String::new(), cookies, token);
+        if let Some(jwt) = jwt {
+            account.name = format!("User_{}", &jwt.user_id[..8.min(jwt.user_id.len())]);
+            account.user_id = jwt.user_id;
+            account.tenant_id = jwt.tenant_id;
+        }
+        account.password = password.filter(|v| !v.is_empty());
+
+        self.store.accounts.push(account.clone());
+
+        // 如果是第一个账号，设为活跃账号
+        if self.store.active_account_id.is_none() {
+            self.store.active_account_id = Some(account.id.clone());
+        }
+
+        self.save_store()?;
+        Ok(account)
+    }
+
     /// Upsert account by token/cookies and refresh profile when it already exists.
     pub async fn upsert_account_by_token(
         &mut self,
@@ -294,7 +763,7 @@ impl AccountManager {
                 acc.jwt_token = Some(token.clone());
                 acc.token_expired_at = None;
                 if let Some(cookie_str) = cookies.as_ref().filter(|v| !v.is_empty()) {
-                    acc.cookies = cookie_str.to_string();
+                    acc.set_cookies(cookie_str.to_string());
                 }
                 if let Some(pass) = password.as_ref().filter(|v| !v.is_empty()) {
                     acc.password = Some(pass.to_string());
@@ -363,8 +832,7 @@ impl AccountManager {
         );
 
         account.avatar_url = user_info.avatar_url.unwrap_or_default();
-        account.jwt_token = Some(login_result.token);
-        account.token_expired_at = Some(login_result.expired_at);
+        account.set_token(login_result.token, Some(login_result.expired_at), login_result.refresh_token);
         account.password = Some(password);
 
         self.store.accounts.push(account.clone());
@@ -385,9 +853,14 @@ impl AccountManager {
             .accounts
             .iter()
             .position(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?;
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
 
         self.store.accounts.remove(index);
+        self.store.workspace_mappings.retain(|m| m.account_id != account_id);
+        request_log::clear(account_id);
+        if let Err(e) = crate::usage_history::delete_account(account_id) {
+            println!("[WARN] 删除使用量历史失败: {}", e);
+        }
 
         // 如果删除的是活跃账号，重置活跃账号
         if self.store.active_account_id.as_deref() == Some(account_id) {
@@ -411,7 +884,7 @@ impl AccountManager {
     /// 设置活跃账号
     pub fn set_active_account(&mut self, account_id: &str) -> Result<()> {
         if !self.store.accounts.iter().any(|a| a.id == account_id) {
-            return Err(anyhow!("账号不存在"));
+            return Err(coded(ErrorCode::NotFound, "账号不存在"));
         }
 
         self.store.active_account_id = Some(account_id.to_string());
@@ -421,6 +894,8 @@ impl AccountManager {
 
     /// 切换账号（设置活跃账号并将登录信息写入 Trae IDE）
     pub fn switch_account(&mut self, account_id: &str, force: bool) -> Result<()> {
+        crate::machine::ensure_trae_installed()?;
+
         // 检查是否已经是当前使用的账号
         if !force && self.store.current_account_id.as_deref() == Some(account_id) {
             return Err(anyhow!("该账号已经是当前使用的账号"));
@@ -428,7 +903,7 @@ impl AccountManager {
 
         let account = self.store.accounts.iter()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?
             .clone();
 
         // 检查账号是否有有效的 Token
@@ -436,16 +911,7 @@ impl AccountManager {
             .ok_or_else(|| anyhow!("账号没有有效的 Token，无法切换"))?;
 
         // 构建 Trae IDE 登录信息
-        let login_info = crate::machine::TraeLoginInfo {
-            token: token.clone(),
-            refresh_token: None, // 如果有 refresh token 可以在这里设置
-            user_id: account.user_id.clone(),
-            email: account.email.clone(),
-            username: account.name.clone(),
-            avatar_url: account.avatar_url.clone(),
-            host: String::new(), // 根据 region 自动选择
-            region: if account.region.is_empty() { "SG".to_string() } else { account.region.clone() },
-        };
+        let login_info = account.to_login_info(token.clone(), self.entitlement_mode);
 
         // 切换 Trae IDE 到该账号（清除旧登录状态并写入新账号信息）
         crate::machine::switch_trae_account(&login_info, account.machine_id.as_deref())?;
@@ -461,12 +927,201 @@ impl AccountManager {
         // 设置活跃账号和当前使用的账号
         self.store.active_account_id = Some(account_id.to_string());
         self.store.current_account_id = Some(account_id.to_string());
+        if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
+            let now = chrono::Utc::now().timestamp();
+            acc.last_switched_at = Some(now);
+            acc.last_active_at = Some(now);
+            acc.switch_count = acc.switch_count.saturating_add(1);
+        }
         self.save_store()?;
 
         println!("[INFO] 已切换到账号: {}", account.email);
         Ok(())
     }
 
+    /// 更新账号标签和备注
+    pub fn update_account_notes(&mut self, account_id: &str, tags: Vec<String>, notes: Option<String>) -> Result<Account> {
+        let account = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+
+        let mut unique_tags: Vec<String> = Vec::new();
+        for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if !unique_tags.iter().any(|t| t == tag) {
+                unique_tags.push(tag.to_string());
+            }
+        }
+        account.tags = unique_tags;
+        account.notes = notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        account.updated_at = chrono::Utc::now().timestamp();
+        let snapshot = account.clone();
+        self.save_store()?;
+        Ok(snapshot)
+    }
+
+    /// 设置账号本地显示名称（为空时恢复使用服务器返回的名称）
+    pub fn update_account_alias(&mut self, account_id: &str, alias: Option<String>) -> Result<Account> {
+        let account = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+        account.alias = alias.map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+        account.updated_at = chrono::Utc::now().timestamp();
+        let snapshot = account.clone();
+        self.save_store()?;
+        Ok(snapshot)
+    }
+
+    /// 设置账号的两步验证密钥（为空时清除），密钥需已通过 totp::normalize_secret 校验
+    pub fn update_account_totp_secret(&mut self, account_id: &str, secret: Option<String>) -> Result<Account> {
+        let account = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+        account.totp_secret = secret;
+        account.updated_at = chrono::Utc::now().timestamp();
+        let snapshot = account.clone();
+        self.save_store()?;
+        Ok(snapshot)
+    }
+
+    /// 查找两步验证密钥：优先使用指定账号，否则使用同邮箱的账号
+    pub fn totp_secret_for(&self, account_id: Option<&str>, email: &str) -> Option<String> {
+        let email = email.trim();
+        self.store.accounts.iter()
+            .find(|a| match account_id {
+                Some(id) => a.id == id,
+                None => !email.is_empty() && a.email.eq_ignore_ascii_case(email),
+            })
+            .and_then(|a| a.totp_secret.clone())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// 生成号池概览 Markdown 表格（不包含任何凭据）
+    pub fn pool_summary_markdown(&self, usages: &HashMap<String, UsageSummary>) -> String {
+        fn cell(value: &str) -> String {
+            let value = value.replace('|', "\\|").replace(['\r', '\n'], " ");
+            if value.trim().is_empty() { "-".to_string() } else { value }
+        }
+        fn format_date(ts: i64) -> String {
+            chrono::DateTime::from_timestamp(ts, 0)
+                .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        }
+
+        let mut total_left = 0.0;
+        let mut rows = Vec::new();
+        for account in &self.store.accounts {
+            let usage = usages.get(&account.id);
+            let plan = usage.map(|u| u.plan_type.as_str()).unwrap_or(account.plan_type.as_str());
+            let quota = match usage {
+                Some(u) => {
+                    let left = u.fast_request_left + u.extra_fast_request_left;
+                    let limit = u.fast_request_limit + u.extra_fast_request_limit;
+                    total_left += left;
+                    format!("{} / {}", left.round(), limit)
+                }
+                None => "-".to_string(),
+            };
+            let expiry = match usage {
+                Some(u) if u.reset_time > 0 => format_date(u.reset_time),
+                _ => "-".to_string(),
+            };
+            rows.push(format!(
+                "| {} | {} | {} | {} | {} | {} |",
+                cell(&account.email),
+                cell(plan),
+                quota,
+                expiry,
+                cell(&account.tags.join(", ")),
+                cell(account.notes.as_deref().unwrap_or_default()),
+            ));
+        }
+
+        let mut md = String::new();
+        md.push_str("# Trae 号池概览\n\n");
+        md.push_str(&format!(
+            "生成时间: {}  \n账号数: {}，剩余快速请求合计: {}\n\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M"),
+            self.store.accounts.len(),
+            total_left.round()
+        ));
+        md.push_str("| 邮箱 | 套餐 | 剩余快速请求 | 重置/到期 | 标签 | 备注 |\n");
+        md.push_str("| --- | --- | --- | --- | --- | --- |\n");
+        for row in rows {
+            md.push_str(&row);
+            md.push('\n');
+        }
+        md
+    }
+
+    /// 获取所有工作区映射
+    pub fn workspace_mappings(&self) -> Vec<WorkspaceMapping> {
+        self.store.workspace_mappings.clone()
+    }
+
+    /// 添加或更新工作区映射（同一目录只保留一个映射）
+    pub fn set_workspace_mapping(&mut self, folder: &str, account_id: &str, auto_switch: bool) -> Result<WorkspaceMapping> {
+        let folder = folder.trim().trim_end_matches(['/', '\\']).to_string();
+        if folder.is_empty() {
+            return Err(anyhow!("工作区目录不能为空"));
+        }
+        if !self.store.accounts.iter().any(|a| a.id == account_id) {
+            return Err(coded(ErrorCode::NotFound, "账号不存在"));
+        }
+
+        let key = normalize_workspace_path(std::path::Path::new(&folder));
+        let mapping = match self
+            .store
+            .workspace_mappings
+            .iter_mut()
+            .find(|m| normalize_workspace_path(std::path::Path::new(&m.folder)) == key)
+        {
+            Some(existing) => {
+                existing.account_id = account_id.to_string();
+                existing.auto_switch = auto_switch;
+                existing.clone()
+            }
+            None => {
+                let mapping = WorkspaceMapping {
+                    id: Uuid::new_v4().to_string(),
+                    folder,
+                    account_id: account_id.to_string(),
+                    auto_switch,
+                    created_at: chrono::Utc::now().timestamp(),
+                };
+                self.store.workspace_mappings.push(mapping.clone());
+                mapping
+            }
+        };
+        self.save_store()?;
+        Ok(mapping)
+    }
+
+    /// 删除工作区映射
+    pub fn remove_workspace_mapping(&mut self, mapping_id: &str) -> Result<()> {
+        let before = self.store.workspace_mappings.len();
+        self.store.workspace_mappings.retain(|m| m.id != mapping_id);
+        if self.store.workspace_mappings.len() == before {
+            return Err(anyhow!("映射不存在"));
+        }
+        self.save_store()
+    }
+
+    /// 查找工作区对应的映射（目录本身或其上级目录，取最具体的一个）
+    pub fn find_workspace_mapping(&self, workspace: &std::path::Path) -> Option<WorkspaceMapping> {
+        let workspace = normalize_workspace_path(workspace);
+        self.store
+            .workspace_mappings
+            .iter()
+            .filter(|m| workspace.starts_with(normalize_workspace_path(std::path::Path::new(&m.folder))))
+            .max_by_key(|m| m.folder.len())
+            .cloned()
+    }
+
+    /// 当前 Trae IDE 使用的账号 ID
+    pub fn current_account_id(&self) -> Option<String> {
+        self.store.current_account_id.clone()
+    }
+
     /// 绑定当前系统机器码到账号
     pub fn bind_machine_id(&mut self, account_id: &str) -> Result<String> {
         // 获取当前系统机器码
@@ -475,7 +1130,7 @@ impl AccountManager {
         // 更新账号的机器码
         let account = self.store.accounts.iter_mut()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?;
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
 
         account.machine_id = Some(current_machine_id.clone());
         account.updated_at = chrono::Utc::now().timestamp();
@@ -487,6 +1142,24 @@ impl AccountManager {
         Ok(current_machine_id)
     }
 
+    /// 为账号生成并绑定新的机器码
+    pub fn bind_new_machine_id(&mut self, account_id: &str) -> Result<String> {
+        let new_machine_id = crate::machine::generate_machine_guid();
+
+        let account = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+
+        account.machine_id = Some(new_machine_id.clone());
+        account.updated_at = chrono::Utc::now().timestamp();
+        let email = account.email.clone();
+
+        self.save_store()?;
+        println!("[INFO] 已为账号 {} 生成新机器码 {}", email, new_machine_id);
+
+        Ok(new_machine_id)
+    }
+
     /// 获取所有账号列表
     pub fn get_accounts(&self) -> Vec<AccountBrief> {
         let current_id = self.store.current_account_id.as_deref();
@@ -511,52 +1184,86 @@ impl AccountManager {
             .iter()
             .find(|a| a.id == account_id)
             .cloned()
-            .ok_or_else(|| anyhow!("账号不存在"))
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))
+    }
+
+    /// 记录账号操作结果：失败时保存错误，成功时清除上一次的错误
+    fn track_result<T>(&mut self, account_id: &str, result: &Result<T>) {
+        match result {
+            Ok(_) => self.clear_account_error(account_id),
+            Err(e) => self.record_account_error(account_id, e),
+        }
+    }
+
+    /// 记录账号最近一次错误
+    pub fn record_account_error(&mut self, account_id: &str, err: &anyhow::Error) {
+        let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) else {
+            return;
+        };
+        acc.last_error = Some(AccountError::from_error(err));
+        if let Err(e) = self.save_store() {
+            println!("[WARN] 保存账号错误信息失败: {}", e);
+        }
+    }
+
+    /// 清除账号错误
+    pub fn clear_account_error(&mut self, account_id: &str) {
+        let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) else {
+            return;
+        };
+        if acc.last_error.take().is_some() {
+            if let Err(e) = self.save_store() {
+                println!("[WARN] 保存账号错误信息失败: {}", e);
+            }
+        }
+    }
+
+    /// 获取账号指定的 API 端点（区域/API 地址覆盖）
+    fn account_api_base(&self, account_id: &str) -> Option<String> {
+        self.store.accounts
+            .iter()
+            .find(|a| a.id == account_id)
+            .and_then(|a| a.api_base_override())
     }
 
     /// 获取账号使用量
     pub async fn get_account_usage(&mut self, account_id: &str) -> Result<UsageSummary> {
+        let result = request_log::scope(account_id, self.get_account_usage_internal(account_id)).await;
+        self.track_result(account_id, &result);
+        result
+    }
+
+    async fn get_account_usage_internal(&mut self, account_id: &str) -> Result<UsageSummary> {
         let account = self
             .store
             .accounts
             .iter()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?
             .clone();
+        let api_base = account.api_base_override();
 
         // 根据账号类型选择不同的方式获取使用量
         let summary = if let Some(token) = &account.jwt_token {
             // 优先使用 Token
-            let client = TraeApiClient::new_with_token(token)?;
+            let client = TraeApiClient::new_with_token(token)?.with_api_base(api_base.as_deref());
             match client.get_usage_summary_by_token().await {
                 Ok(summary) => summary,
                 Err(e) => {
-                    let error_msg = e.to_string();
-                    // 如果是 401 错误且有 Cookies，尝试刷新 Token
-                    if error_msg.contains("401") && !account.cookies.is_empty() {
-                        println!("[INFO] Token 已过期，尝试使用 Cookies 刷新...");
-                        // 使用 Cookies 刷新 Token
-                        let mut cookie_client = TraeApiClient::new(&account.cookies)?;
-                        let token_result = cookie_client.get_user_token().await?;
+                    // 如果是 401 错误且有刷新 Token 或 Cookies，尝试刷新 Token
+                    if ErrorCode::of(&e) == ErrorCode::AuthExpired && account.can_renew_token() {
+                        println!("[INFO] Token 已过期，尝试刷新...");
+                        let token_result = renew_token(&account).await?;
 
                         // 更新存储的 Token
                         if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
-                            acc.jwt_token = Some(token_result.token.clone());
-                            acc.token_expired_at = Some(token_result.expired_at.clone());
+                            acc.set_token(token_result.token.clone(), Some(token_result.expired_at.clone()), token_result.refresh_token.clone());
                         }
                         self.save_store()?;
 
-                        if self.store.current_account_id.as_deref() == Some(account_id) {
-                            let login_info = crate::machine::TraeLoginInfo {
-                                token: token_result.token.clone(),
-                                refresh_token: None,
-                                user_id: account.user_id.clone(),
-                                email: account.email.clone(),
-                                username: account.name.clone(),
-                                avatar_url: account.avatar_url.clone(),
-                                host: String::new(),
-                                region: if account.region.is_empty() { "SG".to_string() } else { account.region.clone() },
-                            };
+                        // 未安装 Trae IDE 时只更新本地 Token
+                        if self.store.current_account_id.as_deref() == Some(account_id) && crate::machine::is_trae_installed() {
+                            let login_info = account.to_login_info(token_result.token.clone(), self.entitlement_mode);
 
                             if let Err(e) = crate::machine::write_trae_login_info(&login_info) {
                                 println!("[WARN] 更新 Trae IDE Token 失败: {}", e);
@@ -571,10 +1278,10 @@ impl AccountManager {
 
 
                         // 使用新 Token 重新获取使用量
-                        let new_client = TraeApiClient::new_with_token(&token_result.token)?;
+                        let new_client = TraeApiClient::new_with_token(&token_result.token)?.with_api_base(api_base.as_deref());
                         new_client.get_usage_summary_by_token().await?
-                    } else if error_msg.contains("401") {
-                        return Err(anyhow!("Token 已过期，请更新 Token 或 Cookies"));
+                    } else if ErrorCode::of(&e) == ErrorCode::AuthExpired {
+                        return Err(coded(ErrorCode::AuthExpired, "Token 已过期，请更新 Token 或 Cookies"));
                     } else {
                         return Err(e);
                     }
@@ -582,17 +1289,17 @@ impl AccountManager {
             }
         } else if !account.cookies.is_empty() {
             // 使用 Cookies
-            let mut client = TraeApiClient::new(&account.cookies)?;
+            let mut client = TraeApiClient::new(&account.cookies)?.with_api_base(api_base.as_deref());
             client.get_usage_summary().await?
         } else {
             return Err(anyhow!("账号没有有效的 Token 或 Cookies"));
         };
 
-        // 更新账号的 plan_type
+        // 更新账号的 plan_type 和使用量缓存
         if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
-            acc.plan_type = summary.plan_type.clone();
-            acc.updated_at = chrono::Utc::now().timestamp();
+            acc.record_usage(&summary, chrono::Utc::now().timestamp());
         }
+        record_usage_sample(account_id, &summary);
         self.save_store()?;
 
         Ok(summary)
@@ -600,20 +1307,23 @@ impl AccountManager {
 
     /// 刷新账号 Token
     pub async fn refresh_token(&mut self, account_id: &str) -> Result<()> {
-        let account = self
+        let result = request_log::scope(account_id, self.refresh_token_internal(account_id)).await;
+        self.track_result(account_id, &result);
+        result
+    }
+
+    async fn refresh_token_internal(&mut self, account_id: &str) -> Result<()> {
+        let account = self
             .store
             .accounts
             .iter()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?
             .clone();
-
-        let mut client = TraeApiClient::new(&account.cookies)?;
-        let token_result = client.get_user_token().await?;
+        let token_result = renew_token(&account).await?;
 
         if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
-            acc.jwt_token = Some(token_result.token);
-            acc.token_expired_at = Some(token_result.expired_at);
+            acc.set_token(token_result.token, Some(token_result.expired_at), token_result.refresh_token);
             acc.updated_at = chrono::Utc::now().timestamp();
         }
 
@@ -628,7 +1338,7 @@ impl AccountManager {
             .accounts
             .iter()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?
             .clone();
 
         if account.email.is_empty() {
@@ -642,9 +1352,8 @@ impl AccountManager {
         }
 
         if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
-            acc.cookies = login_result.cookies;
-            acc.jwt_token = Some(login_result.token);
-            acc.token_expired_at = Some(login_result.expired_at);
+            acc.set_cookies(login_result.cookies);
+            acc.set_token(login_result.token, Some(login_result.expired_at), login_result.refresh_token);
             acc.password = Some(password.to_string());
             acc.updated_at = chrono::Utc::now().timestamp();
         }
@@ -665,7 +1374,7 @@ impl AccountManager {
             .accounts
             .iter()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?
             .clone();
 
         let login_result = login_with_email(&email, &password).await?;
@@ -681,9 +1390,8 @@ impl AccountManager {
         if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
             acc.email = email;
             acc.password = Some(password);
-            acc.cookies = login_result.cookies;
-            acc.jwt_token = Some(login_result.token);
-            acc.token_expired_at = Some(login_result.expired_at);
+            acc.set_cookies(login_result.cookies);
+            acc.set_token(login_result.token, Some(login_result.expired_at), login_result.refresh_token);
             acc.tenant_id = login_result.tenant_id;
             acc.plan_type = summary.plan_type.clone();
             acc.updated_at = chrono::Utc::now().timestamp();
@@ -701,12 +1409,13 @@ impl AccountManager {
         cookies: Option<String>,
         password: Option<String>,
     ) -> Result<()> {
-        let client = TraeApiClient::new_with_token(&token)?;
+        let api_base = self.get_account(account_id)?.api_base_override();
+        let client = TraeApiClient::new_with_token(&token)?.with_api_base(api_base.as_deref());
         let user_info = client.get_user_info_by_token().await?;
 
         let acc = self.store.accounts.iter_mut()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?;
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
 
         if acc.user_id != user_info.user_id {
             return Err(anyhow!("Token 对应的用户与当前账号不匹配"));
@@ -717,12 +1426,12 @@ impl AccountManager {
 
         if let Some(cookie_str) = cookies.as_ref().filter(|v| !v.is_empty()) {
             match TraeApiClient::new(cookie_str) {
-                Ok(mut cookie_client) => match cookie_client.get_user_token().await {
+                Ok(cookie_client) => match cookie_client.with_api_base(api_base.as_deref()).get_user_token().await {
                     Ok(token_result) => {
                         if token_result.user_id != acc.user_id {
                             return Err(anyhow!("Cookies 对应的用户与当前账号不匹配"));
                         }
-                        acc.cookies = cookie_str.to_string();
+                        acc.set_cookies(cookie_str.to_string());
                         token_to_store = token_result.token;
                         expired_at = Some(token_result.expired_at);
                     }
@@ -749,7 +1458,8 @@ impl AccountManager {
 
     /// 更新账号 Token
     pub async fn update_account_token(&mut self, account_id: &str, token: String) -> Result<UsageSummary> {
-        let client = TraeApiClient::new_with_token(&token)?;
+        let api_base = self.account_api_base(account_id);
+        let client = TraeApiClient::new_with_token(&token)?.with_api_base(api_base.as_deref());
 
         // 验证 Token 并获取用户信息
         let user_info = client.get_user_info_by_token().await?;
@@ -757,7 +1467,7 @@ impl AccountManager {
         // 查找账号
         let acc = self.store.accounts.iter_mut()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?;
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
 
         // 确保是同一个用户
         if acc.user_id != user_info.user_id {
@@ -779,7 +1489,8 @@ impl AccountManager {
     /// 更新账号 Cookies
     pub async fn update_cookies(&mut self, account_id: &str, cookies: String) -> Result<()> {
         // 验证新 cookies 是否有效
-        let mut client = TraeApiClient::new(&cookies)?;
+        let api_base = self.account_api_base(account_id);
+        let mut client = TraeApiClient::new(&cookies)?.with_api_base(api_base.as_deref());
         let token_result = client.get_user_token().await?;
 
         if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
@@ -788,18 +1499,53 @@ impl AccountManager {
                 return Err(anyhow!("Cookies 对应的用户与当前账号不匹配"));
             }
 
-            acc.cookies = cookies;
-            acc.jwt_token = Some(token_result.token);
-            acc.token_expired_at = Some(token_result.expired_at);
+            acc.set_cookies(cookies);
+            acc.set_token(token_result.token, Some(token_result.expired_at), token_result.refresh_token);
             acc.updated_at = chrono::Utc::now().timestamp();
         } else {
-            return Err(anyhow!("账号不存在"));
+            return Err(coded(ErrorCode::NotFound, "账号不存在"));
         }
 
         self.save_store()?;
         Ok(())
     }
 
+    /// 获取账号 Cookies 的各个条目
+    pub fn get_account_cookies_parsed(&self, account_id: &str) -> Result<Vec<CookieEntry>> {
+        let account = self.get_account(account_id)?;
+        Ok(CookieEntry::parse_header(&account.cookies))
+    }
+
+    /// 修改账号的单个 Cookie（值为空时删除），校验通过后保存并返回新的条目列表
+    pub async fn set_account_cookie(&mut self, account_id: &str, name: &str, value: &str) -> Result<Vec<CookieEntry>> {
+        let name = name.trim();
+        let value = value.trim();
+        if name.is_empty() || name.contains(|c: char| c == ';' || c == '=' || c.is_whitespace()) {
+            return Err(anyhow!("Cookie 名称无效"));
+        }
+        if value.contains(';') {
+            return Err(anyhow!("Cookie 值不能包含分号"));
+        }
+
+        let mut entries = self.get_account_cookies_parsed(account_id)?;
+        match entries.iter().position(|c| c.name == name) {
+            Some(index) if value.is_empty() => {
+                entries.remove(index);
+            }
+            Some(index) => entries[index].value = value.to_string(),
+            None if value.is_empty() => return Err(anyhow!("Cookie {} 不存在", name)),
+            None => entries.push(CookieEntry {
+                name: name.to_string(),
+                value: value.to_string(),
+            }),
+        }
+
+        // 复用完整更新流程：校验新 Cookies 属于同一用户并刷新 Token
+        self.update_cookies(account_id, CookieEntry::join_header(&entries)).await?;
+        println!("[INFO] 已更新账号 Cookie: {}", name);
+        Ok(entries)
+    }
+
     /// 导出账号数据
     pub fn export_accounts(&self) -> Result<String> {
         let export_data: Vec<serde_json::Value> = self.store.accounts.iter().map(|acc| {
@@ -813,8 +1559,23 @@ impl AccountManager {
                 "plan_type": acc.plan_type,
                 "avatar_url": acc.avatar_url,
                 "jwt_token": acc.jwt_token,
+                "token_expired_at": acc.token_expired_at,
+                "refresh_token": acc.refresh_token,
                 "machine_id": acc.machine_id,
                 "password": acc.password,
+                "created_at": acc.created_at,
+                "last_switched_at": acc.last_switched_at,
+                "last_active_at": acc.last_active_at,
+                "switch_count": acc.switch_count,
+                "unverified": acc.unverified,
+                "region_override": acc.region_override,
+                "api_host": acc.api_host,
+                "tags": acc.tags,
+                "notes": acc.notes,
+                "locale": acc.locale,
+                "alias": acc.alias,
+                "totp_secret": acc.totp_secret,
+                "registration": acc.registration,
             })
         }).collect();
 
@@ -822,15 +1583,75 @@ impl AccountManager {
             .map_err(|e| anyhow!("导出失败: {}", e))
     }
 
+    /// 完整导入：恢复导出文件中的全部字段（Token、密码、机器码、标签等），不调用 API。
+    /// 已有账号按 user_id（其次邮箱）合并，导入数据中的非空字段覆盖本地值
+    pub fn merge_import_accounts(&mut self, data: &str) -> Result<ImportMergeReport> {
+        let records = parse_import_data(data)?;
+        let mut report = ImportMergeReport::default();
+
+        for record in &records {
+            let text = |key: &str| {
+                record.get(key)
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+            };
+            let user_id = text("user_id");
+            let email = text("email");
+
+            let index = user_id.as_ref()
+                .and_then(|uid| self.store.accounts.iter().position(|a| &a.user_id == uid))
+                .or_else(|| email.as_ref().and_then(|e| {
+                    self.store.accounts.iter().position(|a| a.email.eq_ignore_ascii_case(e))
+                }));
+
+            if let Some(index) = index {
+                apply_import_record(&mut self.store.accounts[index], record);
+                report.merged += 1;
+                continue;
+            }
+            if text("cookies").is_none() && text("jwt_token").is_none() {
+                report.skipped += 1;
+                continue;
+            }
+
+            let mut account = Account::new_unverified(email.unwrap_or_default(), String::new(), None);
+            apply_import_record(&mut account, record);
+            // 有 user_id 的记录来自已校验的账号，保留其校验状态
+            account.unverified = user_id.is_none()
+                || record.get("unverified").and_then(|v| v.as_bool()).unwrap_or(false);
+            if account.machine_id.is_none() {
+                account.machine_id = Some(Uuid::new_v4().to_string());
+            }
+            if account.registration.source == AccountSource::Unknown {
+                account.registration = AccountRegistration::new(AccountSource::Import);
+            }
+            self.store.accounts.push(account);
+            report.added += 1;
+        }
+
+        if report.added + report.merged > 0 {
+            if self.store.active_account_id.is_none() && !self.store.accounts.is_empty() {
+                self.store.active_account_id = Some(self.store.accounts[0].id.clone());
+            }
+            self.save_store()?;
+        }
+        println!(
+            "[INFO] 完整导入完成: 新增 {} 个, 合并 {} 个, 跳过 {} 个",
+            report.added, report.merged, report.skipped
+        );
+        Ok(report)
+    }
+
     /// 导入账号数据
-    pub async fn import_accounts(&mut self, data: &str) -> Result<usize> {
-        let import_data: Vec<serde_json::Value> = serde_json::from_str(data)
-            .map_err(|e| anyhow!("JSON 解析失败: {}", e))?;
+    pub async fn import_accounts(&mut self, data: &str, options: &ImportOptions) -> Result<usize> {
+        let import_data = parse_import_data(data)?;
 
         // 1. Prepare tasks for fetching account info
         let mut tasks = Vec::new();
-        // Limit concurrency to 5 to avoid rate limits
-        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(5));
+        let mut deferred = Vec::new();
+        // Limit concurrency to avoid tripping Trae risk control
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(options.concurrency.max(1)));
         
         // Pre-check for existing accounts by email to skip unnecessary requests
         let existing_emails: std::collections::HashSet<String> = self.store.accounts.iter()
@@ -859,7 +1680,18 @@ impl AccountManager {
                 .map(|v| v.to_string())
                 .filter(|v| !v.is_empty());
 
-            if cookies.is_empty() {
+            let jwt_token = item.get("jwt_token")
+                .and_then(|v| v.as_str())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+
+            // 导出文件中带有来源信息时保留，否则记为导入
+            let registration = item.get("registration")
+                .and_then(|v| serde_json::from_value::<AccountRegistration>(v.clone()).ok())
+                .filter(|r| r.source != AccountSource::Unknown)
+                .unwrap_or_else(|| AccountRegistration::new(AccountSource::Import));
+
+            if cookies.is_empty() && jwt_token.is_none() {
                 continue;
             }
             
@@ -878,32 +1710,64 @@ impl AccountManager {
                                  existing.password = Some(new_pass);
                              }
                         }
-                        // Always update cookies for existing account
-                        existing.cookies = cookies;
+                        // Always update credentials for existing account
+                        if !cookies.is_empty() {
+                            existing.set_cookies(cookies);
+                        }
+                        if jwt_token.is_some() {
+                            existing.jwt_token = jwt_token;
+                        }
                         self.save_store()?;
                         continue;
                     }
                 }
             }
             
-            let cookies_clone = cookies.clone();
+            if options.defer_validation {
+                // Store raw credentials only; the account is validated on first use
+                let is_same = |a: &Account| {
+                    if cookies.is_empty() {
+                        a.jwt_token == jwt_token
+                    } else {
+                        a.cookies == cookies
+                    }
+                };
+                if self.store.accounts.iter().any(is_same) || deferred.iter().any(is_same) {
+                    continue;
+                }
+                let mut account = Account::new_unverified(email.unwrap_or_default(), cookies, jwt_token);
+                account.password = password;
+                if machine_id.is_some() {
+                    account.machine_id = machine_id;
+                }
+                account.registration = registration;
+                deferred.push(account);
+                continue;
+            }
+
             let semaphore_clone = semaphore.clone();
+            let wait_ms = options.delay_ms + random_jitter_ms(options.jitter_ms);
             
             tasks.push(tokio::spawn(async move {
                 let _permit = semaphore_clone.acquire().await.unwrap();
+                // Space out logins so bulk imports don't look like an attack
+                if wait_ms > 0 {
+                    tokio::time::sleep(std::time::Duration::from_millis(wait_ms)).await;
+                }
                 // Perform network requests
-                let result = fetch_account_info_internal(cookies_clone, password).await;
-                (result, machine_id)
+                let result = fetch_import_account(cookies, jwt_token, password).await;
+                (result, machine_id, registration)
             }));
         }
 
         // 2. Wait for all tasks to complete
         let mut new_accounts = Vec::new();
         for task in tasks {
-            if let Ok((Ok(mut account), machine_id)) = task.await {
+            if let Ok((Ok(mut account), machine_id, registration)) = task.await {
                 if let Some(mid) = machine_id {
                     account.machine_id = Some(mid);
                 }
+                account.registration = registration;
                 new_accounts.push(account);
             }
         }
@@ -936,6 +1800,12 @@ impl AccountManager {
              changed = true;
         }
 
+        for account in deferred {
+            self.store.accounts.push(account);
+            imported_count += 1;
+            changed = true;
+        }
+
         if self.store.active_account_id.is_none() && !self.store.accounts.is_empty() {
             self.store.active_account_id = Some(self.store.accounts[0].id.clone());
             changed = true;
@@ -948,6 +1818,401 @@ impl AccountManager {
         Ok(imported_count)
     }
 
+    /// 预览导入结果：解析数据并可选地通过 API 校验，返回将添加/跳过/失败的账号，不写入任何数据
+    pub async fn preview_import(&self, data: &str, verify: bool, options: &ImportOptions) -> Result<ImportPreview> {
+        let import_data = parse_import_data(data)?;
+
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(options.concurrency.max(1)));
+        let mut entries = Vec::new();
+        let mut tasks = Vec::new();
+        let mut seen_credentials = std::collections::HashSet::new();
+
+        for (index, item) in import_data.iter().enumerate() {
+            let cookies = item.get("cookies")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let email = item.get("email")
+                .and_then(|v| v.as_str())
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default();
+            let jwt_token = item.get("jwt_token")
+                .and_then(|v| v.as_str())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+            // 没有 Cookies 时以 Token 作为去重依据
+            let credential = if cookies.is_empty() {
+                jwt_token.clone().unwrap_or_default()
+            } else {
+                cookies.clone()
+            };
+            let mut entry = ImportPreviewEntry {
+                index,
+                email: email.clone(),
+                user_id: None,
+                status: ImportPreviewStatus::Add,
+                reason: None,
+            };
+
+            if credential.is_empty() {
+                entry.status = ImportPreviewStatus::Failed;
+                entry.reason = Some("缺少 Cookies 或 Token".to_string());
+            } else if !email.is_empty() && self.store.accounts.iter().any(|a| a.email.eq_ignore_ascii_case(&email)) {
+                entry.status = ImportPreviewStatus::Duplicate;
+                entry.reason = Some("邮箱已存在，将更新已有账号的凭据".to_string());
+            } else if self.store.accounts.iter().any(|a| {
+                if cookies.is_empty() { a.jwt_token == jwt_token } else { a.cookies == cookies }
+            }) || !seen_credentials.insert(credential) {
+                entry.status = ImportPreviewStatus::Duplicate;
+                entry.reason = Some(if cookies.is_empty() { "Token 重复" } else { "Cookies 重复" }.to_string());
+            } else if verify {
+                let semaphore_clone = semaphore.clone();
+                let wait_ms = options.delay_ms + random_jitter_ms(options.jitter_ms);
+                tasks.push((entries.len(), tokio::spawn(async move {
+                    let _permit = semaphore_clone.acquire().await.unwrap();
+                    if wait_ms > 0 {
+                        tokio::time::sleep(std::time::Duration::from_millis(wait_ms)).await;
+                    }
+                    fetch_import_account(cookies, jwt_token, None).await
+                })));
+            }
+            entries.push(entry);
+        }
+
+        let mut seen_user_ids = std::collections::HashSet::new();
+        for (entry_index, task) in tasks {
+            let entry = &mut entries[entry_index];
+            match task.await {
+                Ok(Ok(account)) => {
+                    if entry.email.is_empty() {
+                        entry.email = account.email.clone();
+                    }
+                    if self.store.accounts.iter().any(|a| a.user_id == account.user_id)
+                        || !seen_user_ids.insert(account.user_id.clone())
+                    {
+                        entry.status = ImportPreviewStatus::Duplicate;
+                        entry.reason = Some("账号已存在".to_string());
+                    }
+                    entry.user_id = Some(account.user_id);
+                }
+                Ok(Err(e)) => {
+                    entry.status = ImportPreviewStatus::Failed;
+                    entry.reason = Some(e.to_string());
+                }
+                Err(e) => {
+                    entry.status = ImportPreviewStatus::Failed;
+                    entry.reason = Some(format!("校验任务异常: {}", e));
+                }
+            }
+        }
+
+        let mut preview = ImportPreview::default();
+        for entry in &entries {
+            match entry.status {
+                ImportPreviewStatus::Add => preview.to_add += 1,
+                ImportPreviewStatus::Duplicate => preview.duplicates += 1,
+                ImportPreviewStatus::Failed => preview.failed += 1,
+            }
+        }
+        preview.entries = entries;
+        Ok(preview)
+    }
+
+    /// 校验未验证账号，补全用户信息；若与已有账号重复则合并到已有账号
+    pub async fn validate_account(&mut self, account_id: &str) -> Result<Account> {
+        let account = self.get_account(account_id)?;
+        if !account.unverified {
+            return Ok(account);
+        }
+        let verified = Self::fetch_verification(&account).await;
+        self.apply_verification(account_id, verified)
+    }
+
+    /// 所有未验证账号的快照（用于在不持有锁的情况下逐个校验）
+    pub fn unverified_accounts(&self) -> Vec<Account> {
+        self.store.accounts.iter().filter(|a| a.unverified).cloned().collect()
+    }
+
+    /// 用账号保存的 Cookies 或 Token 获取用户信息（只发请求，不修改账号数据）
+    pub async fn fetch_verification(account: &Account) -> Result<Account> {
+        request_log::scope(&account.id, async {
+            if !account.cookies.trim().is_empty() {
+                fetch_account_info_internal(account.cookies.clone(), account.password.clone()).await
+            } else if let Some(token) = account.jwt_token.clone() {
+                fetch_account_info_by_token(token, account.password.clone()).await
+            } else {
+                Err(anyhow!("账号没有有效的 Token 或 Cookies"))
+            }
+        })
+        .await
+    }
+
+    /// 保存校验结果；校验期间账号已被删除或已完成校验时以当前数据为准
+    pub fn apply_verification(&mut self, account_id: &str, verified: Result<Account>) -> Result<Account> {
+        let result = verified.and_then(|verified| self.apply_verified_account(account_id, verified));
+        self.track_result(account_id, &result);
+        result
+    }
+
+    fn apply_verified_account(&mut self, account_id: &str, verified: Account) -> Result<Account> {
+        let account = self.get_account(account_id)?;
+        if !account.unverified {
+            return Ok(account);
+        }
+
+        // 同一用户已存在：删除未验证记录，把凭据合并到已有账号
+        if self.store.accounts.iter().any(|a| a.id != account_id && a.user_id == verified.user_id) {
+            self.remove_account(account_id)?;
+            let existing = self.store.accounts.iter_mut()
+                .find(|a| a.user_id == verified.user_id)
+                .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+            if !verified.cookies.is_empty() {
+                existing.set_cookies(verified.cookies);
+            }
+            existing.jwt_token = verified.jwt_token;
+            existing.token_expired_at = verified.token_expired_at;
+            if account.password.is_some() {
+                existing.password = account.password;
+            }
+            existing.updated_at = chrono::Utc::now().timestamp();
+            let merged = existing.clone();
+            self.save_store()?;
+            return Ok(merged);
+        }
+
+        let acc = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+        acc.name = verified.name;
+        if !verified.email.is_empty() {
+            acc.email = verified.email;
+        }
+        acc.avatar_url = verified.avatar_url;
+        if !verified.region.is_empty() {
+            acc.region = verified.region;
+        }
+        acc.user_id = verified.user_id;
+        acc.tenant_id = verified.tenant_id;
+        acc.jwt_token = verified.jwt_token;
+        acc.token_expired_at = verified.token_expired_at;
+        acc.unverified = false;
+        acc.last_error = None;
+        acc.updated_at = chrono::Utc::now().timestamp();
+        let updated = acc.clone();
+
+        self.save_store()?;
+        println!("[INFO] 已完成账号校验: {}", updated.email);
+        Ok(updated)
+    }
+
+    /// 刷新账号资料（名称/头像/邮箱/区域/套餐）
+    pub async fn refresh_account_profile(&mut self, account_id: &str) -> Result<Account> {
+        let result = self.refresh_account_profile_internal(account_id).await;
+        self.track_result(account_id, &result);
+        result
+    }
+
+    async fn refresh_account_profile_internal(&mut self, account_id: &str) -> Result<Account> {
+        let account = self.get_account(account_id)?;
+        let update = request_log::scope(account_id, fetch_profile_update(account)).await?;
+        self.apply_profile_update(account_id, update)
+    }
+
+    /// 保存从 API 获取的账号资料
+    fn apply_profile_update(&mut self, account_id: &str, update: ProfileUpdate) -> Result<Account> {
+        let acc = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+        if let Some(name) = update.name.filter(|v| !v.trim().is_empty()) {
+            acc.name = name;
+        }
+        if let Some(avatar_url) = update.avatar_url.filter(|v| !v.trim().is_empty()) {
+            acc.avatar_url = avatar_url;
+        }
+        if let Some(email) = update.email.filter(|v| !v.trim().is_empty()) {
+            acc.email = email;
+        }
+        if let Some(region) = update.region.filter(|v| !v.trim().is_empty()) {
+            acc.region = region;
+        }
+        if let Some(plan_type) = update.plan_type {
+            acc.plan_type = plan_type;
+        }
+        if let Some((token, expired_at)) = update.new_token {
+            acc.jwt_token = Some(token);
+            acc.token_expired_at = Some(expired_at);
+        }
+        let now = chrono::Utc::now().timestamp();
+        acc.profile_refreshed_at = Some(now);
+        acc.last_error = None;
+        acc.updated_at = now;
+        let updated = acc.clone();
+
+        self.save_store()?;
+        println!("[INFO] 已刷新账号资料: {}", updated.email);
+        Ok(updated)
+    }
+
+    /// 批量操作账号，网络请求以有限并发执行，返回每个账号的结果
+    pub async fn batch_account_action(&mut self, action: BatchAction, account_ids: &[String]) -> Vec<BatchActionResult> {
+        let mut results = Vec::with_capacity(account_ids.len());
+        match action {
+            BatchAction::BindMachineId => {
+                for account_id in account_ids {
+                    results.push(BatchActionResult::new(account_id, &self.bind_machine_id(account_id)));
+                }
+            }
+            BatchAction::Remove => {
+                for account_id in account_ids {
+                    results.push(BatchActionResult::new(account_id, &self.remove_account(account_id)));
+                }
+            }
+            BatchAction::Refresh | BatchAction::ClaimGift => {
+                let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(BATCH_CONCURRENCY));
+                let mut tasks = Vec::new();
+                for account_id in account_ids {
+                    let account = match self.get_account(account_id) {
+                        Ok(account) => account,
+                        Err(e) => {
+                            results.push(BatchActionResult::new::<()>(account_id, &Err(e)));
+                            continue;
+                        }
+                    };
+                    let semaphore = semaphore.clone();
+                    let log_id = account_id.clone();
+                    tasks.push((account_id.clone(), tokio::spawn(async move {
+                        let _permit = semaphore.acquire().await?;
+                        request_log::scope(&log_id, async move {
+                            if action == BatchAction::Refresh {
+                                fetch_profile_update(account).await.map(Some)
+                            } else {
+                                claim_gift_for_account(account).await.map(|_| None)
+                            }
+                        })
+                        .await
+                    })));
+                }
+
+                for (account_id, task) in tasks {
+                    let result = match task.await {
+                        Ok(Ok(Some(update))) => self.apply_profile_update(&account_id, update).map(|_| ()),
+                        Ok(Ok(None)) => Ok(()),
+                        Ok(Err(e)) => Err(e),
+                        Err(e) => Err(anyhow!("任务执行失败: {}", e)),
+                    };
+                    if action == BatchAction::Refresh {
+                        self.track_result(&account_id, &result);
+                    }
+                    results.push(BatchActionResult::new(&account_id, &result));
+                }
+            }
+        }
+
+        let failed = results.iter().filter(|r| !r.success).count();
+        println!("[INFO] 批量操作 {:?} 完成: 成功 {} 个, 失败 {} 个", action, results.len() - failed, failed);
+        results
+    }
+
+    /// 获取资料超过指定时长未刷新的账号 ID（未验证账号由校验流程处理）
+    pub fn accounts_with_stale_profile(&self, max_age_secs: i64) -> Vec<String> {
+        let now = chrono::Utc::now().timestamp();
+        self.store.accounts.iter()
+            .filter(|a| !a.unverified)
+            .filter(|a| !matches!(a.profile_refreshed_at, Some(t) if now - t < max_age_secs))
+            .map(|a| a.id.clone())
+            .collect()
+    }
+
+    /// 本地检查所有账号的 Token 过期时间和 Cookies 更新时间（不发起网络请求），返回需要处理的账号
+    pub fn token_sweep(&self, now: i64, expiring_within_secs: i64, cookie_max_age_secs: i64) -> TokenSweepSummary {
+        let mut summary = TokenSweepSummary::default();
+        for account in self.store.accounts.iter().filter(|a| !a.unverified) {
+            summary.checked += 1;
+            let expires_at = account.token_expires_at();
+            let mut reasons = Vec::new();
+            match (&account.jwt_token, expires_at) {
+                (None, _) => reasons.push(AttentionReason::NoToken),
+                (Some(_), Some(exp)) if exp <= now => reasons.push(AttentionReason::TokenExpired),
+                (Some(_), Some(exp)) if expiring_within_secs > 0 && exp - now <= expiring_within_secs => {
+                    reasons.push(AttentionReason::TokenExpiring)
+                }
+                _ => {}
+            }
+            let cookies_updated_at = account.cookies_updated_at.unwrap_or(account.created_at);
+            if !account.cookies.trim().is_empty() && now - cookies_updated_at > cookie_max_age_secs {
+                reasons.push(AttentionReason::CookiesStale);
+            }
+            if !reasons.is_empty() {
+                summary.accounts.push(AccountAttention {
+                    account_id: account.id.clone(),
+                    email: account.email.clone(),
+                    reasons,
+                    token_expires_at: expires_at,
+                    can_renew: account.can_renew_token(),
+                });
+            }
+        }
+        summary
+    }
+
+    /// 按过期时间更新账号的 token_expiring 标记，返回新进入过期窗口的账号
+    pub fn mark_expiring_tokens(&mut self, within_secs: i64) -> Vec<TokenExpiringEvent> {
+        let now = chrono::Utc::now().timestamp();
+        let mut newly_expiring = Vec::new();
+        let mut changed = false;
+        for account in self.store.accounts.iter_mut().filter(|a| !a.unverified) {
+            let expires_at = account.token_expires_at();
+            let expiring = expires_at.is_some_and(|exp| exp - now <= within_secs);
+            if expiring == account.token_expiring {
+                continue;
+            }
+            account.token_expiring = expiring;
+            changed = true;
+            if let (true, Some(expires_at)) = (expiring, expires_at) {
+                newly_expiring.push(TokenExpiringEvent {
+                    account_id: account.id.clone(),
+                    email: account.email.clone(),
+                    expires_at,
+                });
+            }
+        }
+        if changed {
+            if let Err(e) = self.save_store() {
+                println!("[WARN] 保存 Token 过期标记失败: {}", e);
+            }
+        }
+        newly_expiring
+    }
+
+    /// 获取 Token 将在指定时长内过期且可以用 Cookies 刷新的账号 ID
+    pub fn accounts_with_expiring_token(&self, within_secs: i64) -> Vec<String> {
+        let now = chrono::Utc::now().timestamp();
+        self.store.accounts.iter()
+            .filter(|a| !a.unverified && !a.cookies.trim().is_empty())
+            .filter(|a| a.token_expires_at().map_or(a.jwt_token.is_none(), |exp| exp - now <= within_secs))
+            .map(|a| a.id.clone())
+            .collect()
+    }
+
+    /// 依次刷新指定账号的资料
+    pub async fn refresh_account_profiles(&mut self, account_ids: &[String]) -> ProfileRefreshReport {
+        let mut report = ProfileRefreshReport::default();
+        for account_id in account_ids {
+            match self.refresh_account_profile(account_id).await {
+                Ok(_) => report.refreshed += 1,
+                Err(e) => report.failed.push(ValidationFailure {
+                    account_id: account_id.clone(),
+                    email: self.store.accounts.iter()
+                        .find(|a| &a.id == account_id)
+                        .map(|a| a.email.clone())
+                        .unwrap_or_default(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+        report
+    }
+
     /// 获取使用事件
     pub async fn get_usage_events(
         &mut self,
@@ -962,36 +2227,33 @@ impl AccountManager {
             .accounts
             .iter()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?
             .clone();
+        let api_base = account.api_base_override();
 
         // 根据账号类型选择不同的方式调用 API
         if let Some(token) = &account.jwt_token {
             // 优先使用 Token
-            let client = TraeApiClient::new_with_token(token)?;
+            let client = TraeApiClient::new_with_token(token)?.with_api_base(api_base.as_deref());
             match client.query_usage(start_time, end_time, page_size, page_num).await {
                 Ok(response) => Ok(response),
                 Err(e) => {
-                    let error_msg = e.to_string();
-                    // 如果是 401 错误且有 Cookies，尝试刷新 Token
-                    if error_msg.contains("401") && !account.cookies.is_empty() {
-                        println!("[INFO] Token 已过期，尝试使用 Cookies 刷新...");
-                        // 使用 Cookies 刷新 Token
-                        let mut cookie_client = TraeApiClient::new(&account.cookies)?;
-                        let token_result = cookie_client.get_user_token().await?;
+                    // 如果是 401 错误且有刷新 Token 或 Cookies，尝试刷新 Token
+                    if ErrorCode::of(&e) == ErrorCode::AuthExpired && account.can_renew_token() {
+                        println!("[INFO] Token 已过期，尝试刷新...");
+                        let token_result = renew_token(&account).await?;
 
                         // 更新存储的 Token
                         if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
-                            acc.jwt_token = Some(token_result.token.clone());
-                            acc.token_expired_at = Some(token_result.expired_at.clone());
+                            acc.set_token(token_result.token.clone(), Some(token_result.expired_at.clone()), token_result.refresh_token.clone());
                         }
                         self.save_store()?;
 
                         // 使用新 Token 重新查询
-                        let new_client = TraeApiClient::new_with_token(&token_result.token)?;
+                        let new_client = TraeApiClient::new_with_token(&token_result.token)?.with_api_base(api_base.as_deref());
                         new_client.query_usage(start_time, end_time, page_size, page_num).await
-                    } else if error_msg.contains("401") {
-                        Err(anyhow!("Token 已过期，请更新 Token 或 Cookies"))
+                    } else if ErrorCode::of(&e) == ErrorCode::AuthExpired {
+                        Err(coded(ErrorCode::AuthExpired, "Token 已过期，请更新 Token 或 Cookies"))
                     } else {
                         Err(e)
                     }
@@ -999,7 +2261,7 @@ impl AccountManager {
             }
         } else if !account.cookies.is_empty() {
             // 使用 Cookies
-            let mut client = TraeApiClient::new(&account.cookies)?;
+            let mut client = TraeApiClient::new(&account.cookies)?.with_api_base(api_base.as_deref());
             // 先获取 token
             client.get_user_token().await?;
             client.query_usage(start_time, end_time, page_size, page_num).await
@@ -1008,30 +2270,50 @@ impl AccountManager {
         }
     }
 
+    /// 分页拉取时间范围内的全部使用事件并生成 CSV，返回 (CSV 内容, 事件数)
+    pub async fn export_usage_events_csv(&mut self, account_id: &str, start_time: i64, end_time: i64) -> Result<(String, usize)> {
+        let mut csv = String::from(
+            "time,session_id,mode,model_name,amount,cost,max_mode,input_token,output_token,cache_read_token,cache_write_token\n",
+        );
+        let mut count = 0usize;
+        for page_num in 1..=USAGE_EXPORT_MAX_PAGES {
+            let page = self
+                .get_usage_events(account_id, start_time, end_time, page_num, USAGE_EXPORT_PAGE_SIZE)
+                .await?;
+            let fetched = page.user_usage_group_by_sessions.len();
+            for event in &page.user_usage_group_by_sessions {
+                let time = chrono::DateTime::from_timestamp(event.usage_time, 0)
+                    .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{},{},{},{},{}",
+                    time,
+                    csv_field(&event.session_id),
+                    csv_field(&event.mode),
+                    csv_field(&event.model_name),
+                    event.amount_float,
+                    event.cost_money_float,
+                    event.use_max_mode,
+                    event.extra_info.input_token,
+                    event.extra_info.output_token,
+                    event.extra_info.cache_read_token,
+                    event.extra_info.cache_write_token
+                );
+            }
+            count += fetched;
+            let total = usize::try_from(page.total).unwrap_or(0);
+            if fetched == 0 || count >= total {
+                break;
+            }
+        }
+        Ok((csv, count))
+    }
+
     /// 从 Trae IDE 读取当前登录账号
     pub async fn read_trae_ide_account(&mut self) -> Result<Option<Account>> {
-        // 获取 Trae IDE 配置文件路径（跨平台支持）
-        #[cfg(target_os = "windows")]
-        let trae_data_path = {
-            let appdata = std::env::var("APPDATA")
-                .map_err(|_| anyhow!("无法获取 APPDATA 环境变量"))?;
-            PathBuf::from(appdata).join("Trae")
-        };
-        
-        #[cfg(target_os = "macos")]
-        let trae_data_path = {
-            let home = std::env::var("HOME")
-                .map_err(|_| anyhow!("无法获取 HOME 环境变量"))?;
-            PathBuf::from(home)
-                .join("Library")
-                .join("Application Support")
-                .join("Trae")
-        };
-        
-        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
-        let trae_data_path: PathBuf = {
-            return Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"));
-        };
+        crate::machine::ensure_trae_installed()?;
+        let trae_data_path = crate::machine::get_trae_data_path()?;
 
         let storage_path = trae_data_path
             .join("User")
@@ -1128,6 +2410,7 @@ impl AccountManager {
             avatar_url
         };
         account.jwt_token = Some(token);
+        account.registration = AccountRegistration::new(AccountSource::TraeIde);
 
         // 添加到账号列表
         self.store.accounts.push(account.clone());
@@ -1145,33 +2428,86 @@ impl AccountManager {
 
     /// 领取生日礼包
     pub async fn claim_birthday_bonus(&mut self, account_id: &str) -> Result<()> {
-        let account = self.store.accounts.iter()
-            .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?;
-
-        let token = account.jwt_token.as_ref()
-            .ok_or_else(|| anyhow!("账号没有 Token"))?;
+        let account = self.get_account(account_id)?;
+        request_log::scope(account_id, claim_gift_for_account(account)).await
+    }
 
-        let client = TraeApiClient::new_with_token(token)?;
+    /// 为所有账号领取礼包（有限并发），已领取过的账号跳过，返回每个账号的结果
+    pub async fn claim_gift_all(&self) -> GiftClaimReport {
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(BATCH_CONCURRENCY));
+        let tasks: Vec<_> = self.store.accounts.iter()
+            .cloned()
+            .map(|account| {
+                let semaphore = semaphore.clone();
+                let (account_id, email) = (account.id.clone(), account.email.clone());
+                let task = tokio::spawn(async move {
+                    let _permit = semaphore.acquire().await?;
+                    let log_id = account.id.clone();
+                    request_log::scope(&log_id, try_claim_gift(account)).await
+                });
+                (account_id, email, task)
+            })
+            .collect();
 
-        // 先查询是否已领取
-        let claimed = client.query_birthday_bonus().await?;
-        if claimed {
-            return Err(anyhow!("该账号已领取过礼包"));
+        let mut report = GiftClaimReport::default();
+        for (account_id, email, task) in tasks {
+            let result = task.await.unwrap_or_else(|e| Err(anyhow!("任务执行失败: {}", e)));
+            let (status, error) = match result {
+                Ok(true) => {
+                    report.claimed += 1;
+                    (GiftClaimStatus::Claimed, None)
+                }
+                Ok(false) => {
+                    report.skipped += 1;
+                    (GiftClaimStatus::AlreadyClaimed, None)
+                }
+                Err(e) => {
+                    report.failed += 1;
+                    (GiftClaimStatus::Failed, Some(e.to_string()))
+                }
+            };
+            report.results.push(GiftClaimResult { account_id, email, status, error });
         }
 
-        // 领取礼包
-        client.claim_birthday_bonus().await?;
+        println!(
+            "[INFO] 一键领取礼包完成: 领取 {} 个, 已领取跳过 {} 个, 失败 {} 个",
+            report.claimed, report.skipped, report.failed
+        );
+        report
+    }
 
-        println!("[INFO] 成功领取礼包: {}", account.email);
-        Ok(())
+    /// 列出账号当前可领取的活动礼包
+    pub async fn list_promotions(&self, account_id: &str) -> Result<Vec<crate::api::Promotion>> {
+        let account = self.get_account(account_id)?;
+        let token = account.jwt_token.as_ref()
+            .ok_or_else(|| anyhow!("账号没有 Token"))?;
+        let client = TraeApiClient::new_with_token(token)?.with_api_base(account.api_base_override().as_deref());
+        request_log::scope(account_id, client.list_promotions()).await
+    }
+
+    /// 领取指定活动礼包
+    pub async fn claim_promotion(&self, account_id: &str, promo_id: &str) -> Result<()> {
+        if promo_id != crate::api::trae_api::BIRTHDAY_PROMOTION_ID {
+            return Err(coded(ErrorCode::InvalidInput, format!("不支持领取该活动礼包: {}", promo_id)));
+        }
+        let account = self.get_account(account_id)?;
+        request_log::scope(account_id, claim_gift_for_account(account)).await
     }
 
     /// 获取账号统计数据
-    pub async fn get_account_statistics(&self, account_id: &str) -> Result<crate::api::UserStatisticResult> {
+    ///
+    /// 缓存未过期时直接返回（force 为 true 时总是请求接口），查询成功后保存当天的统计采样
+    pub async fn get_account_statistics(&mut self, account_id: &str, force: bool) -> Result<crate::api::UserStatisticResult> {
+        let now = chrono::Utc::now().timestamp();
         let account = self.store.accounts.iter()
             .find(|a| a.id == account_id)
-            .ok_or_else(|| anyhow!("账号不存在"))?;
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+        if !force && account.statistics_checked_at.is_some_and(|at| now - at < STATISTICS_CACHE_TTL) {
+            if let Some(cached) = account.statistics_cache.clone() {
+                return Ok(cached);
+            }
+        }
+        let api_base = account.api_base_override();
 
         let token = account.jwt_token.as_ref()
             .ok_or_else(|| anyhow!("账号没有有效的 Token"))?;
@@ -1181,28 +2517,453 @@ impl AccountManager {
         } else {
             TraeApiClient::new_with_token_and_cookies(token, &account.cookies)?
         };
-        client.get_user_statistic_data().await
+        let client = client.with_api_base(api_base.as_deref());
+        let stats = request_log::scope(account_id, client.get_user_statistic_data()).await?;
+
+        if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
+            acc.statistics_cache = Some(stats.clone());
+            acc.statistics_checked_at = Some(now);
+        }
+        self.save_store()?;
+        if let Err(e) = crate::usage_history::record_statistic_sample(account_id, &stats, now) {
+            println!("[WARN] 保存统计数据采样失败: {}", e);
+        }
+        Ok(stats)
+    }
+
+    /// 查询账号订阅信息，并保存套餐到期时间和自动续费状态
+    pub async fn get_subscription_info(&mut self, account_id: &str) -> Result<crate::api::SubscriptionInfo> {
+        let account = self.get_account(account_id)?;
+        let token = account.jwt_token.as_ref()
+            .ok_or_else(|| anyhow!("账号没有有效的 Token"))?;
+        let client = TraeApiClient::new_with_token(token)?.with_api_base(account.api_base_override().as_deref());
+        let info = request_log::scope(account_id, client.get_subscription_info()).await?;
+
+        if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
+            acc.plan_type = info.plan_type.clone();
+            acc.plan_expires_at = info.expires_at;
+            acc.plan_auto_renew = info.auto_renew;
+            acc.updated_at = chrono::Utc::now().timestamp();
+        }
+        self.save_store()?;
+        Ok(info)
+    }
+
+    /// 需要自动刷新使用量的账号（按活跃程度决定间隔），最久未检查的排在前面
+    pub fn accounts_due_for_usage_poll(&self, now: i64) -> Vec<Account> {
+        let current = self.store.current_account_id.as_deref();
+        // 失败的查询也算一次尝试，避免出错账号每轮都被重试
+        let last_attempt = |a: &Account| a.last_usage_check_at.max(a.last_error.as_ref().map(|e| e.occurred_at));
+        let mut due: Vec<&Account> = self.store.accounts.iter()
+            .filter(|a| !a.unverified)
+            .filter(|a| {
+                let interval = usage_poll_interval(a, current == Some(a.id.as_str()), now);
+                last_attempt(a).is_none_or(|at| now - at >= interval)
+            })
+            .collect();
+        due.sort_by_key(|a| last_attempt(a).unwrap_or(0));
+        due.into_iter().cloned().collect()
+    }
+
+    /// 在有效期内的使用量缓存（ttl_secs 为 0 时不使用缓存）
+    pub fn cached_usage(&self, account_id: &str, ttl_secs: u64) -> Option<UsageSummary> {
+        let ttl = i64::try_from(ttl_secs).ok().filter(|ttl| *ttl > 0)?;
+        let now = chrono::Utc::now().timestamp();
+        self.store.accounts.iter()
+            .find(|a| a.id == account_id)
+            .filter(|a| a.last_usage_check_at.is_some_and(|at| now - at < ttl))
+            .and_then(|a| a.usage_cache.clone())
+    }
+
+    /// 当前账号额度用完时的下一个可用账号：从当前账号之后依次查找，
+    /// 优先选择已知还有剩余额度的账号，其次是尚未查询过使用量的账号
+    pub fn next_healthy_account(&self, now: i64) -> Option<Account> {
+        let current = self.store.current_account_id.as_deref();
+        let start = current
+            .and_then(|id| self.store.accounts.iter().position(|a| a.id == id))
+            .map_or(0, |index| index + 1);
+        let len = self.store.accounts.len();
+        let candidates: Vec<&Account> = (0..len)
+            .map(|offset| &self.store.accounts[(start + offset) % len])
+            .filter(|a| Some(a.id.as_str()) != current)
+            .filter(|a| is_healthy(a, now))
+            .collect();
+
+        candidates.iter()
+            .find(|a| fast_requests_left(a).is_some_and(|left| left > 0.0))
+            .or_else(|| candidates.iter().find(|a| fast_requests_left(a).is_none()))
+            .map(|a| (*a).clone())
+    }
+
+    /// 可用且额度未用完的账号数（尚未查询过使用量的账号也计入）
+    pub fn available_account_count(&self, now: i64) -> usize {
+        self.store.accounts.iter()
+            .filter(|a| is_healthy(a, now))
+            .filter(|a| fast_requests_left(a).is_none_or(|left| left > 0.0))
+            .count()
+    }
+
+    /// 免费额度已用完的账号（已查询过使用量且剩余快速请求为 0），不包括 Trae IDE 当前使用的账号和已归档的账号
+    pub fn exhausted_free_accounts(&self) -> Vec<Account> {
+        let current = self.store.current_account_id.as_deref();
+        self.store.accounts.iter()
+            .filter(|a| Some(a.id.as_str()) != current)
+            .filter(|a| a.archived_at.is_none())
+            .filter(|a| a.plan_type.trim().is_empty() || a.plan_type.eq_ignore_ascii_case("free"))
+            .filter(|a| fast_requests_left(a).is_some_and(|left| left <= 0.0))
+            .cloned()
+            .collect()
+    }
+
+    /// 归档账号：保留账号数据，但不再参与自动切换和可用账号统计
+    pub fn archive_account(&mut self, account_id: &str, now: i64) -> Result<()> {
+        let account = self.store.accounts.iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| coded(ErrorCode::NotFound, "账号不存在"))?;
+        account.archived_at = Some(now);
+        account.updated_at = now;
+        self.save_store()
     }
 
     pub fn update_account_info_after_usage_check(
         &mut self,
         account_id: &str,
-        plan_type: String,
+        summary: &UsageSummary,
         new_token: Option<(String, String)>, // (token, expired_at)
     ) -> Result<()> {
         if let Some(acc) = self.store.accounts.iter_mut().find(|a| a.id == account_id) {
-            acc.plan_type = plan_type;
+            acc.record_usage(summary, chrono::Utc::now().timestamp());
             if let Some((token, expired_at)) = new_token {
                 acc.jwt_token = Some(token);
                 acc.token_expired_at = Some(expired_at);
             }
-            acc.updated_at = chrono::Utc::now().timestamp();
+            acc.last_error = None;
             self.save_store()?;
+            record_usage_sample(account_id, summary);
         }
         Ok(())
     }
 }
 
+/// 账号已验证、Token 未过期，且最近一次查询使用量没有失败
+fn is_healthy(account: &Account, now: i64) -> bool {
+    !account.unverified
+        && account.archived_at.is_none()
+        && account.jwt_token.is_some()
+        && account.token_expires_at().is_none_or(|exp| exp > now)
+        && account.last_error.as_ref().is_none_or(|e| {
+            account.last_usage_check_at.is_some_and(|at| at > e.occurred_at)
+        })
+}
+
+/// 缓存的剩余快速请求数，未查询过使用量时为 None
+fn fast_requests_left(account: &Account) -> Option<f64> {
+    account.usage_cache.as_ref().map(|u| u.fast_request_left + u.extra_fast_request_left)
+}
+
+/// 保存使用量历史采样（失败不影响查询结果）
+fn record_usage_sample(account_id: &str, summary: &UsageSummary) {
+    if let Err(e) = crate::usage_history::record_sample(account_id, summary, chrono::Utc::now().timestamp()) {
+        println!("[WARN] 保存使用量历史失败: {}", e);
+    }
+}
+
+/// 导出使用事件时每页条数
+const USAGE_EXPORT_PAGE_SIZE: i32 = 100;
+/// 导出使用事件的最大页数（防止接口返回异常 total 时无限翻页）
+const USAGE_EXPORT_MAX_PAGES: i32 = 500;
+
+/// CSV 字段转义：包含逗号、引号或换行时加引号
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 当前账号的使用量刷新间隔
+const POLL_INTERVAL_CURRENT: i64 = 5 * 60;
+/// 最近一天内活跃账号的刷新间隔
+const POLL_INTERVAL_ACTIVE: i64 = 15 * 60;
+/// 闲置账号的刷新间隔
+const POLL_INTERVAL_IDLE: i64 = 3600;
+const ACTIVE_WINDOW: i64 = 24 * 3600;
+
+/// 按账号活跃程度决定自动刷新使用量的间隔（秒）
+fn usage_poll_interval(account: &Account, is_current: bool, now: i64) -> i64 {
+    if is_current {
+        POLL_INTERVAL_CURRENT
+    } else if account.last_active_at.is_some_and(|at| now - at < ACTIVE_WINDOW) {
+        POLL_INTERVAL_ACTIVE
+    } else {
+        POLL_INTERVAL_IDLE
+    }
+}
+
+/// 从 API 获取的账号资料
+struct ProfileUpdate {
+    new_token: Option<(String, String)>,
+    name: Option<String>,
+    avatar_url: Option<String>,
+    email: Option<String>,
+    region: Option<String>,
+    plan_type: Option<String>,
+}
+
+/// 从 API 获取账号最新资料（不修改存储，可在多个账号间并发执行）
+async fn fetch_profile_update(account: Account) -> Result<ProfileUpdate> {
+    let api_base = account.api_base_override();
+
+    let (token, new_token, name, avatar_url, email, region) = if !account.cookies.trim().is_empty() {
+        // Cookies 可以拿到完整资料（含区域），同时顺带刷新 Token
+        let mut client = TraeApiClient::new(&account.cookies)?.with_api_base(api_base.as_deref());
+        let token_result = client.get_user_token().await?;
+        if !account.user_id.is_empty() && token_result.user_id != account.user_id {
+            return Err(anyhow!("Cookies 对应的用户与当前账号不匹配"));
+        }
+        let user_info = client.get_user_info().await?;
+        (
+            token_result.token.clone(),
+            Some((token_result.token, token_result.expired_at)),
+            Some(user_info.screen_name),
+            Some(user_info.avatar_url),
+            user_info.non_plain_text_email,
+            Some(user_info.region),
+        )
+    } else if let Some(token) = account.jwt_token.clone() {
+        let client = TraeApiClient::new_with_token(&token)?.with_api_base(api_base.as_deref());
+        let user_info = client.get_user_info_by_token().await?;
+        if !account.user_id.is_empty() && user_info.user_id != account.user_id {
+            return Err(anyhow!("Token 对应的用户与当前账号不匹配"));
+        }
+        (token, None, user_info.screen_name, user_info.avatar_url, user_info.email, user_info.region)
+    } else {
+        return Err(anyhow!("账号没有有效的 Token 或 Cookies"));
+    };
+
+    // 套餐信息查询失败不影响资料刷新
+    let plan_type = match TraeApiClient::new_with_token(&token)?
+        .with_api_base(api_base.as_deref())
+        .get_usage_summary_by_token()
+        .await
+    {
+        Ok(summary) => Some(summary.plan_type),
+        Err(e) => {
+            println!("[WARN] 刷新套餐信息失败 ({}): {}", account.email, e);
+            None
+        }
+    };
+
+    Ok(ProfileUpdate { new_token, name, avatar_url, email, region, plan_type })
+}
+
+/// 领取账号礼包（不修改存储）
+async fn claim_gift_for_account(account: Account) -> Result<()> {
+    if !try_claim_gift(account).await? {
+        return Err(anyhow!("该账号已领取过礼包"));
+    }
+    Ok(())
+}
+
+/// 领取账号礼包，已领取过时返回 false（不修改存储）
+async fn try_claim_gift(account: Account) -> Result<bool> {
+    let api_base = account.api_base_override();
+    let token = account.jwt_token.as_ref()
+        .ok_or_else(|| anyhow!("账号没有 Token"))?;
+
+    let client = TraeApiClient::new_with_token(token)?.with_api_base(api_base.as_deref());
+
+    // 先查询是否已领取
+    if client.query_birthday_bonus().await? {
+        return Ok(false);
+    }
+
+    // 领取礼包
+    client.claim_birthday_bonus().await?;
+
+    println!("[INFO] 成功领取礼包: {}", account.email);
+    Ok(true)
+}
+
+async fn fetch_account_info_by_token(token: String, password: Option<String>) -> Result<Account> {
+    let client = TraeApiClient::new_with_token(&token)?;
+    let user_info = client.get_user_info_by_token().await?;
+
+    let mut account = Account::new(
+        user_info.screen_name.unwrap_or_else(|| format!("User_{}", &user_info.user_id[..8.min(user_info.user_id.len())])),
+        user_info.email.unwrap_or_default(),
+        String::new(),
+        user_info.user_id,
+        user_info.tenant_id,
+    );
+    account.avatar_url = user_info.avatar_url.unwrap_or_default();
+    account.jwt_token = Some(token);
+    account.password = password;
+
+    Ok(account)
+}
+
+/// 统一工作区路径用于比较（Windows 下不区分大小写）
+fn normalize_workspace_path(path: &std::path::Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        PathBuf::from(path.to_string_lossy().to_lowercase().replace('/', "\\"))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// 将导入记录中的字段写入账号（只覆盖非空字段，标签取并集，切换统计取较大值）
+fn apply_import_record(account: &mut Account, record: &serde_json::Value) {
+    let text = |key: &str| {
+        record.get(key)
+            .and_then(|v| v.as_str())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    for (key, field) in [
+        ("name", &mut account.name),
+        ("email", &mut account.email),
+        ("avatar_url", &mut account.avatar_url),
+        ("cookies", &mut account.cookies),
+        ("user_id", &mut account.user_id),
+        ("tenant_id", &mut account.tenant_id),
+        ("region", &mut account.region),
+        ("plan_type", &mut account.plan_type),
+    ] {
+        if let Some(value) = text(key) {
+            *field = value;
+        }
+    }
+    for (key, field) in [
+        ("jwt_token", &mut account.jwt_token),
+        ("token_expired_at", &mut account.token_expired_at),
+        ("refresh_token", &mut account.refresh_token),
+        ("machine_id", &mut account.machine_id),
+        ("region_override", &mut account.region_override),
+        ("api_host", &mut account.api_host),
+        ("notes", &mut account.notes),
+        ("locale", &mut account.locale),
+        ("alias", &mut account.alias),
+        ("totp_secret", &mut account.totp_secret),
+    ] {
+        if let Some(value) = text(key) {
+            *field = Some(value);
+        }
+    }
+    // 密码不做 trim，避免改变首尾包含空格的密码
+    if let Some(password) = record.get("password").and_then(|v| v.as_str()).filter(|v| !v.is_empty()) {
+        account.password = Some(password.to_string());
+    }
+
+    if let Some(tags) = record.get("tags").and_then(|v| v.as_array()) {
+        for tag in tags.iter().filter_map(|t| t.as_str()).map(str::trim).filter(|t| !t.is_empty()) {
+            if !account.tags.iter().any(|t| t == tag) {
+                account.tags.push(tag.to_string());
+            }
+        }
+    }
+    if let Some(count) = record.get("switch_count").and_then(|v| v.as_u64()) {
+        account.switch_count = account.switch_count.max(u32::try_from(count).unwrap_or(u32::MAX));
+    }
+    if let Some(ts) = record.get("last_switched_at").and_then(|v| v.as_i64()) {
+        account.last_switched_at = account.last_switched_at.max(Some(ts));
+    }
+    if let Some(ts) = record.get("last_active_at").and_then(|v| v.as_i64()) {
+        account.last_active_at = account.last_active_at.max(Some(ts));
+    }
+    if let Some(ts) = record.get("created_at").and_then(|v| v.as_i64()).filter(|ts| *ts > 0) {
+        account.created_at = account.created_at.min(ts);
+    }
+    // 来源信息：保留已有记录，缺失时采用导出文件中的记录
+    if account.registration.source == AccountSource::Unknown {
+        if let Some(registration) = record.get("registration")
+            .and_then(|v| serde_json::from_value::<AccountRegistration>(v.clone()).ok())
+        {
+            account.registration = registration;
+        }
+    }
+    account.updated_at = chrono::Utc::now().timestamp();
+}
+
+/// 解析导入数据：JSON 数组，或每行一个 Token（也可以是 `token,cookies`）的纯文本
+fn parse_import_data(data: &str) -> Result<Vec<serde_json::Value>> {
+    let trimmed = data.trim_start_matches('\u{feff}').trim();
+    if trimmed.starts_with('[') {
+        return serde_json::from_str(trimmed).map_err(|e| anyhow!("JSON 解析失败: {}", e));
+    }
+
+    let mut items = Vec::new();
+    for (line_no, line) in trimmed.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // JWT 中不含逗号，第一个逗号之后的内容都是 Cookies
+        let (token, cookies) = match line.split_once(',') {
+            Some((token, cookies)) => (token.trim(), cookies.trim()),
+            None => (line, ""),
+        };
+        if token.split('.').count() != 3 {
+            return Err(anyhow!("第 {} 行不是有效的 Token", line_no + 1));
+        }
+        items.push(serde_json::json!({
+            "jwt_token": token,
+            "cookies": cookies,
+        }));
+    }
+    if items.is_empty() {
+        return Err(anyhow!("导入数据为空"));
+    }
+    Ok(items)
+}
+
+/// 按导入数据中的凭据获取账号信息：优先使用 Cookies，失败时回退到 Token
+async fn fetch_import_account(cookies: String, jwt_token: Option<String>, password: Option<String>) -> Result<Account> {
+    if cookies.is_empty() {
+        let token = jwt_token.ok_or_else(|| anyhow!("缺少 Cookies 或 Token"))?;
+        return fetch_account_info_by_token(token, password).await;
+    }
+    match fetch_account_info_internal(cookies.clone(), password.clone()).await {
+        Ok(account) => Ok(account),
+        Err(e) => match jwt_token {
+            Some(token) => {
+                println!("[WARN] Cookies 校验失败，尝试使用 Token: {}", e);
+                let mut account = fetch_account_info_by_token(token, password).await?;
+                account.set_cookies(cookies);
+                Ok(account)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// 生成 [0, max] 范围内的随机抖动（毫秒）
+fn random_jitter_ms(max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+    (Uuid::new_v4().as_u128() % (max as u128 + 1)) as u64
+}
+
+/// 换取新 Token：优先使用刷新 Token，没有或失败时使用 Cookies
+async fn renew_token(account: &Account) -> Result<crate::api::UserTokenResult> {
+    let api_base = account.api_base_override();
+    let mut client = TraeApiClient::new(&account.cookies)?.with_api_base(api_base.as_deref());
+    if let Some(refresh_token) = account.refresh_token.as_deref().filter(|t| !t.trim().is_empty()) {
+        match client.refresh_via_refresh_token(refresh_token, &account.user_id).await {
+            Ok(result) => return Ok(result),
+            Err(e) if account.cookies.trim().is_empty() => return Err(e),
+            Err(e) => println!("[WARN] 使用刷新 Token 换取 Token 失败，改用 Cookies: {}", e),
+        }
+    }
+    if account.cookies.trim().is_empty() {
+        return Err(anyhow!("账号没有 Cookies 或刷新 Token，无法刷新 Token"));
+    }
+    client.get_user_token().await
+}
+
 async fn fetch_account_info_internal(cookies: String, password: Option<String>) -> Result<Account> {
     let mut client = TraeApiClient::new(&cookies)?;
     let token_result = client.get_user_token().await?;
@@ -1217,8 +2978,7 @@ async fn fetch_account_info_internal(cookies: String, password: Option<String>)
     );
     account.avatar_url = user_info.avatar_url;
     account.region = user_info.region;
-    account.jwt_token = Some(token_result.token);
-    account.token_expired_at = Some(token_result.expired_at);
+    account.set_token(token_result.token, Some(token_result.expired_at), token_result.refresh_token);
     account.password = password;
     
     Ok(account)
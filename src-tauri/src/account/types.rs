@@ -23,6 +23,77 @@ pub struct Account {
     /// 账号关联的机器码
     #[serde(default)]
     pub machine_id: Option<String>,
+    /// 最近一次切换到该账号的时间
+    #[serde(default)]
+    pub last_switched_at: Option<i64>,
+    /// 累计切换次数
+    #[serde(default)]
+    pub switch_count: u32,
+    /// 最近一次查询使用量的时间
+    #[serde(default)]
+    pub last_usage_check_at: Option<i64>,
+    /// 是否尚未通过 API 校验（延迟验证导入的账号）
+    #[serde(default)]
+    pub unverified: bool,
+    /// 手动指定的区域（US / SG / CN），优先于 API 返回的 region
+    #[serde(default)]
+    pub region_override: Option<String>,
+    /// 手动指定的 API 地址，优先于区域对应的默认端点
+    #[serde(default)]
+    pub api_host: Option<String>,
+    /// 最近一次刷新用户资料（名称/头像/套餐）的时间
+    #[serde(default)]
+    pub profile_refreshed_at: Option<i64>,
+    /// 最近一次后台操作失败的错误（下次成功后清除）
+    #[serde(default)]
+    pub last_error: Option<AccountError>,
+    /// 用户自定义标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 用户备注
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// 切换到该账号时写入 Trae 的界面语言（如 zh-cn / en），为空时不修改
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Token 即将过期（由后台检查标记，重新登录后清除）
+    #[serde(default)]
+    pub token_expiring: bool,
+    /// 本地显示名称（优先于服务器返回的名称，不会同步到服务器）
+    #[serde(default)]
+    pub alias: Option<String>,
+    /// 最近一次查询到的使用量（查询时间为 last_usage_check_at），在有效期内直接返回
+    #[serde(default)]
+    pub usage_cache: Option<crate::api::UsageSummary>,
+    /// 最近一次活跃时间（切换到该账号或使用量增加时更新），用于调整自动刷新频率
+    #[serde(default)]
+    pub last_active_at: Option<i64>,
+    /// 付费套餐到期时间（查询订阅信息时更新）
+    #[serde(default)]
+    pub plan_expires_at: Option<i64>,
+    /// 付费套餐是否自动续费
+    #[serde(default)]
+    pub plan_auto_renew: bool,
+    /// 刷新 Token（登录时捕获），JWT 过期后用于换取新 Token，并写入 Trae IDE
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// 最近一次查询到的统计数据（查询时间为 statistics_checked_at），在有效期内直接返回
+    #[serde(default)]
+    pub statistics_cache: Option<crate::api::UserStatisticResult>,
+    #[serde(default)]
+    pub statistics_checked_at: Option<i64>,
+    /// Cookies 最近一次更新时间，旧数据为 None（按创建时间计算）
+    #[serde(default)]
+    pub cookies_updated_at: Option<i64>,
+    /// 账号的添加方式和注册信息
+    #[serde(default)]
+    pub registration: AccountRegistration,
+    /// 免费额度用完后被自动归档的时间，归档的账号不参与自动切换；额度恢复后自动取消归档
+    #[serde(default)]
+    pub archived_at: Option<i64>,
+    /// 两步验证密钥（Base32），登录/注册页面要求两步验证码时自动生成并填写
+    #[serde(default)]
+    pub totp_secret: Option<String>,
 }
 
 impl Account {
@@ -35,13 +106,14 @@ impl Account {
     ) -> Self {
         let now = chrono::Utc::now().timestamp();
         Self {
-            id: uuid_simple(),
+            id: Uuid::new_v4().to_string(),
             name,
             email,
             avatar_url: String::new(),
             cookies,
             jwt_token: None,
             token_expired_at: None,
+            refresh_token: None,
             password: None,
             user_id,
             tenant_id,
@@ -51,6 +123,447 @@ impl Account {
             updated_at: now,
             is_active: true,
             machine_id: Some(Uuid::new_v4().to_string()),
+            last_switched_at: None,
+            switch_count: 0,
+            last_usage_check_at: None,
+            unverified: false,
+            region_override: None,
+            api_host: None,
+            profile_refreshed_at: None,
+            last_error: None,
+            tags: Vec::new(),
+            notes: None,
+            locale: None,
+            token_expiring: false,
+            alias: None,
+            usage_cache: None,
+            last_active_at: None,
+            plan_expires_at: None,
+            plan_auto_renew: false,
+            statistics_cache: None,
+            statistics_checked_at: None,
+            cookies_updated_at: Some(now),
+            registration: AccountRegistration::default(),
+            archived_at: None,
+            totp_secret: None,
+        }
+    }
+
+    /// 付费套餐即将到期（未开启自动续费且在 within_secs 内到期）
+    pub fn plan_expiring(&self, now: i64, within_secs: i64) -> bool {
+        !self.plan_auto_renew
+            && self.plan_expires_at.is_some_and(|exp| exp > now && exp - now <= within_secs)
+    }
+
+    /// 实际使用的区域：手动指定 > API 返回 > 默认 SG
+    pub fn effective_region(&self) -> String {
+        self.region_override
+            .as_deref()
+            .filter(|r| !r.trim().is_empty())
+            .map(|r| r.trim().to_uppercase())
+            .unwrap_or_else(|| if self.region.is_empty() { "SG".to_string() } else { self.region.clone() })
+    }
+
+    /// 账号指定的 API 端点，未指定区域或地址时返回 None（由客户端自动检测）
+    ///
+    /// 国内版账号即使未手动指定区域也固定使用国内端点，海外端点无法访问国内版账号
+    pub fn api_base_override(&self) -> Option<String> {
+        if let Some(host) = self.api_host.as_deref().filter(|h| !h.trim().is_empty()) {
+            return Some(host.trim().to_string());
+        }
+        self.region_override
+            .as_deref()
+            .filter(|r| !r.trim().is_empty())
+            .or_else(|| Some(self.region.as_str()).filter(|r| r.trim().eq_ignore_ascii_case("CN")))
+            .map(|r| crate::api::api_base_for_region(r).to_string())
+    }
+
+    /// 保存查询到的使用量，已用次数增加时视为活跃
+    pub fn record_usage(&mut self, summary: &crate::api::UsageSummary, now: i64) {
+        let used_more = self
+            .usage_cache
+            .as_ref()
+            .is_some_and(|previous| summary.total_used() > previous.total_used());
+        if used_more {
+            self.last_active_at = Some(now);
+        }
+        self.plan_type = summary.plan_type.clone();
+        if self.archived_at.is_some() && summary.fast_request_left + summary.extra_fast_request_left > 0.0 {
+            self.archived_at = None;
+        }
+        self.usage_cache = Some(summary.clone());
+        self.last_usage_check_at = Some(now);
+        self.updated_at = now;
+    }
+
+    /// 列表中显示的名称：设置了别名时使用别名
+    pub fn display_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Token 过期时间（Unix 时间戳，秒），优先使用接口返回值，其次解析 JWT
+    pub fn token_expires_at(&self) -> Option<i64> {
+        if let Some(ts) = self.token_expired_at.as_deref()
+            .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+        {
+            return Some(ts.timestamp());
+        }
+        self.jwt_token.as_deref()
+            .and_then(|t| crate::api::TraeApiClient::parse_jwt_token(t).ok())
+            .map(|payload| payload.exp)
+    }
+
+    /// 更新 Cookies 并记录更新时间
+    pub fn set_cookies(&mut self, cookies: String) {
+        self.cookies = cookies;
+        self.cookies_updated_at = Some(chrono::Utc::now().timestamp());
+    }
+
+    /// 是否可以在 Token 过期后自动换取新 Token（有刷新 Token 或 Cookies）
+    pub fn can_renew_token(&self) -> bool {
+        self.refresh_token.as_deref().is_some_and(|t| !t.trim().is_empty()) || !self.cookies.trim().is_empty()
+    }
+
+    /// 保存获取到的 Token，返回了刷新 Token 时一并更新
+    pub fn set_token(&mut self, token: String, expired_at: Option<String>, refresh_token: Option<String>) {
+        self.jwt_token = Some(token);
+        self.token_expired_at = expired_at;
+        if let Some(refresh_token) = refresh_token.filter(|t| !t.trim().is_empty()) {
+            self.refresh_token = Some(refresh_token);
+        }
+    }
+
+    /// 构建写入 Trae IDE 的登录信息
+    pub fn to_login_info(&self, token: String, entitlement_mode: crate::machine::EntitlementMode) -> crate::machine::TraeLoginInfo {
+        crate::machine::TraeLoginInfo {
+            token,
+            refresh_token: self.refresh_token.clone(),
+            user_id: self.user_id.clone(),
+            email: self.email.clone(),
+            username: self.name.clone(),
+            avatar_url: self.avatar_url.clone(),
+            host: self.api_host.clone().unwrap_or_default(), // 为空时根据 region 自动选择
+            region: self.effective_region(),
+            locale: self.locale.clone(),
+            plan_type: self.plan_type.clone(),
+            entitlement_mode,
+        }
+    }
+
+    /// 创建未验证账号（仅保存原始凭据，首次使用时再调用 API 校验）
+    pub fn new_unverified(email: String, cookies: String, jwt_token: Option<String>) -> Self {
+        let name = match email.split('@').next() {
+            Some(prefix) if !prefix.is_empty() => prefix.to_string(),
+            _ => "未验证账号".to_string(),
+        };
+        let mut account = Self::new(name, email, cookies, String::new(), String::new());
+        account.jwt_token = jwt_token;
+        account.unverified = true;
+        account
+    }
+}
+
+/// 账号的添加方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountSource {
+    /// 旧数据，未记录
+    #[default]
+    Unknown,
+    /// 快速注册
+    QuickRegister,
+    /// 浏览器登录窗口
+    BrowserLogin,
+    /// 读取 Trae IDE 当前登录的账号
+    TraeIde,
+    /// 读取已安装浏览器的 Cookie
+    BrowserCookies,
+    /// 手动填写 Token / Cookies
+    Token,
+    /// 邮箱密码登录
+    EmailLogin,
+    /// 从导出文件导入
+    Import,
+}
+
+/// 账号的添加方式和注册信息，用于按邮箱服务统计封禁情况
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountRegistration {
+    pub source: AccountSource,
+    /// 注册使用的临时邮箱服务（仅快速注册）
+    pub mail_provider: Option<String>,
+    /// 注册邮箱的域名
+    pub mail_domain: Option<String>,
+    /// 注册时间（仅快速注册）
+    pub registered_at: Option<i64>,
+    /// 注册窗口使用的 User-Agent（仅快速注册）
+    pub user_agent: Option<String>,
+}
+
+impl AccountRegistration {
+    pub fn new(source: AccountSource) -> Self {
+        Self {
+            source,
+            ..Default::default()
+        }
+    }
+}
+
+/// 账号最近一次错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountError {
+    pub message: String,
+    /// 错误分类
+    #[serde(default)]
+    pub code: crate::error::ErrorCode,
+    pub occurred_at: i64,
+}
+
+impl AccountError {
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        Self {
+            message: err.to_string(),
+            code: crate::error::ErrorCode::of(err),
+            occurred_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// 批量校验未验证账号的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub validated: usize,
+    pub failed: Vec<ValidationFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationFailure {
+    pub account_id: String,
+    pub email: String,
+    pub error: String,
+}
+
+/// 批量刷新账号资料的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileRefreshReport {
+    pub refreshed: usize,
+    pub failed: Vec<ValidationFailure>,
+}
+
+/// Cookies 字符串中的单个条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CookieEntry {
+    pub name: String,
+    pub value: String,
+}
+
+impl CookieEntry {
+    /// 解析 `name=value; name2=value2` 格式的 Cookies 字符串（保持原有顺序）
+    pub fn parse_header(cookies: &str) -> Vec<CookieEntry> {
+        cookies
+            .split(';')
+            .filter_map(|part| {
+                let (name, value) = part.split_once('=')?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return None;
+                }
+                Some(CookieEntry {
+                    name: name.to_string(),
+                    value: value.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// 拼接为 Cookies 字符串
+    pub fn join_header(entries: &[CookieEntry]) -> String {
+        entries
+            .iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// 付费套餐在该时长内到期时标记（7 天）
+pub const PLAN_EXPIRY_WARNING_SECS: i64 = 7 * 24 * 3600;
+
+/// Token 即将过期的事件（token_expiring）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenExpiringEvent {
+    pub account_id: String,
+    pub email: String,
+    pub expires_at: i64,
+}
+
+/// 账号需要处理的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttentionReason {
+    /// 没有 Token
+    NoToken,
+    TokenExpired,
+    /// Token 在提醒窗口内过期
+    TokenExpiring,
+    /// Cookies 长时间未更新，可能已失效
+    CookiesStale,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountAttention {
+    pub account_id: String,
+    pub email: String,
+    pub reasons: Vec<AttentionReason>,
+    pub token_expires_at: Option<i64>,
+    /// 是否可以自动换取新 Token（有刷新 Token 或 Cookies）
+    pub can_renew: bool,
+}
+
+/// 启动时本地检查 Token 和 Cookies 的结果（token_sweep 事件负载）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenSweepSummary {
+    pub checked: usize,
+    pub accounts: Vec<AccountAttention>,
+}
+
+/// 批量操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchAction {
+    /// 刷新账号资料
+    Refresh,
+    /// 绑定当前系统机器码
+    BindMachineId,
+    /// 领取礼包
+    ClaimGift,
+    /// 删除账号
+    Remove,
+}
+
+/// 批量操作中单个账号的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchActionResult {
+    pub account_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl BatchActionResult {
+    pub fn new<T>(account_id: &str, result: &anyhow::Result<T>) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            success: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        }
+    }
+}
+
+/// 一键领取礼包中单个账号的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GiftClaimStatus {
+    Claimed,
+    /// 已领取过，跳过
+    AlreadyClaimed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiftClaimResult {
+    pub account_id: String,
+    pub email: String,
+    pub status: GiftClaimStatus,
+    pub error: Option<String>,
+}
+
+/// 一键领取所有账号礼包的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GiftClaimReport {
+    pub claimed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub results: Vec<GiftClaimResult>,
+}
+
+/// 导入预览中单个条目的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportPreviewStatus {
+    /// 将作为新账号添加
+    Add,
+    /// 与已有账号或前面的条目重复，将被跳过（已有账号仅更新凭据）
+    Duplicate,
+    /// 数据无效或校验失败
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPreviewEntry {
+    /// 在导入数据中的序号（从 0 开始）
+    pub index: usize,
+    pub email: String,
+    pub user_id: Option<String>,
+    pub status: ImportPreviewStatus,
+    pub reason: Option<String>,
+}
+
+/// 导入预览（不写入任何数据）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportPreview {
+    pub entries: Vec<ImportPreviewEntry>,
+    pub to_add: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+}
+
+/// 完整导入（合并）结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportMergeReport {
+    /// 新增的账号数
+    pub added: usize,
+    /// 合并到已有账号的记录数
+    pub merged: usize,
+    /// 缺少凭据而跳过的记录数
+    pub skipped: usize,
+}
+
+/// 账号数据备份（backups 目录下的 accounts-<时间>.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    /// 备份标识（创建时间，格式 YYYYMMDD-HHMMSS）
+    pub id: String,
+    pub path: String,
+    pub created_at: i64,
+    pub size: u64,
+    pub account_count: usize,
+    /// 签名校验是否通过
+    pub valid: bool,
+    /// 是否同时备份了设置
+    pub has_settings: bool,
+}
+
+/// 导入参数（限速与延迟验证）
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// 同时进行校验的最大账号数
+    pub concurrency: usize,
+    /// 每个账号校验前的固定等待时间（毫秒）
+    pub delay_ms: u64,
+    /// 在固定等待基础上追加的随机抖动上限（毫秒）
+    pub jitter_ms: u64,
+    /// 仅保存原始数据，不立即调用 API 校验
+    pub defer_validation: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 5,
+            delay_ms: 0,
+            jitter_ms: 0,
+            defer_validation: false,
         }
     }
 }
@@ -63,15 +576,21 @@ pub struct AccountStore {
     /// 当前 Trae IDE 正在使用的账号 ID
     #[serde(default)]
     pub current_account_id: Option<String>,
+    /// 工作区与账号的映射
+    #[serde(default)]
+    pub workspace_mappings: Vec<WorkspaceMapping>,
 }
 
-/// 简单的 UUID 生成
-fn uuid_simple() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let duration = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap();
-    format!("{:x}{:x}", duration.as_secs(), duration.subsec_nanos())
+/// 工作区映射：Trae 打开该目录（或其子目录）时使用指定账号
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceMapping {
+    pub id: String,
+    pub folder: String,
+    pub account_id: String,
+    /// true 时自动切换，否则只提示
+    #[serde(default)]
+    pub auto_switch: bool,
+    pub created_at: i64,
 }
 
 /// 账号简要信息（用于列表展示）
@@ -88,13 +607,39 @@ pub struct AccountBrief {
     pub machine_id: Option<String>,
     /// 是否是当前 Trae IDE 正在使用的账号
     pub is_current: bool,
+    /// 最近一次切换到该账号的时间
+    pub last_switched_at: Option<i64>,
+    /// 累计切换次数
+    pub switch_count: u32,
+    /// 最近一次查询使用量的时间
+    pub last_usage_check_at: Option<i64>,
+    /// 是否尚未通过 API 校验
+    pub unverified: bool,
+    /// 最近一次后台操作失败的错误
+    pub last_error: Option<AccountError>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    /// Token 过期时间（Unix 时间戳，秒）
+    pub token_expires_at: Option<i64>,
+    /// Token 即将过期
+    pub token_expiring: bool,
+    /// 本地别名（name 已按别名优先处理）
+    pub alias: Option<String>,
+    /// 付费套餐到期时间
+    pub plan_expires_at: Option<i64>,
+    /// 付费套餐即将到期且未开启自动续费
+    pub plan_expiring: bool,
+    /// 账号的添加方式和注册信息
+    pub registration: AccountRegistration,
+    /// 额度用完后被自动归档的时间
+    pub archived_at: Option<i64>,
 }
 
 impl From<&Account> for AccountBrief {
     fn from(account: &Account) -> Self {
         Self {
             id: account.id.clone(),
-            name: account.name.clone(),
+            name: account.display_name().to_string(),
             email: account.email.clone(),
             avatar_url: account.avatar_url.clone(),
             plan_type: account.plan_type.clone(),
@@ -102,6 +647,20 @@ impl From<&Account> for AccountBrief {
             created_at: account.created_at,
             machine_id: account.machine_id.clone(),
             is_current: false, // 默认为 false，由 AccountManager 设置
+            last_switched_at: account.last_switched_at,
+            switch_count: account.switch_count,
+            last_usage_check_at: account.last_usage_check_at,
+            unverified: account.unverified,
+            last_error: account.last_error.clone(),
+            tags: account.tags.clone(),
+            notes: account.notes.clone(),
+            token_expires_at: account.token_expires_at(),
+            token_expiring: account.token_expiring,
+            alias: account.alias.clone(),
+            plan_expires_at: account.plan_expires_at,
+            plan_expiring: account.plan_expiring(chrono::Utc::now().timestamp(), PLAN_EXPIRY_WARNING_SECS),
+            registration: account.registration.clone(),
+            archived_at: account.archived_at,
         }
     }
 }
@@ -111,7 +670,7 @@ impl AccountBrief {
     pub fn from_account(account: &Account, is_current: bool) -> Self {
         Self {
             id: account.id.clone(),
-            name: account.name.clone(),
+            name: account.display_name().to_string(),
             email: account.email.clone(),
             avatar_url: account.avatar_url.clone(),
             plan_type: account.plan_type.clone(),
@@ -119,6 +678,20 @@ impl AccountBrief {
             created_at: account.created_at,
             machine_id: account.machine_id.clone(),
             is_current,
+            last_switched_at: account.last_switched_at,
+            switch_count: account.switch_count,
+            last_usage_check_at: account.last_usage_check_at,
+            unverified: account.unverified,
+            last_error: account.last_error.clone(),
+            tags: account.tags.clone(),
+            notes: account.notes.clone(),
+            token_expires_at: account.token_expires_at(),
+            token_expiring: account.token_expiring,
+            alias: account.alias.clone(),
+            plan_expires_at: account.plan_expires_at,
+            plan_expiring: account.plan_expiring(chrono::Utc::now().timestamp(), PLAN_EXPIRY_WARNING_SECS),
+            registration: account.registration.clone(),
+            archived_at: account.archived_at,
         }
     }
 }
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+
+use super::network::{hex_encode, is_trae_host, normalize_fingerprint};
+
+/// 证书固定：在 TLS 握手阶段校验服务器证书
+///
+/// 先按正常流程校验证书链和域名，Trae 域名的证书还必须匹配配置的指纹之一，
+/// 不匹配时握手直接失败，请求头和请求体不会发送到对端
+#[derive(Debug)]
+struct PinnedCertVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: Vec<String>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self
+            .inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+        let host = match server_name {
+            ServerName::DnsName(name) => name.as_ref().to_string(),
+            _ => return Ok(verified),
+        };
+        if !is_trae_host(&host) {
+            return Ok(verified);
+        }
+        let actual = hex_encode(&Sha256::digest(end_entity.as_ref()));
+        if self.pins.contains(&actual) {
+            Ok(verified)
+        } else {
+            println!("[ERROR] 证书固定校验失败: {} 的证书指纹 {} 不在允许列表中", host, actual);
+            Err(rustls::Error::General(format!("证书固定校验失败: {} 的证书与固定的指纹不匹配", host)))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// 读取 PEM 格式的 CA 证书文件（DER 格式，供 rustls 使用）
+pub fn load_ca_der(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let pem = std::fs::read(path.trim()).map_err(|e| anyhow!("无法读取证书文件 {}: {}", path, e))?;
+    CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow!("证书文件格式无效: {}", e))
+}
+
+/// 创建启用证书固定的 TLS 配置
+///
+/// 信任内置的根证书（webpki-roots）和设置中的自定义 CA 证书
+pub fn tls_config(pins: &[String], extra_ca: &[CertificateDer<'static>]) -> Result<ClientConfig> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    for cert in extra_ca {
+        roots
+            .add(cert.clone())
+            .map_err(|e| anyhow!("自定义 CA 证书无效: {}", e))?;
+    }
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let inner = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+        .build()
+        .map_err(|e| anyhow!("创建证书校验器失败: {}", e))?;
+    let verifier = PinnedCertVerifier {
+        inner,
+        pins: pins.iter().map(|pin| normalize_fingerprint(pin)).collect(),
+    };
+
+    let mut config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| anyhow!("创建 TLS 配置失败: {}", e))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
@@ -0,0 +1,109 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+
+const LOG_FILE: &str = "api-debug.log";
+/// 日志超过该大小时轮转为 .1 文件（只保留一份旧日志）
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// 查询参数中需要隐藏取值的关键字
+const SENSITIVE_PARAMS: &[&str] = &["token", "key", "secret", "password", "code", "session", "auth"];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// 串行写入，避免并发请求的日志行交错
+static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 开启或关闭调试日志（由设置控制）
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 调试日志文件路径
+pub fn log_path() -> Result<PathBuf> {
+    let dir = crate::account::AccountManager::data_dir()?.join("logs");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(LOG_FILE))
+}
+
+/// 去掉地址中的用户名密码，并隐藏敏感查询参数的取值
+fn sanitize_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            let lower = key.to_lowercase();
+            let value = if SENSITIVE_PARAMS.iter().any(|p| lower.contains(p)) {
+                "***".to_string()
+            } else {
+                value.into_owned()
+            };
+            (key.into_owned(), value)
+        })
+        .collect();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+/// 记录一次请求（未开启时忽略），只记录方法、脱敏后的地址、状态码和耗时，不记录请求头和请求体
+pub(crate) fn record(method: &str, url: &reqwest::Url, status: Option<u16>, latency: Duration, error: Option<&str>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let line = format!(
+        "{} {} {} {} {}ms{}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+        method,
+        sanitize_url(url),
+        status.map_or_else(|| "-".to_string(), |s| s.to_string()),
+        latency.as_millis(),
+        error.map(|e| format!(" error={}", e)).unwrap_or_default(),
+    );
+    if let Err(e) = append(&line) {
+        println!("[WARN] 写入调试日志失败: {}", e);
+    }
+}
+
+fn append(line: &str) -> Result<()> {
+    let _guard = WRITE_LOCK.lock().map_err(|_| anyhow::anyhow!("调试日志锁已损坏"))?;
+    let path = log_path()?;
+    if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let _ = fs::rename(&path, path.with_extension("log.1"));
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// 将当前的调试日志轮转为 .1 文件（定时维护时调用），返回是否发生了轮转
+pub fn rotate() -> Result<bool> {
+    let _guard = WRITE_LOCK.lock().map_err(|_| anyhow::anyhow!("调试日志锁已损坏"))?;
+    let path = log_path()?;
+    if !fs::metadata(&path).is_ok_and(|m| m.len() > 0) {
+        return Ok(false);
+    }
+    fs::rename(&path, path.with_extension("log.1"))?;
+    Ok(true)
+}
+
+/// 清空调试日志（包括轮转的旧日志）
+pub fn clear() -> Result<()> {
+    let _guard = WRITE_LOCK.lock().map_err(|_| anyhow::anyhow!("调试日志锁已损坏"))?;
+    let path = log_path()?;
+    for file in [path.clone(), path.with_extension("log.1")] {
+        if file.exists() {
+            fs::remove_file(&file)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{header, Client};
+use serde::Deserialize;
+
+const MIN_TTL_SECS: u64 = 60;
+/// 缓存时间上限，避免异常的 TTL 让解析结果长期不更新
+const MAX_TTL_SECS: u64 = 86_400;
+
+/// 域名 -> (过期时间, 地址)
+type DnsCache = HashMap<String, (Instant, Vec<IpAddr>)>;
+
+#[derive(Debug, Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: i32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    #[serde(rename = "TTL", default)]
+    ttl: u64,
+    data: String,
+}
+
+/// DNS-over-HTTPS 解析器（JSON 格式，兼容 Cloudflare / Google / AliDNS）
+///
+/// DoH 查询失败时回退到系统 DNS，避免 DoH 服务不可用时所有请求都失败。
+#[derive(Clone)]
+pub struct DohResolver {
+    endpoint: String,
+    client: Client,
+    cache: Arc<Mutex<DnsCache>>,
+}
+
+impl DohResolver {
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let endpoint = endpoint.trim();
+        if !endpoint.starts_with("https://") {
+            return Err(anyhow!("DoH 地址必须以 https:// 开头"));
+        }
+        // 解析器自身使用系统 DNS，不能再套用 DoH
+        let client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        Ok(Self {
+            endpoint: endpoint.to_string(),
+            client,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    async fn query(&self, host: &str, record_type: u16) -> Result<(Vec<IpAddr>, u64)> {
+        let response: DohResponse = self
+            .client
+            .get(&self.endpoint)
+            .query(&[("name", host.to_string()), ("type", record_type.to_string())])
+            .header(header::ACCEPT, "application/dns-json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if response.status != 0 {
+            return Err(anyhow!("DoH 查询失败 (status {}): {}", response.status, host));
+        }
+
+        let mut ttl = u64::MAX;
+        let ips: Vec<IpAddr> = response
+            .answer
+            .into_iter()
+            .filter(|a| a.record_type == record_type)
+            .filter_map(|a| {
+                ttl = ttl.min(a.ttl);
+                a.data.parse().ok()
+            })
+            .collect();
+        Ok((ips, ttl.clamp(MIN_TTL_SECS, MAX_TTL_SECS)))
+    }
+
+    async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>> {
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return Ok(vec![ip]);
+        }
+        if let Some((expires, ips)) = self.cache.lock().unwrap().get(host) {
+            if *expires > Instant::now() {
+                return Ok(ips.clone());
+            }
+        }
+
+        let (mut ips, mut ttl) = self.query(host, 1).await?;
+        if ips.is_empty() {
+            // 只有 IPv6 地址（或只有 CNAME）时，缓存时间以 AAAA 查询的结果为准
+            (ips, ttl) = self.query(host, 28).await?;
+        }
+        if ips.is_empty() {
+            return Err(anyhow!("DoH 未返回 {} 的地址", host));
+        }
+
+        self.cache.lock().unwrap().insert(
+            host.to_string(),
+            (Instant::now() + Duration::from_secs(ttl), ips.clone()),
+        );
+        Ok(ips)
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = match resolver.lookup(&host).await {
+                Ok(ips) => ips.into_iter().map(|ip| SocketAddr::new(ip, 0)).collect(),
+                Err(e) => {
+                    println!("[WARN] DoH 解析 {} 失败，回退到系统 DNS: {}", host, e);
+                    tokio::net::lookup_host((host.as_str(), 0)).await?.collect()
+                }
+            };
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::network::shared_client;
+
+/// 单个端点的探测超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 单个端点的探测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointHealth {
+    pub name: String,
+    pub url: String,
+    /// 服务器有响应且不是 5xx（401 等业务错误也说明服务可达）
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// 健康检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub checked_at: i64,
+    pub endpoints: Vec<EndpointHealth>,
+    /// 所有端点均可达
+    pub healthy: bool,
+}
+
+/// 探测 Trae 登录、使用量接口和临时邮箱服务的可达性和延迟（不携带账号凭据，不重试）
+///
+/// 使用共用的 Client，代理、自定义 CA 和证书固定与正常请求一致
+pub async fn check(mail_url: &str) -> HealthReport {
+    let mut targets = super::trae_api::health_probe_endpoints();
+    targets.push(("临时邮箱".to_string(), mail_url.to_string()));
+
+    let endpoints = match shared_client() {
+        Ok(client) => {
+            let tasks: Vec<_> = targets
+                .into_iter()
+                .map(|(name, url)| {
+                    let client = client.clone();
+                    tokio::spawn(async move { probe(&client, name, url).await })
+                })
+                .collect();
+            let mut endpoints = Vec::with_capacity(tasks.len());
+            for task in tasks {
+                if let Ok(result) = task.await {
+                    endpoints.push(result);
+                }
+            }
+            endpoints
+        }
+        Err(e) => targets
+            .into_iter()
+            .map(|(name, url)| EndpointHealth {
+                name,
+                url,
+                reachable: false,
+                status: None,
+                latency_ms: 0,
+                error: Some(format!("创建 HTTP 客户端失败: {}", e)),
+            })
+            .collect(),
+    };
+
+    let healthy = !endpoints.is_empty() && endpoints.iter().all(|e| e.reachable);
+    let unreachable = endpoints.iter().filter(|e| !e.reachable).count();
+    println!("[INFO] API 健康检查完成: {} 个端点, 不可达 {} 个", endpoints.len(), unreachable);
+    HealthReport {
+        checked_at: chrono::Utc::now().timestamp(),
+        endpoints,
+        healthy,
+    }
+}
+
+async fn probe(client: &reqwest::Client, name: String, url: String) -> EndpointHealth {
+    let started = Instant::now();
+    let result = client.post(&url).timeout(PROBE_TIMEOUT).json(&serde_json::json!({})).send().await;
+    let latency_ms = crate::millis(started.elapsed());
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            EndpointHealth {
+                name,
+                url,
+                reachable: !status.is_server_error(),
+                status: Some(status.as_u16()),
+                latency_ms,
+                error: status.is_server_error().then(|| format!("服务器错误: {}", status)),
+            }
+        }
+        Err(e) => EndpointHealth {
+            name,
+            url,
+            reachable: false,
+            status: None,
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+    }
+}
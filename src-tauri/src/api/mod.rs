@@ -1,6 +1,16 @@
+pub mod cert_pin;
+pub mod debug_log;
+pub mod doh;
+pub mod health;
+pub mod network;
+pub mod rate_limit;
+pub mod request_log;
 pub mod trae_api;
 pub mod types;
 
 pub use trae_api::TraeApiClient;
 pub use trae_api::login_with_email;
+pub use trae_api::api_base_for_region;
+pub use trae_api::TRAE_ENDPOINTS;
+pub use trae_api::user_token_endpoints;
 pub use types::*;
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+/// 端点预算：桶容量（允许的突发请求数）和每秒补充的令牌数
+#[derive(Debug, Clone, Copy)]
+struct Budget {
+    burst: f64,
+    per_sec: f64,
+}
+
+/// 各端点的预算，按路径前缀匹配（靠前的优先），批量操作时同一端点的请求共用一个桶
+const BUDGETS: &[(&str, Budget)] = &[
+    // 换取 Token 最容易触发风控
+    ("/cloudide/api/v3/common/GetUserToken", Budget { burst: 3.0, per_sec: 0.2 }),
+    ("/cloudide/api/v3/trae/oauth/ExchangeToken", Budget { burst: 3.0, per_sec: 0.2 }),
+    ("/trae/api/v1/pay/claim_", Budget { burst: 2.0, per_sec: 0.1 }),
+    ("/trae/api/v1/pay/query_user_usage", Budget { burst: 5.0, per_sec: 1.0 }),
+    ("/trae/api/v1/pay/", Budget { burst: 5.0, per_sec: 1.0 }),
+    ("/cloudide/api/v3/trae/", Budget { burst: 5.0, per_sec: 1.0 }),
+];
+
+/// 未匹配任何前缀的请求共用的预算
+const DEFAULT_BUDGET: Budget = Budget { burst: 10.0, per_sec: 2.0 };
+const DEFAULT_KEY: &str = "*";
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+static BUCKETS: Lazy<Mutex<HashMap<&'static str, Bucket>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn budget_for(path: &str) -> (&'static str, Budget) {
+    BUDGETS
+        .iter()
+        .find(|(prefix, _)| path.starts_with(prefix))
+        .map_or((DEFAULT_KEY, DEFAULT_BUDGET), |(prefix, budget)| (*prefix, *budget))
+}
+
+/// 获取一个请求令牌，端点预算用完时等待补充（所有 TraeApiClient 共用）
+pub async fn acquire(path: &str) {
+    let (key, budget) = budget_for(path);
+    loop {
+        let wait = {
+            let Ok(mut buckets) = BUCKETS.lock() else {
+                return;
+            };
+            let now = Instant::now();
+            let bucket = buckets.entry(key).or_insert(Bucket { tokens: budget.burst, updated: now });
+            let refill = now.duration_since(bucket.updated).as_secs_f64() * budget.per_sec;
+            bucket.tokens = (bucket.tokens + refill).min(budget.burst);
+            bucket.updated = now;
+            if bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+                return;
+            }
+            Duration::from_secs_f64((1.0 - bucket.tokens) / budget.per_sec)
+        };
+        println!("[INFO] 请求 {} 达到限速，等待 {} 毫秒", path, wait.as_millis());
+        tokio::time::sleep(wait).await;
+    }
+}
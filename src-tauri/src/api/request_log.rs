@@ -0,0 +1,74 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// 每个账号保留的最近请求数
+const MAX_ENTRIES_PER_ACCOUNT: usize = 50;
+/// 错误信息最大长度（字符）
+const MAX_ERROR_CHARS: usize = 200;
+
+/// 单次 API 请求记录（只记录路径，不记录查询参数和请求头，避免泄露凭据）
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestLogEntry {
+    pub at: i64,
+    pub method: String,
+    pub host: String,
+    pub path: String,
+    /// HTTP 状态码，请求未完成（网络错误、超时）时为 None
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+static LOGS: Lazy<Mutex<HashMap<String, VecDeque<RequestLogEntry>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+tokio::task_local! {
+    static CURRENT_ACCOUNT: String;
+}
+
+/// 在指定账号的上下文中执行请求，期间经 send_checked 发出的请求都会记录到该账号
+pub async fn scope<F: Future>(account_id: &str, fut: F) -> F::Output {
+    CURRENT_ACCOUNT.scope(account_id.to_string(), fut).await
+}
+
+/// 记录一次请求（不在账号上下文中时忽略）
+pub(crate) fn record(method: &str, url: &reqwest::Url, status: Option<u16>, latency: Duration, error: Option<String>) {
+    let Ok(account_id) = CURRENT_ACCOUNT.try_with(|id| id.clone()) else {
+        return;
+    };
+    let entry = RequestLogEntry {
+        at: chrono::Utc::now().timestamp_millis(),
+        method: method.to_string(),
+        host: url.host_str().unwrap_or_default().to_string(),
+        path: url.path().to_string(),
+        status,
+        latency_ms: u64::try_from(latency.as_millis()).unwrap_or(u64::MAX),
+        error: error.map(|e| e.chars().take(MAX_ERROR_CHARS).collect()),
+    };
+    let Ok(mut logs) = LOGS.lock() else {
+        return;
+    };
+    let entries = logs.entry(account_id).or_default();
+    if entries.len() >= MAX_ENTRIES_PER_ACCOUNT {
+        entries.pop_front();
+    }
+    entries.push_back(entry);
+}
+
+/// 获取账号最近的请求记录（按时间从新到旧）
+pub fn entries(account_id: &str) -> Vec<RequestLogEntry> {
+    LOGS.lock()
+        .map(|logs| logs.get(account_id).map(|e| e.iter().rev().cloned().collect()).unwrap_or_default())
+        .unwrap_or_default()
+}
+
+/// 清除账号的请求记录（账号删除时调用）
+pub fn clear(account_id: &str) {
+    if let Ok(mut logs) = LOGS.lock() {
+        logs.remove(account_id);
+    }
+}
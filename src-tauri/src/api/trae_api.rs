@@ -6,11 +6,76 @@ use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use std::sync::Arc;
 use chrono::{Local, SecondsFormat, Utc};
 
+use super::network::SendExt;
+use crate::error::status_error;
 use super::types::*;
 
 const API_BASE_US: &str = "https://api-us-east.trae.ai";
 const API_BASE_SG: &str = "https://api-sg-central.trae.ai";
+const API_BASE_CN: &str = "https://api.trae.com.cn";
 const API_BASE_UG: &str = "https://ug-normal.trae.ai";
+const WEB_ORIGIN: &str = "https://www.trae.ai";
+const WEB_ORIGIN_CN: &str = "https://www.trae.com.cn";
+/// 生日礼包在活动列表中的 ID
+pub const BIRTHDAY_PROMOTION_ID: &str = "birthday_bonus";
+
+/// 应用会访问的 Trae 端点（用于获取证书指纹）
+pub const TRAE_ENDPOINTS: &[&str] = &[API_BASE_US, API_BASE_SG, API_BASE_CN, API_BASE_UG, WEB_ORIGIN, WEB_ORIGIN_CN];
+
+/// 拼接 API 地址
+fn endpoint(base: &str, path: &str) -> String {
+    format!("{}{}", base.trim_end_matches('/'), path)
+}
+
+/// 获取 Token 时依次尝试的端点（供注入登录页的脚本使用），自定义 API 地址排在最前
+pub fn user_token_endpoints() -> Vec<String> {
+    super::network::api_base_override()
+        .into_iter()
+        .chain([API_BASE_SG.to_string(), API_BASE_US.to_string(), API_BASE_CN.to_string()])
+        .map(|base| endpoint(&base, "/cloudide/api/v3/common/GetUserToken"))
+        .collect()
+}
+
+/// 健康检查探测的端点（名称, 地址）：各区域的登录（获取 Token）和使用量接口，自定义 API 地址排在最前
+pub fn health_probe_endpoints() -> Vec<(String, String)> {
+    let bases: Vec<(String, String)> = super::network::api_base_override()
+        .map(|base| ("自定义".to_string(), base))
+        .into_iter()
+        .chain([("SG", API_BASE_SG), ("US", API_BASE_US), ("CN", API_BASE_CN)].map(|(region, base)| (region.to_string(), base.to_string())))
+        .collect();
+    bases
+        .iter()
+        .flat_map(|(region, base)| {
+            [
+                (format!("登录 ({})", region), endpoint(base, "/cloudide/api/v3/common/GetUserToken")),
+                (format!("使用量 ({})", region), endpoint(base, "/trae/api/v1/pay/user_current_entitlement_list")),
+            ]
+        })
+        .collect()
+}
+
+/// Cookie 中的机房标识对应的区域（store-idc / trae-target-idc）
+fn region_from_idc(idc: &str) -> Option<&'static str> {
+    let idc = idc.trim().to_lowercase();
+    if idc.starts_with("useast") {
+        Some("US")
+    } else if idc.starts_with("alisg") {
+        Some("SG")
+    } else if idc.starts_with("cn") || ["lf", "hl", "lq"].contains(&idc.as_str()) {
+        Some("CN")
+    } else {
+        None
+    }
+}
+
+/// 根据区域代码获取 API 端点（未知区域默认使用新加坡）
+pub fn api_base_for_region(region: &str) -> &'static str {
+    match region.trim().to_uppercase().as_str() {
+        "US" | "USEAST" => API_BASE_US,
+        "CN" => API_BASE_CN,
+        _ => API_BASE_SG,
+    }
+}
 
 /// 登录响应结构
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -34,6 +99,7 @@ pub struct EmailLoginResult {
     pub tenant_id: String,
     pub cookies: String,
     pub expired_at: String,
+    pub refresh_token: Option<String>,
 }
 
 /// Trae API 客户端
@@ -42,13 +108,13 @@ pub struct TraeApiClient {
     cookies: String,
     jwt_token: Option<String>,
     api_base: String,  // 动态 API 端点
+    api_base_locked: bool,  // 账号指定了区域/API 地址时不再回退到其他端点
 }
 
 impl TraeApiClient {
     /// 创建新的 API 客户端（使用 Cookies）
     pub fn new(cookies: &str) -> Result<Self> {
-        let client = Client::builder()
-            .build()?;
+        let client = super::network::shared_client()?;
 
         // 清理 Cookie 字符串：移除换行符、多余空格
         let cleaned_cookies = cookies
@@ -66,13 +132,14 @@ impl TraeApiClient {
             cookies: cleaned_cookies,
             jwt_token: None,
             api_base,
-        })
+            api_base_locked: false,
+        }
+        .with_api_base(super::network::api_base_override().as_deref()))
     }
 
     /// 创建新的 API 客户端（使用 Token）
     pub fn new_with_token(token: &str) -> Result<Self> {
-        let client = Client::builder()
-            .build()?;
+        let client = super::network::shared_client()?;
 
         // 从 Token 中解析区域信息，默认尝试多个端点
         let api_base = API_BASE_SG.to_string(); // 默认使用新加坡，因为大多数亚洲用户
@@ -82,7 +149,9 @@ impl TraeApiClient {
             cookies: String::new(),
             jwt_token: Some(token.to_string()),
             api_base,
-        })
+            api_base_locked: false,
+        }
+        .with_api_base(super::network::api_base_override().as_deref()))
     }
 
     /// 创建新的 API 客户端（使用 Cookies + Token）
@@ -92,13 +161,42 @@ impl TraeApiClient {
         Ok(client)
     }
 
+    /// 使用指定的 API 端点（账号的区域/API 地址覆盖，优先于全局自定义 API 地址），传入 None 时保持当前端点
+    pub fn with_api_base(mut self, api_base: Option<&str>) -> Self {
+        if let Some(base) = api_base.map(|v| v.trim().trim_end_matches('/')).filter(|v| !v.is_empty()) {
+            self.api_base = base.to_string();
+            self.api_base_locked = true;
+        }
+        self
+    }
+
+    /// 依次尝试的 API 端点列表
+    fn candidate_bases(&self) -> Vec<String> {
+        if self.api_base_locked {
+            return vec![self.api_base.clone()];
+        }
+        let mut bases = vec![self.api_base.clone()];
+        if self.is_cn() {
+            return bases;
+        }
+        for base in [API_BASE_SG, API_BASE_US] {
+            if !bases.iter().any(|b| b == base) {
+                bases.push(base.to_string());
+            }
+        }
+        bases
+    }
+
     /// 从 Cookies 中检测 API 端点
     fn detect_api_base_from_cookies(cookies: &str) -> String {
         // 检查 store-idc 或 trae-target-idc
-        if cookies.contains("store-idc=useast") || cookies.contains("trae-target-idc=useast") {
-            API_BASE_US.to_string()
-        } else if cookies.contains("store-idc=alisg") || cookies.contains("trae-target-idc=alisg") {
-            API_BASE_SG.to_string()
+        let idc_region = cookies
+            .split(';')
+            .filter_map(|pair| pair.split_once('='))
+            .filter(|(name, _)| matches!(name.trim(), "store-idc" | "trae-target-idc"))
+            .find_map(|(_, value)| region_from_idc(value));
+        if let Some(region) = idc_region {
+            api_base_for_region(region).to_string()
         } else if cookies.contains("store-country-code=us") {
             API_BASE_US.to_string()
         } else {
@@ -107,6 +205,32 @@ impl TraeApiClient {
         }
     }
 
+    /// 当前端点是否为国内版（trae.com.cn）
+    fn is_cn(&self) -> bool {
+        self.api_base == API_BASE_CN
+    }
+
+    /// 请求头中的 Origin，国内版使用 trae.com.cn
+    fn web_origin(&self) -> &'static str {
+        if self.is_cn() {
+            WEB_ORIGIN_CN
+        } else {
+            WEB_ORIGIN
+        }
+    }
+
+    /// 用户资料和统计接口的地址：设置了自定义 API 地址时走该地址，国内版直接使用 API 端点
+    fn ug_base(&self) -> String {
+        if let Some(base) = super::network::api_base_override() {
+            return base;
+        }
+        if self.is_cn() {
+            API_BASE_CN.to_string()
+        } else {
+            API_BASE_UG.to_string()
+        }
+    }
+
     /// 尝试多个 API 端点获取数据
     async fn try_api_endpoints<T, F, Fut>(&self, path: &str, request_fn: F) -> Result<T>
     where
@@ -114,20 +238,24 @@ impl TraeApiClient {
         Fut: std::future::Future<Output = Result<T>>,
     {
         // 先尝试当前设置的端点
-        let url = format!("{}{}", self.api_base, path);
+        let url = endpoint(&self.api_base, path);
         match request_fn(url).await {
             Ok(result) => return Ok(result),
+            Err(e) if self.api_base_locked => return Err(e),
             Err(_) => {}
         }
 
-        // 如果失败，尝试其他端点
+        // 如果失败，尝试其他端点（国内版账号无法使用海外端点）
+        if self.is_cn() {
+            return Err(anyhow!("国内版 API 请求失败: {}", path));
+        }
         let other_base = if self.api_base == API_BASE_SG {
             API_BASE_US
         } else {
             API_BASE_SG
         };
 
-        let url = format!("{}{}", other_base, path);
+        let url = endpoint(other_base, path);
         request_fn(url).await
     }
 
@@ -136,8 +264,8 @@ impl TraeApiClient {
         let mut headers = header::HeaderMap::new();
         headers.insert(header::CONTENT_TYPE, "application/json".parse()?);
         headers.insert(header::ACCEPT, "application/json, text/plain, */*".parse()?);
-        headers.insert(header::ORIGIN, "https://www.trae.ai".parse()?);
-        headers.insert(header::REFERER, "https://www.trae.ai/".parse()?);
+        headers.insert(header::ORIGIN, self.web_origin().parse()?);
+        headers.insert(header::REFERER, format!("{}/", self.web_origin()).parse()?);
         headers.insert(
             header::USER_AGENT,
             "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36".parse()?,
@@ -161,19 +289,19 @@ impl TraeApiClient {
 
         // 尝试多个 API 端点
         let headers = self.build_headers_token_only()?;
-        let endpoints = [&self.api_base, API_BASE_SG, API_BASE_US];
+        let endpoints = self.candidate_bases();
 
         let mut last_error = anyhow!("所有 API 端点都失败");
 
         for base in endpoints.iter() {
-            let url = format!("{}/trae/api/v1/pay/user_current_entitlement_list", base);
+            let url = endpoint(base, "/trae/api/v1/pay/user_current_entitlement_list");
 
             let response = self
                 .client
                 .post(&url)
                 .headers(headers.clone())
                 .json(&json!({"require_usage": true}))
-                .send()
+                .send_retryable()
                 .await;
 
             match response {
@@ -199,6 +327,7 @@ impl TraeApiClient {
                                     }
                                 }),
                                 email: user_detail.as_ref().and_then(|u| u.non_plain_text_email.clone()),
+                                region: user_detail.as_ref().map(|u| u.region.clone()),
                             });
                         }
                         Err(e) => {
@@ -207,7 +336,7 @@ impl TraeApiClient {
                     }
                 }
                 Ok(resp) => {
-                    last_error = anyhow!("API 返回错误: {}", resp.status());
+                    last_error = status_error("API 返回错误", resp.status());
                 }
                 Err(e) => {
                     last_error = anyhow!("请求失败: {}", e);
@@ -220,7 +349,7 @@ impl TraeApiClient {
 
     /// 尝试用 Token 调用 GetUserInfo 接口
     async fn get_user_info_with_token(&self) -> Result<UserInfoResult> {
-        let url = format!("{}/cloudide/api/v3/trae/GetUserInfo", API_BASE_UG);
+        let url = endpoint(&self.ug_base(), "/cloudide/api/v3/trae/GetUserInfo");
         let headers = self.build_headers_token_only()?;
 
         let response = self
@@ -228,11 +357,11 @@ impl TraeApiClient {
             .post(&url)
             .headers(headers)
             .json(&json!({"IfWebPage": true}))
-            .send()
+            .send_retryable()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("获取用户信息失败: {}", response.status()));
+            return Err(status_error("获取用户信息失败", response.status()));
         }
 
         let data: GetUserInfoResponse = response.json().await?;
@@ -240,7 +369,7 @@ impl TraeApiClient {
     }
 
     /// 解析 JWT Token 获取用户信息
-    fn parse_jwt_token(token: &str) -> Result<JwtPayload> {
+    pub(crate) fn parse_jwt_token(token: &str) -> Result<JwtPayload> {
         // JWT 格式: header.payload.signature
         let parts: Vec<&str> = token.split('.').collect();
         if parts.len() != 3 {
@@ -267,6 +396,7 @@ impl TraeApiClient {
         Ok(JwtPayload {
             user_id: payload.data.id,
             tenant_id: payload.data.tenant_id,
+            exp: payload.exp,
         })
     }
 
@@ -283,8 +413,8 @@ impl TraeApiClient {
             headers.insert(header::COOKIE, cookie_value);
         }
 
-        headers.insert(header::ORIGIN, "https://www.trae.ai".parse()?);
-        headers.insert(header::REFERER, "https://www.trae.ai/".parse()?);
+        headers.insert(header::ORIGIN, self.web_origin().parse()?);
+        headers.insert(header::REFERER, format!("{}/", self.web_origin()).parse()?);
         headers.insert(
             header::USER_AGENT,
             "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".parse()?,
@@ -302,19 +432,75 @@ impl TraeApiClient {
         Ok(headers)
     }
 
+    /// 使用刷新 Token 换取新的 Token（不依赖 Cookies，JWT 过期后仍可使用）
+    pub async fn refresh_via_refresh_token(&self, refresh_token: &str, user_id: &str) -> Result<UserTokenResult> {
+        let url = endpoint(&self.api_base, "/cloudide/api/v3/trae/oauth/ExchangeToken");
+        let mut headers = self.build_headers_token_only()?;
+        headers.remove(header::AUTHORIZATION);
+
+        let response = self
+            .client
+            .post(&url)
+            .headers(headers)
+            .json(&json!({
+                "RefreshToken": refresh_token,
+                "ClientSecret": "-",
+                "UserID": user_id,
+            }))
+            .send_checked()
+            .await?;
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(anyhow!("刷新 Token 失败: {} - {}", status, body));
+        }
+        let data: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| anyhow!("解析刷新 Token 响应失败: {}", e))?;
+        let result = data.get("Result").or_else(|| data.get("result")).unwrap_or(&data);
+        let text = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| result.get(*key).and_then(|v| v.as_str()))
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+
+        let token = text(&["Token", "token"]).ok_or_else(|| anyhow!("刷新 Token 响应中没有 Token"))?;
+        let payload = Self::parse_jwt_token(&token)?;
+        // 过期时间可能是 RFC3339 字符串或毫秒时间戳，缺失时使用 JWT 的 exp
+        let expired_at = text(&["ExpiredAt", "TokenExpireAt"])
+            .or_else(|| {
+                result.get("TokenExpireAt")
+                    .and_then(|v| v.as_i64())
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Millis, true))
+            })
+            .or_else(|| chrono::DateTime::from_timestamp(payload.exp, 0).map(|dt| dt.to_rfc3339_opts(SecondsFormat::Millis, true)))
+            .unwrap_or_default();
+
+        Ok(UserTokenResult {
+            token,
+            expired_at,
+            user_id: payload.user_id,
+            tenant_id: payload.tenant_id,
+            // 刷新后接口可能返回新的刷新 Token，未返回时沿用原来的
+            refresh_token: text(&["RefreshToken", "refreshToken"]).or_else(|| Some(refresh_token.to_string())),
+        })
+    }
+
     /// 获取用户 Token
     pub async fn get_user_token(&mut self) -> Result<UserTokenResult> {
         // Force API_BASE_SG for token retrieval if US base fails
         // Or detect if we should use SG based on cookies even if it says US
-        let url = format!("{}/cloudide/api/v3/common/GetUserToken", self.api_base);
+        let url = endpoint(&self.api_base, "/cloudide/api/v3/common/GetUserToken");
         
         let mut headers = header::HeaderMap::new();
         headers.insert(header::USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".parse()?);
         headers.insert(header::CONTENT_TYPE, "application/json".parse()?);
         headers.insert(header::ACCEPT, "application/json, text/plain, */*".parse()?);
         // Important: Some endpoints require Origin/Referer to match the base domain
-        headers.insert(header::ORIGIN, "https://www.trae.ai".parse()?);
-        headers.insert(header::REFERER, "https://www.trae.ai/".parse()?);
+        headers.insert(header::ORIGIN, self.web_origin().parse()?);
+        headers.insert(header::REFERER, format!("{}/", self.web_origin()).parse()?);
         
         if !self.cookies.trim().is_empty() {
             let cookie_value = header::HeaderValue::from_bytes(self.cookies.as_bytes())
@@ -329,7 +515,7 @@ impl TraeApiClient {
             .client
             .post(&url)
             .headers(headers.clone()) // Clone for retry if needed
-            .send()
+            .send_checked()
             .await?;
 
         let status = response.status();
@@ -340,10 +526,10 @@ impl TraeApiClient {
              
              // Retry with SG endpoint if US endpoint failed with 401
              if (status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN) 
-                && self.api_base == API_BASE_US {
+                && self.api_base == API_BASE_US && !self.api_base_locked {
                  println!("[DEBUG] Retrying with SG endpoint...");
-                 let url_sg = format!("{}/cloudide/api/v3/common/GetUserToken", API_BASE_SG);
-                 let response_sg = self.client.post(&url_sg).headers(headers).send().await?;
+                 let url_sg = endpoint(API_BASE_SG, "/cloudide/api/v3/common/GetUserToken");
+                 let response_sg = self.client.post(&url_sg).headers(headers).send_checked().await?;
                  
                  if response_sg.status().is_success() {
                      let data: GetUserTokenResponse = response_sg.json().await?;
@@ -367,7 +553,7 @@ impl TraeApiClient {
 
     /// 获取用户信息
     pub async fn get_user_info(&self) -> Result<UserInfoResult> {
-        let url = format!("{}/cloudide/api/v3/trae/GetUserInfo", API_BASE_UG);
+        let url = endpoint(&self.ug_base(), "/cloudide/api/v3/trae/GetUserInfo");
         let headers = self.build_headers(false)?;
 
         let response = self
@@ -375,11 +561,11 @@ impl TraeApiClient {
             .post(&url)
             .headers(headers)
             .json(&json!({"IfWebPage": true}))
-            .send()
+            .send_retryable()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("获取用户信息失败: {}", response.status()));
+            return Err(status_error("获取用户信息失败", response.status()));
         }
 
         let data: GetUserInfoResponse = response.json().await?;
@@ -388,7 +574,7 @@ impl TraeApiClient {
 
     /// 获取用户配额和使用量
     pub async fn get_entitlement_list(&self) -> Result<EntitlementListResponse> {
-        let url = format!("{}/trae/api/v1/pay/user_current_entitlement_list", self.api_base);
+        let url = endpoint(&self.api_base, "/trae/api/v1/pay/user_current_entitlement_list");
         let headers = self.build_headers(true)?;
 
         let response = self
@@ -396,11 +582,11 @@ impl TraeApiClient {
             .post(&url)
             .headers(headers)
             .json(&json!({"require_usage": true}))
-            .send()
+            .send_retryable()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("获取配额信息失败: {}", response.status()));
+            return Err(status_error("获取配额信息失败", response.status()));
         }
 
         let data: EntitlementListResponse = response.json().await?;
@@ -415,10 +601,7 @@ impl TraeApiClient {
         page_size: i32,
         page_num: i32,
     ) -> Result<UsageQueryResponse> {
-        let url = format!(
-            "{}/trae/api/v1/pay/query_user_usage_group_by_session",
-            self.api_base
-        );
+        let url = endpoint(&self.api_base, "/trae/api/v1/pay/query_user_usage_group_by_session");
         let headers = self.build_headers(true)?;
 
         let response = self
@@ -431,11 +614,11 @@ impl TraeApiClient {
                 "page_size": page_size,
                 "page_num": page_num
             }))
-            .send()
+            .send_retryable()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("查询使用记录失败: {}", response.status()));
+            return Err(status_error("查询使用记录失败", response.status()));
         }
 
         let data: UsageQueryResponse = response.json().await?;
@@ -456,12 +639,12 @@ impl TraeApiClient {
     /// 通过 Token 获取使用量汇总
     pub async fn get_usage_summary_by_token(&self) -> Result<UsageSummary> {
         let headers = self.build_headers_token_only()?;
-        let endpoints = [&self.api_base, API_BASE_SG, API_BASE_US];
+        let endpoints = self.candidate_bases();
 
         let mut last_error = anyhow!("所有 API 端点都失败");
 
         for base in endpoints.iter() {
-            let url = format!("{}/trae/api/v1/pay/user_current_entitlement_list", base);
+            let url = endpoint(base, "/trae/api/v1/pay/user_current_entitlement_list");
             println!("[DEBUG] Trying API endpoint: {}", url);
 
             let response = self
@@ -469,7 +652,7 @@ impl TraeApiClient {
                 .post(&url)
                 .headers(headers.clone())
                 .json(&json!({"require_usage": true}))
-                .send()
+                .send_retryable()
                 .await;
 
             match response {
@@ -499,7 +682,7 @@ impl TraeApiClient {
                 Ok(resp) => {
                     println!("[DEBUG] API {} returned error: {}", base, resp.status());
                     // 404 或 403 可能意味着该区域不可用，继续尝试其他区域
-                    last_error = anyhow!("API 返回错误: {}", resp.status());
+                    last_error = status_error("API 返回错误", resp.status());
                 }
                 Err(e) => {
                     println!("[DEBUG] API {} request failed: {}", base, e);
@@ -574,20 +757,79 @@ impl TraeApiClient {
         Ok(summary)
     }
 
+    /// 获取订阅信息（续费时间、自动续费、付款记录）
+    pub async fn get_subscription_info(&self) -> Result<SubscriptionInfo> {
+        let url = endpoint(&self.api_base, "/trae/api/v1/pay/user_current_entitlement_list");
+        let headers = self.build_headers_token_only()?;
+
+        let response = self
+            .client
+            .post(&url)
+            .headers(headers)
+            .json(&json!({"require_usage": false}))
+            .send_retryable()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error("获取订阅信息失败", response.status()));
+        }
+
+        let data: EntitlementListResponse = response.json().await?;
+        Ok(Self::parse_subscription(data))
+    }
+
+    /// 解析配额信息为订阅信息
+    fn parse_subscription(entitlements: EntitlementListResponse) -> SubscriptionInfo {
+        let packs = entitlements.user_entitlement_pack_list;
+        let plan = packs.iter()
+            .filter(|p| p.entitlement_base_info.product_type != 2)
+            .max_by_key(|p| p.entitlement_base_info.product_id);
+
+        let paid = plan.filter(|p| p.entitlement_base_info.product_id != 0);
+        let renews_at = paid
+            .filter(|p| p.next_billing_time > 0 && !p.is_last_period)
+            .map(|p| p.next_billing_time);
+        let expires_at = paid.map(|p| {
+            if p.expire_time > 0 { p.expire_time } else { p.entitlement_base_info.end_time }
+        });
+
+        let mut payments: Vec<PaymentRecord> = packs.iter()
+            .map(|p| &p.entitlement_base_info)
+            .filter(|base| base.charge_amount > 0)
+            .map(|base| PaymentRecord {
+                entitlement_id: base.entitlement_id.clone(),
+                product_type: base.product_type,
+                amount: base.charge_amount,
+                currency: base.currency,
+                start_time: base.start_time,
+                end_time: base.end_time,
+            })
+            .collect();
+        payments.sort_by_key(|p| std::cmp::Reverse(p.start_time));
+
+        SubscriptionInfo {
+            plan_type: if paid.is_some() { "Pro".to_string() } else { "Free".to_string() },
+            renews_at,
+            expires_at,
+            auto_renew: renews_at.is_some(),
+            payments,
+        }
+    }
+
     /// 查询礼包状态
     pub async fn query_birthday_bonus(&self) -> Result<bool> {
-        let url = format!("{}/trae/api/v1/pay/query_birthday_bonus", self.api_base);
+        let url = endpoint(&self.api_base, "/trae/api/v1/pay/query_birthday_bonus");
         let headers = self.build_headers_token_only()?;
 
         let response = self
             .client
             .post(&url)
             .headers(headers)
-            .send()
+            .send_retryable()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("查询礼包状态失败: {}", response.status()));
+            return Err(status_error("查询礼包状态失败", response.status()));
         }
 
         let data: serde_json::Value = response.json().await?;
@@ -598,26 +840,39 @@ impl TraeApiClient {
 
     /// 领取礼包
     pub async fn claim_birthday_bonus(&self) -> Result<()> {
-        let url = format!("{}/trae/api/v1/pay/claim_birthday_bonus", self.api_base);
+        let url = endpoint(&self.api_base, "/trae/api/v1/pay/claim_birthday_bonus");
         let headers = self.build_headers_token_only()?;
 
         let response = self
             .client
             .post(&url)
             .headers(headers)
-            .send()
+            .send_checked()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("领取礼包失败: {}", response.status()));
+            return Err(status_error("领取礼包失败", response.status()));
         }
 
         Ok(())
     }
 
+    /// 列出当前可领取的活动礼包
+    ///
+    /// 目前只有生日礼包的查询和领取接口是确定可用的，其他活动暂不支持
+    pub async fn list_promotions(&self) -> Result<Vec<Promotion>> {
+        Ok(vec![Promotion {
+            id: BIRTHDAY_PROMOTION_ID.to_string(),
+            name: "生日礼包".to_string(),
+            description: String::new(),
+            claimed: self.query_birthday_bonus().await?,
+            expires_at: None,
+        }])
+    }
+
     /// 获取用户统计数据
     pub async fn get_user_statistic_data(&self) -> Result<UserStatisticResult> {
-        let url = format!("{}/cloudide/api/v3/trae/GetUserStasticData", API_BASE_UG);
+        let url = endpoint(&self.ug_base(), "/cloudide/api/v3/trae/GetUserStasticData");
         let headers = self.build_headers(true)?;
 
         // Calculate time info for payload
@@ -637,11 +892,11 @@ impl TraeApiClient {
             .post(&url)
             .headers(headers)
             .json(&payload)
-            .send()
+            .send_retryable()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("获取用户统计数据失败: {}", response.status()));
+            return Err(status_error("获取用户统计数据失败", response.status()));
         }
 
         let data: GetUserStatisticResponse = response.json().await?;
@@ -662,7 +917,7 @@ pub async fn login_with_email(email: &str, password: &str) -> Result<EmailLoginR
     }
 
     let cookie_jar = Arc::new(Jar::default());
-    let client = Client::builder()
+    let client = super::network::client_builder()
         .cookie_store(true)
         .cookie_provider(cookie_jar.clone())
         .build()?;
@@ -672,7 +927,7 @@ pub async fn login_with_email(email: &str, password: &str) -> Result<EmailLoginR
     let _ = client
         .get(init_url)
         .header(header::USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .send()
+        .send_checked()
         .await?;
 
     // Step 2: 获取 CSRF token
@@ -702,11 +957,11 @@ pub async fn login_with_email(email: &str, password: &str) -> Result<EmailLoginR
         .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
         .query(&login_params)
         .form(&login_body)
-        .send()
+        .send_checked()
         .await?;
 
     if !login_response.status().is_success() {
-        return Err(anyhow!("登录请求失败: {}", login_response.status()));
+        return Err(status_error("登录请求失败", login_response.status()));
     }
 
     let login_result: serde_json::Value = login_response.json().await?;
@@ -739,11 +994,11 @@ pub async fn login_with_email(email: &str, password: &str) -> Result<EmailLoginR
         .header(header::ORIGIN, "https://www.trae.ai")
         .header(header::REFERER, "https://www.trae.ai/")
         .header(header::CONTENT_TYPE, "application/json")
-        .send()
+        .send_checked()
         .await?;
 
     if !trae_login_response.status().is_success() {
-        return Err(anyhow!("Trae 登录失败: {}", trae_login_response.status()));
+        return Err(status_error("Trae 登录失败", trae_login_response.status()));
     }
 
     // Detect API base from cookies
@@ -753,8 +1008,8 @@ pub async fn login_with_email(email: &str, password: &str) -> Result<EmailLoginR
         .unwrap_or_default();
     let api_base = TraeApiClient::detect_api_base_from_cookies(&cookies_str);
 
-    // Step 5: 获取用户 Token
-    let token_url = format!("{}/cloudide/api/v3/common/GetUserToken", api_base);
+    // Step 5: 获取用户 Token（登录态保存在 trae.ai 域名的 cookie jar 中，这里不使用自定义 API 地址）
+    let token_url = endpoint(&api_base, "/cloudide/api/v3/common/GetUserToken");
 
     let token_response = client
         .post(&token_url)
@@ -762,11 +1017,11 @@ pub async fn login_with_email(email: &str, password: &str) -> Result<EmailLoginR
         .header(header::ORIGIN, "https://www.trae.ai")
         .header(header::REFERER, "https://www.trae.ai/")
         .header(header::CONTENT_TYPE, "application/json")
-        .send()
+        .send_checked()
         .await?;
 
     if !token_response.status().is_success() {
-        return Err(anyhow!("获取 Token 失败: {}", token_response.status()));
+        return Err(status_error("获取 Token 失败", token_response.status()));
     }
 
     let token_data: GetUserTokenResponse = token_response.json().await?;
@@ -787,5 +1042,6 @@ pub async fn login_with_email(email: &str, password: &str) -> Result<EmailLoginR
         tenant_id: token_data.result.tenant_id,
         cookies,
         expired_at: token_data.result.expired_at,
+        refresh_token: token_data.result.refresh_token,
     })
 }
@@ -23,6 +23,8 @@ pub struct JwtData {
 pub struct JwtPayload {
     pub user_id: String,
     pub tenant_id: String,
+    /// 过期时间（Unix 时间戳，秒）
+    pub exp: i64,
 }
 
 /// 通过 Token 获取的用户信息
@@ -33,6 +35,7 @@ pub struct TokenUserInfo {
     pub screen_name: Option<String>,
     pub avatar_url: Option<String>,
     pub email: Option<String>,
+    pub region: Option<String>,
 }
 
 /// 用户 Token 响应
@@ -62,6 +65,9 @@ pub struct UserTokenResult {
     pub user_id: String,
     #[serde(rename = "TenantID")]
     pub tenant_id: String,
+    /// 刷新 Token（接口返回时才有）
+    #[serde(rename = "RefreshToken", default)]
+    pub refresh_token: Option<String>,
 }
 
 /// 用户信息响应
@@ -247,6 +253,47 @@ pub struct UsageExtraInfo {
     pub output_token: i64,
 }
 
+/// 付款记录（接口未提供独立的订单列表，取自权益包的计费信息）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentRecord {
+    pub entitlement_id: String,
+    /// 权益类型（2 为额外礼包）
+    pub product_type: i32,
+    /// 金额（最小货币单位）
+    pub amount: i64,
+    pub currency: i32,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+/// 订阅信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionInfo {
+    pub plan_type: String,
+    /// 下次自动续费时间，未开启自动续费时为 None
+    pub renews_at: Option<i64>,
+    /// 当前套餐到期时间，Free 套餐为 None
+    pub expires_at: Option<i64>,
+    pub auto_renew: bool,
+    /// 付款记录（按开始时间从新到旧）
+    pub payments: Vec<PaymentRecord>,
+}
+
+/// 可领取的活动礼包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Promotion {
+    /// 活动 ID（生日礼包固定为 birthday_bonus）
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 是否已领取
+    pub claimed: bool,
+    /// 活动截止时间，未知时为 None
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
 /// 简化的使用量汇总（用于前端展示）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageSummary {
@@ -281,6 +328,17 @@ pub struct UsageSummary {
     pub autocomplete_left: f64,
 }
 
+impl UsageSummary {
+    /// 各类请求的已用次数合计（用于判断账号是否在使用）
+    pub fn total_used(&self) -> f64 {
+        self.fast_request_used
+            + self.extra_fast_request_used
+            + self.slow_request_used
+            + self.advanced_model_used
+            + self.autocomplete_used
+    }
+}
+
 impl Default for UsageSummary {
     fn default() -> Self {
         Self {
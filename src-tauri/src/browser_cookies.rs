@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{self, ErrorCode};
+
+/// 只读取这些域名下的 Cookie
+const COOKIE_DOMAINS: &[&str] = &["trae.ai", "trae.com.cn"];
+
+/// 支持读取 Cookie 的浏览器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Browser {
+    Chrome,
+    Edge,
+    Firefox,
+}
+
+impl Browser {
+    pub const ALL: [Browser; 3] = [Browser::Chrome, Browser::Edge, Browser::Firefox];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Browser::Chrome => "Chrome",
+            Browser::Edge => "Edge",
+            Browser::Firefox => "Firefox",
+        }
+    }
+}
+
+/// 从某个浏览器配置文件中读取到的 Trae Cookie
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserCookieSet {
+    pub browser: Browser,
+    pub profile: String,
+    /// Cookie 请求头格式（name=value; name2=value2）
+    pub cookies: String,
+    pub cookie_count: usize,
+}
+
+/// 读取失败的浏览器配置文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserCookieError {
+    pub browser: Browser,
+    pub profile: String,
+    pub error: String,
+}
+
+/// 从浏览器导入账号的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrowserImportReport {
+    pub imported: Vec<crate::account::Account>,
+    /// 已存在的账号（按浏览器/配置文件）
+    pub skipped: Vec<BrowserCookieError>,
+    pub failed: Vec<BrowserCookieError>,
+}
+
+/// 扫描已安装浏览器的所有配置文件，返回包含 Trae Cookie 的配置
+pub fn read_trae_cookies(browsers: &[Browser]) -> (Vec<BrowserCookieSet>, Vec<BrowserCookieError>) {
+    let mut found = Vec::new();
+    let mut errors = Vec::new();
+
+    for &browser in browsers {
+        let profiles = match browser {
+            Browser::Firefox => firefox_profiles(),
+            _ => chromium_profiles(browser),
+        };
+        for (profile, cookie_db) in profiles {
+            let result = match browser {
+                Browser::Firefox => read_firefox_cookies(&cookie_db),
+                _ => read_chromium_cookies(browser, &cookie_db),
+            };
+            match result {
+                Ok(cookies) if cookies.is_empty() => {}
+                Ok(cookies) => {
+                    println!("[INFO] 从 {} ({}) 读取到 {} 个 Trae Cookie", browser.name(), profile, cookies.len());
+                    found.push(BrowserCookieSet {
+                        browser,
+                        profile,
+                        cookie_count: cookies.len(),
+                        cookies: join_cookies(cookies),
+                    });
+                }
+                Err(e) => {
+                    println!("[WARN] 读取 {} ({}) Cookie 失败: {}", browser.name(), profile, e);
+                    errors.push(BrowserCookieError {
+                        browser,
+                        profile,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    (found, errors)
+}
+
+fn join_cookies(cookies: Vec<(String, String)>) -> String {
+    cookies
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// 把用户粘贴的 Cookie 整理为请求头格式（name=value; name2=value2）
+///
+/// 支持开发者工具中复制的 `Cookie:` 请求头和 Netscape 格式的 cookies.txt（只保留 Trae 域名下的 Cookie）
+pub fn parse_cookie_input(raw: &str) -> Result<String> {
+    let raw = raw.trim();
+    let is_cookies_txt = raw.lines().any(|line| {
+        let line = line.trim();
+        line.starts_with("# Netscape HTTP Cookie File")
+            || line.starts_with("# HTTP Cookie File")
+            || (!line.starts_with('#') && line.split('\t').count() >= 7)
+    });
+    let pairs = if is_cookies_txt {
+        parse_cookies_txt(raw)?
+    } else {
+        parse_cookie_header(raw)
+    };
+
+    // 同名 Cookie 只保留第一个
+    let mut seen = std::collections::HashSet::new();
+    let pairs: Vec<(String, String)> = pairs.into_iter().filter(|(name, _)| seen.insert(name.clone())).collect();
+    if pairs.is_empty() {
+        return Err(error::coded(ErrorCode::InvalidInput, "未识别到 Cookie，请粘贴 Cookie 请求头或 cookies.txt 内容"));
+    }
+    Ok(join_cookies(pairs))
+}
+
+fn parse_cookie_header(raw: &str) -> Vec<(String, String)> {
+    let joined = raw.lines().map(str::trim).collect::<Vec<_>>().join("");
+    let value = match joined.split_once(':') {
+        Some((name, value)) if name.trim().eq_ignore_ascii_case("cookie") => value,
+        _ => joined.as_str(),
+    };
+    value
+        .split(';')
+        .filter_map(|part| part.trim().split_once('='))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+/// cookies.txt 每行：domain, include_subdomains, path, secure, expires, name, value（以 Tab 分隔）
+fn parse_cookies_txt(raw: &str) -> Result<Vec<(String, String)>> {
+    let mut found_any = false;
+    let pairs: Vec<(String, String)> = raw
+        .lines()
+        // curl / 部分导出插件用 #HttpOnly_ 前缀标记 HttpOnly Cookie
+        .map(|line| line.strip_prefix("#HttpOnly_").unwrap_or(line))
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            found_any = true;
+            is_trae_domain(fields[0].trim())
+                .then(|| (fields[5].trim().to_string(), fields[6].trim().to_string()))
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect();
+    if found_any && pairs.is_empty() {
+        return Err(error::coded(ErrorCode::InvalidInput, "cookies.txt 中没有 trae.ai 或 trae.com.cn 的 Cookie"));
+    }
+    Ok(pairs)
+}
+
+fn is_trae_domain(host: &str) -> bool {
+    let host = host.trim_start_matches('.');
+    COOKIE_DOMAINS
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+}
+
+/// 浏览器运行时会锁定 Cookie 数据库，复制到临时目录后再读取，用完自动清理
+struct CookieDbCopy {
+    conn: Option<Connection>,
+    dir: PathBuf,
+}
+
+impl CookieDbCopy {
+    fn open(db_path: &Path) -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("trae-cookies-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir)?;
+        let mut copy = Self { conn: None, dir };
+        let temp_db = copy.dir.join("cookies.db");
+        fs::copy(db_path, &temp_db)
+            .map_err(|e| anyhow!("无法复制 Cookie 数据库（请先关闭浏览器后重试）: {}", e))?;
+        for suffix in ["-wal", "-shm"] {
+            let mut side = db_path.as_os_str().to_os_string();
+            side.push(suffix);
+            let side = PathBuf::from(side);
+            if side.exists() {
+                let _ = fs::copy(&side, copy.dir.join(format!("cookies.db{}", suffix)));
+            }
+        }
+        copy.conn = Some(Connection::open_with_flags(&temp_db, OpenFlags::SQLITE_OPEN_READ_ONLY)?);
+        Ok(copy)
+    }
+
+    fn conn(&self) -> &Connection {
+        self.conn.as_ref().expect("connection is opened in CookieDbCopy::open")
+    }
+}
+
+impl Drop for CookieDbCopy {
+    fn drop(&mut self) {
+        // 先关闭连接再删除文件（Windows 下文件被占用时无法删除）
+        self.conn.take();
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+// ==================== Firefox ====================
+
+fn firefox_profiles_dir() -> Option<PathBuf> {
+    let dirs = directories::BaseDirs::new()?;
+    if cfg!(target_os = "windows") {
+        Some(dirs.config_dir().join("Mozilla").join("Firefox").join("Profiles"))
+    } else if cfg!(target_os = "macos") {
+        Some(dirs.config_dir().join("Firefox").join("Profiles"))
+    } else {
+        Some(dirs.home_dir().join(".mozilla").join("firefox"))
+    }
+}
+
+fn firefox_profiles() -> Vec<(String, PathBuf)> {
+    let Some(root) = firefox_profiles_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&root) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join("cookies.sqlite").exists())
+        .map(|path| {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            (name, path.join("cookies.sqlite"))
+        })
+        .collect()
+}
+
+/// Firefox 的 Cookie 以明文保存
+fn read_firefox_cookies(db_path: &Path) -> Result<Vec<(String, String)>> {
+    let db = CookieDbCopy::open(db_path)?;
+    let mut stmt = db.conn().prepare("SELECT host, name, value FROM moz_cookies")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    })?;
+    let mut cookies = HashMap::new();
+    for (host, name, value) in rows.flatten() {
+        if is_trae_domain(&host) && !value.is_empty() {
+            cookies.insert(name, value);
+        }
+    }
+    Ok(cookies.into_iter().collect())
+}
+
+// ==================== Chrome / Edge ====================
+
+fn chromium_user_data_dir(browser: Browser) -> Option<PathBuf> {
+    let dirs = directories::BaseDirs::new()?;
+    let path = if cfg!(target_os = "windows") {
+        match browser {
+            Browser::Chrome => dirs.data_local_dir().join("Google").join("Chrome").join("User Data"),
+            _ => dirs.data_local_dir().join("Microsoft").join("Edge").join("User Data"),
+        }
+    } else if cfg!(target_os = "macos") {
+        match browser {
+            Browser::Chrome => dirs.config_dir().join("Google").join("Chrome"),
+            _ => dirs.config_dir().join("Microsoft Edge"),
+        }
+    } else {
+        match browser {
+            Browser::Chrome => dirs.config_dir().join("google-chrome"),
+            _ => dirs.config_dir().join("microsoft-edge"),
+        }
+    };
+    Some(path)
+}
+
+fn chromium_profiles(browser: Browser) -> Vec<(String, PathBuf)> {
+    let Some(root) = chromium_user_data_dir(browser) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&root) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            if name != "Default" && !name.starts_with("Profile ") {
+                return None;
+            }
+            // 新版本 Cookie 数据库位于 Network 子目录
+            let db = [path.join("Network").join("Cookies"), path.join("Cookies")]
+                .into_iter()
+                .find(|p| p.exists())?;
+            Some((name, db))
+        })
+        .collect()
+}
+
+fn read_chromium_cookies(browser: Browser, db_path: &Path) -> Result<Vec<(String, String)>> {
+    let db = CookieDbCopy::open(db_path)?;
+    // meta.version >= 24 时解密结果前 32 字节是域名哈希
+    let db_version: i64 = db
+        .conn()
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| row.get::<_, String>(0))
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let mut stmt = db.conn().prepare("SELECT host_key, name, value, encrypted_value FROM cookies")?;
+    let rows: Vec<(String, String, String, Vec<u8>)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Vec<u8>>(3)?,
+            ))
+        })?
+        .flatten()
+        .filter(|(host, ..)| is_trae_domain(host))
+        .collect();
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let user_data_dir = chromium_user_data_dir(browser).ok_or_else(|| anyhow!("无法获取浏览器数据目录"))?;
+    let key = chromium_key(browser, &user_data_dir)?;
+
+    let mut cookies = HashMap::new();
+    for (_, name, value, encrypted) in rows {
+        let value = if !value.is_empty() || encrypted.is_empty() {
+            value
+        } else {
+            let mut plain = decrypt_chromium_value(&key, &encrypted)?;
+            if db_version >= 24 && plain.len() >= 32 {
+                plain.drain(..32);
+            }
+            String::from_utf8(plain).map_err(|_| anyhow!("Cookie {} 解密结果无效", name))?
+        };
+        if !value.is_empty() {
+            cookies.insert(name, value);
+        }
+    }
+    Ok(cookies.into_iter().collect())
+}
+
+/// Windows: Local State 中的密钥经 DPAPI 保护，Cookie 使用 AES-256-GCM 加密
+#[cfg(target_os = "windows")]
+fn chromium_key(_browser: Browser, user_data_dir: &Path) -> Result<Vec<u8>> {
+    use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+    let local_state = fs::read_to_string(user_data_dir.join("Local State"))?;
+    let local_state: serde_json::Value = serde_json::from_str(&local_state)?;
+    let encoded = local_state["os_crypt"]["encrypted_key"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Local State 中没有加密密钥"))?;
+    let encrypted = BASE64.decode(encoded)?;
+    let encrypted = encrypted
+        .strip_prefix(b"DPAPI")
+        .ok_or_else(|| anyhow!("不支持的密钥格式"))?;
+    dpapi_decrypt(encrypted)
+}
+
+#[cfg(target_os = "windows")]
+fn dpapi_decrypt(data: &[u8]) -> Result<Vec<u8>> {
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Cryptography::{CryptUnprotectData, CRYPT_INTEGER_BLOB};
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB {
+        cbData: 0,
+        pbData: std::ptr::null_mut(),
+    };
+    unsafe {
+        let ok = CryptUnprotectData(
+            &input,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null(),
+            0,
+            &mut output,
+        );
+        if ok == 0 {
+            return Err(anyhow!("DPAPI 解密失败"));
+        }
+        let result = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+        LocalFree(output.pbData as _);
+        Ok(result)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn decrypt_chromium_value(key: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    match encrypted.get(..3) {
+        Some(b"v10") | Some(b"v11") => {
+            if encrypted.len() < 3 + 12 + 16 {
+                return Err(anyhow!("Cookie 密文长度无效"));
+            }
+            let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| anyhow!("浏览器密钥长度无效"))?;
+            cipher
+                .decrypt(Nonce::from_slice(&encrypted[3..15]), &encrypted[15..])
+                .map_err(|_| anyhow!("Cookie 解密失败"))
+        }
+        Some(b"v20") => Err(anyhow!("浏览器启用了应用绑定加密（v20），无法直接读取 Cookie，请使用内置登录窗口")),
+        // 旧版本直接使用 DPAPI 加密
+        _ => dpapi_decrypt(encrypted),
+    }
+}
+
+/// macOS: 密钥保存在钥匙串 "<浏览器> Safe Storage" 中，Cookie 使用 AES-128-CBC 加密
+#[cfg(target_os = "macos")]
+fn chromium_key(browser: Browser, _user_data_dir: &Path) -> Result<Vec<u8>> {
+    let (service, account) = match browser {
+        Browser::Chrome => ("Chrome Safe Storage", "Chrome"),
+        _ => ("Microsoft Edge Safe Storage", "Microsoft Edge"),
+    };
+    let password = keyring::Entry::new(service, account)?
+        .get_password()
+        .map_err(|e| anyhow!("无法从钥匙串读取 {}: {}", service, e))?;
+    Ok(derive_cbc_key(password.as_bytes(), 1003))
+}
+
+/// Linux: 未接入密钥环时浏览器使用固定密码 "peanuts"（v10）
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn chromium_key(_browser: Browser, _user_data_dir: &Path) -> Result<Vec<u8>> {
+    Ok(derive_cbc_key(b"peanuts", 1))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn derive_cbc_key(password: &[u8], iterations: u32) -> Vec<u8> {
+    let mut key = vec![0u8; 16];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password, b"saltysalt", iterations, &mut key);
+    key
+}
+
+#[cfg(not(target_os = "windows"))]
+fn decrypt_chromium_value(key: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
+    use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+
+    match encrypted.get(..3) {
+        Some(b"v10") => {
+            let iv = [b' '; 16];
+            cbc::Decryptor::<aes::Aes128>::new_from_slices(key, &iv)
+                .map_err(|_| anyhow!("浏览器密钥长度无效"))?
+                .decrypt_padded_vec_mut::<Pkcs7>(&encrypted[3..])
+                .map_err(|_| anyhow!("Cookie 解密失败"))
+        }
+        Some(b"v11") => Err(anyhow!("Cookie 由系统密钥环加密，暂不支持读取")),
+        _ => Err(anyhow!("不支持的 Cookie 加密格式")),
+    }
+}
@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, WebviewWindow};
+
+use crate::api::network::client_builder;
+use crate::error::{self, ErrorCode};
+use crate::register_steps::{self, RegisterStep};
+
+/// 等待打码服务返回结果的最长时间
+const SOLVE_TIMEOUT: Duration = Duration::from_secs(180);
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 第三方打码服务配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptchaSolverConfig {
+    /// 不使用打码服务，出现人机验证时由用户手动完成
+    #[default]
+    Disabled,
+    /// 2Captcha
+    TwoCaptcha { api_key: String },
+    /// Anti-Captcha
+    AntiCaptcha { api_key: String },
+}
+
+impl CaptchaSolverConfig {
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, CaptchaSolverConfig::Disabled)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            CaptchaSolverConfig::Disabled => Ok(()),
+            CaptchaSolverConfig::TwoCaptcha { api_key } | CaptchaSolverConfig::AntiCaptcha { api_key } => {
+                if api_key.trim().is_empty() {
+                    return Err(error::coded(ErrorCode::InvalidInput, "打码服务 API Key 不能为空"));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 服务名称和接口地址（两者都兼容 createTask / getTaskResult 接口）
+    fn endpoint(&self) -> Option<(&'static str, &'static str, &str)> {
+        match self {
+            CaptchaSolverConfig::Disabled => None,
+            CaptchaSolverConfig::TwoCaptcha { api_key } => Some(("2Captcha", "https://api.2captcha.com", api_key.trim())),
+            CaptchaSolverConfig::AntiCaptcha { api_key } => {
+                Some(("Anti-Captcha", "https://api.anti-captcha.com", api_key.trim()))
+            }
+        }
+    }
+}
+
+/// 人机验证类型（由注册页脚本识别）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptchaKind {
+    Recaptcha,
+    Hcaptcha,
+    Turnstile,
+}
+
+impl CaptchaKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "recaptcha" => Some(CaptchaKind::Recaptcha),
+            "hcaptcha" => Some(CaptchaKind::Hcaptcha),
+            "turnstile" => Some(CaptchaKind::Turnstile),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            CaptchaKind::Recaptcha => "recaptcha",
+            CaptchaKind::Hcaptcha => "hcaptcha",
+            CaptchaKind::Turnstile => "turnstile",
+        }
+    }
+
+    fn task_type(self) -> &'static str {
+        match self {
+            CaptchaKind::Recaptcha => "RecaptchaV2TaskProxyless",
+            CaptchaKind::Hcaptcha => "HCaptchaTaskProxyless",
+            CaptchaKind::Turnstile => "TurnstileTaskProxyless",
+        }
+    }
+}
+
+/// 注册页上识别到的人机验证
+#[derive(Debug, Clone)]
+pub struct CaptchaChallenge {
+    pub kind: CaptchaKind,
+    pub site_key: String,
+    pub page_url: String,
+}
+
+/// 调用打码服务获取验证结果 Token
+pub async fn solve(config: &CaptchaSolverConfig, challenge: &CaptchaChallenge) -> Result<String> {
+    let (name, base, api_key) = config.endpoint().ok_or_else(|| anyhow!("未配置打码服务"))?;
+    let client = client_builder().timeout(Duration::from_secs(30)).build()?;
+
+    let created = post(
+        &client,
+        name,
+        &format!("{}/createTask", base),
+        json!({
+            "clientKey": api_key,
+            "task": {
+                "type": challenge.kind.task_type(),
+                "websiteURL": challenge.page_url,
+                "websiteKey": challenge.site_key,
+            },
+        }),
+    )
+    .await?;
+    let task_id = created
+        .get("taskId")
+        .cloned()
+        .ok_or_else(|| anyhow!("{} 未返回任务 ID", name))?;
+    println!("[INFO] {} 已创建打码任务 {}", name, task_id);
+
+    let started = std::time::Instant::now();
+    while started.elapsed() < SOLVE_TIMEOUT {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let result = post(
+            &client,
+            name,
+            &format!("{}/getTaskResult", base),
+            json!({ "clientKey": api_key, "taskId": task_id }),
+        )
+        .await?;
+        if result.get("status").and_then(|v| v.as_str()) != Some("ready") {
+            continue;
+        }
+        let solution = result.get("solution");
+        let token = solution
+            .and_then(|s| s.get("gRecaptchaResponse").or_else(|| s.get("token")))
+            .and_then(|v| v.as_str())
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| anyhow!("{} 返回的结果中没有 Token", name))?;
+        return Ok(token.to_string());
+    }
+    Err(anyhow!("{} 在 {} 秒内未返回结果", name, SOLVE_TIMEOUT.as_secs()))
+}
+
+async fn post(client: &Client, name: &str, url: &str, body: Value) -> Result<Value> {
+    let response = client.post(url).json(&body).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("{} 请求失败: HTTP {}", name, status));
+    }
+    let data: Value = response.json().await?;
+    if data.get("errorId").and_then(|v| v.as_i64()).unwrap_or(0) != 0 {
+        let code = data.get("errorCode").and_then(|v| v.as_str()).unwrap_or("");
+        let description = data.get("errorDescription").and_then(|v| v.as_str()).unwrap_or("");
+        return Err(anyhow!("{} 返回错误 {}: {}", name, code, description));
+    }
+    Ok(data)
+}
+
+/// 后台调用打码服务并把结果填回注册页；失败时显示注册窗口交给用户手动完成
+pub fn spawn_solve(
+    app: &AppHandle,
+    webview: &WebviewWindow,
+    window_label: &str,
+    step: RegisterStep,
+    config: CaptchaSolverConfig,
+    challenge: CaptchaChallenge,
+) {
+    let app = app.clone();
+    let webview = webview.clone();
+    let window_label = window_label.to_string();
+    tauri::async_runtime::spawn(async move {
+        match solve(&config, &challenge).await {
+            Ok(token) => {
+                println!("[quick-register] 打码服务已返回 {} 结果", challenge.kind.as_str());
+                let kind_js = serde_json::to_string(challenge.kind.as_str()).unwrap_or_else(|_| "\"\"".to_string());
+                let token_js = serde_json::to_string(&token).unwrap_or_else(|_| "\"\"".to_string());
+                let _ = webview.eval(format!(
+                    "window.__traeAutoRegister && window.__traeAutoRegister.applyCaptchaToken({}, {});",
+                    kind_js, token_js
+                ));
+            }
+            Err(err) => {
+                println!("[WARN] 打码服务处理失败，改为手动验证: {}", err);
+                register_steps::hand_off_captcha(&app, &webview, &window_label, step);
+            }
+        }
+    });
+}
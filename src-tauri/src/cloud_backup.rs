@@ -0,0 +1,295 @@
+use std::fmt::Write as _;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
+use reqwest::{header, Client, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::network::{client_builder, hex_encode};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Gist 中保存备份的文件名（历史备份通过 Gist 的修订记录保留）
+const GIST_FILE_NAME: &str = "trae-accounts-backup.json";
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub type UploadFuture<'a> = Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+
+/// 远程备份目标
+pub trait BackupTarget: Send + Sync {
+    /// 目标名称（用于日志）
+    fn name(&self) -> &'static str;
+
+    /// 上传备份内容，返回远程位置（URL / 对象键 / Gist ID）
+    fn upload<'a>(&'a self, file_name: &'a str, content: &'a [u8]) -> UploadFuture<'a>;
+}
+
+/// 云端备份配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CloudBackupConfig {
+    /// 不上传
+    #[default]
+    Disabled,
+    /// WebDAV 目录（如坚果云、Nextcloud）
+    Webdav {
+        url: String,
+        username: String,
+        password: String,
+    },
+    /// S3 兼容存储（AWS S3、Cloudflare R2、MinIO 等，使用路径风格地址）
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        #[serde(default)]
+        prefix: String,
+        access_key_id: String,
+        secret_access_key: String,
+    },
+    /// 私有 GitHub Gist，gist_id 为空时首次上传会自动创建
+    Gist {
+        token: String,
+        #[serde(default)]
+        gist_id: String,
+    },
+}
+
+impl CloudBackupConfig {
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, CloudBackupConfig::Disabled)
+    }
+
+    /// 按配置创建备份目标，未启用时返回 None
+    pub fn target(&self) -> Result<Option<Box<dyn BackupTarget>>> {
+        let client = client_builder().timeout(UPLOAD_TIMEOUT).build()?;
+        let target: Box<dyn BackupTarget> = match self {
+            CloudBackupConfig::Disabled => return Ok(None),
+            CloudBackupConfig::Webdav { url, username, password } => {
+                let url = url.trim();
+                if !(url.starts_with("https://") || url.starts_with("http://")) {
+                    return Err(anyhow!("WebDAV 地址无效"));
+                }
+                Box::new(WebdavTarget {
+                    client,
+                    base_url: url.trim_end_matches('/').to_string(),
+                    username: username.clone(),
+                    password: password.clone(),
+                })
+            }
+            CloudBackupConfig::S3 { endpoint, region, bucket, prefix, access_key_id, secret_access_key } => {
+                let endpoint = Url::parse(endpoint.trim()).map_err(|e| anyhow!("S3 地址无效: {}", e))?;
+                if bucket.trim().is_empty() || access_key_id.trim().is_empty() || secret_access_key.is_empty() {
+                    return Err(anyhow!("S3 配置不完整"));
+                }
+                Box::new(S3Target {
+                    client,
+                    endpoint,
+                    region: Some(region.trim().to_string())
+                        .filter(|r| !r.is_empty())
+                        .unwrap_or_else(|| "us-east-1".to_string()),
+                    bucket: bucket.trim().to_string(),
+                    prefix: prefix.trim().trim_matches('/').to_string(),
+                    access_key_id: access_key_id.trim().to_string(),
+                    secret_access_key: secret_access_key.clone(),
+                })
+            }
+            CloudBackupConfig::Gist { token, gist_id } => {
+                if token.trim().is_empty() {
+                    return Err(anyhow!("GitHub Token 不能为空"));
+                }
+                Box::new(GistTarget {
+                    client,
+                    token: token.trim().to_string(),
+                    gist_id: Some(gist_id.trim().to_string()).filter(|id| !id.is_empty()),
+                })
+            }
+        };
+        Ok(Some(target))
+    }
+}
+
+/// 检查上传响应状态，失败时带上服务端返回的内容
+async fn check_response(response: reqwest::Response, target: &str) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(anyhow!("{} 上传失败 ({}): {}", target, status, body.chars().take(200).collect::<String>()))
+}
+
+struct WebdavTarget {
+    client: Client,
+    base_url: String,
+    username: String,
+    password: String,
+}
+
+impl BackupTarget for WebdavTarget {
+    fn name(&self) -> &'static str {
+        "WebDAV"
+    }
+
+    fn upload<'a>(&'a self, file_name: &'a str, content: &'a [u8]) -> UploadFuture<'a> {
+        Box::pin(async move {
+            let url = format!("{}/{}", self.base_url, file_name);
+            let response = self
+                .client
+                .put(&url)
+                .basic_auth(&self.username, Some(&self.password))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(content.to_vec())
+                .send()
+                .await?;
+            check_response(response, self.name()).await?;
+            Ok(url)
+        })
+    }
+}
+
+struct S3Target {
+    client: Client,
+    endpoint: Url,
+    region: String,
+    bucket: String,
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+/// S3 路径编码（保留 /，其余非 unreserved 字符按 RFC 3986 编码）
+fn s3_uri_encode(path: &str) -> String {
+    path.bytes().fold(String::with_capacity(path.len()), |mut out, b| {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => out.push(b as char),
+            _ => {
+                let _ = write!(out, "%{:02X}", b);
+            }
+        }
+        out
+    })
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Result<Vec<u8>> {
+    let mut mac = HmacSha256::new_from_slice(key).map_err(|e| anyhow!("HMAC 密钥无效: {}", e))?;
+    mac.update(data.as_bytes());
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+impl S3Target {
+    /// AWS Signature V4 签名，返回 Authorization 头
+    fn authorization(&self, host: &str, canonical_uri: &str, payload_hash: &str, amz_date: &str) -> Result<String> {
+        let date = &amz_date[..8];
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "PUT\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            canonical_uri, host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex_encode(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let mut key = hmac_sha256(format!("AWS4{}", self.secret_access_key).as_bytes(), date)?;
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            key = hmac_sha256(&key, part)?;
+        }
+        let signature = hex_encode(&hmac_sha256(&key, &string_to_sign)?);
+        Ok(format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id, scope, signed_headers, signature
+        ))
+    }
+}
+
+impl BackupTarget for S3Target {
+    fn name(&self) -> &'static str {
+        "S3"
+    }
+
+    fn upload<'a>(&'a self, file_name: &'a str, content: &'a [u8]) -> UploadFuture<'a> {
+        Box::pin(async move {
+            let key = if self.prefix.is_empty() {
+                file_name.to_string()
+            } else {
+                format!("{}/{}", self.prefix, file_name)
+            };
+            let host = match (self.endpoint.host_str(), self.endpoint.port()) {
+                (Some(host), Some(port)) => format!("{}:{}", host, port),
+                (Some(host), None) => host.to_string(),
+                _ => return Err(anyhow!("S3 地址缺少主机名")),
+            };
+            let base_path = self.endpoint.path().trim_end_matches('/');
+            let canonical_uri = s3_uri_encode(&format!("{}/{}/{}", base_path, self.bucket, key));
+            let payload_hash = hex_encode(&Sha256::digest(content));
+            let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+            let authorization = self.authorization(&host, &canonical_uri, &payload_hash, &amz_date)?;
+
+            let url = format!("{}://{}{}", self.endpoint.scheme(), host, canonical_uri);
+            let response = self
+                .client
+                .put(&url)
+                .header(header::AUTHORIZATION, authorization)
+                .header("x-amz-content-sha256", payload_hash)
+                .header("x-amz-date", amz_date)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(content.to_vec())
+                .send()
+                .await?;
+            check_response(response, self.name()).await?;
+            Ok(format!("s3://{}/{}", self.bucket, key))
+        })
+    }
+}
+
+struct GistTarget {
+    client: Client,
+    token: String,
+    gist_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GistResponse {
+    id: String,
+}
+
+impl BackupTarget for GistTarget {
+    fn name(&self) -> &'static str {
+        "GitHub Gist"
+    }
+
+    fn upload<'a>(&'a self, file_name: &'a str, content: &'a [u8]) -> UploadFuture<'a> {
+        Box::pin(async move {
+            let text = std::str::from_utf8(content).map_err(|_| anyhow!("Gist 只支持文本内容"))?;
+            let mut body = serde_json::json!({
+                "description": format!("Trae Account Manager 备份 ({})", file_name),
+                "files": { GIST_FILE_NAME: { "content": text } },
+            });
+            let request = match &self.gist_id {
+                Some(id) => self.client.patch(format!("https://api.github.com/gists/{}", id)),
+                None => {
+                    body["public"] = serde_json::Value::Bool(false);
+                    self.client.post("https://api.github.com/gists")
+                }
+            };
+            let response = request
+                .bearer_auth(&self.token)
+                .header(header::ACCEPT, "application/vnd.github+json")
+                .header(header::USER_AGENT, "trae-account-manager")
+                .header("X-GitHub-Api-Version", "2022-11-28")
+                .json(&body)
+                .send()
+                .await?;
+            let gist: GistResponse = check_response(response, self.name()).await?.json().await?;
+            Ok(gist.id)
+        })
+    }
+}
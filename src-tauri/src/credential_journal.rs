@@ -0,0 +1,230 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Result};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{self, ErrorCode};
+
+const JOURNAL_FILE: &str = "credential-journal.log";
+/// 系统密钥链不可用时使用的密钥文件（与日志放在同一目录）
+const KEY_FILE: &str = "credential-journal.key";
+const KEYRING_SERVICE: &str = "trae-account-manager";
+const KEYRING_USER: &str = "credential-journal-key";
+
+static KEYRING_KEY: OnceCell<Option<Key<Aes256Gcm>>> = OnceCell::new();
+static FILE_KEY: OnceCell<Key<Aes256Gcm>> = OnceCell::new();
+/// 追加写入时加锁，避免并行注册的记录交错
+static WRITE_LOCK: StdMutex<()> = StdMutex::new(());
+
+/// 注册结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialStatus {
+    /// 注册已完成（已获取登录 Token），尚未导入账号
+    SignedUp,
+    /// 账号已导入
+    Added,
+    Failed,
+}
+
+/// 注册凭据记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialRecord {
+    pub at: i64,
+    pub email: String,
+    pub password: String,
+    pub status: CredentialStatus,
+    pub account_id: Option<String>,
+    pub mail_provider: Option<String>,
+    /// 失败原因
+    pub reason: Option<String>,
+}
+
+impl CredentialRecord {
+    pub fn new(email: &str, password: &str, status: CredentialStatus) -> Self {
+        Self {
+            at: chrono::Utc::now().timestamp(),
+            email: email.to_string(),
+            password: password.to_string(),
+            status,
+            account_id: None,
+            mail_provider: None,
+            reason: None,
+        }
+    }
+}
+
+/// 日志中的一行：单独加密的一条记录
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalLine {
+    nonce: String,
+    data: String,
+}
+
+fn journal_path() -> Result<PathBuf> {
+    Ok(crate::account::AccountManager::data_dir()?.join(JOURNAL_FILE))
+}
+
+/// 从系统密钥链读取日志加密密钥，不存在时生成并保存
+fn keyring_key() -> Option<&'static Key<Aes256Gcm>> {
+    KEYRING_KEY
+        .get_or_init(|| match load_or_create_key() {
+            Ok(key) => Some(key),
+            Err(e) => {
+                println!("[WARN] 系统密钥链不可用，注册凭据日志改用数据目录中的密钥文件加密: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+fn load_or_create_key() -> Result<Key<Aes256Gcm>> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+    let bytes = match entry.get_password() {
+        Ok(encoded) => BASE64
+            .decode(encoded.trim())
+            .map_err(|e| anyhow!("密钥链中的凭据日志密钥已损坏: {}", e))?,
+        Err(keyring::Error::NoEntry) => {
+            let mut key = vec![0u8; 32];
+            OsRng.fill_bytes(&mut key);
+            entry.set_password(&BASE64.encode(&key))?;
+            println!("[INFO] 已在系统密钥链中创建注册凭据日志密钥");
+            key
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if bytes.len() != 32 {
+        return Err(anyhow!("密钥链中的凭据日志密钥长度错误"));
+    }
+    Ok(Key::<Aes256Gcm>::clone_from_slice(&bytes))
+}
+
+/// 读取数据目录中的密钥文件，create 为 true 且文件不存在时生成
+///
+/// 只在系统密钥链不可用时使用，避免注册凭据因为没有密钥而丢失
+fn file_key(create: bool) -> Result<Option<&'static Key<Aes256Gcm>>> {
+    if let Some(key) = FILE_KEY.get() {
+        return Ok(Some(key));
+    }
+    let path = crate::account::AccountManager::data_dir()?.join(KEY_FILE);
+    let bytes = if path.exists() {
+        BASE64
+            .decode(fs::read_to_string(&path)?.trim())
+            .map_err(|e| anyhow!("凭据日志密钥文件已损坏: {}", e))?
+    } else if create {
+        let mut key = vec![0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        write_key_file(&path, &BASE64.encode(&key))?;
+        println!("[INFO] 已创建注册凭据日志密钥文件: {}", path.display());
+        key
+    } else {
+        return Ok(None);
+    };
+    if bytes.len() != 32 {
+        return Err(anyhow!("凭据日志密钥文件长度错误"));
+    }
+    Ok(Some(FILE_KEY.get_or_init(|| Key::<Aes256Gcm>::clone_from_slice(&bytes))))
+}
+
+/// 写入密钥文件（Unix 上只允许当前用户读写）
+fn write_key_file(path: &Path, content: &str) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_data()?;
+    Ok(())
+}
+
+/// 写入日志使用的密钥：优先使用系统密钥链，不可用时使用密钥文件
+fn journal_key() -> Result<&'static Key<Aes256Gcm>> {
+    match keyring_key() {
+        Some(key) => Ok(key),
+        None => file_key(true)?.ok_or_else(|| anyhow!("无法创建凭据日志密钥文件")),
+    }
+}
+
+/// 追加一条注册凭据记录（只追加，不修改已有记录）；失败时只记录警告，不影响注册
+pub fn append(record: &CredentialRecord) {
+    if let Err(e) = try_append(record) {
+        println!("[WARN] 写入注册凭据日志失败: {}", e);
+    }
+}
+
+fn try_append(record: &CredentialRecord) -> Result<()> {
+    let key = journal_key()?;
+    let plaintext = serde_json::to_vec(record)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(key)
+        .encrypt(&nonce, plaintext.as_ref())
+        .map_err(|_| anyhow!("加密失败"))?;
+    let line = serde_json::to_string(&JournalLine {
+        nonce: BASE64.encode(nonce),
+        data: BASE64.encode(ciphertext),
+    })?;
+
+    let _lock = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = OpenOptions::new().create(true).append(true).open(journal_path()?)?;
+    writeln!(file, "{}", line)?;
+    file.sync_data()?;
+    Ok(())
+}
+
+/// 读取全部注册凭据记录（最新的在前），无法解密的行会被跳过
+pub fn read_all() -> Result<Vec<CredentialRecord>> {
+    let path = journal_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    // 密钥链曾经不可用时，部分记录是用密钥文件加密的，两个密钥都要尝试
+    let ciphers: Vec<Aes256Gcm> = keyring_key()
+        .into_iter()
+        .chain(file_key(false).unwrap_or_else(|e| {
+            println!("[WARN] 读取凭据日志密钥文件失败: {}", e);
+            None
+        }))
+        .map(Aes256Gcm::new)
+        .collect();
+    if ciphers.is_empty() {
+        return Err(error::coded(ErrorCode::Unknown, "系统密钥链不可用且没有凭据日志密钥文件，无法读取注册凭据日志"));
+    }
+    let content = fs::read_to_string(path)?;
+    let mut skipped = 0;
+    let mut records: Vec<CredentialRecord> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let record = ciphers.iter().find_map(|cipher| decrypt_line(cipher, line));
+            if record.is_none() {
+                skipped += 1;
+            }
+            record
+        })
+        .collect();
+    if skipped > 0 {
+        println!("[WARN] 注册凭据日志中有 {} 行无法解密，已跳过", skipped);
+    }
+    records.reverse();
+    Ok(records)
+}
+
+fn decrypt_line(cipher: &Aes256Gcm, line: &str) -> Option<CredentialRecord> {
+    let line: JournalLine = serde_json::from_str(line).ok()?;
+    let nonce = BASE64.decode(line.nonce).ok().filter(|n| n.len() == 12)?;
+    let ciphertext = BASE64.decode(line.data).ok()?;
+    let plaintext = cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref()).ok()?;
+    serde_json::from_slice(&plaintext).ok()
+}
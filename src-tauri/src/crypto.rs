@@ -0,0 +1,97 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Result};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+const EXPORT_FORMAT: &str = "trae-account-manager/encrypted";
+const EXPORT_VERSION: u32 = 1;
+const KDF_NAME: &str = "pbkdf2-sha256";
+const PBKDF2_ITERATIONS: u32 = 210_000;
+/// 导入时接受的迭代次数上限，迭代次数来自导入文件，过大会导致解密长时间卡住
+const MAX_PBKDF2_ITERATIONS: u32 = 10 * PBKDF2_ITERATIONS;
+const SALT_LEN: usize = 16;
+
+/// 加密导出文件的头部与密文（JSON 格式，便于识别版本）
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedExport {
+    format: String,
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    data: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Key<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key.into()
+}
+
+fn parse_envelope(data: &str) -> Option<EncryptedExport> {
+    serde_json::from_str::<EncryptedExport>(data.trim())
+        .ok()
+        .filter(|e| e.format == EXPORT_FORMAT)
+}
+
+/// 判断导入内容是否为加密导出格式
+pub fn is_encrypted_export(data: &str) -> bool {
+    parse_envelope(data).is_some()
+}
+
+/// 使用密码加密导出内容
+pub fn encrypt_export(plaintext: &str, passphrase: &str) -> Result<String> {
+    if passphrase.is_empty() {
+        return Err(anyhow!("导出密码不能为空"));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(&key)
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| anyhow!("加密失败"))?;
+
+    let envelope = EncryptedExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        kdf: KDF_NAME.to_string(),
+        iterations: PBKDF2_ITERATIONS,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        data: BASE64.encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&envelope).map_err(|e| anyhow!("导出失败: {}", e))
+}
+
+/// 使用密码解密导出内容
+pub fn decrypt_export(data: &str, passphrase: &str) -> Result<String> {
+    let envelope = parse_envelope(data).ok_or_else(|| anyhow!("不是有效的加密导出文件"))?;
+    if envelope.version > EXPORT_VERSION {
+        return Err(anyhow!("不支持的加密导出版本: {}，请升级程序", envelope.version));
+    }
+    if envelope.kdf != KDF_NAME {
+        return Err(anyhow!("不支持的密钥派生算法: {}", envelope.kdf));
+    }
+    if !(PBKDF2_ITERATIONS..=MAX_PBKDF2_ITERATIONS).contains(&envelope.iterations) {
+        return Err(anyhow!("加密文件的迭代次数无效: {}", envelope.iterations));
+    }
+
+    let salt = BASE64.decode(&envelope.salt).map_err(|e| anyhow!("加密文件已损坏: {}", e))?;
+    let nonce = BASE64.decode(&envelope.nonce).map_err(|e| anyhow!("加密文件已损坏: {}", e))?;
+    let ciphertext = BASE64.decode(&envelope.data).map_err(|e| anyhow!("加密文件已损坏: {}", e))?;
+    if nonce.len() != 12 {
+        return Err(anyhow!("加密文件已损坏: nonce 长度错误"));
+    }
+
+    let key = derive_key(passphrase, &salt, envelope.iterations);
+    let plaintext = Aes256Gcm::new(&key)
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| anyhow!("密码错误或文件已损坏"))?;
+    String::from_utf8(plaintext).map_err(|e| anyhow!("解密内容不是有效文本: {}", e))
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// 错误分类（随 ApiError 返回给前端，前端据此处理而不必匹配错误信息）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Token 或 Cookies 已失效（HTTP 401）
+    #[serde(alias = "token_expired")]
+    AuthExpired,
+    /// 服务器拒绝访问（HTTP 403）
+    Forbidden,
+    /// 账号、文件等不存在
+    NotFound,
+    /// 请求过于频繁（HTTP 429）
+    RateLimited,
+    Timeout,
+    /// 网络连接失败或服务器错误
+    Network,
+    /// 未安装 Trae IDE 或未找到其数据目录
+    TraeNotInstalled,
+    /// 没有文件或目录的访问权限
+    PermissionDenied,
+    /// 参数或设置无效
+    InvalidInput,
+    /// 账号数据正被另一个实例使用
+    ReadOnly,
+    /// 用户取消了操作（如关闭登录窗口）
+    Cancelled,
+    #[default]
+    Unknown,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::AuthExpired => "auth_expired",
+            ErrorCode::Forbidden => "forbidden",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::Network => "network",
+            ErrorCode::TraeNotInstalled => "trae_not_installed",
+            ErrorCode::PermissionDenied => "permission_denied",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::ReadOnly => "read_only",
+            ErrorCode::Cancelled => "cancelled",
+            ErrorCode::Unknown => "unknown",
+        }
+    }
+
+    /// 按 HTTP 状态码分类
+    pub fn from_status(status: reqwest::StatusCode) -> Self {
+        match status.as_u16() {
+            401 => ErrorCode::AuthExpired,
+            403 => ErrorCode::Forbidden,
+            404 => ErrorCode::NotFound,
+            429 => ErrorCode::RateLimited,
+            408 | 504 => ErrorCode::Timeout,
+            500..=599 => ErrorCode::Network,
+            _ => ErrorCode::Unknown,
+        }
+    }
+
+    /// 错误的分类：优先使用错误链中显式标注的分类，其次按底层错误类型判断，最后按错误信息推断
+    pub fn of(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if let Some(coded) = cause.downcast_ref::<CodedError>() {
+                return coded.code;
+            }
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                if e.is_timeout() {
+                    return ErrorCode::Timeout;
+                }
+                if let Some(status) = e.status() {
+                    return Self::from_status(status);
+                }
+                if e.is_connect() || e.is_request() {
+                    return ErrorCode::Network;
+                }
+            }
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                match e.kind() {
+                    std::io::ErrorKind::PermissionDenied => return ErrorCode::PermissionDenied,
+                    std::io::ErrorKind::NotFound => return ErrorCode::NotFound,
+                    std::io::ErrorKind::TimedOut => return ErrorCode::Timeout,
+                    _ => {}
+                }
+            }
+        }
+
+        let message = err.to_string();
+        let lower = message.to_lowercase();
+        if lower.contains("401") || message.contains("已过期") {
+            ErrorCode::AuthExpired
+        } else if lower.contains("403") {
+            ErrorCode::Forbidden
+        } else if lower.contains("429") {
+            ErrorCode::RateLimited
+        } else if lower.contains("timed out") || message.contains("超时") {
+            ErrorCode::Timeout
+        } else if lower.contains("error sending request") || lower.contains("connect") {
+            ErrorCode::Network
+        } else {
+            ErrorCode::Unknown
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 带分类的错误
+#[derive(Debug)]
+pub struct CodedError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl fmt::Display for CodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CodedError {}
+
+/// 创建带分类的错误
+pub fn coded(code: ErrorCode, message: impl Into<String>) -> anyhow::Error {
+    anyhow::Error::new(CodedError { code, message: message.into() })
+}
+
+/// 接口返回非成功状态码时的错误（按状态码分类，信息格式为 "{context}: {status}"）
+pub fn status_error(context: &str, status: reqwest::StatusCode) -> anyhow::Error {
+    coded(ErrorCode::from_status(status), format!("{}: {}", context, status))
+}
@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+/// 更新安装包保留时间
+const INSTALLER_MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+/// 读取浏览器 Cookies 时的临时副本，正常情况下用完即删，残留说明进程异常退出
+const COOKIE_COPY_MAX_AGE: Duration = Duration::from_secs(3600);
+/// 轮转日志保留时间
+const LOG_MAX_AGE: Duration = Duration::from_secs(14 * 24 * 3600);
+
+/// 被清理的单个文件或目录
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupItem {
+    pub path: String,
+    pub size: u64,
+    pub reason: String,
+}
+
+/// 清理结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    pub items: Vec<CleanupItem>,
+    pub reclaimed_bytes: u64,
+}
+
+impl CleanupReport {
+    fn remove(&mut self, path: &Path, reason: &str) {
+        let size = path_size(path);
+        let result = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+        match result {
+            Ok(()) => {
+                self.reclaimed_bytes += size;
+                self.items.push(CleanupItem {
+                    path: path.to_string_lossy().to_string(),
+                    size,
+                    reason: reason.to_string(),
+                });
+            }
+            Err(e) => println!("[WARN] 清理 {} 失败: {}", path.display(), e),
+        }
+    }
+
+    /// 记录由其他模块删除的数据（如过期备份）
+    pub fn record(&mut self, path: String, size: u64, reason: &str) {
+        self.reclaimed_bytes += size;
+        self.items.push(CleanupItem {
+            path,
+            size,
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// 文件或目录占用的字节数
+fn path_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| path_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+fn older_than(path: &Path, max_age: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > max_age)
+}
+
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// 账号专属 WebView 数据目录
+pub fn webview_profiles_dir() -> anyhow::Result<PathBuf> {
+    Ok(crate::account::AccountManager::data_dir()?.join("webview-profiles"))
+}
+
+/// 日志目录
+pub fn logs_dir() -> anyhow::Result<PathBuf> {
+    Ok(crate::account::AccountManager::data_dir()?.join("logs"))
+}
+
+/// 清理临时目录中的旧安装包、残留的 Cookie 数据库副本、已删除账号的 WebView 数据和过期的轮转日志
+///
+/// account_ids 为 None 时（账号数据只读）不清理 WebView 数据，避免误删其他实例新建账号的数据
+pub fn clean_stale_files(account_ids: Option<&HashSet<String>>) -> CleanupReport {
+    let mut report = CleanupReport::default();
+
+    for path in entries(&std::env::temp_dir()) {
+        let name = file_name(&path);
+        if name.starts_with("trae-account-manager-update-") && older_than(&path, INSTALLER_MAX_AGE) {
+            report.remove(&path, "旧的更新安装包");
+        } else if name.starts_with("trae-cookies-") && older_than(&path, COOKIE_COPY_MAX_AGE) {
+            report.remove(&path, "残留的 Cookie 数据库副本");
+        }
+    }
+
+    if let (Some(account_ids), Ok(dir)) = (account_ids, webview_profiles_dir()) {
+        for path in entries(&dir) {
+            let name = file_name(&path);
+            if path.is_dir() && name != crate::webview_profile::SHARED_LOGIN_PROFILE && !account_ids.contains(&name) {
+                report.remove(&path, "已删除账号的 WebView 数据");
+            }
+        }
+    }
+
+    if let Ok(dir) = logs_dir() {
+        for path in entries(&dir) {
+            // 只清理轮转后的日志（如 app.log.1），当前日志保持不动
+            let rotated = file_name(&path)
+                .rsplit_once(".log.")
+                .is_some_and(|(_, suffix)| !suffix.is_empty());
+            if rotated && older_than(&path, LOG_MAX_AGE) {
+                report.remove(&path, "过期的轮转日志");
+            }
+        }
+    }
+
+    report
+}
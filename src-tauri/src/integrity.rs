@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use anyhow::{anyhow, Result};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use hmac::{Hmac, Mac};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const KEYRING_SERVICE: &str = "trae-account-manager";
+const KEYRING_USER: &str = "data-integrity-key";
+/// 账号数据签名过后写入密钥链的标记，之后签名文件缺失按篡改处理
+const KEYRING_SIGNED_USER: &str = "data-integrity-signed";
+const SIGNATURE_EXT: &str = "sig";
+
+static INTEGRITY_KEY: OnceCell<Option<Vec<u8>>> = OnceCell::new();
+static STORE_SIGNED: Lazy<AtomicBool> = Lazy::new(|| {
+    let signed = keyring::Entry::new(KEYRING_SERVICE, KEYRING_SIGNED_USER)
+        .and_then(|entry| entry.get_password())
+        .is_ok();
+    AtomicBool::new(signed)
+});
+
+/// 文件完整性校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityState {
+    /// 签名匹配
+    Valid,
+    /// 没有签名文件（旧版本数据或首次运行）
+    Unsigned,
+    /// 签名不匹配（被篡改或损坏）
+    Invalid,
+    /// 系统密钥链不可用，无法校验
+    Unavailable,
+}
+
+/// 加载时发现的完整性问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub path: String,
+    pub reason: String,
+    pub detected_at: i64,
+    /// 最近一个校验通过的备份
+    pub latest_valid_backup: Option<String>,
+    /// 文件内容可以正常解析（仅签名不匹配），可以信任当前内容并重新签名
+    #[serde(default)]
+    pub trustable: bool,
+}
+
+/// 从系统密钥链读取 HMAC 密钥，不存在时生成并保存
+fn integrity_key() -> Option<&'static [u8]> {
+    INTEGRITY_KEY
+        .get_or_init(|| match load_or_create_key() {
+            Ok(key) => Some(key),
+            Err(e) => {
+                println!("[WARN] 系统密钥链不可用，跳过数据完整性校验: {}", e);
+                None
+            }
+        })
+        .as_deref()
+}
+
+fn load_or_create_key() -> Result<Vec<u8>> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+    match entry.get_password() {
+        Ok(encoded) => BASE64
+            .decode(encoded.trim())
+            .map_err(|e| anyhow!("密钥链中的完整性密钥已损坏: {}", e)),
+        Err(keyring::Error::NoEntry) => {
+            let mut key = vec![0u8; 32];
+            OsRng.fill_bytes(&mut key);
+            entry.set_password(&BASE64.encode(&key))?;
+            println!("[INFO] 已在系统密钥链中创建数据完整性密钥");
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(".");
+    name.push(SIGNATURE_EXT);
+    path.with_file_name(name)
+}
+
+fn compute(key: &[u8], data: &[u8]) -> Result<HmacSha256> {
+    let mut mac = HmacSha256::new_from_slice(key).map_err(|e| anyhow!("HMAC 密钥无效: {}", e))?;
+    mac.update(data);
+    Ok(mac)
+}
+
+/// 为文件写入 HMAC 签名（<文件名>.sig）
+pub fn sign_file(path: &Path) -> Result<()> {
+    let Some(key) = integrity_key() else {
+        return Ok(());
+    };
+    let data = fs::read(path)?;
+    let tag = compute(key, &data)?.finalize().into_bytes();
+    fs::write(signature_path(path), BASE64.encode(tag))?;
+    Ok(())
+}
+
+/// 校验文件内容与签名是否一致
+pub fn verify_file(path: &Path) -> IntegrityState {
+    let Some(key) = integrity_key() else {
+        return IntegrityState::Unavailable;
+    };
+    let Ok(signature) = fs::read_to_string(signature_path(path)) else {
+        return IntegrityState::Unsigned;
+    };
+    let (Ok(data), Ok(expected)) = (fs::read(path), BASE64.decode(signature.trim())) else {
+        return IntegrityState::Invalid;
+    };
+    match compute(key, &data).map(|mac| mac.verify_slice(&expected)) {
+        Ok(Ok(())) => IntegrityState::Valid,
+        _ => IntegrityState::Invalid,
+    }
+}
+
+/// 为账号数据文件签名，并在密钥链中记录数据已签名
+pub fn sign_store(path: &Path) -> Result<()> {
+    sign_file(path)?;
+    if integrity_key().is_none() || STORE_SIGNED.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_SIGNED_USER)?;
+    entry.set_password("1")?;
+    STORE_SIGNED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// 校验账号数据文件：签名过后签名文件缺失视为被篡改（删除 .sig 不能绕过校验）
+pub fn verify_store(path: &Path) -> IntegrityState {
+    match verify_file(path) {
+        IntegrityState::Unsigned if STORE_SIGNED.load(Ordering::Relaxed) => IntegrityState::Invalid,
+        state => state,
+    }
+}
+
+/// 复制文件并同时复制签名
+pub fn copy_signed(from: &Path, to: &Path) -> Result<()> {
+    fs::copy(from, to)?;
+    let from_sig = signature_path(from);
+    if from_sig.exists() {
+        fs::copy(from_sig, signature_path(to))?;
+    } else {
+        sign_file(to)?;
+    }
+    Ok(())
+}
+
+/// 在候选备份中找出最近一个校验通过的文件（按修改时间倒序）
+pub fn latest_valid_backup(candidates: Vec<PathBuf>) -> Option<PathBuf> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = candidates
+        .into_iter()
+        .filter_map(|p| {
+            let modified = fs::metadata(&p).and_then(|m| m.modified()).ok()?;
+            Some((modified, p))
+        })
+        .collect();
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    files
+        .into_iter()
+        .map(|(_, p)| p)
+        .find(|p| matches!(verify_file(p), IntegrityState::Valid | IntegrityState::Unavailable))
+}
@@ -4,26 +4,61 @@
 mod api;
 mod account;
 mod autostart;
+mod captcha_solver;
+mod browser_cookies;
+mod cloud_backup;
+mod credential_journal;
+mod crypto;
+mod error;
+mod gc;
+mod integrity;
+mod login_oauth;
 mod machine;
+mod mail;
+mod maintenance;
+mod notify;
+mod pool_topup;
 mod privacy;
+mod quota_watch;
+mod register_fingerprint;
+mod register_proxy;
+mod register_queue;
+mod register_sessions;
+mod register_steps;
+mod report;
+mod retirement;
+mod rotation;
+mod self_test;
+mod store_watch;
+mod token_watch;
+mod totp;
+mod updater;
+mod usage_history;
+mod usage_poll;
+mod webview_channel;
+mod webview_log;
+mod webview_profile;
+mod workspace;
 
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, Instant};
 
-use reqwest::Client;
-use serde_json::Value;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::{oneshot, Mutex};
 use tauri::{AppHandle, Emitter, Manager, State, Url, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
 use tauri::webview::PageLoadEvent;
 use uuid::Uuid;
-use warp::Filter;
 
-use account::{AccountBrief, AccountManager, Account};
+use account::{AccountBrief, AccountManager, Account, AccountRegistration, AccountSource, BackupEntry, BatchAction, BatchActionResult, CookieEntry, ImportMergeReport, ImportOptions, ImportPreview, ProfileRefreshReport, ValidationFailure, ValidationReport, WorkspaceMapping};
 use api::{TraeApiClient, UsageSummary, UsageQueryResponse, UserStatisticResult};
+use api::network::SendExt;
+use mail::{MailClient, MailOptions, MailProviderKind};
+use register_sessions::RegisterSession;
+use register_steps::{CodeSource, RegisterStage, RegisterStep};
 
 #[cfg(target_os = "windows")]
 fn hide_console_window() {
@@ -45,6 +80,95 @@ pub struct AppSettings {
     pub privacy_auto_enable: bool,
     pub auto_update_check: bool,
     pub auto_start_enabled: bool,
+    /// 导入时同时校验的账号数
+    pub import_concurrency: usize,
+    /// 导入时每个账号校验前的等待时间（毫秒）
+    pub import_delay_ms: u64,
+    /// 导入等待时间的随机抖动上限（毫秒）
+    pub import_jitter_ms: u64,
+    /// 导入时仅保存原始数据，首次使用时再校验
+    pub import_defer_validation: bool,
+    /// 自动刷新账号资料的间隔（小时），0 表示关闭
+    pub profile_refresh_interval_hours: u64,
+    /// 定时维护的间隔（小时），0 表示关闭
+    pub maintenance_interval_hours: u64,
+    /// 自动备份的间隔（小时），0 表示关闭
+    pub backup_interval_hours: u64,
+    /// 保留的备份数量
+    pub backup_retention: usize,
+    /// 监听 Trae 打开的工作区并按映射切换账号
+    pub workspace_watch_enabled: bool,
+    /// 代理模式（默认读取环境变量 / 系统代理 / 直连 / 手动代理）
+    pub proxy_mode: api::network::ProxyMode,
+    /// 手动代理地址（HTTP / HTTPS / SOCKS5，可包含用户名和密码）
+    pub proxy_url: String,
+    /// DNS-over-HTTPS 服务地址，为空时使用系统 DNS
+    pub doh_url: String,
+    /// 额外信任的 CA 证书文件（PEM），用于企业代理等场景
+    pub extra_ca_path: String,
+    /// Trae API 证书固定（SHA-256 指纹），为空时不启用
+    pub pinned_certs: Vec<String>,
+    /// 云端备份目标（WebDAV / S3 / Gist），定时备份后自动上传
+    pub cloud_backup: cloud_backup::CloudBackupConfig,
+    /// 云端备份加密密码，为空时上传明文
+    pub cloud_backup_passphrase: String,
+    /// 每周自动生成账号周报
+    pub weekly_report_enabled: bool,
+    /// 旧版周报 Webhook 地址，读取后迁移到 notify.webhook_url
+    #[serde(skip_serializing)]
+    pub weekly_report_webhook: String,
+    /// 切换账号时写入 Trae 权益信息的方式
+    pub entitlement_mode: machine::EntitlementMode,
+    /// Token 在该时长（小时）内过期时提醒，0 表示关闭
+    pub token_expiry_warning_hours: u64,
+    /// 快速注册进度通知的详细程度
+    pub quick_register_notice_level: NoticeLevel,
+    /// 快速注册成功后为账号生成并绑定新的机器码
+    pub quick_register_new_machine_id: bool,
+    /// 快速注册成功后立即切换到新账号
+    pub quick_register_auto_switch: bool,
+    /// 每次快速注册随机使用不同的 User-Agent、语言和窗口大小
+    pub quick_register_random_fingerprint: bool,
+    /// 浏览器登录等待完成的时长（秒）
+    pub browser_login_timeout_secs: u64,
+    /// 浏览器登录窗口的起始页（如国内版或企业 SSO 入口），为空时打开 Trae 海外版登录页
+    pub browser_login_url: String,
+    /// 快速注册使用的临时邮箱服务（不可用时自动切换到其他服务）
+    pub mail_provider: MailProviderKind,
+    /// 自定义收件域名（如自有的 catch-all 域名），为空时使用临时邮箱服务自带的域名
+    pub mail_domains: Vec<String>,
+    /// 验证码识别规则（长度、字符类型、关键词）
+    pub verification_code: mail::CodeExtraction,
+    /// 快速注册遇到人机验证时使用的打码服务，未配置时由用户手动完成
+    pub captcha_solver: captcha_solver::CaptchaSolverConfig,
+    /// 快速注册使用的代理池（注册窗口和临时邮箱请求）
+    pub register_proxy: register_proxy::RegisterProxyConfig,
+    /// 使用量缓存有效期（秒），有效期内不重复请求接口，0 表示不缓存
+    pub usage_cache_ttl_secs: u64,
+    /// 通知渠道和各事件的路由规则
+    pub notify: notify::NotifyConfig,
+    /// 账号 ID -> 剩余快速请求提醒阈值，未设置的账号不监控
+    pub quota_thresholds: HashMap<String, f64>,
+    /// 当前账号额度用完时自动切换到下一个可用账号
+    pub auto_switch_on_exhausted: bool,
+    /// 账号轮换池和时间表
+    pub rotation: rotation::RotationConfig,
+    /// 可用账号不足时自动添加注册任务
+    pub pool_topup: pool_topup::PoolTopUpConfig,
+    /// 自动归档或删除额度用完的免费账号
+    pub retirement: retirement::RetirementConfig,
+    /// 网络请求超时（秒），下载安装包时为连接和读取超时
+    pub http_timeout_secs: u64,
+    /// 网络错误、超时、429 和 5xx 响应的重试次数
+    pub http_retry_count: u32,
+    /// 重试等待的基数（毫秒），每次重试翻倍
+    pub http_retry_backoff_ms: u64,
+    /// 自定义 Trae API 地址（镜像/中转），为空时使用内置的各区域端点
+    pub api_base_url: String,
+    /// 将 API 和临时邮箱请求（脱敏地址、状态码、耗时）写入调试日志
+    pub api_debug_log: bool,
+    /// 将登录、注册窗口的控制台输出和页面状态写入窗口控制台日志
+    pub webview_console_log: bool,
 }
 
 impl Default for AppSettings {
@@ -55,6 +179,90 @@ impl Default for AppSettings {
             privacy_auto_enable: true,
             auto_update_check: true,
             auto_start_enabled: false,
+            import_concurrency: 2,
+            import_delay_ms: 800,
+            import_jitter_ms: 1200,
+            import_defer_validation: false,
+            profile_refresh_interval_hours: 24,
+            maintenance_interval_hours: 12,
+            backup_interval_hours: 24,
+            backup_retention: 10,
+            workspace_watch_enabled: true,
+            proxy_mode: api::network::ProxyMode::Default,
+            proxy_url: String::new(),
+            doh_url: String::new(),
+            extra_ca_path: String::new(),
+            pinned_certs: Vec::new(),
+            cloud_backup: cloud_backup::CloudBackupConfig::Disabled,
+            cloud_backup_passphrase: String::new(),
+            weekly_report_enabled: false,
+            weekly_report_webhook: String::new(),
+            entitlement_mode: machine::EntitlementMode::default(),
+            token_expiry_warning_hours: 24,
+            quick_register_notice_level: NoticeLevel::default(),
+            quick_register_new_machine_id: false,
+            quick_register_auto_switch: false,
+            quick_register_random_fingerprint: true,
+            browser_login_timeout_secs: 300,
+            browser_login_url: String::new(),
+            mail_provider: MailProviderKind::default(),
+            mail_domains: Vec::new(),
+            verification_code: mail::CodeExtraction::default(),
+            captcha_solver: captcha_solver::CaptchaSolverConfig::default(),
+            register_proxy: register_proxy::RegisterProxyConfig::default(),
+            usage_cache_ttl_secs: 300,
+            notify: notify::NotifyConfig::default(),
+            quota_thresholds: HashMap::new(),
+            auto_switch_on_exhausted: false,
+            rotation: rotation::RotationConfig::default(),
+            pool_topup: pool_topup::PoolTopUpConfig::default(),
+            retirement: retirement::RetirementConfig::default(),
+            http_timeout_secs: 30,
+            http_retry_count: 2,
+            http_retry_backoff_ms: 500,
+            api_base_url: String::new(),
+            api_debug_log: false,
+            webview_console_log: false,
+        }
+    }
+}
+
+impl AppSettings {
+    fn import_options(&self) -> ImportOptions {
+        ImportOptions {
+            concurrency: self.import_concurrency.max(1),
+            delay_ms: self.import_delay_ms,
+            jitter_ms: self.import_jitter_ms,
+            defer_validation: self.import_defer_validation,
+        }
+    }
+
+    fn mail_options(&self) -> MailOptions {
+        MailOptions {
+            provider: self.mail_provider,
+            domains: self.mail_domains.iter().filter_map(|d| mail::normalize_domain(d)).collect(),
+            code: self.verification_code.clone(),
+            proxy: None,
+        }
+    }
+
+    fn network_config(&self) -> api::network::NetworkConfig {
+        api::network::NetworkConfig {
+            proxy_mode: self.proxy_mode,
+            proxy_url: Some(self.proxy_url.trim().to_string()).filter(|url| !url.is_empty()),
+            doh_url: Some(self.doh_url.trim().to_string()).filter(|url| !url.is_empty()),
+            extra_ca_path: Some(self.extra_ca_path.trim().to_string()).filter(|path| !path.is_empty()),
+            pinned_certs: self
+                .pinned_certs
+                .iter()
+                .map(|pin| pin.trim().to_string())
+                .filter(|pin| !pin.is_empty())
+                .collect(),
+            timeout: Duration::from_secs(self.http_timeout_secs.max(1)),
+            retries: self.http_retry_count,
+            retry_backoff: Duration::from_millis(self.http_retry_backoff_ms),
+            api_base: Some(self.api_base_url.trim().trim_end_matches('/').to_string()).filter(|url| !url.is_empty()),
+            debug_log: self.api_debug_log,
         }
     }
 }
@@ -76,8 +284,12 @@ fn load_settings_from_disk() -> anyhow::Result<AppSettings> {
     if content.trim().is_empty() {
         return Ok(AppSettings::default());
     }
-    let settings = serde_json::from_str(&content)
+    let mut settings: AppSettings = serde_json::from_str(&content)
         .unwrap_or_else(|_| AppSettings::default());
+    let legacy_webhook = std::mem::take(&mut settings.weekly_report_webhook);
+    if settings.notify.webhook_url.is_empty() && !legacy_webhook.trim().is_empty() {
+        settings.notify.webhook_url = legacy_webhook.trim().to_string();
+    }
     Ok(settings)
 }
 
@@ -93,16 +305,138 @@ pub struct AppState {
     pub account_manager: Mutex<AccountManager>,
     browser_login: Mutex<Option<BrowserLoginSession>>,
     browser_login_cancel: Mutex<Option<oneshot::Sender<()>>>,
+    /// 延长正在进行的浏览器登录的等待时间（秒）
+    browser_login_extend: Mutex<Option<tokio::sync::mpsc::UnboundedSender<u64>>>,
     settings: Mutex<AppSettings>,
+    register_queue: Mutex<register_queue::RegisterQueue>,
+}
+
+/// 登录页回调中捕获的 Token
+struct CapturedToken {
+    token: String,
+    /// GetUserToken 请求地址（用于读取该请求的 Cookie）
+    url: String,
+    refresh_token: Option<String>,
 }
 
 struct BrowserLoginSession {
-    receiver: oneshot::Receiver<(String, String)>,
+    receiver: oneshot::Receiver<CapturedToken>,
     shutdown: Arc<StdMutex<Option<oneshot::Sender<()>>>>,
     cancel: oneshot::Receiver<()>,
     window_close: oneshot::Receiver<()>,
     webview: WebviewWindow,
     credentials: Arc<StdMutex<BrowserLoginCredentials>>,
+    /// 登录窗口使用的独立 WebView 数据，登录成功后转为账号的数据
+    profile: webview_profile::WebviewProfile,
+    /// 第三方登录（Google / GitHub）进度
+    oauth: login_oauth::OAuthTracker,
+    /// 登录窗口注入脚本的回传通道，会话结束时注销
+    _callback_channel: webview_channel::ChannelGuard,
+    extend: tokio::sync::mpsc::UnboundedReceiver<u64>,
+    /// 登录页显示的登录失败提示
+    failure: tokio::sync::mpsc::UnboundedReceiver<BrowserLoginFailure>,
+    /// 重新登录的账号，完成后更新该账号而不是添加新账号
+    relogin_account_id: Option<String>,
+}
+
+/// 浏览器登录等待中（browser_login_waiting 事件负载）
+#[derive(Debug, Clone, serde::Serialize)]
+struct BrowserLoginWaiting {
+    remaining_secs: u64,
+    timeout_secs: u64,
+    /// 登录窗口当前打开的页面
+    url: Option<String>,
+}
+
+/// 浏览器登录的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum BrowserLoginStage {
+    /// 登录窗口已打开
+    WindowOpened,
+    /// 登录窗口打开了新页面
+    PageNavigated,
+    /// 已读取到登录页填写的邮箱和密码
+    CredentialsCaptured,
+    /// 已获取登录 Token
+    TokenReceived,
+    /// 已读取登录 Cookie，正在导入账号
+    CookiesCollected,
+}
+
+/// 浏览器登录进度（browser_login_progress 事件负载）
+#[derive(Debug, Clone, serde::Serialize)]
+struct BrowserLoginProgress {
+    stage: BrowserLoginStage,
+    message: String,
+    /// 页面地址（打开新页面时）
+    url: Option<String>,
+    /// 登录邮箱（读取到时）
+    email: Option<String>,
+}
+
+fn emit_browser_login_progress(app: &AppHandle, stage: BrowserLoginStage, message: &str, url: Option<String>, email: Option<String>) {
+    let progress = BrowserLoginProgress {
+        stage,
+        message: message.to_string(),
+        url,
+        email,
+    };
+    let _ = app.emit("browser_login_progress", &progress);
+}
+
+/// 登录页提示的登录失败原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BrowserLoginFailureKind {
+    /// 邮箱或密码错误
+    WrongPassword,
+    /// 账号被锁定或封禁
+    AccountLocked,
+    /// 尝试次数过多
+    TooManyAttempts,
+    Other,
+}
+
+impl BrowserLoginFailureKind {
+    fn parse(kind: &str) -> Self {
+        match kind {
+            "wrong_password" => Self::WrongPassword,
+            "account_locked" => Self::AccountLocked,
+            "too_many_attempts" => Self::TooManyAttempts,
+            _ => Self::Other,
+        }
+    }
+
+    fn code(self) -> error::ErrorCode {
+        match self {
+            Self::WrongPassword => error::ErrorCode::InvalidInput,
+            Self::AccountLocked => error::ErrorCode::Forbidden,
+            Self::TooManyAttempts => error::ErrorCode::RateLimited,
+            Self::Other => error::ErrorCode::Unknown,
+        }
+    }
+}
+
+/// 登录页显示的登录失败提示
+#[derive(Debug, Clone)]
+struct BrowserLoginFailure {
+    kind: BrowserLoginFailureKind,
+    message: String,
+}
+
+/// 浏览器登录等待中事件的发送间隔
+const BROWSER_LOGIN_WAITING_INTERVAL: Duration = Duration::from_secs(5);
+/// 未指定时每次延长的等待时间（秒）
+const BROWSER_LOGIN_DEFAULT_EXTEND_SECS: u64 = 120;
+/// 未设置起始页时登录窗口打开的页面
+const DEFAULT_BROWSER_LOGIN_URL: &str = "https://www.trae.ai/login";
+
+/// 解析登录窗口的起始页，只允许 http / https 地址
+fn parse_browser_login_url(raw: &str) -> anyhow::Result<Url> {
+    Url::parse(raw.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+        .ok_or_else(|| error::coded(error::ErrorCode::InvalidInput, format!("登录起始页地址无效: {}", raw.trim())))
 }
 
 #[derive(Debug, Default, Clone)]
@@ -115,11 +449,14 @@ struct BrowserLoginCredentials {
 #[derive(Debug, serde::Serialize)]
 pub struct ApiError {
     pub message: String,
+    /// 错误分类，前端据此处理（如重新登录、稍后重试）
+    pub code: error::ErrorCode,
 }
 
 impl From<anyhow::Error> for ApiError {
     fn from(err: anyhow::Error) -> Self {
         Self {
+            code: error::ErrorCode::of(&err),
             message: err.to_string(),
         }
     }
@@ -129,32 +466,413 @@ type Result<T> = std::result::Result<T, ApiError>;
 
 // ============ Tauri 命令 ============
 
+/// 快速注册进度通知的详细程度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeLevel {
+    /// 不推送通知
+    Silent,
+    /// 隐藏浏览器窗口时推送关键节点
+    #[default]
+    Milestones,
+    /// 推送每个步骤及耗时（显示浏览器窗口时也推送）
+    Verbose,
+}
+
+/// 快速注册进度（quick_register_progress 事件负载）
 #[derive(Debug, Clone, serde::Serialize)]
-struct QuickRegisterNotice {
+struct RegisterProgress {
+    /// 进度标识（同一账号同一阶段相同），供界面去重
     id: String,
+    stage: RegisterStage,
+    /// 重试或失败时对应的注册步骤
+    step: Option<RegisterStep>,
+    /// 0-100
+    percent: u8,
+    /// 中文说明（界面可按 stage 自行本地化）
     message: String,
+    /// 失败原因
+    error: Option<String>,
+    /// 注册使用的邮箱（邮箱就绪后才有）
+    email: Option<String>,
+    /// 批量注册中的序号（从 0 开始）和总数
+    batch_index: Option<usize>,
+    batch_total: Option<usize>,
+    /// 距注册开始的耗时（毫秒）
+    elapsed_ms: u64,
+    /// 距上一步的耗时（毫秒）
+    step_ms: u64,
+    milestone: bool,
+    /// 按通知级别是否应弹出提示
+    toast: bool,
 }
 
-fn emit_quick_register_notice(app: &AppHandle, id: &str, message: &str) {
-    let payload = QuickRegisterNotice {
-        id: id.to_string(),
-        message: message.to_string(),
-    };
-    let _ = app.emit("quick_register_notice", payload);
+/// 推送快速注册进度，按通知级别标记是否弹出提示，并记录每一步的耗时
+struct RegisterNotifier {
+    app: AppHandle,
+    level: NoticeLevel,
+    show_window: bool,
+    started: Instant,
+    last: Instant,
+    percent: u8,
+    email: Option<String>,
+    /// 批量注册时的 (序号, 总数)，用于区分各账号的进度
+    batch: Option<(usize, usize)>,
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
 }
 
-/// 添加账号（通过 Token，可选 Cookies）
+impl RegisterNotifier {
+    fn new(app: &AppHandle, level: NoticeLevel, show_window: bool) -> Self {
+        let now = Instant::now();
+        Self {
+            app: app.clone(),
+            level,
+            show_window,
+            started: now,
+            last: now,
+            percent: 0,
+            email: None,
+            batch: None,
+        }
+    }
+
+    /// 批量注册中的第 index 个账号（从 0 开始）
+    fn for_batch(mut self, index: usize, total: usize) -> Self {
+        self.batch = Some((index, total));
+        self
+    }
+
+    /// 邮箱就绪
+    fn mail_ready(&mut self, email: &str, message: &str) {
+        self.email = Some(email.to_string());
+        self.emit(RegisterStage::MailReady, None, message, None, false);
+    }
+
+    /// 普通步骤：仅 Verbose 级别弹出提示
+    fn step(&mut self, stage: RegisterStage, message: &str) {
+        self.emit(stage, None, message, None, false);
+    }
+
+    /// 关键节点
+    fn milestone(&mut self, stage: RegisterStage, message: &str) {
+        self.emit(stage, None, message, None, true);
+    }
+
+    /// 步骤失败后重试
+    fn retrying(&mut self, step: RegisterStep, message: &str) {
+        self.emit(RegisterStage::Retrying, Some(step), message, None, false);
+    }
+
+    /// 注册成功：按通知路由发送注册结果
+    fn succeeded(&mut self, email: &str) {
+        self.email = Some(email.to_string());
+        self.emit(RegisterStage::Done, None, "导入成功", None, true);
+        notify::spawn_dispatch(
+            &self.app,
+            notify::Notification::new(notify::NotifyEvent::Registration, "快速注册成功", format!("账号 {} 已添加", email)),
+        );
+    }
+
+    /// 注册失败：按通知路由发送注册结果
+    fn failed(&mut self, step: Option<RegisterStep>, message: &str) {
+        self.emit(RegisterStage::Failed, step, message, Some(message), true);
+        notify::spawn_dispatch(
+            &self.app,
+            notify::Notification::new(notify::NotifyEvent::Registration, "快速注册失败", message),
+        );
+    }
+
+    /// 记录步骤耗时，返回 (距上一步, 距开始) 毫秒
+    fn log(&mut self, stage: &str, message: &str) -> (u64, u64) {
+        let now = Instant::now();
+        let step_ms = millis(now.duration_since(self.last));
+        self.last = now;
+        println!("[quick-register] {} ({} ms): {}", stage, step_ms, message);
+        (step_ms, millis(now.duration_since(self.started)))
+    }
+
+    fn emit(
+        &mut self,
+        stage: RegisterStage,
+        step: Option<RegisterStep>,
+        message: &str,
+        error: Option<&str>,
+        milestone: bool,
+    ) {
+        let (step_ms, elapsed_ms) = self.log(stage.id(), message);
+        if let Some(percent) = stage.percent() {
+            self.percent = percent;
+        }
+
+        // 注册结果由通知路由发送，不再弹出提示
+        let finished = matches!(stage, RegisterStage::Done | RegisterStage::Failed);
+        let toast = !finished
+            && match self.level {
+                NoticeLevel::Silent => false,
+                NoticeLevel::Milestones => milestone && !self.show_window,
+                NoticeLevel::Verbose => true,
+            };
+        let (id, message) = match self.batch {
+            Some((index, total)) => (
+                format!("quick_register_{}_{}", index, stage.id()),
+                format!("[{}/{}] {}", index + 1, total, message),
+            ),
+            None => (format!("quick_register_{}", stage.id()), message.to_string()),
+        };
+        let payload = RegisterProgress {
+            id,
+            stage,
+            step,
+            percent: self.percent,
+            message,
+            error: error.map(str::to_string),
+            email: self.email.clone(),
+            batch_index: self.batch.map(|(index, _)| index),
+            batch_total: self.batch.map(|(_, total)| total),
+            elapsed_ms,
+            step_ms,
+            milestone,
+            toast,
+        };
+        let _ = self.app.emit("quick_register_progress", payload);
+    }
+}
+
+/// 添加账号（通过 Token，可选 Cookies；verify 为 false 时不调用 API，稍后再校验）
 #[tauri::command]
-async fn add_account_by_token(token: String, cookies: Option<String>, state: State<'_, AppState>) -> Result<Account> {
+async fn add_account_by_token(
+    token: String,
+    cookies: Option<String>,
+    verify: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Account> {
     let mut manager = state.account_manager.lock().await;
-    manager.add_account_by_token(token, cookies, None).await.map_err(ApiError::from)
+    let account = if verify.unwrap_or(true) {
+        manager.add_account_by_token(token, cookies, None).await
+    } else {
+        manager.add_account_unverified(Some(token), cookies, None)
+    }
+    .map_err(ApiError::from)?;
+    manager
+        .set_registration(&account.id, AccountRegistration::new(AccountSource::Token))
+        .map_err(ApiError::from)
+}
+
+/// 添加未验证账号（仅保存 Cookies 或 Token，不调用 API）
+#[tauri::command]
+async fn add_account_unverified(
+    token: Option<String>,
+    cookies: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Account> {
+    let mut manager = state.account_manager.lock().await;
+    let account = manager
+        .add_account_unverified(token, cookies, None)
+        .map_err(ApiError::from)?;
+    manager
+        .set_registration(&account.id, AccountRegistration::new(AccountSource::Token))
+        .map_err(ApiError::from)
+}
+
+/// 校验单个未验证账号
+#[tauri::command]
+async fn validate_account(account_id: String, state: State<'_, AppState>) -> Result<Account> {
+    let mut manager = state.account_manager.lock().await;
+    manager.validate_account(&account_id).await.map_err(ApiError::from)
+}
+
+/// 校验所有未验证账号
+#[tauri::command]
+async fn validate_unverified_accounts(state: State<'_, AppState>) -> Result<ValidationReport> {
+    Ok(validate_unverified_with_state(&state).await)
+}
+
+/// 依次校验所有未验证账号：请求期间不持有账号锁，每个账号的结果单独加锁保存
+async fn validate_unverified_with_state(state: &AppState) -> ValidationReport {
+    let pending = state.account_manager.lock().await.unverified_accounts();
+    let mut report = ValidationReport::default();
+    for account in pending {
+        let verified = AccountManager::fetch_verification(&account).await;
+        let result = state
+            .account_manager
+            .lock()
+            .await
+            .apply_verification(&account.id, verified);
+        match result {
+            Ok(_) => report.validated += 1,
+            Err(e) => report.failed.push(ValidationFailure {
+                account_id: account.id,
+                email: account.email,
+                error: e.to_string(),
+            }),
+        }
+    }
+    report
+}
+
+/// 刷新账号资料（名称/头像/区域/套餐）
+#[tauri::command]
+async fn refresh_account_profile(account_id: String, state: State<'_, AppState>) -> Result<Account> {
+    let mut manager = state.account_manager.lock().await;
+    manager.refresh_account_profile(&account_id).await.map_err(ApiError::from)
+}
+
+/// 刷新所有账号资料
+#[tauri::command]
+async fn refresh_all_account_profiles(state: State<'_, AppState>) -> Result<ProfileRefreshReport> {
+    let mut manager = state.account_manager.lock().await;
+    let account_ids = manager.accounts_with_stale_profile(0);
+    Ok(manager.refresh_account_profiles(&account_ids).await)
+}
+
+/// 对多个账号执行同一操作（刷新资料 / 绑定机器码 / 领取礼包 / 删除），返回每个账号的结果
+#[tauri::command]
+async fn batch_account_action(
+    action: BatchAction,
+    ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<BatchActionResult>> {
+    let mut manager = state.account_manager.lock().await;
+    Ok(manager.batch_account_action(action, &ids).await)
+}
+
+/// 清理过期数据（旧安装包、残留临时文件、已删除账号的 WebView 数据、过期备份和日志）
+async fn run_cleanup_with_state(state: &AppState) -> gc::CleanupReport {
+    let retention = state.settings.lock().await.backup_retention;
+    let (account_ids, pruned) = {
+        let manager = state.account_manager.lock().await;
+        if manager.is_read_only() {
+            (None, (0, 0))
+        } else {
+            let ids: HashSet<String> = manager.get_accounts().into_iter().map(|a| a.id).collect();
+            (Some(ids), manager.prune_backups(retention))
+        }
+    };
+
+    let mut report = tokio::task::spawn_blocking(move || gc::clean_stale_files(account_ids.as_ref()))
+        .await
+        .unwrap_or_default();
+    let (removed_backups, backup_bytes) = pruned;
+    if removed_backups > 0 {
+        report.record(format!("{} 份备份", removed_backups), backup_bytes, "超出保留数量的备份");
+    }
+    println!(
+        "[INFO] 清理完成: 删除 {} 项, 释放 {} 字节",
+        report.items.len(),
+        report.reclaimed_bytes
+    );
+    report
+}
+
+/// 立即清理过期数据，返回清理明细和释放的空间
+#[tauri::command]
+async fn run_cleanup(state: State<'_, AppState>) -> Result<gc::CleanupReport> {
+    Ok(run_cleanup_with_state(&state).await)
+}
+
+/// 立即生成账号周报（保存到数据目录下的 reports 文件夹）
+#[tauri::command]
+async fn generate_weekly_report(app: AppHandle) -> Result<report::WeeklyReport> {
+    report::generate_weekly_report(&app).await.map_err(ApiError::from)
+}
+
+/// 环境自检：检查注册页、临时邮箱和 IDE 写入（不创建账号），register_url 可指定测试环境地址
+#[tauri::command]
+async fn self_test(
+    register_url: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<self_test::SelfTestCheck>> {
+    let mail_options = state.settings.lock().await.mail_options();
+    Ok(self_test::run(&app, register_url, mail_options).await)
+}
+
+/// 向指定通知渠道发送一条测试通知
+#[tauri::command]
+async fn send_test_notification(sink: notify::SinkKind, app: AppHandle) -> Result<()> {
+    notify::send_test(&app, sink).await.map_err(ApiError::from)
+}
+
+/// 执行一次完整维护（刷新 Token/资料、查询使用量、领取礼包），进度通过 maintenance_progress 事件推送
+#[tauri::command]
+async fn run_maintenance(app: AppHandle) -> Result<maintenance::MaintenanceReport> {
+    maintenance::run_maintenance(&app).await.map_err(ApiError::from)
 }
 
 /// 添加账号（通过邮箱密码登录）
 #[tauri::command]
 async fn add_account_by_email(email: String, password: String, state: State<'_, AppState>) -> Result<Account> {
     let mut manager = state.account_manager.lock().await;
-    manager.add_account_by_email(email, password).await.map_err(ApiError::from)
+    let account = manager.add_account_by_email(email, password).await.map_err(ApiError::from)?;
+    manager
+        .set_registration(&account.id, AccountRegistration::new(AccountSource::EmailLogin))
+        .map_err(ApiError::from)
+}
+
+/// 添加账号（粘贴 Cookie 请求头或 Netscape 格式的 cookies.txt）
+#[tauri::command]
+async fn add_account_by_cookie_header(raw: String, state: State<'_, AppState>) -> Result<Account> {
+    let cookies = browser_cookies::parse_cookie_input(&raw).map_err(ApiError::from)?;
+    let mut manager = state.account_manager.lock().await;
+    let account = manager.add_account(cookies, None).await.map_err(ApiError::from)?;
+    manager
+        .set_registration(&account.id, AccountRegistration::new(AccountSource::Token))
+        .map_err(ApiError::from)
+}
+
+/// 读取已安装浏览器（Chrome/Edge/Firefox）中的 Trae Cookie 并创建账号
+#[tauri::command]
+async fn import_browser_cookies(
+    browsers: Option<Vec<browser_cookies::Browser>>,
+    state: State<'_, AppState>,
+) -> Result<browser_cookies::BrowserImportReport> {
+    let browsers = browsers
+        .filter(|list| !list.is_empty())
+        .unwrap_or_else(|| browser_cookies::Browser::ALL.to_vec());
+    // 读取数据库和系统密钥链可能较慢（macOS 会弹出钥匙串授权），放到阻塞线程执行
+    let (found, errors) = tokio::task::spawn_blocking(move || browser_cookies::read_trae_cookies(&browsers))
+        .await
+        .map_err(|e| ApiError::from(anyhow::anyhow!("读取浏览器 Cookie 失败: {}", e)))?;
+
+    let mut report = browser_cookies::BrowserImportReport {
+        failed: errors,
+        ..Default::default()
+    };
+    if found.is_empty() && report.failed.is_empty() {
+        return Err(ApiError::from(anyhow::anyhow!("未在浏览器中找到 Trae 登录状态，请先在浏览器中登录 trae.ai")));
+    }
+
+    let mut manager = state.account_manager.lock().await;
+    for set in found {
+        let added = manager.add_account(set.cookies, None).await.and_then(|account| {
+            manager.set_registration(&account.id, AccountRegistration::new(AccountSource::BrowserCookies))
+        });
+        match added {
+            Ok(account) => report.imported.push(account),
+            Err(e) => {
+                let entry = browser_cookies::BrowserCookieError {
+                    browser: set.browser,
+                    profile: set.profile,
+                    error: e.to_string(),
+                };
+                if e.to_string().contains("已存在") {
+                    report.skipped.push(entry);
+                } else {
+                    report.failed.push(entry);
+                }
+            }
+        }
+    }
+    println!(
+        "[INFO] 浏览器导入完成: 新增 {} 个, 已存在 {} 个, 失败 {} 个",
+        report.imported.len(),
+        report.skipped.len(),
+        report.failed.len()
+    );
+    Ok(report)
 }
 
 #[tauri::command]
@@ -168,15 +886,105 @@ async fn update_settings(settings: AppSettings, state: State<'_, AppState>) -> R
     if let Err(err) = autostart::set_auto_start(settings.auto_start_enabled) {
         return Err(ApiError::from(err));
     }
+    let network_config = settings.network_config();
+    if let Some(path) = &network_config.extra_ca_path {
+        api::network::load_ca_bundle(path).map_err(ApiError::from)?;
+    }
+    if !network_config.pinned_certs.is_empty() {
+        let extra_ca = match &network_config.extra_ca_path {
+            Some(path) => api::cert_pin::load_ca_der(path).map_err(ApiError::from)?,
+            None => Vec::new(),
+        };
+        api::cert_pin::tls_config(&network_config.pinned_certs, &extra_ca).map_err(ApiError::from)?;
+    }
+    if let (api::network::ProxyMode::Manual, Some(url)) = (network_config.proxy_mode, &network_config.proxy_url) {
+        api::network::parse_proxy_url(url).map_err(ApiError::from)?;
+    }
+    if let Some(domain) = settings.mail_domains.iter().find(|d| mail::normalize_domain(d).is_none()) {
+        return Err(ApiError::from(error::coded(error::ErrorCode::InvalidInput, format!("收件域名无效: {}", domain))));
+    }
+    if !(60..=3600).contains(&settings.browser_login_timeout_secs) {
+        return Err(ApiError::from(error::coded(error::ErrorCode::InvalidInput, "浏览器登录等待时长需在 1 到 60 分钟之间")));
+    }
+    if !settings.browser_login_url.trim().is_empty() {
+        parse_browser_login_url(&settings.browser_login_url).map_err(ApiError::from)?;
+    }
+    settings.captcha_solver.validate().map_err(ApiError::from)?;
+    settings.register_proxy.validate().map_err(ApiError::from)?;
+    settings.pool_topup.validate().map_err(ApiError::from)?;
+    settings.retirement.validate().map_err(ApiError::from)?;
+    if let Some(base) = &network_config.api_base {
+        let valid = Url::parse(base).is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some());
+        if !valid {
+            return Err(ApiError::from(error::coded(error::ErrorCode::InvalidInput, format!("API 地址无效: {}", base))));
+        }
+    }
     {
         let mut current = state.settings.lock().await;
         *current = settings.clone();
     }
+    state.account_manager.lock().await.set_entitlement_mode(settings.entitlement_mode);
+    webview_log::set_enabled(settings.webview_console_log);
     save_settings_to_disk(&settings).map_err(ApiError::from)?;
+    api::network::configure(network_config).await;
     Ok(settings)
 }
 
-/// 下载并运行更新安装包（Windows: .msi）
+/// 获取调试日志文件路径
+#[tauri::command]
+async fn get_api_debug_log_path() -> Result<String> {
+    let path = api::debug_log::log_path().map_err(ApiError::from)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 清空调试日志
+#[tauri::command]
+async fn clear_api_debug_log() -> Result<()> {
+    api::debug_log::clear().map_err(ApiError::from)
+}
+
+/// 获取窗口控制台日志文件路径
+#[tauri::command]
+async fn get_webview_console_log_path() -> Result<String> {
+    let path = webview_log::log_path().map_err(ApiError::from)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 清空窗口控制台日志
+#[tauri::command]
+async fn clear_webview_console_log() -> Result<()> {
+    webview_log::clear().map_err(ApiError::from)
+}
+
+/// 获取 Trae 各端点当前的证书指纹（用于配置证书固定）
+#[tauri::command]
+async fn get_api_certificate_fingerprints() -> Result<Vec<api::network::CertificateFingerprint>> {
+    Ok(api::network::fetch_certificate_fingerprints(api::TRAE_ENDPOINTS).await)
+}
+
+/// 检测 Trae 登录、使用量接口和临时邮箱服务的可达性和延迟，用于区分账号问题和服务故障
+#[tauri::command]
+async fn check_api_health(state: State<'_, AppState>) -> Result<api::health::HealthReport> {
+    let mail_provider = state.settings.lock().await.mail_provider;
+    Ok(api::health::check(&mail_provider.health_url()).await)
+}
+
+/// 检测系统代理设置（用于设置页展示）
+#[tauri::command]
+async fn get_system_proxy() -> Result<api::network::SystemProxy> {
+    if let Some(proxy) = api::network::current_system_proxy() {
+        return Ok(proxy);
+    }
+    Ok(tokio::task::spawn_blocking(api::network::detect_system_proxy).await.unwrap_or_default())
+}
+
+/// 查询最新版本，并选出适用于当前系统和架构的安装包
+#[tauri::command]
+async fn check_for_update() -> Result<updater::LatestRelease> {
+    updater::fetch_latest_release().await.map_err(ApiError::from)
+}
+
+/// 下载并运行更新安装包（按当前系统和架构校验，Windows: .msi / macOS: .dmg）
 #[tauri::command]
 async fn download_and_run_installer(url: String) -> Result<String> {
     let url = url.trim().to_string();
@@ -201,6 +1009,7 @@ async fn download_and_run_installer(url: String) -> Result<String> {
     } else {
         raw_filename
     };
+    updater::ensure_installer_matches(filename).map_err(ApiError::from)?;
 
     let mut dest_path = std::env::temp_dir();
     dest_path.push(format!(
@@ -209,19 +1018,14 @@ async fn download_and_run_installer(url: String) -> Result<String> {
         filename
     ));
 
-    let client = Client::builder()
+    let client = api::network::download_client_builder()
         .user_agent("Trae Account Manager Updater")
-        .timeout(Duration::from_secs(60 * 30))
         .build()
         .map_err(|e| ApiError::from(anyhow::Error::new(e)))?;
 
-    let mut response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| ApiError::from(anyhow::Error::new(e)))?;
+    let mut response = client.get(&url).send_checked().await.map_err(ApiError::from)?;
     if !response.status().is_success() {
-        return Err(anyhow::anyhow!("下载失败: {}", response.status()).into());
+        return Err(error::status_error("下载失败", response.status()).into());
     }
 
     let mut file = tokio::fs::File::create(&dest_path)
@@ -258,173 +1062,73 @@ async fn download_and_run_installer(url: String) -> Result<String> {
     Ok(dest_path.to_string_lossy().to_string())
 }
 
-const MAIL_API_BASE: &str = "https://api.mail.cx/api/v1";
-const MAIL_DOMAINS: [&str; 3] = ["uuf.me", "nqmo.com", "end.tw"];
-
-struct MailClient {
-    client: Client,
-    api_token: String,
-    email: String,
-    processed_ids: HashSet<String>,
-}
-
-impl MailClient {
-    async fn new() -> anyhow::Result<Self> {
-        let client = Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-            .timeout(Duration::from_secs(30))
-            .build()?;
-
-        let token = authorize_mail_token(&client).await?;
-        Ok(Self {
-            client,
-            api_token: token,
-            email: String::new(),
-            processed_ids: HashSet::new(),
-        })
-    }
-
-    fn set_email(&mut self, email: String) {
-        self.email = email;
-    }
-
-    async fn check_for_code(&mut self) -> anyhow::Result<Option<String>> {
-        if self.email.is_empty() {
-            return Ok(None);
-        }
-
-        let url = format!("{MAIL_API_BASE}/mailbox/{}", self.email);
-        let resp = self
-            .client
-            .get(&url)
-            .bearer_auth(&self.api_token)
-            .send()
-            .await?;
-        let data: Value = resp.json().await?;
-        let messages = if let Some(list) = data.as_array() {
-            list.to_vec()
-        } else if let Some(list) = data.get("messages").and_then(|v| v.as_array()) {
-            list.to_vec()
-        } else {
-            Vec::new()
-        };
-
-        if messages.is_empty() {
-            return Ok(None);
-        }
-
-        let latest = &messages[0];
-        let msg_id = latest
-            .get("id")
-            .and_then(|v| v.as_str())
-            .map(|v| v.to_string())
-            .or_else(|| latest.get("id").and_then(|v| v.as_i64()).map(|v| v.to_string()))
-            .unwrap_or_default();
-
-        if msg_id.is_empty() || self.processed_ids.contains(&msg_id) {
-            return Ok(None);
-        }
-
-        self.processed_ids.insert(msg_id.clone());
-
-        let content = self.fetch_message_content(&msg_id).await?;
-        Ok(extract_verification_code(&content))
-    }
-
-    async fn fetch_message_content(&self, msg_id: &str) -> anyhow::Result<String> {
-        let url = format!("{MAIL_API_BASE}/mailbox/{}/{}", self.email, msg_id);
-        let resp = self
-            .client
-            .get(&url)
-            .bearer_auth(&self.api_token)
-            .send()
-            .await?;
-        let data: Value = resp.json().await?;
-        let body = data.get("body").cloned().unwrap_or(Value::Null);
-        let text = body
-            .get("text")
-            .and_then(|v| v.as_str())
-            .map(|v| v.to_string())
-            .or_else(|| body.get("html").and_then(|v| v.as_str()).map(|v| v.to_string()))
-            .unwrap_or_default();
-        Ok(text)
-    }
-}
-
-async fn authorize_mail_token(client: &Client) -> anyhow::Result<String> {
-    let url = format!("{MAIL_API_BASE}/auth/authorize_token");
-    let resp = client.post(&url).json(&serde_json::json!({})).send().await?;
-    let value: Value = resp.json().await?;
-    let token = match value {
-        Value::String(val) => Some(val),
-        Value::Object(map) => map
-            .get("token")
-            .or_else(|| map.get("access_token"))
-            .or_else(|| map.get("data"))
-            .and_then(|v| v.as_str())
-            .map(|v| v.to_string()),
-        _ => None,
-    };
-    token.ok_or_else(|| anyhow::anyhow!("邮箱认证失败，未获取到 token"))
-}
-
-fn generate_email_address() -> String {
-    let raw = Uuid::new_v4().simple().to_string();
-    let username = raw[..8].to_string();
-    let index = (raw.as_bytes()[0] as usize) % MAIL_DOMAINS.len();
-    format!("{}@{}", username, MAIL_DOMAINS[index])
-}
+/// 快速注册打开的注册页
+const QUICK_REGISTER_URL: &str = "https://www.trae.ai/sign-up";
 
 fn generate_password() -> String {
     let raw = Uuid::new_v4().simple().to_string();
     format!("A{}!{}", &raw[..6], &raw[6..12])
 }
 
-fn extract_verification_code(content: &str) -> Option<String> {
-    let mut digits = String::new();
-    for ch in content.chars() {
-        if ch.is_ascii_digit() {
-            digits.push(ch);
-            if digits.len() == 6 {
-                return Some(digits);
-            }
-        } else {
-            digits.clear();
+/// 等待注册页回报步骤信号：收到 ok 返回成功，收到 fail 返回失败原因
+async fn wait_for_signal(
+    signals: &mut tokio::sync::mpsc::UnboundedReceiver<String>,
+    ok: &str,
+    fail: Option<&str>,
+) -> std::result::Result<(), String> {
+    loop {
+        match signals.recv().await {
+            Some(signal) if signal == ok => return Ok(()),
+            Some(signal) if Some(signal.as_str()) == fail => return Err("页面上未找到对应的按钮或输入框".to_string()),
+            Some(_) => {}
+            None => return Err("注册页回调已关闭".to_string()),
         }
     }
-    None
 }
 
-async fn wait_for_verification_code(client: &mut MailClient, timeout: Duration) -> anyhow::Result<String> {
-    let start = Instant::now();
-    while start.elapsed() < timeout {
-        if let Some(code) = client.check_for_code().await? {
-            return Ok(code);
+/// 处理窗口注入脚本回传的消息，直到收到停止信号或回传通道被注销
+async fn receive_callbacks(
+    mut callbacks: tokio::sync::mpsc::UnboundedReceiver<webview_channel::CallbackMessage>,
+    mut shutdown: oneshot::Receiver<()>,
+    handle: impl Fn(HashMap<String, String>),
+) {
+    loop {
+        tokio::select! {
+            message = callbacks.recv() => match message {
+                Some(query) => handle(query),
+                None => break,
+            },
+            _ = &mut shutdown => break,
         }
-        tokio::time::sleep(Duration::from_secs(5)).await;
     }
-    Err(anyhow::anyhow!("等待邮箱验证码超时"))
 }
 
-fn build_register_helper_script(port: u16) -> String {
+/// 注册窗口、登录窗口的注入脚本回传消息
+#[tauri::command]
+fn webview_callback(webview: tauri::Webview, query: HashMap<String, String>) {
+    webview_channel::dispatch(webview.label(), query);
+}
+
+/// 注册窗口、登录窗口的控制台输出和页面状态，开启设置后写入窗口控制台日志
+#[tauri::command]
+fn webview_console(webview: tauri::Webview, entries: Vec<webview_log::ConsoleEntry>) {
+    webview_log::record(webview.label(), &entries);
+}
+
+fn build_register_helper_script() -> String {
     let script = r#"(function() {
   if (window.__traeAutoRegister) return;
 
-  const callback = "http://127.0.0.1:__PORT__/callback";
+  const send = __SEND__;
   
   const sendPayload = (payload) => {
-    const params = new URLSearchParams();
+    const query = {};
     Object.keys(payload || {}).forEach((key) => {
       const value = payload[key];
       if (value === undefined || value === null || value === "") return;
-      params.append(key, value);
+      query[key] = String(value);
     });
-    const url = callback + "?" + params.toString();
-    if (navigator.sendBeacon) {
-      navigator.sendBeacon(url);
-    } else {
-      fetch(url, { mode: "no-cors" });
-    }
+    send(query);
   };
 
   const sendLog = (msg) => {
@@ -456,10 +1160,21 @@ fn build_register_helper_script(port: u16) -> String {
     }
   };
 
-  const sendToken = (token, url) => {
+  const parseRefreshToken = (data) => {
+    if (!data) return null;
+    return (
+      data.result?.refreshToken ||
+      data.result?.RefreshToken ||
+      data.Result?.refreshToken ||
+      data.Result?.RefreshToken ||
+      null
+    );
+  };
+
+  const sendToken = (token, url, data) => {
     if (!token) return;
     sendLog("Found token: " + token.substring(0, 10) + "...");
-    sendPayload({ token, url: normalizeUrl(url) });
+    sendPayload({ token, url: normalizeUrl(url), refresh_token: parseRefreshToken(data) });
   };
 
   const hookFetch = () => {
@@ -478,7 +1193,7 @@ fn build_register_helper_script(port: u16) -> String {
           const data = await res.clone().json();
           const token = parseToken(data);
           if (token) {
-              sendToken(token, resUrl || url);
+              sendToken(token, resUrl || url, data);
           } else {
               sendLog("Parsed token is null from data: " + JSON.stringify(data).substring(0, 100));
           }
@@ -508,7 +1223,7 @@ fn build_register_helper_script(port: u16) -> String {
             const data = JSON.parse(this.responseText);
             const token = parseToken(data);
             if (token) {
-                sendToken(token, this.__trae_url);
+                sendToken(token, this.__trae_url, data);
             } else {
                 sendLog("Parsed token is null from XHR data");
             }
@@ -575,6 +1290,100 @@ fn build_register_helper_script(port: u16) -> String {
     const rect = el.getBoundingClientRect();
     return rect.width > 0 && rect.height > 0;
   };
+  const CAPTCHA_SELECTORS = [
+    'iframe[src*="captcha" i]',
+    'iframe[src*="challenges.cloudflare.com"]',
+    'iframe[title*="captcha" i]',
+    '[id*="captcha" i]',
+    '[class*="captcha" i]',
+    '[class*="geetest" i]',
+    '.cf-turnstile',
+  ];
+  let captchaVisible = false;
+  // 已由打码服务填入结果的验证（站点密钥），组件仍显示时不再重复上报
+  let solvedSitekey = null;
+  // 识别验证类型和站点密钥，供打码服务使用
+  const detectChallenge = () => {
+    const widget = document.querySelector("[data-sitekey]");
+    if (widget) {
+      const cls = String(widget.className || "").toLowerCase();
+      const kind = cls.includes("h-captcha") ? "hcaptcha" : cls.includes("cf-turnstile") ? "turnstile" : "recaptcha";
+      return { kind, sitekey: widget.getAttribute("data-sitekey") };
+    }
+    for (const frame of Array.from(document.querySelectorAll("iframe[src]"))) {
+      let url;
+      try {
+        url = new URL(frame.src);
+      } catch {
+        continue;
+      }
+      if (url.hostname.includes("hcaptcha")) {
+        const sitekey = url.searchParams.get("sitekey") || new URLSearchParams(url.hash.slice(1)).get("sitekey");
+        if (sitekey) return { kind: "hcaptcha", sitekey };
+      } else if (url.pathname.includes("recaptcha")) {
+        const sitekey = url.searchParams.get("k");
+        if (sitekey) return { kind: "recaptcha", sitekey };
+      } else if (url.hostname === "challenges.cloudflare.com") {
+        const match = url.pathname.match(/\/(0x[0-9A-Za-z_-]+)/);
+        if (match) return { kind: "turnstile", sitekey: match[1] };
+      }
+    }
+    return null;
+  };
+  const checkCaptcha = () => {
+    let visible = CAPTCHA_SELECTORS.some((selector) => {
+      try {
+        return Array.from(document.querySelectorAll(selector)).some(isVisible);
+      } catch {
+        return false;
+      }
+    });
+    const challenge = visible ? detectChallenge() : null;
+    if (visible && solvedSitekey && challenge && challenge.sitekey === solvedSitekey) {
+      visible = false;
+    }
+    if (visible === captchaVisible) return;
+    captchaVisible = visible;
+    sendLog(visible ? "CAPTCHA detected" : "CAPTCHA cleared");
+    if (visible) {
+      sendPayload({
+        step: "captcha_required",
+        captcha_kind: challenge && challenge.kind,
+        captcha_sitekey: challenge && challenge.sitekey,
+        captcha_url: location.href,
+      });
+    } else {
+      sendPayload({ step: "captcha_solved" });
+    }
+  };
+  // 填入打码服务返回的 Token 并触发组件回调
+  const applyCaptchaToken = (kind, token) => {
+    const fields = {
+      recaptcha: ["g-recaptcha-response"],
+      hcaptcha: ["h-captcha-response", "g-recaptcha-response"],
+      turnstile: ["cf-turnstile-response"],
+    }[kind] || [];
+    fields.forEach((name) => {
+      document.querySelectorAll(`textarea[name="${name}"], input[name="${name}"]`).forEach((el) => {
+        el.value = token;
+        el.dispatchEvent(new Event("input", { bubbles: true }));
+        el.dispatchEvent(new Event("change", { bubbles: true }));
+      });
+    });
+    const widget = document.querySelector("[data-sitekey][data-callback]");
+    const callbackName = widget && widget.getAttribute("data-callback");
+    if (callbackName && typeof window[callbackName] === "function") {
+      try {
+        window[callbackName](token);
+      } catch (e) {
+        sendLog("CAPTCHA callback error: " + e.message);
+      }
+    }
+    const challenge = detectChallenge();
+    solvedSitekey = challenge ? challenge.sitekey : null;
+    sendLog("Applied CAPTCHA token (" + kind + ")");
+    checkCaptcha();
+  };
   const isClickable = (el) => {
     if (!el || el.disabled) return false;
     const tag = (el.tagName || "").toLowerCase();
@@ -610,13 +1419,16 @@ fn build_register_helper_script(port: u16) -> String {
       }) || null
     );
   };
-  const runWithRetry = (fn, maxTries = 40) => {
+  const runWithRetry = (fn, maxTries = 40, onDone) => {
     let tries = 0;
     const timer = setInterval(() => {
+      // 人机验证期间暂停自动操作，不计入重试次数
+      if (captchaVisible) return;
       tries += 1;
       const ok = fn();
       if (ok || tries >= maxTries) {
         clearInterval(timer);
+        if (onDone) onDone(!!ok);
       }
     }, 500);
   };
@@ -767,20 +1579,29 @@ fn build_register_helper_script(port: u16) -> String {
   window.__traeAutoRegister = {
     started: false,
     completed: false,
-    start: function(email) {
-      if (this.started) return;
+    start: function(email, force) {
+      if (this.started && !force) return;
       this.started = true;
-      runWithRetry(() => tryStart(email));
+      runWithRetry(() => tryStart(email), 40, (ok) => {
+        sendPayload({ step: ok ? "code_sent" : "send_code_failed" });
+      });
     },
-    complete: function(code, password) {
-      if (this.completed) return;
+    complete: function(code, password, force) {
+      if (this.completed && !force) return;
       this.completed = true;
-      runWithRetry(() => tryComplete(code, password));
+      runWithRetry(() => tryComplete(code, password), 40, (ok) => {
+        sendPayload({ step: ok ? "submitted" : "submit_failed" });
+      });
     },
+    applyCaptchaToken,
   };
   setInterval(tryAcceptCookies, 1500);
+  setInterval(checkCaptcha, 1000);
+  __TOTP__
 })();"#;
-    script.replace("__PORT__", &port.to_string())
+    script
+        .replace("__SEND__", webview_channel::JS_SEND)
+        .replace("__TOTP__", totp::JS_TOTP)
 }
 
 async fn wait_for_token_with_cookies(webview: &WebviewWindow, timeout: Duration) -> anyhow::Result<String> {
@@ -856,68 +1677,568 @@ async fn quick_register(app: AppHandle, show_window: bool, state: State<'_, AppS
         return Err(anyhow::anyhow!("浏览器登录正在进行中，请稍后再试").into());
     }
 
-    let mut mail_client = MailClient::new().await.map_err(ApiError::from)?;
-    let email = generate_email_address();
-    let password = generate_password();
-    mail_client.set_email(email.clone());
+    let level = state.settings.lock().await.quick_register_notice_level;
+    let mut notifier = RegisterNotifier::new(&app, level, show_window);
+    let mut mail_client = MailClient::new(&state.settings.lock().await.mail_options()).map_err(ApiError::from)?;
+    let account = run_quick_register(&app, &state, show_window, "trae-register", &mut notifier, &mut mail_client).await?;
+    switch_to_registered(&state, &account).await;
+    Ok(account)
+}
+
+/// 使用自有邮箱注册：自动填写注册页，验证码由用户通过 submit_verification_code 提交
+#[tauri::command]
+async fn quick_register_with_email(
+    app: AppHandle,
+    email: String,
+    show_window: bool,
+    state: State<'_, AppState>,
+) -> Result<Account> {
+    if state.browser_login.lock().await.is_some() {
+        return Err(anyhow::anyhow!("浏览器登录正在进行中，请稍后再试").into());
+    }
+    let email = email.trim().to_string();
+    let valid = email
+        .split_once('@')
+        .is_some_and(|(local, domain)| !local.is_empty() && mail::normalize_domain(domain).is_some());
+    if !valid {
+        return Err(error::coded(error::ErrorCode::InvalidInput, format!("邮箱地址无效: {}", email)).into());
+    }
+
+    let level = state.settings.lock().await.quick_register_notice_level;
+    let mut notifier = RegisterNotifier::new(&app, level, show_window);
+    notifier.mail_ready(&email, &format!("使用自有邮箱注册: {}", email));
+    let proxy = state.settings.lock().await.register_proxy.pick();
+    let session = RegisterSession::new(&email, generate_password(), None, proxy);
+    let account =
+        run_register_flow(&app, &state, show_window, "trae-register-manual", &mut notifier, session, CodeSource::Manual)
+            .await?;
+    switch_to_registered(&state, &account).await;
+    Ok(account)
+}
+
+/// 开启 quick_register_auto_switch 时切换到刚注册的账号；切换失败不影响注册结果
+async fn switch_to_registered(state: &AppState, account: &Account) {
+    if !state.settings.lock().await.quick_register_auto_switch {
+        return;
+    }
+    println!("[INFO] 快速注册完成，正在切换到新账号 {}", account.email);
+    if let Err(err) = switch_account_with_state(state, account.id.clone(), false).await {
+        println!("[WARN] 切换到新注册账号失败: {}", err.message);
+    }
+}
+
+/// 应用中途退出时未完成的注册
+#[tauri::command]
+async fn list_interrupted_registrations() -> Result<Vec<RegisterSession>> {
+    Ok(register_sessions::list())
+}
+
+/// 继续中断的注册：使用原来的邮箱和密码重新打开注册页，临时邮箱继续收信，自有邮箱由用户提交验证码
+#[tauri::command]
+async fn resume_registration(
+    app: AppHandle,
+    session_id: String,
+    show_window: bool,
+    state: State<'_, AppState>,
+) -> Result<Account> {
+    if state.browser_login.lock().await.is_some() {
+        return Err(anyhow::anyhow!("浏览器登录正在进行中，请稍后再试").into());
+    }
+    let session = register_sessions::get(&session_id).map_err(ApiError::from)?;
+    let (level, mail_options) = {
+        let settings = state.settings.lock().await;
+        (settings.quick_register_notice_level, MailOptions { proxy: session.proxy.clone(), ..settings.mail_options() })
+    };
+    let mut notifier = RegisterNotifier::new(&app, level, show_window);
+    println!("[quick-register] 继续中断的注册: {}（{}）", session.email, session.step.label());
+    let account = match session.mail.clone() {
+        Some(mail_session) => {
+            let mut mail_client = match MailClient::resume(&mail_options, &mail_session).await {
+                Ok(client) => client,
+                Err(err) => {
+                    notifier.failed(None, &format!("恢复临时邮箱失败: {}", err));
+                    return Err(ApiError::from(err));
+                }
+            };
+            notifier.mail_ready(&session.email, &format!("继续注册（{}）: {}", mail_client.provider_name(), session.email));
+            run_register_flow(
+                &app,
+                &state,
+                show_window,
+                "trae-register-resume",
+                &mut notifier,
+                session,
+                CodeSource::TempMail(&mut mail_client),
+            )
+            .await?
+        }
+        None => {
+            notifier.mail_ready(&session.email, &format!("继续注册: {}", session.email));
+            run_register_flow(&app, &state, show_window, "trae-register-resume", &mut notifier, session, CodeSource::Manual)
+                .await?
+        }
+    };
+    switch_to_registered(&state, &account).await;
+    Ok(account)
+}
+
+/// 读取注册凭据日志（最新的在前）
+#[tauri::command]
+async fn get_credential_journal() -> Result<Vec<credential_journal::CredentialRecord>> {
+    credential_journal::read_all().map_err(ApiError::from)
+}
+
+/// 放弃中断的注册
+#[tauri::command]
+async fn discard_registration(session_id: String) -> Result<()> {
+    register_sessions::remove(&session_id);
+    Ok(())
+}
+
+/// 提交自有邮箱注册收到的验证码
+#[tauri::command]
+async fn submit_verification_code(window_label: String, code: String) -> Result<()> {
+    let code = code.trim().to_string();
+    if code.is_empty() {
+        return Err(error::coded(error::ErrorCode::InvalidInput, "验证码不能为空").into());
+    }
+    register_steps::send(&window_label, register_steps::StepControl::Code(code)).map_err(ApiError::from)
+}
+
+/// 批量快速注册的最大数量
+const MAX_BATCH_REGISTER: usize = 50;
+/// 批量快速注册同时打开的最大窗口数
+const MAX_PARALLEL_REGISTER: usize = 3;
+
+/// 批量快速注册中单个账号完成（quick_register_batch_progress 事件负载）
+#[derive(Debug, Clone, serde::Serialize)]
+struct BatchRegisterProgress {
+    /// 序号（从 0 开始）
+    index: usize,
+    total: usize,
+    /// 已完成数量（含失败）
+    completed: usize,
+    success: bool,
+    email: Option<String>,
+    error: Option<String>,
+}
+
+/// 批量快速注册结果
+#[derive(Debug, Clone, Default, serde::Serialize)]
+struct BatchRegisterReport {
+    requested: usize,
+    accounts: Vec<Account>,
+    failures: Vec<String>,
+}
+
+/// 检查批量注册数量，超出上限时报错而不是静默截断
+fn check_batch_register_count(count: usize) -> anyhow::Result<()> {
+    if count == 0 {
+        return Err(error::coded(error::ErrorCode::InvalidInput, "注册数量必须大于 0"));
+    }
+    if count > MAX_BATCH_REGISTER {
+        return Err(error::coded(
+            error::ErrorCode::InvalidInput,
+            format!("单次最多批量注册 {} 个账号", MAX_BATCH_REGISTER),
+        ));
+    }
+    Ok(())
+}
+
+/// 连续快速注册多个账号（parallel 为同时打开的窗口数，默认 1），每个账号完成后推送 quick_register_batch_progress 事件
+///
+/// 每个窗口复用同一个临时邮箱会话，只为每个账号生成新的邮箱地址
+#[tauri::command]
+async fn quick_register_batch(
+    app: AppHandle,
+    count: usize,
+    show_window: bool,
+    parallel: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<BatchRegisterReport> {
+    if state.browser_login.lock().await.is_some() {
+        return Err(anyhow::anyhow!("浏览器登录正在进行中，请稍后再试").into());
+    }
+    check_batch_register_count(count).map_err(ApiError::from)?;
+    let total = count;
+    let workers = parallel.unwrap_or(1).clamp(1, MAX_PARALLEL_REGISTER).min(total);
+    let (level, mail_options) = {
+        let settings = state.settings.lock().await;
+        (settings.quick_register_notice_level, settings.mail_options())
+    };
+    println!("[quick-register] 开始批量注册 {} 个账号（{} 个窗口）", total, workers);
+
+    let next_index = Arc::new(AtomicUsize::new(0));
+    let report = Arc::new(Mutex::new(BatchRegisterReport { requested: total, ..Default::default() }));
+    let tasks: Vec<_> = (0..workers)
+        .map(|worker| {
+            let app = app.clone();
+            let next_index = next_index.clone();
+            let report = report.clone();
+            let mail_options = mail_options.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<AppState>();
+                let label = format!("trae-register-{}", worker);
+                let mut mail_client: Option<MailClient> = None;
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::SeqCst);
+                    if index >= total {
+                        break;
+                    }
+                    let mut notifier = RegisterNotifier::new(&app, level, show_window).for_batch(index, total);
+                    let client = match mail_client.take() {
+                        Some(client) => Ok(client),
+                        None => MailClient::new(&mail_options),
+                    };
+                    let result = match client {
+                        Ok(mut client) => {
+                            let result = run_quick_register(&app, &state, show_window, &label, &mut notifier, &mut client).await;
+                            mail_client = Some(client);
+                            result
+                        }
+                        Err(e) => Err(ApiError::from(e)),
+                    };
+
+                    let mut report = report.lock().await;
+                    let progress = match result {
+                        Ok(account) => {
+                            let email = account.email.clone();
+                            report.accounts.push(account);
+                            BatchRegisterProgress {
+                                index,
+                                total,
+                                completed: 0,
+                                success: true,
+                                email: Some(email),
+                                error: None,
+                            }
+                        }
+                        Err(e) => {
+                            report.failures.push(format!("第 {} 个: {}", index + 1, e.message));
+                            BatchRegisterProgress {
+                                index,
+                                total,
+                                completed: 0,
+                                success: false,
+                                email: None,
+                                error: Some(e.message),
+                            }
+                        }
+                    };
+                    let progress = BatchRegisterProgress {
+                        completed: report.accounts.len() + report.failures.len(),
+                        ..progress
+                    };
+                    drop(report);
+                    let _ = app.emit("quick_register_batch_progress", &progress);
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        let _ = task.await;
+    }
+
+    let report = report.lock().await.clone();
+    println!(
+        "[quick-register] 批量注册完成: 成功 {} 个, 失败 {} 个",
+        report.accounts.len(),
+        report.failures.len()
+    );
+    Ok(report)
+}
+
+/// 列出临时邮箱中的邮件（用于排查注册卡在等待验证码的情况）
+#[tauri::command]
+async fn list_mailbox_messages(email: String, state: State<'_, AppState>) -> Result<Vec<mail::MailSummary>> {
+    let options = state.settings.lock().await.mail_options();
+    mail::list_mailbox_messages(&email, &options).await.map_err(ApiError::from)
+}
+
+/// 读取临时邮箱中的一封邮件，并按当前规则识别验证码
+#[tauri::command]
+async fn get_mailbox_message(email: String, id: String, state: State<'_, AppState>) -> Result<mail::MailMessageDetail> {
+    let options = state.settings.lock().await.mail_options();
+    mail::get_mailbox_message(&email, &id, &options).await.map_err(ApiError::from)
+}
+
+/// 向注册队列添加 count 个快速注册任务，队列未暂停时依次执行
+#[tauri::command]
+async fn enqueue_register_jobs(
+    app: AppHandle,
+    count: usize,
+    show_window: bool,
+    state: State<'_, AppState>,
+) -> Result<register_queue::RegisterQueueStatus> {
+    check_batch_register_count(count).map_err(ApiError::from)?;
+    let ids = state
+        .register_queue
+        .lock()
+        .await
+        .enqueue(count, show_window)
+        .map_err(ApiError::from)?;
+    println!("[quick-register] 已添加 {} 个注册任务到队列", ids.len());
+    register_queue::ensure_worker(&app).await;
+    Ok(state.register_queue.lock().await.status())
+}
+
+/// 获取注册队列状态
+#[tauri::command]
+async fn get_register_queue(state: State<'_, AppState>) -> Result<register_queue::RegisterQueueStatus> {
+    Ok(state.register_queue.lock().await.status())
+}
+
+/// 暂停注册队列（正在执行的任务会继续完成）
+#[tauri::command]
+async fn pause_register_queue(app: AppHandle, state: State<'_, AppState>) -> Result<register_queue::RegisterQueueStatus> {
+    state.register_queue.lock().await.set_paused(true);
+    register_queue::ensure_worker(&app).await;
+    Ok(state.register_queue.lock().await.status())
+}
+
+/// 继续执行注册队列
+#[tauri::command]
+async fn resume_register_queue(app: AppHandle, state: State<'_, AppState>) -> Result<register_queue::RegisterQueueStatus> {
+    state.register_queue.lock().await.set_paused(false);
+    register_queue::ensure_worker(&app).await;
+    Ok(state.register_queue.lock().await.status())
+}
+
+/// 取消注册任务（正在执行的任务会关闭注册窗口）
+#[tauri::command]
+async fn cancel_register_job(
+    app: AppHandle,
+    job_id: u64,
+    state: State<'_, AppState>,
+) -> Result<register_queue::RegisterQueueStatus> {
+    state.register_queue.lock().await.cancel(job_id).map_err(ApiError::from)?;
+    register_queue::ensure_worker(&app).await;
+    Ok(state.register_queue.lock().await.status())
+}
+
+/// 移除注册队列中已结束的任务
+#[tauri::command]
+async fn clear_register_queue(state: State<'_, AppState>) -> Result<register_queue::RegisterQueueStatus> {
+    let mut queue = state.register_queue.lock().await;
+    queue.clear_finished();
+    Ok(queue.status())
+}
+
+/// 重新执行快速注册中失败的步骤
+#[tauri::command]
+async fn retry_quick_register_step(window_label: String) -> Result<()> {
+    register_steps::send(&window_label, register_steps::StepControl::Retry).map_err(ApiError::from)
+}
+
+/// 放弃步骤失败的快速注册
+#[tauri::command]
+async fn abort_quick_register(window_label: String) -> Result<()> {
+    register_steps::send(&window_label, register_steps::StepControl::Abort).map_err(ApiError::from)
+}
+
+/// 执行一次快速注册：在 window_label 窗口中打开注册页，用临时邮箱接收验证码，完成后导入账号
+async fn run_quick_register(
+    app: &AppHandle,
+    state: &AppState,
+    show_window: bool,
+    window_label: &str,
+    notifier: &mut RegisterNotifier,
+    mail_client: &mut MailClient,
+) -> Result<Account> {
+    // 开启注册代理时每次注册选择一个代理，临时邮箱请求也通过该代理
+    let (proxy, mail_options) = {
+        let settings = state.settings.lock().await;
+        (settings.register_proxy.pick(), settings.mail_options())
+    };
+    if let Some(proxy) = &proxy {
+        println!("[quick-register] 使用注册代理: {}", proxy);
+        match MailClient::new(&MailOptions { proxy: Some(proxy.clone()), ..mail_options }) {
+            Ok(client) => *mail_client = client,
+            Err(err) => {
+                notifier.failed(None, &format!("注册代理无效: {}", err));
+                return Err(ApiError::from(err));
+            }
+        }
+    }
+    let email = match mail_client.new_address().await {
+        Ok(email) => email,
+        Err(err) => {
+            notifier.failed(None, &format!("创建临时邮箱失败: {}", err));
+            return Err(ApiError::from(err));
+        }
+    };
+    notifier.mail_ready(&email, &format!("临时邮箱已创建（{}）: {}", mail_client.provider_name(), email));
+    let session = RegisterSession::new(&email, generate_password(), mail_client.session(), proxy);
+    run_register_flow(app, state, show_window, window_label, notifier, session, CodeSource::TempMail(mail_client)).await
+}
+
+/// 在 window_label 窗口中用指定邮箱完成注册流程，验证码由 code_source 提供，完成后导入账号；
+/// 结果（含邮箱和密码）追加到加密的注册凭据日志
+async fn run_register_flow(
+    app: &AppHandle,
+    state: &AppState,
+    show_window: bool,
+    window_label: &str,
+    notifier: &mut RegisterNotifier,
+    session: RegisterSession,
+    code_source: CodeSource<'_>,
+) -> Result<Account> {
+    let mut record = credential_journal::CredentialRecord::new(
+        &session.email,
+        &session.password,
+        credential_journal::CredentialStatus::Added,
+    );
+    record.mail_provider = match &code_source {
+        CodeSource::TempMail(mail_client) => Some(mail_client.provider_name().to_string()),
+        CodeSource::Manual => None,
+    };
+    let result = drive_register_flow(app, state, show_window, window_label, notifier, session, code_source).await;
+    record.at = chrono::Utc::now().timestamp();
+    match &result {
+        Ok(account) => record.account_id = Some(account.id.clone()),
+        Err(err) => {
+            record.status = credential_journal::CredentialStatus::Failed;
+            record.reason = Some(err.message.clone());
+        }
+    }
+    credential_journal::append(&record);
+    result
+}
+
+async fn drive_register_flow(
+    app: &AppHandle,
+    state: &AppState,
+    show_window: bool,
+    window_label: &str,
+    notifier: &mut RegisterNotifier,
+    mut session: RegisterSession,
+    mut code_source: CodeSource<'_>,
+) -> Result<Account> {
+    // 继续注册时沿用之前的浏览器特征
+    if session.fingerprint.is_none() && state.settings.lock().await.quick_register_random_fingerprint {
+        session.fingerprint = Some(register_fingerprint::BrowserFingerprint::random());
+    }
+    // 注册进行期间把邮箱、密码和当前步骤保存到磁盘，应用中途退出后可继续
+    let _session_guard = register_sessions::SessionGuard::new(&session);
+    let email = session.email.clone();
+    let password = session.password.clone();
+    let mail_provider = match &code_source {
+        CodeSource::TempMail(mail_client) => Some(mail_client.provider_name().to_string()),
+        CodeSource::Manual => None,
+    };
 
-    let (token_tx, token_rx) = oneshot::channel::<(String, String)>();
+    let (token_tx, mut token_rx) = oneshot::channel::<CapturedToken>();
     let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
     let token_sender = Arc::new(StdMutex::new(Some(token_tx)));
     let shutdown_sender = Arc::new(StdMutex::new(Some(shutdown_tx)));
+    // 页面脚本和页面加载回报的步骤信号（page_loaded / code_sent / submitted 等）
+    let (signal_tx, mut signal_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    // 注册页上是否正显示人机验证
+    let (captcha_tx, mut captcha_rx) = tokio::sync::watch::channel(false);
+    let captcha_tx = Arc::new(captcha_tx);
+    let captcha_challenge: Arc<StdMutex<Option<captcha_solver::CaptchaChallenge>>> = Arc::new(StdMutex::new(None));
+    let captcha_solver = state.settings.lock().await.captcha_solver.clone();
 
     let token_sender_route = token_sender.clone();
     let shutdown_sender_route = shutdown_sender.clone();
-
-    let route = warp::path("callback")
-        .and(warp::query::<HashMap<String, String>>())
-        .map(move |query: HashMap<String, String>| {
-            if let Some(msg) = query.get("log") {
-                println!("[quick-register-js] {}", msg);
-                return warp::reply::html("ok".to_string());
-            }
-
-            let token = query.get("token").cloned().unwrap_or_default();
-            let url = query.get("url").cloned().unwrap_or_default();
-            
-             if !token.is_empty() {
-                if let Some(tx) = token_sender_route.lock().unwrap().take() {
-                    let _ = tx.send((token, url));
+    let signal_tx_route = signal_tx.clone();
+    let captcha_tx_route = captcha_tx.clone();
+    let captcha_challenge_route = captcha_challenge.clone();
+    let totp_app = app.clone();
+    let route_label = window_label.to_string();
+    let totp_email = email.clone();
+
+    // 注入脚本通过应用 IPC 回传消息（日志、步骤信号、Token）
+    let (_channel_guard, callbacks) = webview_channel::register(window_label);
+    let handle_callback = move |query: HashMap<String, String>| {
+        if let Some(msg) = query.get("log") {
+            println!("[quick-register-js] {}", msg);
+            webview_log::record_line(&route_label, "script", msg);
+            return;
+        }
+        if let Some(signal) = query.get("step") {
+            match signal.as_str() {
+                "captcha_required" => {
+                    let challenge = query
+                        .get("captcha_kind")
+                        .and_then(|kind| captcha_solver::CaptchaKind::parse(kind))
+                        .zip(query.get("captcha_sitekey"))
+                        .map(|(kind, site_key)| captcha_solver::CaptchaChallenge {
+                            kind,
+                            site_key: site_key.clone(),
+                            page_url: query.get("captcha_url").cloned().unwrap_or_default(),
+                        });
+                    *captcha_challenge_route.lock().unwrap() = challenge;
+                    captcha_tx_route.send_replace(true);
                 }
-                if let Some(tx) = shutdown_sender_route.lock().unwrap().take() {
-                    let _ = tx.send(());
+                "captcha_solved" => {
+                    captcha_tx_route.send_replace(false);
+                }
+                "totp_required" => {
+                    tauri::async_runtime::spawn(totp::fill(totp_app.clone(), route_label.clone(), None, totp_email.clone()));
+                }
+                _ => {
+                    let _ = signal_tx_route.send(signal.clone());
                 }
-                warp::reply::html("已收到 Token，注册成功。".to_string())
-            } else {
-                warp::reply::html("未收到 Token".to_string())
             }
-        });
+            return;
+        }
 
-    let (addr, server) = warp::serve(route)
-        .bind_with_graceful_shutdown(([127, 0, 0, 1], 0), async move {
-            let _ = shutdown_rx.await;
-        });
-    tokio::spawn(server);
+        let token = query.get("token").cloned().unwrap_or_default();
+        let url = query.get("url").cloned().unwrap_or_default();
+        let refresh_token = query.get("refresh_token").cloned();
+        
+        if !token.is_empty() {
+            if let Some(tx) = token_sender_route.lock().unwrap().take() {
+                let _ = tx.send(CapturedToken { token, url, refresh_token });
+            }
+            if let Some(tx) = shutdown_sender_route.lock().unwrap().take() {
+                let _ = tx.send(());
+            }
+        }
+    };
+    tokio::spawn(receive_callbacks(callbacks, shutdown_rx, handle_callback));
 
     let pending_completion: Arc<StdMutex<Option<(String, String)>>> = Arc::new(StdMutex::new(None));
     let pending_completion_onload = pending_completion.clone();
-    let helper_script = build_register_helper_script(addr.port());
+    let helper_script = build_register_helper_script();
     let helper_script_onload = helper_script.clone();
     let helper_script_init = helper_script.clone();
-    let email_onload = email.clone();
+    let signal_tx_onload = signal_tx.clone();
 
-    if let Some(existing) = app.get_webview_window("trae-register") {
+    if let Some(existing) = app.get_webview_window(window_label) {
         let _ = existing.close();
     }
 
-    let webview = WebviewWindowBuilder::new(&app, "trae-register", WebviewUrl::External("about:blank".parse().unwrap()))
+    let mut builder = WebviewWindowBuilder::new(app, window_label, WebviewUrl::External("about:blank".parse().unwrap()));
+    if let Some(proxy) = &session.proxy {
+        // 不同代理的窗口使用各自的数据目录（WebView2 同一数据目录只能使用一种代理）
+        builder = builder
+            .proxy_url(register_proxy::webview_proxy_url(proxy).map_err(ApiError::from)?)
+            .data_directory(register_proxy::webview_data_dir(proxy).map_err(ApiError::from)?);
+    }
+    let (width, height) = match &session.fingerprint {
+        Some(fingerprint) => {
+            println!("[quick-register] 使用浏览器特征: {}", fingerprint.user_agent);
+            builder = builder
+                .user_agent(&fingerprint.user_agent)
+                .initialization_script(fingerprint.init_script());
+            (f64::from(fingerprint.width), f64::from(fingerprint.height))
+        }
+        None => (1000.0, 720.0),
+    };
+    let webview = builder
         .title("Trae 注册")
-        .inner_size(1000.0, 720.0)
+        .inner_size(width, height)
         .visible(show_window)
+        .initialization_script(webview_log::JS_CAPTURE)
         .initialization_script(&helper_script_init)
         .on_page_load(move |window, payload| {
             if payload.event() == PageLoadEvent::Finished {
                 let _ = window.eval(helper_script_onload.clone());
+                if payload.url().scheme() != "about" {
+                    let _ = signal_tx_onload.send("page_loaded".to_string());
+                }
                 if let Some((code, password)) = pending_completion_onload.lock().unwrap().clone() {
                     let code_js = serde_json::to_string(&code).unwrap_or_else(|_| "\"\"".to_string());
                     let password_js = serde_json::to_string(&password).unwrap_or_else(|_| "\"\"".to_string());
@@ -925,73 +2246,152 @@ async fn quick_register(app: AppHandle, show_window: bool, state: State<'_, AppS
                         "window.__traeAutoRegister && window.__traeAutoRegister.complete({}, {});",
                         code_js, password_js
                     ));
-                } else {
-                    let email_js = serde_json::to_string(&email_onload).unwrap_or_else(|_| "\"\"".to_string());
-                    let _ = window.eval(format!(
-                        "window.__traeAutoRegister && window.__traeAutoRegister.start({});",
-                        email_js
-                    ));
                 }
             }
         })
         .build()
         .map_err(|e| anyhow::anyhow!("无法打开注册窗口: {}", e))?;
 
-    if !show_window {
-        emit_quick_register_notice(&app, "quick_register_init", "初始化完成，等待接收邮箱验证码");
-    }
-
+    notifier.milestone(RegisterStage::Init, "初始化完成，正在打开注册页");
     let _ = webview.clear_all_browsing_data();
-    let _ = webview.navigate(Url::parse("https://www.trae.ai/sign-up").unwrap());
     if show_window {
         let _ = webview.set_focus();
     }
-    let _ = webview.eval(helper_script);
-
-    let code = match wait_for_verification_code(&mut mail_client, Duration::from_secs(60)).await {
-        Ok(code) => code,
-        Err(err) => {
-            let _ = webview.close();
-            if !show_window {
-                emit_quick_register_notice(
-                    &app,
-                    "quick_register_failed",
-                    "快速注册失败，可在设置中开启快速注册显示浏览器查看失败原因。",
-                );
-            }
-            return Err(ApiError::from(err));
-        }
-    };
-
-    if !show_window {
-        emit_quick_register_notice(&app, "quick_register_code_ok", "邮箱验证码获取成功，正在登录");
-    }
 
-    *pending_completion.lock().unwrap() = Some((code.clone(), password.clone()));
-    let code_js = serde_json::to_string(&code).unwrap_or_else(|_| "\"\"".to_string());
+    // 按步骤执行注册：每步失败后自动重试，重试用尽后等待用户选择重试该步骤或放弃
+    let (_control_guard, mut controls) = register_steps::register(window_label);
+    let email_js = serde_json::to_string(&email).unwrap_or_else(|_| "\"\"".to_string());
     let password_js = serde_json::to_string(&password).unwrap_or_else(|_| "\"\"".to_string());
-    let _ = webview.eval(format!(
-        "window.__traeAutoRegister && window.__traeAutoRegister.complete({}, {});",
-        code_js, password_js
-    ));
+    let mut step = RegisterStep::OpenForm;
+    let mut attempt = 0;
+    let mut code = String::new();
+    let CapturedToken { token, url, refresh_token } = loop {
+        attempt += 1;
+        while signal_rx.try_recv().is_ok() {}
+        // 出现人机验证时优先交给打码服务，未配置或无法识别时显示注册窗口交给用户处理
+        let on_captcha = || {
+            let challenge = captcha_challenge.lock().unwrap().clone();
+            match challenge {
+                Some(challenge) if captcha_solver.is_enabled() => {
+                    println!("[quick-register] 注册页出现人机验证，正在调用打码服务");
+                    notifier.step(RegisterStage::Captcha, "注册页面需要人机验证，正在通过打码服务处理");
+                    captcha_solver::spawn_solve(app, &webview, window_label, step, captcha_solver.clone(), challenge);
+                }
+                _ => {
+                    println!("[quick-register] 注册页出现人机验证，等待用户完成");
+                    register_steps::hand_off_captcha(app, &webview, window_label, step);
+                    notifier.step(RegisterStage::Captcha, "注册页面需要人机验证，请在注册窗口中完成");
+                }
+            }
+        };
+        let result: std::result::Result<Option<CapturedToken>, String> = match step {
+            RegisterStep::OpenForm => {
+                let _ = webview.navigate(Url::parse(QUICK_REGISTER_URL).unwrap());
+                let _ = webview.eval(helper_script.clone());
+                let waiting = wait_for_signal(&mut signal_rx, "page_loaded", None);
+                register_steps::run_step(step.timeout(), waiting, &mut captcha_rx, on_captcha).await.map(|_| None)
+            }
+            RegisterStep::SendCode => {
+                let _ = webview.eval(format!(
+                    "window.__traeAutoRegister && window.__traeAutoRegister.start({}, {});",
+                    email_js,
+                    attempt > 1
+                ));
+                let waiting = wait_for_signal(&mut signal_rx, "code_sent", Some("send_code_failed"));
+                register_steps::run_step(step.timeout(), waiting, &mut captcha_rx, on_captcha).await.map(|_| None)
+            }
+            RegisterStep::AwaitCode => {
+                if attempt > 1 {
+                    // 重新点击发送验证码后再等待
+                    let _ = webview.eval(format!(
+                        "window.__traeAutoRegister && window.__traeAutoRegister.start({}, true);",
+                        email_js
+                    ));
+                }
+                let received = match &mut code_source {
+                    CodeSource::TempMail(mail_client) => {
+                        let waiting =
+                            async { mail::wait_for_verification_code(mail_client).await.map_err(|e| e.to_string()) };
+                        register_steps::run_step(step.timeout(), waiting, &mut captcha_rx, on_captcha).await
+                    }
+                    CodeSource::Manual => {
+                        let waiting = register_steps::wait_for_manual_code(app, &mut controls, window_label, &email);
+                        register_steps::run_step(register_steps::MANUAL_CODE_TIMEOUT, waiting, &mut captcha_rx, on_captcha)
+                            .await
+                    }
+                };
+                received.map(|received| {
+                    code = received.trim().to_string();
+                    None
+                })
+            }
+            RegisterStep::Submit => {
+                *pending_completion.lock().unwrap() = Some((code.clone(), password.clone()));
+                let code_js = serde_json::to_string(&code).unwrap_or_else(|_| "\"\"".to_string());
+                let _ = webview.eval(format!(
+                    "window.__traeAutoRegister && window.__traeAutoRegister.complete({}, {}, {});",
+                    code_js,
+                    password_js,
+                    attempt > 1
+                ));
+                let waiting = wait_for_signal(&mut signal_rx, "submitted", Some("submit_failed"));
+                register_steps::run_step(step.timeout(), waiting, &mut captcha_rx, on_captcha).await.map(|_| None)
+            }
+            RegisterStep::AwaitToken => {
+                let waiting = async { (&mut token_rx).await.map_err(|_| "Token 回调已关闭".to_string()) };
+                register_steps::run_step(step.timeout(), waiting, &mut captcha_rx, on_captcha).await.map(Some)
+            }
+        };
 
-    println!("[quick-register] Waiting for login completion (token interception)...");
-    let (token, url) = match token_rx.await {
-        Ok(res) => res,
-        Err(_) => {
-             println!("[quick-register] Token wait channel closed or timed out");
-             let _ = webview.close();
-             if !show_window {
-                emit_quick_register_notice(
-                    &app,
-                    "quick_register_failed",
-                    "快速注册失败，可在设置中开启快速注册显示浏览器查看失败原因。",
+        match result {
+            Ok(Some(captured)) => break captured,
+            Ok(None) => {
+                match step {
+                    RegisterStep::OpenForm => notifier.step(RegisterStage::PageOpened, "已打开注册页面"),
+                    RegisterStep::SendCode => notifier.step(RegisterStage::CodeSent, "已发送验证码，等待接收邮件"),
+                    RegisterStep::AwaitCode => notifier.milestone(RegisterStage::CodeReceived, "邮箱验证码获取成功，正在登录"),
+                    RegisterStep::Submit => notifier.step(RegisterStage::Submitted, "已提交注册，等待登录"),
+                    RegisterStep::AwaitToken => {}
+                }
+                step = step.next().unwrap_or(RegisterStep::AwaitToken);
+                attempt = 0;
+                session.step = step;
+                register_sessions::save(&session);
+            }
+            Err(message) => {
+                println!("[quick-register] 步骤「{}」第 {} 次失败: {}", step.label(), attempt, message);
+                if attempt < register_steps::STEP_ATTEMPTS {
+                    notifier.retrying(step, &format!("{}失败，正在重试（{}/{}）", step.label(), attempt, register_steps::STEP_ATTEMPTS));
+                    continue;
+                }
+                let _ = webview.show();
+                if register_steps::wait_for_resume(app, &mut controls, window_label, step, &message).await {
+                    notifier.retrying(step, &format!("正在重新执行：{}", step.label()));
+                    attempt = 0;
+                    continue;
+                }
+                let _ = webview.close();
+                notifier.failed(
+                    Some(step),
+                    &format!("快速注册失败（{}：{}），可在设置中开启快速注册显示浏览器查看失败原因。", step.label(), message),
                 );
+                return Err(ApiError::from(error::coded(
+                    if step == RegisterStep::AwaitToken || step == RegisterStep::AwaitCode {
+                        error::ErrorCode::Timeout
+                    } else {
+                        error::ErrorCode::Unknown
+                    },
+                    format!("{}失败: {}", step.label(), message),
+                )));
             }
-             return Err(anyhow::anyhow!("等待 Token 超时或失败").into());
         }
     };
-    println!("[quick-register] Token intercepted successfully.");
+    notifier.step(RegisterStage::TokenCaptured, "已获取登录 Token，正在读取 Cookie");
+    // 注册已完成：先记录凭据，之后导入账号失败也不会丢失
+    credential_journal::append(&credential_journal::CredentialRecord {
+        mail_provider: mail_provider.clone(),
+        ..credential_journal::CredentialRecord::new(&email, &password, credential_journal::CredentialStatus::SignedUp)
+    });
     let cookies = match wait_for_request_cookies(&webview, &url, Duration::from_secs(6)).await {
         Ok(cookies) => {
             println!("[quick-register] Captured cookies for {}: {}", url, cookies);
@@ -1001,47 +2401,64 @@ async fn quick_register(app: AppHandle, show_window: bool, state: State<'_, AppS
         Err(err) => {
             println!("[quick-register] Failed to capture GetUserToken cookies: {}", err);
             let _ = webview.close();
-            if !show_window {
-                emit_quick_register_notice(
-                    &app,
-                    "quick_register_failed",
-                    "获取登录 Cookie 失败，请重试。",
-                );
-            }
+            notifier.failed(None, "获取登录 Cookie 失败，请重试。");
             return Err(ApiError::from(err));
         }
     };
 
-    if !show_window {
-        emit_quick_register_notice(&app, "quick_register_login_ok", "登录成功，正在导入账号");
-    }
+    notifier.milestone(RegisterStage::LoginOk, "登录成功，正在导入账号");
 
     let _ = webview.close();
+    let bind_new_machine_id = state.settings.lock().await.quick_register_new_machine_id;
     println!("[quick-register] Adding account to manager...");
     let mut manager = state.account_manager.lock().await;
-    let mut account = manager.add_account_by_token(token, Some(cookies), Some(password)).await.map_err(ApiError::from)?;
-    println!("[quick-register] Account added, ID: {}", account.id);
+    let mut account = match manager.add_account_by_token(token, Some(cookies), Some(password)).await {
+        Ok(account) => account,
+        Err(err) => {
+            notifier.failed(None, &format!("导入账号失败: {}", err));
+            return Err(ApiError::from(err));
+        }
+    };
+    if let Err(e) = manager.set_refresh_token(&account.id, refresh_token) {
+        println!("[WARN] 保存刷新 Token 失败: {}", e);
+    }
+    let registration = AccountRegistration {
+        source: AccountSource::QuickRegister,
+        mail_provider,
+        mail_domain: email.split_once('@').map(|(_, domain)| domain.to_lowercase()),
+        registered_at: Some(chrono::Utc::now().timestamp()),
+        user_agent: session.fingerprint.as_ref().map(|fingerprint| fingerprint.user_agent.clone()),
+    };
+    match manager.set_registration(&account.id, registration) {
+        Ok(updated) => account = updated,
+        Err(e) => println!("[WARN] 保存注册信息失败: {}", e),
+    }
+    notifier.step(RegisterStage::AccountAdded, &format!("账号已保存，ID: {}", account.id));
     let needs_email_override = account.email.trim().is_empty()
         || account.email.contains('*')
         || !account.email.contains('@');
     if needs_email_override {
         manager
-            .update_account_email(&account.id, email.clone())
+            .update_account_email(&account.id, email.to_string())
             .map_err(ApiError::from)?;
         account = manager.get_account(&account.id).map_err(ApiError::from)?;
     }
-    if !show_window {
-        emit_quick_register_notice(&app, "quick_register_done", "导入成功");
+    if bind_new_machine_id {
+        match manager.bind_new_machine_id(&account.id) {
+            Ok(machine_id) => account.machine_id = Some(machine_id),
+            Err(e) => println!("[WARN] 绑定新机器码失败: {}", e),
+        }
     }
+    notifier.succeeded(&account.email);
     Ok(account)
 }
 
-fn build_browser_login_script(port: u16) -> String {
+fn build_browser_login_script() -> String {
     let script = r#"(function() {
   if (window.__traeAutoInjected) return;
   window.__traeAutoInjected = true;
 
-  const callback = "http://127.0.0.1:__PORT__/callback";
+  const send = __SEND__;
   let loginTriggered = false;
   const normalize = (text) => (text || "").toLowerCase();
   const STORAGE_EMAIL_KEY = "__trae_login_email";
@@ -1260,20 +2677,15 @@ fn build_browser_login_script(port: u16) -> String {
     return false;
   };
   const sendPayload = (payload) => {
-    const params = new URLSearchParams();
+    const query = {};
     Object.keys(payload || {}).forEach((key) => {
       const value = payload[key];
       if (value === undefined || value === null || value === "") return;
-      params.append(key, value);
+      query[key] = String(value);
     });
-    if (capturedEmail) params.append("email", capturedEmail);
-    if (capturedPassword) params.append("password", capturedPassword);
-    const url = callback + "?" + params.toString();
-    if (navigator.sendBeacon) {
-      navigator.sendBeacon(url);
-    } else {
-      fetch(url, { mode: "no-cors" });
-    }
+    if (capturedEmail) query.email = capturedEmail;
+    if (capturedPassword) query.password = capturedPassword;
+    send(query);
   };
   const syncCredentials = () => {
     if (!capturedEmail && !capturedPassword) return;
@@ -1291,19 +2703,30 @@ fn build_browser_login_script(port: u16) -> String {
     }
   };
 
-  const sendToken = (token, url) => {
+  const sendToken = (token, url, data) => {
     if (!token) return;
     loginTriggered = true;
-    sendPayload({ token, url: normalizeUrl(url) });
+    sendPayload({ token, url: normalizeUrl(url), refresh_token: parseRefreshToken(data) });
   };
   const sendState = (state, href) => {
     if (!state) return;
     loginTriggered = true;
     sendPayload({ state, href: href || "" });
   };
+  const isTraeHost = (hostname) => {
+    const host = (hostname || "").toLowerCase();
+    return ["trae.ai", "trae.com.cn"].some((d) => host === d || host.endsWith("." + d));
+  };
   const isLoginCompleteUrl = (href) => {
     if (!href) return false;
     const lower = href.toLowerCase();
+    // 第三方登录（Google / GitHub）页面和回调页都不算登录完成
+    try {
+      if (!isTraeHost(new URL(href).hostname)) return false;
+    } catch {
+      return false;
+    }
+    if (lower.includes("oauth") || lower.includes("callback") || lower.includes("authorize")) return false;
     if (lower.includes("/login")) return false;
     if (lower.includes("passport")) return false;
     if (lower.includes("sign-up") || lower.includes("signup") || lower.includes("register")) return false;
@@ -1320,20 +2743,32 @@ fn build_browser_login_script(port: u16) -> String {
       null
     );
   };
+  const parseRefreshToken = (data) => {
+    if (!data) return null;
+    return (
+      data.result?.refreshToken ||
+      data.result?.RefreshToken ||
+      data.Result?.refreshToken ||
+      data.Result?.RefreshToken ||
+      null
+    );
+  };
 
   const markLoginTriggered = () => {
     loginTriggered = true;
   };
   const tryFetch = async () => {
-    const endpoints = [
-      "https://api-sg-central.trae.ai/cloudide/api/v3/common/GetUserToken",
-      "https://api-us-east.trae.ai/cloudide/api/v3/common/GetUserToken"
-    ];
+    // 国内版（trae.com.cn）页面优先请求国内端点，Cookie 只会发送到同站点的端点
+    const isCn = location.hostname.endsWith("trae.com.cn");
+    const endpoints = __TOKEN_ENDPOINTS__.sort(
+      (a, b) => Number(b.includes("trae.com.cn") === isCn) - Number(a.includes("trae.com.cn") === isCn)
+    );
+    const origin = isCn ? "https://www.trae.com.cn" : "https://www.trae.ai";
     const headers = {
       "content-type": "application/json",
       "accept": "application/json, text/plain, */*",
-      "origin": "https://www.trae.ai",
-      "referer": "https://www.trae.ai/"
+      "origin": origin,
+      "referer": origin + "/"
     };
     for (const endpoint of endpoints) {
       try {
@@ -1346,7 +2781,7 @@ fn build_browser_login_script(port: u16) -> String {
         const data = await res.json();
         const token = parseToken(data);
         if (token) {
-          sendToken(token, res.url);
+          sendToken(token, res.url, data);
           return;
         }
       } catch {}
@@ -1370,7 +2805,7 @@ fn build_browser_login_script(port: u16) -> String {
         if (typeof res.url === "string" && res.url.includes("GetUserToken")) {
           const data = await res.clone().json();
           const token = parseToken(data);
-          if (token) sendToken(token, res.url);
+          if (token) sendToken(token, res.url, data);
         }
       } catch {}
       return res;
@@ -1393,7 +2828,7 @@ fn build_browser_login_script(port: u16) -> String {
           if ((this.__trae_url || "").includes("GetUserToken")) {
             const data = JSON.parse(this.responseText);
             const token = parseToken(data);
-            if (token) sendToken(token, this.__trae_url);
+            if (token) sendToken(token, this.__trae_url, data);
           }
         } catch {}
       });
@@ -1404,10 +2839,14 @@ fn build_browser_login_script(port: u16) -> String {
   hookFetch();
   hookXHR();
   hookValueSetter();
-  tryFetch();
+  // 第三方登录跳回 Trae 后由应用调用，立即获取 Token
+  window.__traeCheckLogin = () => {
+    if (isTraeHost(location.hostname)) tryFetch();
+  };
+  if (isTraeHost(location.hostname)) tryFetch();
   tryAcceptCookies();
   scanInputs();
-  setInterval(tryFetch, 3000);
+  setInterval(() => window.__traeCheckLogin(), 3000);
   setInterval(tryAcceptCookies, 1500);
   setInterval(scanInputs, 2000);
   try {
@@ -1469,8 +2908,57 @@ fn build_browser_login_script(port: u16) -> String {
     sendState("logged_in", location.href);
     tryFetch();
   }
+  // 提交登录后检测页面上的错误提示（密码错误、账号锁定、尝试次数过多），回传给应用结束等待
+  const LOGIN_ERRORS = [
+    ["too_many_attempts", ["too many", "try again later", "rate limit", "频繁", "次数过多", "稍后再试"]],
+    ["account_locked", ["locked", "suspended", "banned", "disabled", "锁定", "冻结", "封禁", "禁用"]],
+    ["wrong_password", ["incorrect password", "wrong password", "invalid password", "password is incorrect", "incorrect email or password", "invalid email or password", "密码错误", "密码不正确", "账号或密码"]],
+  ];
+  let lastLoginError = "";
+  const checkLoginError = () => {
+    if (!loginTriggered || !isTraeHost(location.hostname)) return;
+    const banners = Array.from(document.querySelectorAll("[role='alert'], [class*='error'], [class*='Error'], [class*='toast'], [class*='message']"));
+    for (const el of banners) {
+      if (!(el.offsetWidth || el.offsetHeight)) continue;
+      const message = (el.innerText || el.textContent || "").trim().replace(/\s+/g, " ");
+      if (!message || message.length > 200) continue;
+      const lower = message.toLowerCase();
+      const match = LOGIN_ERRORS.find(([, keywords]) => keywords.some((keyword) => lower.includes(keyword)));
+      if (!match) continue;
+      if (message === lastLoginError) return;
+      lastLoginError = message;
+      sendPayload({ state: "error", error: message, error_kind: match[0] });
+      return;
+    }
+  };
+  setInterval(checkLoginError, 1000);
+  __TOTP__
 })();"#;
-    script.replace("__PORT__", &port.to_string())
+    let endpoints = serde_json::to_string(&api::user_token_endpoints()).unwrap_or_else(|_| "[]".to_string());
+    script
+        .replace("__SEND__", webview_channel::JS_SEND)
+        .replace("__TOTP__", totp::JS_TOTP)
+        .replace("__TOKEN_ENDPOINTS__", &endpoints)
+}
+
+/// 删除窗口中 Trae 域名下的 Cookie
+fn clear_trae_cookies(webview: &WebviewWindow) {
+    let cookies = match webview.cookies() {
+        Ok(cookies) => cookies,
+        Err(e) => {
+            println!("[browser-login] 读取登录窗口 Cookie 失败: {}", e);
+            return;
+        }
+    };
+    let mut removed = 0;
+    for cookie in cookies {
+        let domain = cookie.domain().unwrap_or_default().trim_start_matches('.').to_lowercase();
+        let is_trae = ["trae.ai", "trae.com.cn"].iter().any(|d| domain == *d || domain.ends_with(&format!(".{}", d)));
+        if is_trae && webview.delete_cookie(cookie).is_ok() {
+            removed += 1;
+        }
+    }
+    println!("[browser-login] 已清除共享登录数据中的 {} 个 Trae Cookie", removed);
 }
 
 fn collect_trae_cookies(webview: &WebviewWindow, extra_url: Option<&str>) -> String {
@@ -1479,6 +2967,8 @@ fn collect_trae_cookies(webview: &WebviewWindow, extra_url: Option<&str>) -> Str
         "https://www.trae.ai/".to_string(),
         "https://api-sg-central.trae.ai/".to_string(),
         "https://ug-normal.trae.ai/".to_string(),
+        "https://www.trae.com.cn/".to_string(),
+        "https://api.trae.com.cn/".to_string(),
     ];
     
     if let Some(url) = extra_url {
@@ -1517,18 +3007,63 @@ fn collect_trae_cookies(webview: &WebviewWindow, extra_url: Option<&str>) -> Str
     }
     cookies
 }
+/// 打开浏览器登录窗口；incognito 为 false 时使用共享的登录数据，保留 Google / GitHub 等第三方登录状态（默认每次使用空白数据）
+///
+/// start_url 为登录窗口的起始页，未指定时使用设置中的起始页
+#[tauri::command]
+async fn start_browser_login(
+    incognito: Option<bool>,
+    start_url: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    open_browser_login(app, &state, None, incognito.unwrap_or(true), start_url).await
+}
+
+/// 为已有账号重新打开浏览器登录，完成后更新该账号的 Cookies / Token / 密码
 #[tauri::command]
-async fn start_browser_login(app: AppHandle, state: State<'_, AppState>) -> Result<()> {
+async fn start_browser_relogin(account_id: String, app: AppHandle, state: State<'_, AppState>) -> Result<()> {
+    let account = state
+        .account_manager
+        .lock()
+        .await
+        .get_account(&account_id)
+        .map_err(ApiError::from)?;
+    open_browser_login(app, &state, Some(account), false, None).await
+}
+
+/// 打开浏览器登录窗口；relogin 为重新登录的账号，此时窗口使用该账号的 WebView 数据，否则按 incognito 使用空白或共享的登录数据
+async fn open_browser_login(
+    app: AppHandle,
+    state: &AppState,
+    relogin: Option<Account>,
+    incognito: bool,
+    start_url: Option<String>,
+) -> Result<()> {
+    let start_url = match start_url.filter(|url| !url.trim().is_empty()) {
+        Some(url) => url,
+        None => state.settings.lock().await.browser_login_url.clone(),
+    };
+    let start_url = if start_url.trim().is_empty() {
+        Url::parse(DEFAULT_BROWSER_LOGIN_URL).unwrap()
+    } else {
+        parse_browser_login_url(&start_url).map_err(ApiError::from)?
+    };
     let mut browser_login = state.browser_login.lock().await;
     if browser_login.is_some() {
         return Err(anyhow::anyhow!("浏览器登录已在进行中").into());
     }
-    println!("[browser-login] start_browser_login: launching login window");
+    match &relogin {
+        Some(account) => println!("[browser-login] start_browser_relogin: launching login window for {}", account.email),
+        None => println!("[browser-login] start_browser_login: launching login window"),
+    }
 
-    let (token_tx, token_rx) = oneshot::channel::<(String, String)>();
+    let (token_tx, token_rx) = oneshot::channel::<CapturedToken>();
     let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
     let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
     let (window_close_tx, window_close_rx) = oneshot::channel::<()>();
+    let (extend_tx, extend_rx) = tokio::sync::mpsc::unbounded_channel::<u64>();
+    let (failure_tx, failure_rx) = tokio::sync::mpsc::unbounded_channel::<BrowserLoginFailure>();
     let token_sender = Arc::new(StdMutex::new(Some(token_tx)));
     let shutdown_sender = Arc::new(StdMutex::new(Some(shutdown_tx)));
     let window_close_sender = Arc::new(StdMutex::new(Some(window_close_tx)));
@@ -1537,53 +3072,63 @@ async fn start_browser_login(app: AppHandle, state: State<'_, AppState>) -> Resu
     let token_sender_route = token_sender.clone();
     let shutdown_sender_route = shutdown_sender.clone();
     let credentials_route = credentials.clone();
-    let route = warp::path("callback")
-        .and(warp::query::<HashMap<String, String>>())
-        .map(move |query: HashMap<String, String>| {
-            let mut log_query = query.clone();
-            if log_query.contains_key("password") {
-                log_query.insert("password".to_string(), "***".to_string());
+    let app_route = app.clone();
+    let relogin_route = relogin.as_ref().map(|account| account.id.clone());
+    let (channel_guard, callbacks) = webview_channel::register("trae-login");
+    let handle_callback = move |query: HashMap<String, String>| {
+        if query.get("step").is_some_and(|step| step == "totp_required") {
+            let email = credentials_route.lock().unwrap().email.clone().unwrap_or_default();
+            tauri::async_runtime::spawn(totp::fill(app_route.clone(), "trae-login".to_string(), relogin_route.clone(), email));
+            return;
+        }
+        let mut log_query = query.clone();
+        for key in ["password", "refresh_token"] {
+            if log_query.contains_key(key) {
+                log_query.insert(key.to_string(), "***".to_string());
             }
-            println!("[browser-login] callback query: {:?}", log_query);
-            let token = query.get("token").cloned().unwrap_or_default();
-            let state = query.get("state").cloned().unwrap_or_default();
-            let href = query.get("href").cloned().unwrap_or_default();
-            let url = query.get("url").cloned().unwrap_or_default();
-            let email = query.get("email").cloned().unwrap_or_default();
-            let password = query.get("password").cloned().unwrap_or_default();
-
-            if !email.trim().is_empty() || !password.is_empty() {
-                let mut creds = credentials_route.lock().unwrap();
-                if !email.trim().is_empty() {
-                    creds.email = Some(email.trim().to_string());
-                }
-                if !password.is_empty() {
-                    creds.password = Some(password);
-                }
+        }
+        println!("[browser-login] callback query: {:?}", log_query);
+        let token = query.get("token").cloned().unwrap_or_default();
+        let state = query.get("state").cloned().unwrap_or_default();
+        let href = query.get("href").cloned().unwrap_or_default();
+        let url = query.get("url").cloned().unwrap_or_default();
+        let refresh_token = query.get("refresh_token").cloned();
+        let email = query.get("email").cloned().unwrap_or_default();
+        let password = query.get("password").cloned().unwrap_or_default();
+
+        if !email.trim().is_empty() || !password.is_empty() {
+            let mut creds = credentials_route.lock().unwrap();
+            let was_complete = creds.email.is_some() && creds.password.is_some();
+            if !email.trim().is_empty() {
+                creds.email = Some(email.trim().to_string());
             }
-            if !token.is_empty() {
-                if let Some(tx) = token_sender_route.lock().unwrap().take() {
-                    let _ = tx.send((token, url));
-                }
-                if let Some(tx) = shutdown_sender_route.lock().unwrap().take() {
-                    let _ = tx.send(());
-                }
-                warp::reply::html("已收到 Token，可以关闭此页面并返回应用。".to_string())
-            } else if state == "logged_in" {
-                warp::reply::html(format!("检测到登录完成，等待获取 Token。{href}"))
-            } else {
-                warp::reply::html("未收到 Token，请重试。".to_string())
+            if !password.is_empty() {
+                creds.password = Some(password);
             }
-        });
-
-    let (addr, server): (std::net::SocketAddr, _) = warp::serve(route)
-        .bind_with_graceful_shutdown(([127, 0, 0, 1], 0), async move {
-            let _ = shutdown_rx.await;
-        });
-
-    tokio::spawn(server);
+            // 邮箱和密码首次都读取到时通知一次（输入过程中会多次回传）
+            if !was_complete && creds.email.is_some() && creds.password.is_some() {
+                emit_browser_login_progress(&app_route, BrowserLoginStage::CredentialsCaptured, "已读取登录邮箱和密码", None, creds.email.clone());
+            }
+        }
+        if !token.is_empty() {
+            if let Some(tx) = token_sender_route.lock().unwrap().take() {
+                let _ = tx.send(CapturedToken { token, url, refresh_token });
+            }
+            if let Some(tx) = shutdown_sender_route.lock().unwrap().take() {
+                let _ = tx.send(());
+            }
+        } else if state == "logged_in" {
+            println!("[browser-login] 检测到登录完成，等待获取 Token: {}", href);
+        } else if state == "error" {
+            let message = query.get("error").cloned().unwrap_or_default();
+            let kind = BrowserLoginFailureKind::parse(query.get("error_kind").map(String::as_str).unwrap_or_default());
+            println!("[browser-login] 登录页提示登录失败 ({:?}): {}", kind, message);
+            let _ = failure_tx.send(BrowserLoginFailure { kind, message });
+        }
+    };
+    tokio::spawn(receive_callbacks(callbacks, shutdown_rx, handle_callback));
 
-    let script = build_browser_login_script(addr.port());
+    let script = build_browser_login_script();
     let script_init = script.clone();
     let script_onload = script.clone();
 
@@ -1591,18 +3136,51 @@ async fn start_browser_login(app: AppHandle, state: State<'_, AppState>) -> Resu
         let _ = existing.close();
     }
 
-    let webview = WebviewWindowBuilder::new(&app, "trae-login", WebviewUrl::External("about:blank".parse().unwrap()))
-        .title("Trae 登录")
+    let shared_profile = relogin.is_none() && !incognito;
+    let profile = match &relogin {
+        Some(account) => webview_profile::WebviewProfile::for_account(&account.id),
+        None if incognito => webview_profile::WebviewProfile::fresh(),
+        None => webview_profile::WebviewProfile::shared_login(),
+    }
+    .map_err(ApiError::from)?;
+    let title = match &relogin {
+        Some(account) => format!("Trae 重新登录 - {}", account.email),
+        None => "Trae 登录".to_string(),
+    };
+    let builder = WebviewWindowBuilder::new(&app, "trae-login", WebviewUrl::External("about:blank".parse().unwrap()));
+    let oauth = login_oauth::OAuthTracker::default();
+    let nav_oauth = oauth.clone();
+    let nav_app = app.clone();
+    let popup_oauth = oauth.clone();
+    let popup_app = app.clone();
+    let page_app = app.clone();
+    let webview = profile
+        .apply(builder)
+        .title(&title)
         .inner_size(1000.0, 720.0)
+        .initialization_script(webview_log::JS_CAPTURE)
         .initialization_script(&script_init)
+        .on_navigation(move |url| {
+            if nav_oauth.observe(url) {
+                login_oauth::check_login(&nav_app, "trae-login");
+            }
+            true
+        })
+        .on_new_window(move |url, features| {
+            login_oauth::open_popup(&popup_app, &popup_oauth, "trae-login", url, features)
+        })
         .on_page_load(move |window, payload| {
             if payload.event() == PageLoadEvent::Finished {
                 println!("[browser-login] page load finished, injecting script");
                 let _ = window.eval(script_onload.clone());
+                if payload.url().scheme() != "about" {
+                    emit_browser_login_progress(&page_app, BrowserLoginStage::PageNavigated, "已打开页面", Some(payload.url().to_string()), None);
+                }
             }
         })
         .build()
         .map_err(|e| anyhow::anyhow!("无法打开登录窗口: {}", e))?;
+    emit_browser_login_progress(&app, BrowserLoginStage::WindowOpened, "已打开登录窗口", None, None);
 
     let window_close_sender_clone = window_close_sender.clone();
     webview.on_window_event(move |event| {
@@ -1613,12 +3191,13 @@ async fn start_browser_login(app: AppHandle, state: State<'_, AppState>) -> Resu
         }
     });
 
-    if let Err(e) = webview.clear_all_browsing_data() {
-        println!("[browser-login] clear browsing data failed: {}", e);
-    } else {
-        println!("[browser-login] cleared browsing data");
+    if shared_profile {
+        // 共享的登录数据中可能还有上一次登录的 Trae 账号，只清除 Trae 的 Cookie，保留第三方登录状态
+        clear_trae_cookies(&webview);
     }
-    let _ = webview.navigate(Url::parse("https://www.trae.ai/login").unwrap());
+
+    println!("[browser-login] 打开登录页: {}", start_url);
+    let _ = webview.navigate(start_url);
 
     let _ = webview.set_focus();
     let _ = webview.eval(script);
@@ -1630,67 +3209,90 @@ async fn start_browser_login(app: AppHandle, state: State<'_, AppState>) -> Resu
         window_close: window_close_rx,
         webview,
         credentials,
+        profile,
+        oauth,
+        _callback_channel: channel_guard,
+        extend: extend_rx,
+        failure: failure_rx,
+        relogin_account_id: relogin.map(|account| account.id),
     });
     *state.browser_login_cancel.lock().await = Some(cancel_tx);
+    *state.browser_login_extend.lock().await = Some(extend_tx);
 
     Ok(())
 }
 
 #[tauri::command]
-async fn finish_browser_login(state: State<'_, AppState>) -> Result<Account> {
+async fn finish_browser_login(app: AppHandle, state: State<'_, AppState>) -> Result<Account> {
     println!("[browser-login] finish_browser_login: waiting for token");
-    let session = {
+    let mut session = {
         let mut browser_login = state.browser_login.lock().await;
         browser_login.take().ok_or_else(|| anyhow::anyhow!("浏览器登录未开始"))?
     };
 
-    let (token, url) = tokio::select! {
-        res = session.receiver => {
-            match res {
-                Ok(token) => token,
-                Err(_) => {
-                    let _ = state.browser_login_cancel.lock().await.take();
-                    if let Some(tx) = session.shutdown.lock().unwrap().take() {
-                        let _ = tx.send(());
-                    }
-                    let _ = session.webview.close();
-                    return Err(anyhow::anyhow!("浏览器登录已取消").into());
-                }
+    // 按设置的时长等待登录完成，期间定时推送剩余时间和当前页面，用户可延长等待时间
+    let mut timeout_secs = state.settings.lock().await.browser_login_timeout_secs.max(1);
+    let started = tokio::time::Instant::now();
+    let mut ticker = tokio::time::interval(BROWSER_LOGIN_WAITING_INTERVAL);
+    let outcome = loop {
+        let deadline = started + Duration::from_secs(timeout_secs);
+        tokio::select! {
+            res = &mut session.receiver => {
+                break res.map_err(|_| error::coded(error::ErrorCode::Cancelled, "浏览器登录已取消"));
             }
-        }
-        _ = session.cancel => {
-            let _ = state.browser_login_cancel.lock().await.take();
-            if let Some(tx) = session.shutdown.lock().unwrap().take() {
-                let _ = tx.send(());
+            _ = &mut session.cancel => {
+                break Err(error::coded(error::ErrorCode::Cancelled, "浏览器登录已取消"));
             }
-            let _ = session.webview.close();
-            return Err(anyhow::anyhow!("浏览器登录已取消").into());
-        }
-        _ = session.window_close => {
-            let _ = state.browser_login_cancel.lock().await.take();
-            if let Some(tx) = session.shutdown.lock().unwrap().take() {
-                let _ = tx.send(());
+            _ = &mut session.window_close => {
+                break Err(error::coded(error::ErrorCode::Cancelled, "浏览器被主动关闭"));
             }
-            return Err(anyhow::anyhow!("浏览器被主动关闭").into());
-        }
-        _ = tokio::time::sleep(Duration::from_secs(300)) => {
-            let _ = state.browser_login_cancel.lock().await.take();
-            if let Some(tx) = session.shutdown.lock().unwrap().take() {
-                let _ = tx.send(());
+            Some(failure) = session.failure.recv() => {
+                break Err(error::coded(failure.kind.code(), format!("登录失败: {}", failure.message)));
+            }
+            Some(secs) = session.extend.recv() => {
+                timeout_secs = timeout_secs.saturating_add(secs);
+                println!("[browser-login] 等待时间延长 {} 秒", secs);
+            }
+            _ = tokio::time::sleep_until(deadline) => {
+                break Err(error::coded(error::ErrorCode::Timeout, "等待浏览器登录超时"));
+            }
+            _ = ticker.tick() => {
+                let waiting = BrowserLoginWaiting {
+                    remaining_secs: deadline.saturating_duration_since(tokio::time::Instant::now()).as_secs(),
+                    timeout_secs,
+                    url: session.webview.url().ok().map(|url| url.to_string()),
+                };
+                let _ = app.emit("browser_login_waiting", &waiting);
             }
-            let _ = session.webview.close();
-            return Err(anyhow::anyhow!("等待浏览器登录超时").into());
         }
     };
 
+    let _ = state.browser_login_cancel.lock().await.take();
+    let _ = state.browser_login_extend.lock().await.take();
     if let Some(tx) = session.shutdown.lock().unwrap().take() {
         let _ = tx.send(());
     }
-    let _ = state.browser_login_cancel.lock().await.take();
+    let CapturedToken { token, url, refresh_token } = match outcome {
+        Ok(token) => token,
+        Err(err) => {
+            let _ = session.webview.close();
+            return Err(err.into());
+        }
+    };
+    emit_browser_login_progress(&app, BrowserLoginStage::TokenReceived, "已获取登录 Token", None, None);
+
+    // 第三方登录需等跳回 Trae 后 Cookie 才完整
+    if let Some(provider) = session.oauth.pending_provider() {
+        println!("[browser-login] 等待 {} 登录跳回 Trae", provider);
+        if !session.oauth.wait_returned(Duration::from_secs(30)).await {
+            println!("[browser-login] 等待 {} 登录跳回超时，使用当前 Cookie", provider);
+        }
+    }
 
     let cookies = match wait_for_request_cookies(&session.webview, &url, Duration::from_secs(6)).await {
         Ok(cookies) => {
             println!("[browser-login] captured cookies for {}: {}", url, cookies);
+            emit_browser_login_progress(&app, BrowserLoginStage::CookiesCollected, "已读取登录 Cookie，正在导入账号", None, None);
             cookies
         }
         Err(err) => {
@@ -1720,10 +3322,27 @@ async fn finish_browser_login(state: State<'_, AppState>) -> Result<Account> {
     let cookies = if cookies.is_empty() { None } else { Some(cookies) };
 
     let mut manager = state.account_manager.lock().await;
+    if let Some(account_id) = session.relogin_account_id.as_deref() {
+        let password = credentials.password.filter(|p| !p.is_empty());
+        manager
+            .update_account_credentials(account_id, token, cookies, password)
+            .await
+            .map_err(ApiError::from)?;
+        if let Err(e) = manager.set_refresh_token(account_id, refresh_token) {
+            println!("[WARN] 保存刷新 Token 失败: {}", e);
+        }
+        return manager.get_account(account_id).map_err(ApiError::from);
+    }
     let mut account = manager
         .upsert_account_by_token(token, cookies, None)
         .await
         .map_err(ApiError::from)?;
+    if let Err(e) = manager.set_refresh_token(&account.id, refresh_token) {
+        println!("[WARN] 保存刷新 Token 失败: {}", e);
+    }
+    account = manager
+        .set_registration(&account.id, AccountRegistration::new(AccountSource::BrowserLogin))
+        .map_err(ApiError::from)?;
 
     let email = credentials.email.unwrap_or_default();
     let password = credentials.password.unwrap_or_default();
@@ -1735,13 +3354,35 @@ async fn finish_browser_login(state: State<'_, AppState>) -> Result<Account> {
                 &account.id,
                 if has_email { Some(email) } else { None },
                 if has_password { Some(password) } else { None },
+                None,
+                None,
+                None,
             )
             .map_err(ApiError::from)?;
     }
+    drop(manager);
 
+    session.profile.adopt(&account.id).await;
     Ok(account)
 }
 
+/// 延长正在进行的浏览器登录的等待时间，未指定时延长 2 分钟
+#[tauri::command]
+async fn extend_browser_login(secs: Option<u64>, state: State<'_, AppState>) -> Result<()> {
+    let secs = secs.unwrap_or(BROWSER_LOGIN_DEFAULT_EXTEND_SECS).clamp(1, 3600);
+    let sent = state
+        .browser_login_extend
+        .lock()
+        .await
+        .as_ref()
+        .is_some_and(|tx| tx.send(secs).is_ok());
+    if sent {
+        Ok(())
+    } else {
+        Err(ApiError::from(error::coded(error::ErrorCode::NotFound, "浏览器登录未在进行中")))
+    }
+}
+
 #[tauri::command]
 async fn cancel_browser_login(app: AppHandle, state: State<'_, AppState>) -> Result<()> {
     if let Some(tx) = state.browser_login_cancel.lock().await.take() {
@@ -1785,14 +3426,22 @@ async fn get_account(account_id: String, state: State<'_, AppState>) -> Result<A
 /// 切换账号（设置活跃账号并更新机器码）
 #[tauri::command]
 async fn switch_account(account_id: String, force: Option<bool>, state: State<'_, AppState>) -> Result<()> {
+    switch_account_with_state(&state, account_id, force.unwrap_or(false)).await
+}
+
+/// 切换账号并按设置自动开启隐私模式（供命令和工作区自动切换共用）
+pub(crate) async fn switch_account_with_state(state: &AppState, account_id: String, force: bool) -> Result<()> {
     {
         let mut manager = state.account_manager.lock().await;
-        let force = force.unwrap_or(false);
+        let mut account_id = account_id;
+        if manager.get_account(&account_id).map_err(ApiError::from)?.unverified {
+            account_id = manager.validate_account(&account_id).await.map_err(ApiError::from)?.id;
+        }
         manager.switch_account(&account_id, force).map_err(ApiError::from)?;
     }
 
     let settings = state.settings.lock().await.clone();
-    if settings.privacy_auto_enable {
+    if settings.privacy_auto_enable && machine::is_trae_installed() {
         println!("[INFO] 等待 Trae IDE 启动后写入隐私模式设置");
         let db_path = match machine::get_trae_state_db_path() {
             Ok(path) => path,
@@ -1825,56 +3474,274 @@ async fn switch_account(account_id: String, force: Option<bool>, state: State<'_
     Ok(())
 }
 
-/// 获取账号使用量
+/// 获取账号使用量（缓存未过期时直接返回缓存，force 为 true 时强制请求接口）
 #[tauri::command]
-async fn get_account_usage(account_id: String, state: State<'_, AppState>) -> Result<UsageSummary> {
-    // 1. 获取账号信息（持有锁的时间极短）
+async fn get_account_usage(account_id: String, force: Option<bool>, state: State<'_, AppState>) -> Result<UsageSummary> {
+    let ttl = state.settings.lock().await.usage_cache_ttl_secs;
+
+    // 1. 获取账号信息（持有锁的时间极短；未验证账号在此时完成校验）
     let account = {
-        let manager = state.account_manager.lock().await;
-        manager.get_account(&account_id).map_err(ApiError::from)?
+        let mut manager = state.account_manager.lock().await;
+        if !force.unwrap_or(false) {
+            if let Some(cached) = manager.cached_usage(&account_id, ttl) {
+                return Ok(cached);
+            }
+        }
+        let account = manager.get_account(&account_id).map_err(ApiError::from)?;
+        if account.unverified {
+            manager.validate_account(&account_id).await.map_err(ApiError::from)?
+        } else {
+            account
+        }
     };
 
     // 2. 执行网络请求（不持有锁，可并行）
-    let (summary, new_token) = fetch_usage_for_account(&account).await.map_err(ApiError::from)?;
+    let (summary, new_token) = match api::request_log::scope(&account.id, fetch_usage_for_account(&account)).await {
+        Ok(result) => result,
+        Err(e) => {
+            state.account_manager.lock().await.record_account_error(&account.id, &e);
+            return Err(ApiError::from(e));
+        }
+    };
 
     // 3. 更新账号信息（持有锁的时间极短）
     {
         let mut manager = state.account_manager.lock().await;
         // 忽略更新错误（可能账号已被删除），但不影响返回结果
-        let _ = manager.update_account_info_after_usage_check(
-            &account_id,
-            summary.plan_type.clone(),
-            new_token,
-        );
+        let _ = manager.update_account_info_after_usage_check(&account.id, &summary, new_token);
+    }
+
+    Ok(summary)
+}
+
+/// 获取账号最近的 API 请求记录（仅保存在内存中，用于排查单个账号反复失败的原因）
+#[tauri::command]
+async fn get_account_request_log(account_id: String) -> Result<Vec<api::request_log::RequestLogEntry>> {
+    Ok(api::request_log::entries(&account_id))
+}
+
+/// 按本地使用量历史估算账号的平均每日消耗和预计耗尽时间
+#[tauri::command]
+async fn estimate_quota_exhaustion(account_id: String, state: State<'_, AppState>) -> Result<usage_history::QuotaForecast> {
+    let resets_at = state
+        .account_manager
+        .lock()
+        .await
+        .get_account(&account_id)
+        .map_err(ApiError::from)?
+        .usage_cache
+        .map(|usage| usage.reset_time);
+    let now = chrono::Utc::now().timestamp();
+    tokio::task::spawn_blocking(move || usage_history::estimate_exhaustion(&account_id, resets_at, now))
+        .await
+        .map_err(|e| ApiError::from(anyhow::anyhow!("估算额度耗尽时间失败: {}", e)))?
+        .map_err(ApiError::from)
+}
+
+/// 获取账号每天的统计数据采样及变化量（默认最近 7 天）
+#[tauri::command]
+async fn get_statistics_history(
+    account_id: String,
+    range: Option<usage_history::TrendRange>,
+) -> Result<Vec<usage_history::StatisticPoint>> {
+    tokio::task::spawn_blocking(move || usage_history::get_statistic_history(&account_id, range.unwrap_or_default()))
+        .await
+        .map_err(|e| ApiError::from(anyhow::anyhow!("查询统计数据历史失败: {}", e)))?
+        .map_err(ApiError::from)
+}
+
+/// 获取账号每天的使用量变化（默认最近 7 天），用于绘制消耗趋势
+#[tauri::command]
+async fn get_usage_trend(
+    account_id: String,
+    range: Option<usage_history::TrendRange>,
+) -> Result<Vec<usage_history::UsageTrendPoint>> {
+    tokio::task::spawn_blocking(move || usage_history::get_usage_trend(&account_id, range.unwrap_or_default()))
+        .await
+        .map_err(|e| ApiError::from(anyhow::anyhow!("查询使用量趋势失败: {}", e)))?
+        .map_err(ApiError::from)
+}
+
+/// 批量查询使用量的最大并发数
+const USAGE_FETCH_CONCURRENCY: usize = 6;
+
+/// 单个账号的使用量查询结果（查询完成后立即通过 account_usage 事件推送）
+#[derive(Debug, Clone, serde::Serialize)]
+struct AccountUsageResult {
+    account_id: String,
+    usage: Option<UsageSummary>,
+    error: Option<String>,
+}
+
+/// 并发查询所有已验证账号的使用量，每个账号完成后推送 account_usage 事件，最后返回全部结果
+///
+/// 缓存未过期的账号直接返回缓存（force 为 true 时全部重新请求）
+#[tauri::command]
+async fn get_all_accounts_usage(
+    force: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<AccountUsageResult>> {
+    let ttl = if force.unwrap_or(false) { 0 } else { state.settings.lock().await.usage_cache_ttl_secs };
+    let mut results = Vec::new();
+    let accounts: Vec<Account> = {
+        let manager = state.account_manager.lock().await;
+        let mut accounts = Vec::new();
+        for brief in manager.get_accounts().iter().filter(|a| !a.unverified) {
+            if let Some(cached) = manager.cached_usage(&brief.id, ttl) {
+                results.push(AccountUsageResult { account_id: brief.id.clone(), usage: Some(cached), error: None });
+            } else if let Ok(account) = manager.get_account(&brief.id) {
+                accounts.push(account);
+            }
+        }
+        accounts
+    };
+    let cached = results.len();
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(USAGE_FETCH_CONCURRENCY));
+    let mut tasks = tokio::task::JoinSet::new();
+    for account in accounts {
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let result = match semaphore.acquire().await {
+                Ok(_permit) => api::request_log::scope(&account.id, fetch_usage_for_account(&account)).await,
+                Err(e) => Err(anyhow::anyhow!("任务已取消: {}", e)),
+            };
+            (account.id, result)
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        let (account_id, result) = match joined {
+            Ok(pair) => pair,
+            Err(e) => {
+                println!("[WARN] 使用量查询任务执行失败: {}", e);
+                continue;
+            }
+        };
+        let item = {
+            let mut manager = state.account_manager.lock().await;
+            match result {
+                Ok((summary, new_token)) => {
+                    let _ = manager.update_account_info_after_usage_check(&account_id, &summary, new_token);
+                    AccountUsageResult { account_id, usage: Some(summary), error: None }
+                }
+                Err(e) => {
+                    manager.record_account_error(&account_id, &e);
+                    AccountUsageResult { account_id, usage: None, error: Some(e.to_string()) }
+                }
+            }
+        };
+        let _ = app.emit("account_usage", &item);
+        results.push(item);
+    }
+
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    println!(
+        "[INFO] 批量查询使用量完成: 成功 {} 个（缓存 {} 个）, 失败 {} 个",
+        results.len() - failed,
+        cached,
+        failed
+    );
+    Ok(results)
+}
+
+/// 一组账号的快速请求汇总（含额外礼包）
+#[derive(Debug, Clone, Default, serde::Serialize)]
+struct UsageTotals {
+    plan_type: String,
+    accounts: usize,
+    used: f64,
+    limit: f64,
+    left: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, summary: &UsageSummary) {
+        self.accounts += 1;
+        self.used += summary.fast_request_used + summary.extra_fast_request_used;
+        self.limit += (summary.fast_request_limit + summary.extra_fast_request_limit) as f64;
+        self.left += summary.fast_request_left + summary.extra_fast_request_left;
+    }
+}
+
+/// 全部账号的使用量汇总
+#[derive(Debug, Clone, serde::Serialize)]
+struct UsageAggregate {
+    total: UsageTotals,
+    /// 按套餐分组，剩余量多的在前
+    by_plan: Vec<UsageTotals>,
+    /// 本次查询失败、使用了上次缓存的账号数
+    stale: usize,
+    /// 没有任何使用量数据的账号数（未验证或从未查询成功）
+    missing: usize,
+}
+
+/// 汇总所有账号的剩余 / 已用快速请求，按套餐分组（缓存有效时使用缓存，force 为 true 时全部重新请求）
+#[tauri::command]
+async fn get_usage_aggregate(force: Option<bool>, app: AppHandle) -> Result<UsageAggregate> {
+    let results = get_all_accounts_usage(force, app.clone(), app.state()).await?;
+    let mut fresh: HashMap<String, UsageSummary> = results
+        .into_iter()
+        .filter_map(|r| r.usage.map(|usage| (r.account_id, usage)))
+        .collect();
+
+    let state = app.state::<AppState>();
+    let manager = state.account_manager.lock().await;
+    let mut total = UsageTotals { plan_type: "all".to_string(), ..Default::default() };
+    let mut groups: HashMap<String, UsageTotals> = HashMap::new();
+    let (mut stale, mut missing) = (0, 0);
+    for brief in manager.get_accounts() {
+        let summary = match fresh.remove(&brief.id) {
+            Some(summary) => summary,
+            None => match manager.get_account(&brief.id).ok().and_then(|a| a.usage_cache) {
+                Some(summary) => {
+                    stale += 1;
+                    summary
+                }
+                None => {
+                    missing += 1;
+                    continue;
+                }
+            },
+        };
+        let plan_type = if summary.plan_type.trim().is_empty() { "Free".to_string() } else { summary.plan_type.clone() };
+        groups
+            .entry(plan_type.clone())
+            .or_insert_with(|| UsageTotals { plan_type, ..Default::default() })
+            .add(&summary);
+        total.add(&summary);
     }
 
-    Ok(summary)
+    let mut by_plan: Vec<UsageTotals> = groups.into_values().collect();
+    by_plan.sort_by(|a, b| b.left.total_cmp(&a.left));
+    Ok(UsageAggregate { total, by_plan, stale, missing })
 }
 
 async fn fetch_usage_for_account(account: &Account) -> anyhow::Result<(UsageSummary, Option<(String, String)>)> {
     let mut new_token_info = None;
+    let api_base = account.api_base_override();
 
     let summary = if let Some(token) = &account.jwt_token {
         // 优先使用 Token
-        let client = TraeApiClient::new_with_token(token)?;
+        let client = TraeApiClient::new_with_token(token)?.with_api_base(api_base.as_deref());
         match client.get_usage_summary_by_token().await {
             Ok(summary) => summary,
             Err(e) => {
-                let error_msg = e.to_string();
                 // 如果是 401 错误且有 Cookies，尝试刷新 Token
-                if error_msg.contains("401") && !account.cookies.is_empty() {
+                if error::ErrorCode::of(&e) == error::ErrorCode::AuthExpired && !account.cookies.is_empty() {
                     println!("[INFO] Token 已过期，尝试使用 Cookies 刷新...");
                     // 使用 Cookies 刷新 Token
-                    let mut cookie_client = TraeApiClient::new(&account.cookies)?;
+                    let mut cookie_client = TraeApiClient::new(&account.cookies)?.with_api_base(api_base.as_deref());
                     let token_result = cookie_client.get_user_token().await?;
                     
                     new_token_info = Some((token_result.token.clone(), token_result.expired_at.clone()));
 
                     // 使用新 Token 重新获取使用量
-                    let new_client = TraeApiClient::new_with_token(&token_result.token)?;
+                    let new_client = TraeApiClient::new_with_token(&token_result.token)?.with_api_base(api_base.as_deref());
                     new_client.get_usage_summary_by_token().await?
-                } else if error_msg.contains("401") {
-                    return Err(anyhow::anyhow!("Token 已过期，请更新 Token 或 Cookies"));
+                } else if error::ErrorCode::of(&e) == error::ErrorCode::AuthExpired {
+                    return Err(error::coded(error::ErrorCode::AuthExpired, "Token 已过期，请更新 Token 或 Cookies"));
                 } else {
                     return Err(e);
                 }
@@ -1882,7 +3749,7 @@ async fn fetch_usage_for_account(account: &Account) -> anyhow::Result<(UsageSumm
         }
     } else if !account.cookies.is_empty() {
         // 使用 Cookies
-        let mut client = TraeApiClient::new(&account.cookies)?;
+        let mut client = TraeApiClient::new(&account.cookies)?.with_api_base(api_base.as_deref());
         // 先获取 token 以便保存
         let token_result = client.get_user_token().await?;
         new_token_info = Some((token_result.token.clone(), token_result.expired_at.clone()));
@@ -1938,17 +3805,39 @@ async fn login_account_with_email(
         .map_err(ApiError::from)
 }
 
-/// 更新账号邮箱/密码
+/// 获取账号 Cookies 的各个条目
+#[tauri::command]
+async fn get_account_cookies_parsed(account_id: String, state: State<'_, AppState>) -> Result<Vec<CookieEntry>> {
+    let manager = state.account_manager.lock().await;
+    manager.get_account_cookies_parsed(&account_id).map_err(ApiError::from)
+}
+
+/// 修改账号的单个 Cookie（值为空时删除）
+#[tauri::command]
+async fn set_account_cookie(
+    account_id: String,
+    name: String,
+    value: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<CookieEntry>> {
+    let mut manager = state.account_manager.lock().await;
+    manager.set_account_cookie(&account_id, &name, &value).await.map_err(ApiError::from)
+}
+
+/// 更新账号邮箱/密码/区域/API 地址/界面语言
 #[tauri::command]
 async fn update_account_profile(
     account_id: String,
     email: Option<String>,
     password: Option<String>,
+    region: Option<String>,
+    api_host: Option<String>,
+    locale: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<Account> {
     let mut manager = state.account_manager.lock().await;
     manager
-        .update_account_profile(&account_id, email, password)
+        .update_account_profile(&account_id, email, password, region, api_host, locale)
         .map_err(ApiError::from)
 }
 
@@ -1959,28 +3848,445 @@ async fn clear_accounts(state: State<'_, AppState>) -> Result<usize> {
     manager.clear_accounts().map_err(ApiError::from)
 }
 
+/// 生成导出内容：提供密码时加密，否则必须显式选择明文导出
+fn build_export(manager: &AccountManager, passphrase: Option<String>, plaintext: bool) -> anyhow::Result<String> {
+    let content = manager.export_accounts()?;
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => crypto::encrypt_export(&content, &passphrase),
+        None if plaintext => Ok(content),
+        None => Err(anyhow::anyhow!("请设置导出密码，或明确选择明文导出")),
+    }
+}
+
+/// 获取加载账号数据时发现的完整性问题
+#[tauri::command]
+async fn get_integrity_status(state: State<'_, AppState>) -> Result<Option<integrity::IntegrityIssue>> {
+    let manager = state.account_manager.lock().await;
+    Ok(manager.integrity_issue())
+}
+
+/// 账号数据是否处于只读模式（另一个实例持有数据锁）
+#[tauri::command]
+async fn get_store_read_only(state: State<'_, AppState>) -> Result<bool> {
+    let manager = state.account_manager.lock().await;
+    Ok(manager.is_read_only())
+}
+
+/// 重新尝试获取数据锁，成功后重新加载账号数据，返回是否仍为只读
+#[tauri::command]
+async fn retry_store_lock(state: State<'_, AppState>) -> Result<bool> {
+    let mut manager = state.account_manager.lock().await;
+    let acquired = manager.try_reacquire_store_lock().map_err(ApiError::from)?;
+    Ok(!acquired)
+}
+
+/// 从备份恢复账号数据（未指定路径时使用最近一个校验通过的备份）
+#[tauri::command]
+async fn restore_integrity_backup(
+    backup_path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<usize> {
+    let mut manager = state.account_manager.lock().await;
+    manager.restore_from_backup(backup_path).map_err(ApiError::from)
+}
+
+/// 信任当前账号数据并重新签名
+#[tauri::command]
+async fn dismiss_integrity_issue(state: State<'_, AppState>) -> Result<()> {
+    let mut manager = state.account_manager.lock().await;
+    manager.dismiss_integrity_issue().map_err(ApiError::from)
+}
+
+/// 信任外部对账号数据文件的修改并重新签名，返回账号数量
+#[tauri::command]
+async fn trust_external_accounts_change(state: State<'_, AppState>) -> Result<usize> {
+    let mut manager = state.account_manager.lock().await;
+    manager.trust_external_change().map_err(ApiError::from)
+}
+
+/// 备份账号数据和当前设置，prune 为 true 时删除超出保留数量的旧备份
+async fn backup_now(state: &AppState, prune: bool) -> anyhow::Result<BackupEntry> {
+    let settings = state.settings.lock().await.clone();
+    let settings_json = serde_json::to_string_pretty(&settings)?;
+    let manager = state.account_manager.lock().await;
+    manager.create_backup(Some(&settings_json), prune.then_some(settings.backup_retention))
+}
+
+/// 获取所有工作区映射
+#[tauri::command]
+async fn list_workspace_mappings(state: State<'_, AppState>) -> Result<Vec<WorkspaceMapping>> {
+    let manager = state.account_manager.lock().await;
+    Ok(manager.workspace_mappings())
+}
+
+/// 添加或更新工作区映射
+#[tauri::command]
+async fn set_workspace_mapping(
+    folder: String,
+    account_id: String,
+    auto_switch: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceMapping> {
+    let mut manager = state.account_manager.lock().await;
+    manager
+        .set_workspace_mapping(&folder, &account_id, auto_switch.unwrap_or(false))
+        .map_err(ApiError::from)
+}
+
+/// 删除工作区映射
+#[tauri::command]
+async fn remove_workspace_mapping(mapping_id: String, state: State<'_, AppState>) -> Result<()> {
+    let mut manager = state.account_manager.lock().await;
+    manager.remove_workspace_mapping(&mapping_id).map_err(ApiError::from)
+}
+
+/// 获取 Trae IDE 当前的登录信息快照（敏感字段已打码）
+#[tauri::command]
+async fn get_trae_auth_snapshot() -> Result<machine::TraeAuthSnapshot> {
+    machine::get_trae_auth_snapshot().map_err(ApiError::from)
+}
+
+/// 获取 Trae IDE 当前打开的工作区
+#[tauri::command]
+async fn get_trae_open_workspaces() -> Result<Vec<String>> {
+    let folders = machine::get_trae_open_workspaces().map_err(ApiError::from)?;
+    Ok(folders.into_iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+/// 上传备份到云端（设置了密码时先加密），返回远程位置
+async fn upload_backup_to_cloud(state: &AppState, backup: &BackupEntry) -> anyhow::Result<String> {
+    let settings = state.settings.lock().await.clone();
+    let target = settings
+        .cloud_backup
+        .target()?
+        .ok_or_else(|| anyhow::anyhow!("未配置云端备份"))?;
+
+    let path = PathBuf::from(&backup.path);
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| anyhow::anyhow!("备份路径无效"))?;
+    let mut content = fs::read_to_string(&path)?;
+    if settings.cloud_backup_passphrase.is_empty() {
+        println!("[WARN] 未设置云端备份密码，将以明文上传账号数据");
+    } else {
+        content = crypto::encrypt_export(&content, &settings.cloud_backup_passphrase)?;
+    }
+
+    let location = target.upload(&file_name, content.as_bytes()).await?;
+    println!("[INFO] 备份 {} 已上传到 {}: {}", backup.id, target.name(), location);
+
+    // 首次上传到 Gist 时记录自动创建的 Gist ID
+    let mut current = state.settings.lock().await;
+    if let cloud_backup::CloudBackupConfig::Gist { gist_id, .. } = &mut current.cloud_backup {
+        if gist_id.is_empty() {
+            *gist_id = location.clone();
+            save_settings_to_disk(&current)?;
+        }
+    }
+    Ok(location)
+}
+
+/// 上传备份到云端（未指定时上传最近一个备份）
+#[tauri::command]
+async fn upload_backup(backup_id: Option<String>, state: State<'_, AppState>) -> Result<String> {
+    let backup = {
+        let manager = state.account_manager.lock().await;
+        let backups = manager.list_backups();
+        match backup_id {
+            Some(id) => backups.into_iter().find(|b| b.id == id),
+            None => backups.into_iter().next(),
+        }
+    };
+    let backup = match backup {
+        Some(backup) => backup,
+        None => backup_now(&state, true).await.map_err(ApiError::from)?,
+    };
+    upload_backup_to_cloud(&state, &backup).await.map_err(ApiError::from)
+}
+
+/// 立即创建备份
+#[tauri::command]
+async fn create_backup(state: State<'_, AppState>) -> Result<BackupEntry> {
+    backup_now(&state, true).await.map_err(ApiError::from)
+}
+
+/// 列出所有备份
+#[tauri::command]
+async fn list_backups(state: State<'_, AppState>) -> Result<Vec<BackupEntry>> {
+    let manager = state.account_manager.lock().await;
+    Ok(manager.list_backups())
+}
+
+/// 从备份恢复账号数据（restore_settings 为 true 时同时恢复设置），恢复前会先备份当前数据
+#[tauri::command]
+async fn restore_backup(
+    backup_id: String,
+    restore_settings: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<usize> {
+    // 恢复前的备份不清理旧备份，否则要恢复的最旧备份可能先被删除
+    if let Err(e) = backup_now(&state, false).await {
+        println!("[WARN] 恢复前备份当前数据失败: {}", e);
+    }
+
+    let (count, settings_path) = {
+        let mut manager = state.account_manager.lock().await;
+        let count = manager.restore_backup(&backup_id).map_err(ApiError::from)?;
+        (count, manager.backup_settings_path(&backup_id))
+    };
+
+    if let (true, Some(path)) = (restore_settings.unwrap_or(false), settings_path) {
+        let content = fs::read_to_string(&path).map_err(|e| ApiError::from(anyhow::Error::from(e)))?;
+        let settings: AppSettings = serde_json::from_str(&content)
+            .map_err(|e| ApiError::from(anyhow::anyhow!("备份中的设置无法解析: {}", e)))?;
+        save_settings_to_disk(&settings).map_err(ApiError::from)?;
+        api::network::configure(settings.network_config()).await;
+        webview_log::set_enabled(settings.webview_console_log);
+        *state.settings.lock().await = settings;
+        println!("[INFO] 已从备份恢复设置: {}", backup_id);
+    }
+
+    let retention = state.settings.lock().await.backup_retention;
+    state.account_manager.lock().await.prune_backups(retention);
+    Ok(count)
+}
+
 /// 导出账号到指定路径
 #[tauri::command]
-async fn export_accounts_to_path(path: String, state: State<'_, AppState>) -> Result<()> {
+async fn export_accounts_to_path(
+    path: String,
+    passphrase: Option<String>,
+    plaintext: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<()> {
     let manager = state.account_manager.lock().await;
-    let content = manager.export_accounts().map_err(ApiError::from)?;
+    let content = build_export(&manager, passphrase, plaintext.unwrap_or(false))
+        .map_err(ApiError::from)?;
     fs::write(&path, content)
         .map_err(|err| ApiError::from(anyhow::Error::from(err)))?;
     Ok(())
 }
 
+/// 导出号池概览为 Markdown 表格（邮箱、套餐、剩余额度、到期、标签、备注，不含凭据），返回账号数
+#[tauri::command]
+async fn export_pool_summary_md(path: String, state: State<'_, AppState>) -> Result<usize> {
+    let accounts: Vec<Account> = {
+        let manager = state.account_manager.lock().await;
+        manager.get_accounts().iter().filter_map(|a| manager.get_account(&a.id).ok()).collect()
+    };
+
+    let mut usages = HashMap::new();
+    for account in accounts.iter().filter(|a| !a.unverified) {
+        match fetch_usage_for_account(account).await {
+            Ok((summary, _)) => {
+                usages.insert(account.id.clone(), summary);
+            }
+            Err(e) => println!("[WARN] 获取账号 {} 使用量失败，概览中显示为 -: {}", account.email, e),
+        }
+    }
+
+    let manager = state.account_manager.lock().await;
+    let content = manager.pool_summary_markdown(&usages);
+    fs::write(&path, content)
+        .map_err(|err| ApiError::from(anyhow::Error::from(err)))?;
+    println!("[INFO] 号池概览已导出到 {}", path);
+    Ok(accounts.len())
+}
+
+/// 更新账号标签和备注
+#[tauri::command]
+async fn update_account_notes(
+    account_id: String,
+    tags: Vec<String>,
+    notes: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Account> {
+    let mut manager = state.account_manager.lock().await;
+    manager.update_account_notes(&account_id, tags, notes).map_err(ApiError::from)
+}
+
+/// 设置账号本地显示名称（不修改服务器上的资料）
+#[tauri::command]
+async fn update_account_alias(
+    account_id: String,
+    alias: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Account> {
+    let mut manager = state.account_manager.lock().await;
+    manager.update_account_alias(&account_id, alias).map_err(ApiError::from)
+}
+
+/// 设置账号的两步验证密钥（Base32 密钥或 otpauth:// 链接，为空时清除）
+#[tauri::command]
+async fn update_account_totp_secret(
+    account_id: String,
+    secret: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Account> {
+    let secret = match secret.filter(|s| !s.trim().is_empty()) {
+        Some(raw) => Some(totp::normalize_secret(&raw).map_err(ApiError::from)?),
+        None => None,
+    };
+    let mut manager = state.account_manager.lock().await;
+    manager.update_account_totp_secret(&account_id, secret).map_err(ApiError::from)
+}
+
+/// 生成账号当前的两步验证码
+#[tauri::command]
+async fn get_account_totp_code(account_id: String, state: State<'_, AppState>) -> Result<totp::TotpCode> {
+    let secret = state
+        .account_manager
+        .lock()
+        .await
+        .totp_secret_for(Some(&account_id), "")
+        .ok_or_else(|| ApiError::from(error::coded(error::ErrorCode::NotFound, "账号没有设置两步验证密钥")))?;
+    totp::current(&secret).map_err(ApiError::from)
+}
+
+/// 设置账号剩余额度提醒阈值（None 或 0 表示不监控）
+#[tauri::command]
+async fn set_quota_threshold(
+    account_id: String,
+    threshold: Option<f64>,
+    state: State<'_, AppState>,
+) -> Result<AppSettings> {
+    let mut settings = state.settings.lock().await;
+    match threshold.filter(|t| *t > 0.0) {
+        Some(threshold) => {
+            settings.quota_thresholds.insert(account_id, threshold);
+        }
+        None => {
+            settings.quota_thresholds.remove(&account_id);
+        }
+    }
+    save_settings_to_disk(&settings).map_err(ApiError::from)?;
+    Ok(settings.clone())
+}
+
+/// 开始账号轮换（使用设置中的轮换池和时间表，从现在起重新计时）
+#[tauri::command]
+async fn start_rotation(state: State<'_, AppState>) -> Result<rotation::RotationStatus> {
+    let mut settings = state.settings.lock().await;
+    {
+        let manager = state.account_manager.lock().await;
+        let available = settings.rotation.account_ids.iter()
+            .filter(|id| manager.get_account(id).is_ok())
+            .count();
+        if available < 2 {
+            return Err(ApiError::from(anyhow::anyhow!("轮换池中至少需要 2 个账号")));
+        }
+    }
+    settings.rotation.enabled = true;
+    save_settings_to_disk(&settings).map_err(ApiError::from)?;
+    rotation::reset(&settings.rotation);
+    println!("[INFO] 已开始账号轮换");
+    Ok(rotation::status())
+}
+
+/// 停止账号轮换
+#[tauri::command]
+async fn stop_rotation(state: State<'_, AppState>) -> Result<rotation::RotationStatus> {
+    let mut settings = state.settings.lock().await;
+    settings.rotation.enabled = false;
+    save_settings_to_disk(&settings).map_err(ApiError::from)?;
+    rotation::reset(&settings.rotation);
+    println!("[INFO] 已停止账号轮换");
+    Ok(rotation::status())
+}
+
+/// 获取账号轮换状态
+#[tauri::command]
+async fn get_rotation_status() -> Result<rotation::RotationStatus> {
+    Ok(rotation::status())
+}
+
 /// 导出账号
 #[tauri::command]
-async fn export_accounts(state: State<'_, AppState>) -> Result<String> {
+async fn export_accounts(
+    passphrase: Option<String>,
+    plaintext: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    let manager = state.account_manager.lock().await;
+    build_export(&manager, passphrase, plaintext.unwrap_or(false)).map_err(ApiError::from)
+}
+
+/// 导入数据为加密格式时使用密码解密
+fn decrypt_import_data(data: String, passphrase: Option<String>) -> Result<String> {
+    if !crypto::is_encrypted_export(&data) {
+        return Ok(data);
+    }
+    let passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| ApiError::from(anyhow::anyhow!("该文件已加密，请输入导出密码")))?;
+    crypto::decrypt_export(&data, &passphrase).map_err(ApiError::from)
+}
+
+/// 预览导入结果（verify 为 true 时通过 API 校验每个账号），不写入任何数据
+#[tauri::command]
+async fn preview_import(
+    data: String,
+    verify: Option<bool>,
+    passphrase: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ImportPreview> {
+    let data = decrypt_import_data(data, passphrase)?;
+    let options = state.settings.lock().await.import_options();
     let manager = state.account_manager.lock().await;
-    manager.export_accounts().map_err(ApiError::from)
+    manager
+        .preview_import(&data, verify.unwrap_or(false), &options)
+        .await
+        .map_err(ApiError::from)
+}
+
+/// 导入账号（可通过 defer_validation 覆盖设置中的延迟验证选项，加密文件需提供密码）
+#[tauri::command]
+async fn import_accounts(
+    data: String,
+    defer_validation: Option<bool>,
+    passphrase: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<usize> {
+    let data = decrypt_import_data(data, passphrase)?;
+    let mut options = state.settings.lock().await.import_options();
+    if let Some(defer) = defer_validation {
+        options.defer_validation = defer;
+    }
+    let mut manager = state.account_manager.lock().await;
+    manager.import_accounts(&data, &options).await.map_err(ApiError::from)
 }
 
-/// 导入账号
+/// 完整导入（保留 Token、密码、机器码等全部字段，按 user_id 合并到已有账号）
 #[tauri::command]
-async fn import_accounts(data: String, state: State<'_, AppState>) -> Result<usize> {
+async fn import_accounts_merge(
+    data: String,
+    passphrase: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ImportMergeReport> {
+    let data = decrypt_import_data(data, passphrase)?;
     let mut manager = state.account_manager.lock().await;
-    manager.import_accounts(&data).await.map_err(ApiError::from)
+    manager.merge_import_accounts(&data).map_err(ApiError::from)
+}
+
+/// 导出时间范围内的全部使用事件为 CSV，返回事件数
+#[tauri::command]
+async fn export_usage_events(
+    account_id: String,
+    start_time: i64,
+    end_time: i64,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<usize> {
+    let (csv, count) = {
+        let mut manager = state.account_manager.lock().await;
+        manager.export_usage_events_csv(&account_id, start_time, end_time)
+            .await
+            .map_err(ApiError::from)?
+    };
+    fs::write(&path, csv).map_err(|err| ApiError::from(anyhow::Error::from(err)))?;
+    println!("[INFO] 已导出 {} 条使用事件到 {}", count, path);
+    Ok(count)
 }
 
 /// 获取使用事件
@@ -2049,6 +4355,14 @@ async fn clear_trae_login_state() -> Result<()> {
     machine::clear_trae_login_state().map_err(ApiError::from)
 }
 
+/// 检测 Trae IDE 安装状态（未安装时界面禁用依赖 IDE 的功能）
+#[tauri::command]
+async fn get_trae_install_status() -> Result<machine::TraeInstallStatus> {
+    tokio::task::spawn_blocking(machine::get_trae_install_status)
+        .await
+        .map_err(|e| ApiError::from(anyhow::anyhow!("检测 Trae 安装状态失败: {}", e)))
+}
+
 /// 获取保存的 Trae IDE 路径
 #[tauri::command]
 async fn get_trae_path() -> Result<String> {
@@ -2074,6 +4388,27 @@ async fn claim_gift(account_id: String, state: State<'_, AppState>) -> Result<()
     manager.claim_birthday_bonus(&account_id).await.map_err(ApiError::from)
 }
 
+/// 为所有账号领取礼包，已领取过的账号跳过
+#[tauri::command]
+async fn claim_gift_all(state: State<'_, AppState>) -> Result<account::GiftClaimReport> {
+    let manager = state.account_manager.lock().await;
+    Ok(manager.claim_gift_all().await)
+}
+
+/// 列出账号当前可领取的活动礼包
+#[tauri::command]
+async fn list_promotions(account_id: String, state: State<'_, AppState>) -> Result<Vec<api::Promotion>> {
+    let manager = state.account_manager.lock().await;
+    manager.list_promotions(&account_id).await.map_err(ApiError::from)
+}
+
+/// 领取指定活动礼包
+#[tauri::command]
+async fn claim_promotion(account_id: String, promo_id: String, state: State<'_, AppState>) -> Result<()> {
+    let manager = state.account_manager.lock().await;
+    manager.claim_promotion(&account_id, &promo_id).await.map_err(ApiError::from)
+}
+
 /// 打开购买页面（内置浏览器，携带账号 Cookies）
 #[tauri::command]
 async fn open_pricing(account_id: String, app: AppHandle, state: State<'_, AppState>) -> Result<()> {
@@ -2154,11 +4489,14 @@ async fn open_pricing(account_id: String, app: AppHandle, state: State<'_, AppSt
     );
 
     let script_onload = js_onload.clone();
-    let webview = WebviewWindowBuilder::new(
+    let profile = webview_profile::WebviewProfile::for_account(&account.id).map_err(ApiError::from)?;
+    let builder = WebviewWindowBuilder::new(
         &app,
         "trae-pricing",
         WebviewUrl::External("about:blank".parse().unwrap()),
-    )
+    );
+    let webview = profile
+    .apply(builder)
     .title("Trae 购买 Pro")
     .inner_size(1000.0, 720.0)
     .on_page_load(move |window, payload| {
@@ -2169,13 +4507,6 @@ async fn open_pricing(account_id: String, app: AppHandle, state: State<'_, AppSt
     .build()
     .map_err(|e| anyhow::anyhow!("无法打开购买窗口: {}", e))?;
 
-    // 强制清理数据
-    if let Err(e) = webview.clear_all_browsing_data() {
-        println!("[pricing] clear browsing data failed: {}", e);
-    } else {
-        println!("[pricing] cleared browsing data");
-    }
-
     // 先导航到一个轻量页(404)来建立域上下文并执行注入，然后再由脚本跳转到 pricing
     // 这样可以确保 Cookie 在请求 pricing 之前就已经准备好
     let _ = webview.navigate(Url::parse("https://www.trae.ai/404_auth_init").unwrap());
@@ -2185,14 +4516,35 @@ async fn open_pricing(account_id: String, app: AppHandle, state: State<'_, AppSt
 
 /// 获取用户统计数据
 #[tauri::command]
-async fn get_user_statistics(account_id: String, state: State<'_, AppState>) -> Result<UserStatisticResult> {
-    let manager = state.account_manager.lock().await;
-    manager.get_account_statistics(&account_id).await.map_err(ApiError::from)
+async fn get_user_statistics(
+    account_id: String,
+    force: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<UserStatisticResult> {
+    let mut manager = state.account_manager.lock().await;
+    manager
+        .get_account_statistics(&account_id, force.unwrap_or(false))
+        .await
+        .map_err(ApiError::from)
+}
+
+/// 获取账号订阅信息（续费时间、自动续费、付款记录），并保存套餐到期时间
+#[tauri::command]
+async fn get_subscription_info(account_id: String, state: State<'_, AppState>) -> Result<api::SubscriptionInfo> {
+    let mut manager = state.account_manager.lock().await;
+    manager.get_subscription_info(&account_id).await.map_err(ApiError::from)
 }
 
 async fn handle_silent_start() -> anyhow::Result<()> {
+    let settings = load_settings_from_disk().unwrap_or_default();
+    api::network::configure(settings.network_config()).await;
     let mut manager = AccountManager::new()?;
-    
+    manager.set_entitlement_mode(settings.entitlement_mode);
+    if manager.is_read_only() {
+        println!("[Silent] 账号数据正被另一个实例使用，跳过静默刷新");
+        return Ok(());
+    }
+
     // 1. Refresh all accounts
     let account_ids: Vec<String> = manager.get_accounts().into_iter().map(|a| a.id).collect();
     for id in account_ids {
@@ -2204,21 +4556,14 @@ async fn handle_silent_start() -> anyhow::Result<()> {
     }
 
     // 2. Sync with Trae IDE if it's not running
-    if !machine::is_trae_running() {
+    if !machine::is_trae_installed() {
+        println!("[Silent] Trae IDE is not installed, skipping sync");
+    } else if !machine::is_trae_running() {
         let accounts = manager.get_accounts();
         if let Some(current) = accounts.iter().find(|a| a.is_current) {
              if let Ok(account) = manager.get_account(&current.id) {
-                if let Some(token) = account.jwt_token {
-                     let login_info = machine::TraeLoginInfo {
-                        token,
-                        refresh_token: None,
-                        user_id: account.user_id,
-                        email: account.email,
-                        username: account.name,
-                        avatar_url: account.avatar_url,
-                        host: String::new(),
-                        region: if account.region.is_empty() { "SG".to_string() } else { account.region },
-                    };
+                if let Some(token) = account.jwt_token.clone() {
+                     let login_info = account.to_login_info(token, settings.entitlement_mode);
                     if let Err(e) = machine::write_trae_login_info(&login_info) {
                         println!("[Silent] Failed to write Trae login info: {}", e);
                     } else {
@@ -2250,11 +4595,13 @@ pub fn run() {
         std::process::exit(0);
     }
 
-    let account_manager = AccountManager::new().expect("无法初始化账号管理器");
+    let mut account_manager = AccountManager::new().expect("无法初始化账号管理器");
     let settings = load_settings_from_disk().unwrap_or_else(|err| {
         println!("[WARN] 读取设置失败，使用默认值: {}", err);
         AppSettings::default()
     });
+    account_manager.set_entitlement_mode(settings.entitlement_mode);
+    webview_log::set_enabled(settings.webview_console_log);
     if let Err(err) = autostart::set_auto_start(settings.auto_start_enabled) {
         println!("[WARN] 设置开机自启动失败: {}", err);
     }
@@ -2266,16 +4613,198 @@ pub fn run() {
             account_manager: Mutex::new(account_manager),
             browser_login: Mutex::new(None),
             browser_login_cancel: Mutex::new(None),
+            browser_login_extend: Mutex::new(None),
             settings: Mutex::new(settings),
+            register_queue: Mutex::new(register_queue::RegisterQueue::default()),
+        })
+        .setup(|app| {
+            // 启动后在后台校验未验证账号（离线时失败的账号保持未验证状态）
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                let network_config = state.settings.lock().await.network_config();
+                api::network::configure(network_config).await;
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                let report = validate_unverified_with_state(&state).await;
+                if report.validated > 0 || !report.failed.is_empty() {
+                    println!(
+                        "[INFO] 未验证账号校验完成: 成功 {} 个, 失败 {} 个",
+                        report.validated,
+                        report.failed.len()
+                    );
+                }
+
+                // 定时维护：每 10 分钟检查一次是否到达设定间隔
+                let mut last_run: Option<Instant> = None;
+                loop {
+                    let interval_hours = state.settings.lock().await.maintenance_interval_hours;
+                    let interval = Duration::from_secs(interval_hours * 3600);
+                    let due = interval_hours > 0
+                        && !matches!(last_run, Some(t) if t.elapsed() < interval);
+                    if due {
+                        last_run = Some(Instant::now());
+                        if let Err(e) = maintenance::run_maintenance(&handle).await {
+                            println!("[WARN] 定时维护未执行: {}", e);
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_secs(600)).await;
+                }
+            });
+
+            // 只读模式下定期重试获取数据锁（例如静默启动的实例已退出）
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                while state.account_manager.lock().await.is_read_only() {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    match state.account_manager.lock().await.try_reacquire_store_lock() {
+                        Ok(true) => {
+                            let _ = handle.emit("store_lock_acquired", ());
+                        }
+                        Ok(false) => {}
+                        Err(e) => println!("[WARN] 重新加载账号数据失败: {}", e),
+                    }
+                }
+            });
+
+            // 监听 accounts.json 的外部修改并重新加载
+            store_watch::spawn_watcher(app.handle().clone());
+
+            // 检查 Token 过期时间，即将过期时提醒重新登录
+            token_watch::spawn_startup_sweep(app.handle().clone());
+            token_watch::spawn_watcher(app.handle().clone());
+
+            // 按账号活跃程度自动刷新使用量
+            usage_poll::spawn_watcher(app.handle().clone());
+
+            // 监控设置了提醒阈值的账号的剩余额度
+            quota_watch::spawn_watcher(app.handle().clone());
+
+            // 按时间表在轮换池中切换账号
+            rotation::spawn_watcher(app.handle().clone());
+
+            // 可用账号不足时自动补充注册
+            pool_topup::spawn_watcher(app.handle().clone());
+
+            // 自动清理额度用完的免费账号
+            retirement::spawn_watcher(app.handle().clone());
+
+            // 启动后在后台清理过期数据
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                run_cleanup_with_state(&handle.state::<AppState>()).await;
+            });
+
+            // 每周生成账号周报
+            report::spawn_scheduler(app.handle().clone());
+
+            // 监听 Trae 打开的工作区，按映射提示或自动切换账号
+            workspace::spawn_watcher(app.handle().clone());
+
+            // 定时备份：以最近一次备份时间为准，重启后不会重复备份
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                loop {
+                    tokio::time::sleep(Duration::from_secs(600)).await;
+                    let interval_hours = state.settings.lock().await.backup_interval_hours;
+                    if interval_hours == 0 {
+                        continue;
+                    }
+                    let latest = state
+                        .account_manager
+                        .lock()
+                        .await
+                        .list_backups()
+                        .first()
+                        .map(|b| b.created_at);
+                    let due = !matches!(
+                        latest,
+                        Some(t) if chrono::Utc::now().timestamp() - t < (interval_hours * 3600) as i64
+                    );
+                    if !due {
+                        continue;
+                    }
+                    match backup_now(&state, true).await {
+                        Ok(backup) => {
+                            if state.settings.lock().await.cloud_backup.is_enabled() {
+                                if let Err(e) = upload_backup_to_cloud(&state, &backup).await {
+                                    println!("[WARN] 上传云端备份失败: {}", e);
+                                }
+                            }
+                        }
+                        Err(e) => println!("[WARN] 定时备份失败: {}", e),
+                    }
+                }
+            });
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             add_account_by_token,
+            add_account_unverified,
+            validate_account,
+            validate_unverified_accounts,
+            refresh_account_profile,
+            refresh_all_account_profiles,
+            batch_account_action,
+            run_cleanup,
+            run_maintenance,
+            generate_weekly_report,
+            send_test_notification,
+            self_test,
+            get_integrity_status,
+            get_store_read_only,
+            retry_store_lock,
+            restore_integrity_backup,
+            dismiss_integrity_issue,
+            trust_external_accounts_change,
+            create_backup,
+            list_workspace_mappings,
+            set_workspace_mapping,
+            remove_workspace_mapping,
+            get_trae_open_workspaces,
+            get_trae_auth_snapshot,
+            list_backups,
+            restore_backup,
+            upload_backup,
             add_account_by_email,
+            add_account_by_cookie_header,
+            webview_callback,
+            webview_console,
+            extend_browser_login,
             get_settings,
             update_settings,
+            get_system_proxy,
+            get_api_certificate_fingerprints,
+            check_api_health,
+            get_api_debug_log_path,
+            clear_api_debug_log,
+            get_webview_console_log_path,
+            clear_webview_console_log,
+            import_browser_cookies,
+            check_for_update,
             download_and_run_installer,
             quick_register,
+            quick_register_batch,
+            enqueue_register_jobs,
+            get_register_queue,
+            pause_register_queue,
+            resume_register_queue,
+            cancel_register_job,
+            clear_register_queue,
+            retry_quick_register_step,
+            quick_register_with_email,
+            submit_verification_code,
+            list_interrupted_registrations,
+            resume_registration,
+            discard_registration,
+            get_credential_journal,
+            abort_quick_register,
+            list_mailbox_messages,
+            get_mailbox_message,
             start_browser_login,
+            start_browser_relogin,
             finish_browser_login,
             cancel_browser_login,
             remove_account,
@@ -2283,16 +4812,36 @@ pub fn run() {
             get_account,
             switch_account,
             get_account_usage,
+            get_all_accounts_usage,
+            get_usage_aggregate,
+            get_account_request_log,
+            get_usage_trend,
+            get_statistics_history,
+            estimate_quota_exhaustion,
             update_account_token,
             refresh_token,
             refresh_token_with_password,
             login_account_with_email,
             update_account_profile,
+            get_account_cookies_parsed,
+            set_account_cookie,
             export_accounts,
+            export_pool_summary_md,
+            update_account_notes,
+            update_account_alias,
+            update_account_totp_secret,
+            get_account_totp_code,
+            set_quota_threshold,
+            start_rotation,
+            stop_rotation,
+            get_rotation_status,
             export_accounts_to_path,
             import_accounts,
+            import_accounts_merge,
+            preview_import,
             clear_accounts,
             get_usage_events,
+            export_usage_events,
             read_trae_account,
             get_machine_id,
             reset_machine_id,
@@ -2301,11 +4850,16 @@ pub fn run() {
             get_trae_machine_id,
             set_trae_machine_id,
             clear_trae_login_state,
+            get_trae_install_status,
             get_trae_path,
             set_trae_path,
             scan_trae_path,
             claim_gift,
+            claim_gift_all,
+            list_promotions,
+            claim_promotion,
             get_user_statistics,
+            get_subscription_info,
             open_pricing,
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,150 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use tauri::webview::{NewWindowFeatures, NewWindowResponse};
+use tauri::{AppHandle, Manager, Url, WebviewUrl, WebviewWindowBuilder, Wry};
+use tokio::sync::Notify;
+
+/// 第三方登录服务的域名
+const OAUTH_PROVIDERS: [(&str, &str); 3] = [
+    ("accounts.google.com", "Google"),
+    ("github.com", "GitHub"),
+    ("appleid.apple.com", "Apple"),
+];
+
+/// 弹出窗口序号，用于生成窗口标签
+static NEXT_POPUP: AtomicU32 = AtomicU32::new(1);
+
+#[derive(Debug, Default)]
+struct OAuthState {
+    /// 正在进行的第三方登录
+    provider: Option<&'static str>,
+    /// 已从第三方登录页跳回 Trae
+    returned: bool,
+}
+
+/// 浏览器登录中的第三方登录（Google / GitHub）进度：跟踪登录窗口和弹出窗口的跳转，
+/// 在第三方登录页跳回 Trae（或弹出窗口关闭）后才认为第三方登录结束
+#[derive(Clone, Default)]
+pub struct OAuthTracker {
+    state: Arc<StdMutex<OAuthState>>,
+    returned: Arc<Notify>,
+}
+
+impl OAuthTracker {
+    /// 记录一次跳转，返回 true 表示这次跳转结束了第三方登录（从第三方登录页回到 Trae）
+    pub fn observe(&self, url: &Url) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(provider) = provider_for(url) {
+            if state.provider != Some(provider) || state.returned {
+                println!("[browser-login] {} 登录开始: {}", provider, url);
+            }
+            state.provider = Some(provider);
+            state.returned = false;
+            return false;
+        }
+        if state.provider.is_some() && !state.returned && is_trae_url(url) {
+            state.returned = true;
+            println!("[browser-login] 第三方登录已跳回 Trae: {}", url);
+            self.returned.notify_waiters();
+            return true;
+        }
+        false
+    }
+
+    /// 第三方登录弹出窗口关闭：登录完成后弹出窗口会自行关闭，用户关闭时也不再等待
+    fn popup_closed(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.provider.is_some() && !state.returned {
+            state.returned = true;
+            self.returned.notify_waiters();
+            return true;
+        }
+        false
+    }
+
+    /// 正在进行（尚未跳回 Trae）的第三方登录
+    pub fn pending_provider(&self) -> Option<&'static str> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.provider.filter(|_| !state.returned)
+    }
+
+    /// 等待第三方登录跳回 Trae，超时返回 false
+    pub async fn wait_returned(&self, timeout: Duration) -> bool {
+        let wait = async {
+            loop {
+                let notified = self.returned.notified();
+                if self.pending_provider().is_none() {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.is_ok()
+    }
+}
+
+fn provider_for(url: &Url) -> Option<&'static str> {
+    let host = url.host_str()?;
+    OAUTH_PROVIDERS
+        .iter()
+        .find(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+        .map(|(_, name)| *name)
+}
+
+fn is_trae_url(url: &Url) -> bool {
+    url.host_str()
+        .is_some_and(|host| ["trae.ai", "trae.com.cn"].iter().any(|d| host == *d || host.ends_with(&format!(".{}", d))))
+}
+
+/// 登录页打开的弹出窗口（第三方登录通常使用弹出窗口）：使用与登录窗口相同的 WebView 数据打开，并跟踪其中的跳转
+pub fn open_popup(
+    app: &AppHandle,
+    tracker: &OAuthTracker,
+    login_label: &str,
+    url: Url,
+    features: NewWindowFeatures,
+) -> NewWindowResponse<Wry> {
+    tracker.observe(&url);
+    let label = format!("{}-popup-{}", login_label, NEXT_POPUP.fetch_add(1, Ordering::Relaxed));
+    let nav_tracker = tracker.clone();
+    let nav_app = app.clone();
+    let nav_login_label = login_label.to_string();
+    let window = WebviewWindowBuilder::new(app, &label, WebviewUrl::External("about:blank".parse().unwrap()))
+        .window_features(features)
+        .title("Trae 登录")
+        .on_navigation(move |url| {
+            if nav_tracker.observe(url) {
+                check_login(&nav_app, &nav_login_label);
+            }
+            true
+        })
+        .build();
+    match window {
+        Ok(window) => {
+            let close_tracker = tracker.clone();
+            let close_app = app.clone();
+            let close_login_label = login_label.to_string();
+            window.on_window_event(move |event| {
+                if let tauri::WindowEvent::Destroyed = event {
+                    if close_tracker.popup_closed() {
+                        check_login(&close_app, &close_login_label);
+                    }
+                }
+            });
+            NewWindowResponse::Create { window }
+        }
+        Err(e) => {
+            println!("[browser-login] 无法打开登录弹出窗口，使用默认窗口: {}", e);
+            NewWindowResponse::Allow
+        }
+    }
+}
+
+/// 第三方登录结束后让登录窗口立即检查登录状态（获取 Token）
+pub fn check_login(app: &AppHandle, login_label: &str) {
+    if let Some(window) = app.get_webview_window(login_label) {
+        let _ = window.eval("window.__traeCheckLogin && window.__traeCheckLogin();");
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Result};
+use crate::error::{coded, ErrorCode};
 use uuid::Uuid;
 use std::fs;
 use std::path::PathBuf;
@@ -65,14 +66,14 @@ pub fn reset_machine_guid() -> Result<String> {
 
 /// 获取 Trae IDE 数据目录路径
 #[cfg(target_os = "windows")]
-fn get_trae_data_path() -> Result<PathBuf> {
+pub fn get_trae_data_path() -> Result<PathBuf> {
     let appdata = std::env::var("APPDATA")
         .map_err(|_| anyhow!("无法获取 APPDATA 环境变量"))?;
     Ok(PathBuf::from(appdata).join("Trae"))
 }
 
 #[cfg(target_os = "macos")]
-fn get_trae_data_path() -> Result<PathBuf> {
+pub fn get_trae_data_path() -> Result<PathBuf> {
     let home = std::env::var("HOME")
         .map_err(|_| anyhow!("无法获取 HOME 环境变量"))?;
     Ok(PathBuf::from(home)
@@ -81,9 +82,23 @@ fn get_trae_data_path() -> Result<PathBuf> {
         .join("Trae"))
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
-fn get_trae_data_path() -> Result<PathBuf> {
-    Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"))
+/// Linux 上与 VS Code 相同，配置目录位于 $XDG_CONFIG_HOME/Trae（默认 ~/.config/Trae）
+#[cfg(target_os = "linux")]
+pub fn get_trae_data_path() -> Result<PathBuf> {
+    let config_home = match std::env::var("XDG_CONFIG_HOME").ok().filter(|dir| !dir.trim().is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let home = std::env::var("HOME")
+                .map_err(|_| anyhow!("无法获取 HOME 环境变量"))?;
+            PathBuf::from(home).join(".config")
+        }
+    };
+    Ok(config_home.join("Trae"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn get_trae_data_path() -> Result<PathBuf> {
+    Err(anyhow!("此功能仅支持 Windows、macOS 和 Linux 系统"))
 }
 
 /// 获取 Trae IDE 的 state.vscdb 路径
@@ -92,8 +107,124 @@ pub fn get_trae_state_db_path() -> Result<PathBuf> {
     Ok(trae_path.join("User").join("globalStorage").join("state.vscdb"))
 }
 
+/// Trae IDE 是否正在使用：进程在运行，且状态数据库在 within 时间内有写入
+pub fn is_trae_busy(within: std::time::Duration) -> bool {
+    if !is_trae_running() {
+        return false;
+    }
+    let Ok(db_path) = get_trae_state_db_path() else {
+        return false;
+    };
+    // WAL 模式下最近的写入先落到 -wal 文件
+    let wal_path = db_path.with_file_name("state.vscdb-wal");
+    [db_path, wal_path].iter()
+        .filter_map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .any(|modified| modified.elapsed().is_ok_and(|elapsed| elapsed < within))
+}
+
+/// Trae IDE 安装状态
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TraeInstallStatus {
+    /// 当前系统是否支持管理 Trae IDE
+    pub supported: bool,
+    /// 是否检测到 Trae 配置目录（安装后至少启动过一次）
+    pub installed: bool,
+    pub data_dir: Option<String>,
+    /// 已保存或自动扫描到的 Trae 程序路径
+    pub executable: Option<String>,
+    pub running: bool,
+    /// 不可用时的原因，用于界面提示
+    pub reason: Option<String>,
+}
+
+/// 检测 Trae IDE 是否安装，依赖 IDE 的功能（切换账号、机器码、隐私模式等）据此禁用
+pub fn get_trae_install_status() -> TraeInstallStatus {
+    let data_dir = match get_trae_data_path() {
+        Ok(dir) => dir,
+        Err(e) => {
+            return TraeInstallStatus {
+                supported: false,
+                installed: false,
+                data_dir: None,
+                executable: None,
+                running: false,
+                reason: Some(e.to_string()),
+            };
+        }
+    };
+    let installed = data_dir.join("User").is_dir();
+    let executable = get_saved_trae_path().or_else(|_| scan_trae_path()).ok();
+    TraeInstallStatus {
+        supported: true,
+        installed,
+        data_dir: Some(data_dir.to_string_lossy().to_string()),
+        executable,
+        running: is_trae_running(),
+        reason: (!installed).then(|| {
+            format!("未检测到 Trae IDE 数据目录 ({})，请安装并启动一次 Trae", data_dir.display())
+        }),
+    }
+}
+
+/// 是否检测到 Trae IDE 数据目录
+pub fn is_trae_installed() -> bool {
+    get_trae_data_path().is_ok_and(|dir| dir.join("User").is_dir())
+}
+
+/// 依赖 Trae IDE 的操作前统一检查，返回明确的错误提示
+pub fn ensure_trae_installed() -> Result<()> {
+    let data_dir = get_trae_data_path()?;
+    if !data_dir.join("User").is_dir() {
+        return Err(coded(ErrorCode::TraeNotInstalled, "未检测到 Trae IDE，该功能不可用。请安装并启动一次 Trae 后重试"));
+    }
+    Ok(())
+}
+
+/// 修改 Trae 数据前要求的最小剩余磁盘空间
+const MIN_FREE_SPACE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 修改或清除 Trae 数据前的安全检查：
+/// 目录必须像一个 Trae 配置目录（包含 User 目录及 machineid / storage.json / Local State 之一），
+/// 并且磁盘剩余空间足够写入新文件，避免路径配置错误时误删其他目录
+fn ensure_trae_profile_safe(trae_path: &std::path::Path) -> Result<()> {
+    if !trae_path.is_dir() {
+        return Err(anyhow!("Trae 数据目录不存在: {}", trae_path.display()));
+    }
+    if trae_path.parent().is_none() || directories::UserDirs::new().is_some_and(|dirs| dirs.home_dir() == trae_path) {
+        return Err(anyhow!("Trae 数据目录配置异常，拒绝修改: {}", trae_path.display()));
+    }
+
+    let markers = [
+        trae_path.join("machineid"),
+        trae_path.join("User").join("globalStorage").join("storage.json"),
+        trae_path.join("Local State"),
+    ];
+    if !trae_path.join("User").is_dir() || !markers.iter().any(|p| p.exists()) {
+        return Err(anyhow!(
+            "目录 {} 看起来不是 Trae 配置目录（缺少 User 目录或 machineid / storage.json），已取消操作。如果是新安装的 Trae，请先启动一次",
+            trae_path.display()
+        ));
+    }
+
+    let storage_size = fs::metadata(&markers[1]).map(|m| m.len()).unwrap_or(0);
+    let required = MIN_FREE_SPACE_BYTES + storage_size * 2;
+    match fs2::available_space(trae_path) {
+        Ok(available) if available < required => Err(anyhow!(
+            "磁盘剩余空间不足（剩余 {} MB，至少需要 {} MB），已取消操作",
+            available / 1024 / 1024,
+            required / 1024 / 1024
+        )),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            println!("[WARN] 无法获取磁盘剩余空间，跳过检查: {}", e);
+            Ok(())
+        }
+    }
+}
+
 /// 读取 Trae IDE 的机器码
 pub fn get_trae_machine_id() -> Result<String> {
+    ensure_trae_installed()?;
     let trae_path = get_trae_data_path()?;
     let machine_id_path = trae_path.join("machineid");
 
@@ -109,6 +240,7 @@ pub fn get_trae_machine_id() -> Result<String> {
 
 /// 设置 Trae IDE 的机器码
 pub fn set_trae_machine_id(new_id: &str) -> Result<()> {
+    ensure_trae_installed()?;
     let trae_path = get_trae_data_path()?;
     let machine_id_path = trae_path.join("machineid");
 
@@ -232,14 +364,60 @@ pub fn kill_trae() -> Result<()> {
     Ok(())
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+/// Linux 上 Trae 的进程名（不区分大小写精确匹配，避免匹配到本程序）
+#[cfg(target_os = "linux")]
+const LINUX_TRAE_PROCESS: &str = "trae";
+
+#[cfg(target_os = "linux")]
+pub fn is_trae_running() -> bool {
+    Command::new("pgrep")
+        .args(["-x", "-i", LINUX_TRAE_PROCESS])
+        .output()
+        .map(|out| out.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+pub fn kill_trae() -> Result<()> {
+    if !is_trae_running() {
+        println!("[INFO] Trae IDE 未运行");
+        return Ok(());
+    }
+
+    println!("[INFO] 正在关闭 Trae IDE...");
+
+    // 先发送 SIGTERM 让 Trae 正常退出
+    let _ = Command::new("pkill")
+        .args(["-x", "-i", LINUX_TRAE_PROCESS])
+        .output();
+
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+
+    if is_trae_running() {
+        println!("[INFO] 优雅关闭失败，正在强制关闭...");
+        let _ = Command::new("pkill")
+            .args(["-9", "-x", "-i", LINUX_TRAE_PROCESS])
+            .output();
+
+        std::thread::sleep(std::time::Duration::from_millis(1000));
+    }
+
+    if is_trae_running() {
+        return Err(anyhow!("无法关闭 Trae IDE，请手动关闭后重试"));
+    }
+
+    println!("[INFO] Trae IDE 已关闭");
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn is_trae_running() -> bool {
     false
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn kill_trae() -> Result<()> {
-    Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"))
+    Err(anyhow!("此功能仅支持 Windows、macOS 和 Linux 系统"))
 }
 
 /// 获取 Trae IDE 配置文件路径
@@ -296,9 +474,28 @@ pub fn save_trae_path(path: &str) -> Result<()> {
     Ok(())
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(target_os = "linux")]
+pub fn save_trae_path(path: &str) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let exe_path = PathBuf::from(path);
+    if !exe_path.exists() {
+        return Err(anyhow!("指定的路径不存在"));
+    }
+    // 可执行文件或 AppImage
+    let executable = fs::metadata(&exe_path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0);
+    if !executable {
+        return Err(anyhow!("请选择 Trae 可执行文件（如 /usr/share/trae/trae 或 Trae 的 AppImage）"));
+    }
+    let config_path = get_trae_config_path()?;
+    fs::write(&config_path, path)?;
+    println!("[INFO] 已保存 Trae IDE 路径: {}", path);
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn save_trae_path(_path: &str) -> Result<()> {
-    Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"))
+    Err(anyhow!("此功能仅支持 Windows、macOS 和 Linux 系统"))
 }
 
 /// 自动扫描 Trae IDE 安装路径
@@ -321,12 +518,51 @@ pub fn scan_trae_path() -> Result<String> {
         }
     }
     
-    Err(anyhow!("未找到 Trae IDE，请手动设置路径"))
+    Err(coded(ErrorCode::TraeNotInstalled, "未找到 Trae IDE，请手动设置路径"))
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(target_os = "linux")]
 pub fn scan_trae_path() -> Result<String> {
-    Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"))
+    let home = std::env::var("HOME").unwrap_or_default();
+    // PATH 中的 trae 命令，其次是 deb / rpm / 手动解压的常见安装位置
+    let mut candidates: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).map(|dir| dir.join("trae")).collect())
+        .unwrap_or_default();
+    candidates.extend(
+        [
+            "/usr/share/trae/trae",
+            "/opt/Trae/trae",
+            "/opt/trae/trae",
+            &format!("{}/.local/share/trae/trae", home),
+        ]
+        .iter()
+        .map(PathBuf::from),
+    );
+
+    if let Some(path) = candidates.iter().find(|path| path.is_file()) {
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    // ~/Applications 下的 AppImage
+    let appimage = fs::read_dir(PathBuf::from(&home).join("Applications"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+            name.starts_with("trae") && name.ends_with(".appimage")
+        });
+    if let Some(path) = appimage {
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    Err(coded(ErrorCode::TraeNotInstalled, "未找到 Trae IDE，请手动设置路径"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn scan_trae_path() -> Result<String> {
+    Err(anyhow!("此功能仅支持 Windows、macOS 和 Linux 系统"))
 }
 
 /// 打开 Trae IDE
@@ -395,9 +631,54 @@ pub fn open_trae() -> Result<()> {
     Ok(())
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(target_os = "linux")]
+pub fn open_trae() -> Result<()> {
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+
+    let trae_exe = match get_saved_trae_path() {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => match scan_trae_path() {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => return Err(anyhow!("未设置 Trae IDE 路径，请在设置中配置")),
+        },
+    };
+
+    if !trae_exe.exists() {
+        return Err(anyhow!("Trae IDE 路径无效，请在设置中重新配置"));
+    }
+
+    println!("[INFO] 正在启动 Trae IDE: {}", trae_exe.display());
+
+    // 使用独立的进程组，本程序退出时不影响 Trae
+    Command::new(&trae_exe)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| anyhow!("启动 Trae IDE 失败: {}", e))?;
+
+    println!("[INFO] Trae IDE 已启动");
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn open_trae() -> Result<()> {
-    Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"))
+    Err(anyhow!("此功能仅支持 Windows、macOS 和 Linux 系统"))
+}
+
+/// 切换账号时写入 iCubeEntitlementInfo 的方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntitlementMode {
+    /// 按账号套餐生成，无法识别的套餐不写入
+    #[default]
+    FromPlan,
+    /// 固定写入 Free 权益（旧版行为）
+    Free,
+    /// 不写入，由 Trae IDE 登录后自行拉取
+    Skip,
 }
 
 /// 账号登录信息结构（用于写入 Trae IDE）
@@ -411,12 +692,125 @@ pub struct TraeLoginInfo {
     pub avatar_url: String,
     pub host: String,
     pub region: String,
+    /// 切换后写入 Trae 的界面语言，为 None 时不修改
+    pub locale: Option<String>,
+    /// 账号套餐（Free / Pro 等）
+    pub plan_type: String,
+    pub entitlement_mode: EntitlementMode,
+}
+
+/// 读取 Trae IDE 当前打开的工作区目录（最近活动的窗口在前）
+pub fn get_trae_open_workspaces() -> Result<Vec<PathBuf>> {
+    let storage_path = get_trae_data_path()?.join("User").join("globalStorage").join("storage.json");
+    if !storage_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&storage_path)
+        .map_err(|e| anyhow!("读取 storage.json 失败: {}", e))?;
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("storage.json 格式错误: {}", e))?;
+
+    let windows_state = &json["windowsState"];
+    let mut folders = Vec::new();
+    let last_active = std::iter::once(&windows_state["lastActiveWindow"]);
+    let opened = windows_state["openedWindows"].as_array().into_iter().flatten();
+    for window in last_active.chain(opened) {
+        let Some(uri) = window["folder"].as_str() else {
+            continue;
+        };
+        let Some(path) = reqwest::Url::parse(uri).ok().and_then(|url| url.to_file_path().ok()) else {
+            continue;
+        };
+        if !folders.contains(&path) {
+            folders.push(path);
+        }
+    }
+    Ok(folders)
+}
+
+/// Trae IDE storage.json 中登录信息的只读快照（敏感字段已打码）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TraeAuthSnapshot {
+    pub storage_path: String,
+    /// storage.json 最后修改时间
+    pub modified_at: Option<i64>,
+    /// iCubeAuthInfo://icube.cloudide
+    pub auth_info: Option<serde_json::Value>,
+    /// iCubeEntitlementInfo://icube.cloudide
+    pub entitlement_info: Option<serde_json::Value>,
+}
+
+/// 打码 token 类字段：只保留首尾 4 个字符和长度
+fn mask_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                let lower = key.to_lowercase();
+                // tokenReleaseAt 之类的时间字段不打码
+                let secret = (lower.contains("token") || lower.contains("secret") || lower.contains("password"))
+                    && !lower.ends_with("at");
+                match item {
+                    serde_json::Value::String(text) if secret && !text.is_empty() => {
+                        let chars: Vec<char> = text.chars().collect();
+                        *text = if chars.len() <= 12 {
+                            format!("*** ({} 字符)", chars.len())
+                        } else {
+                            let head: String = chars[..4].iter().collect();
+                            let tail: String = chars[chars.len() - 4..].iter().collect();
+                            format!("{}…{} ({} 字符)", head, tail, chars.len())
+                        };
+                    }
+                    _ => mask_secrets(item),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(mask_secrets),
+        _ => {}
+    }
+}
+
+/// 读取 Trae IDE 当前的登录信息（iCubeAuthInfo / iCubeEntitlementInfo），用于排查问题
+pub fn get_trae_auth_snapshot() -> Result<TraeAuthSnapshot> {
+    ensure_trae_installed()?;
+    let storage_path = get_trae_data_path()?.join("User").join("globalStorage").join("storage.json");
+    if !storage_path.exists() {
+        return Err(anyhow!("Trae IDE storage.json 不存在: {}", storage_path.display()));
+    }
+    let content = fs::read_to_string(&storage_path)
+        .map_err(|e| anyhow!("读取 storage.json 失败: {}", e))?;
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("storage.json 格式错误: {}", e))?;
+
+    // 这两项在 storage.json 中以 JSON 字符串形式保存
+    let read_blob = |key: &str| {
+        let mut value = match &json[key] {
+            serde_json::Value::String(raw) => serde_json::from_str(raw)
+                .unwrap_or_else(|_| serde_json::Value::String(raw.clone())),
+            serde_json::Value::Null => return None,
+            other => other.clone(),
+        };
+        mask_secrets(&mut value);
+        Some(value)
+    };
+
+    Ok(TraeAuthSnapshot {
+        modified_at: fs::metadata(&storage_path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp()),
+        auth_info: read_blob("iCubeAuthInfo://icube.cloudide"),
+        entitlement_info: read_blob("iCubeEntitlementInfo://icube.cloudide"),
+        storage_path: storage_path.to_string_lossy().to_string(),
+    })
 }
 
 /// 将账号登录信息写入 Trae IDE
 pub fn write_trae_login_info(info: &TraeLoginInfo) -> Result<()> {
-    let trae_path = get_trae_data_path()?;
+    write_trae_login_info_to(&get_trae_data_path()?, info)
+}
 
+/// 将账号登录信息写入指定的 Trae 数据目录（自检时写入临时目录）
+pub fn write_trae_login_info_to(trae_path: &std::path::Path, info: &TraeLoginInfo) -> Result<()> {
     // 确保目录存在
     let storage_dir = trae_path.join("User").join("globalStorage");
     fs::create_dir_all(&storage_dir)
@@ -443,11 +837,7 @@ pub fn write_trae_login_info(info: &TraeLoginInfo) -> Result<()> {
 
     // 构建 host URL
     let host = if info.host.is_empty() {
-        match info.region.to_uppercase().as_str() {
-            "SG" => "https://api-sg-central.trae.ai",
-            "CN" => "https://api.trae.com.cn",
-            _ => "https://api-sg-central.trae.ai",
-        }
+        crate::api::api_base_for_region(&info.region)
     } else {
         &info.host
     };
@@ -483,36 +873,36 @@ pub fn write_trae_login_info(info: &TraeLoginInfo) -> Result<()> {
         }
     });
 
-    // 构建 iCubeEntitlementInfo
-    let entitlement_info = serde_json::json!({
-        "identityStr": "Free",
-        "identity": 0,
-        "isPayFreshman": false,
-        "isSupportCommercialization": true,
-        "hasPackage": false,
-        "enableEntitlement": true,
-        "detail": {
-            "can_gen_solo_code": false,
-            "fast_request_per": 1,
-            "in_wait": false,
-            "permission": 1,
-            "toast_read": false,
-            "toastRead": false,
-            "canGenSoloCode": false,
-            "fastRequestPer": 1,
-            "inWaitlist": false
+    // 构建 iCubeEntitlementInfo（按设置决定写入内容或不写入）
+    let entitlement_info = match info.entitlement_mode {
+        EntitlementMode::Skip => None,
+        EntitlementMode::Free => Some(build_entitlement_info("Free", 0)),
+        EntitlementMode::FromPlan => {
+            let identity = entitlement_identity(&info.plan_type);
+            if identity.is_none() {
+                println!("[WARN] 无法识别套餐 {}，不写入权益信息", info.plan_type);
+            }
+            identity.map(|(name, identity)| build_entitlement_info(name, identity))
         }
-    });
+    };
 
     // 写入登录信息
     obj.insert(
         "iCubeAuthInfo://icube.cloudide".to_string(),
         serde_json::Value::String(serde_json::to_string(&auth_info).unwrap())
     );
-    obj.insert(
-        "iCubeEntitlementInfo://icube.cloudide".to_string(),
-        serde_json::Value::String(serde_json::to_string(&entitlement_info).unwrap())
-    );
+    // 不写入权益时移除旧账号留下的权益信息，避免 IDE 沿用错误的功能开关
+    match entitlement_info {
+        Some(entitlement_info) => {
+            obj.insert(
+                "iCubeEntitlementInfo://icube.cloudide".to_string(),
+                serde_json::Value::String(serde_json::to_string(&entitlement_info).unwrap())
+            );
+        }
+        None => {
+            obj.remove("iCubeEntitlementInfo://icube.cloudide");
+        }
+    }
 
     // 写回文件
     let new_content = serde_json::to_string_pretty(&json)
@@ -524,13 +914,48 @@ pub fn write_trae_login_info(info: &TraeLoginInfo) -> Result<()> {
     Ok(())
 }
 
+/// 套餐名称对应的 IDE 身份（identityStr, identity），无法识别时返回 None
+fn entitlement_identity(plan_type: &str) -> Option<(&'static str, i64)> {
+    match plan_type.trim().to_lowercase().as_str() {
+        "" | "free" => Some(("Free", 0)),
+        "pro" => Some(("Pro", 1)),
+        _ => None,
+    }
+}
+
+/// 构建 iCubeEntitlementInfo
+fn build_entitlement_info(identity_str: &str, identity: i64) -> serde_json::Value {
+    let is_paid = identity > 0;
+    serde_json::json!({
+        "identityStr": identity_str,
+        "identity": identity,
+        "isPayFreshman": false,
+        "isSupportCommercialization": true,
+        "hasPackage": is_paid,
+        "enableEntitlement": true,
+        "detail": {
+            "can_gen_solo_code": false,
+            "fast_request_per": 1,
+            "in_wait": false,
+            "permission": 1,
+            "toast_read": false,
+            "toastRead": false,
+            "canGenSoloCode": false,
+            "fastRequestPer": 1,
+            "inWaitlist": false
+        }
+    })
+}
+
 /// 切换 Trae IDE 到指定账号（清除旧登录状态并写入新账号信息）
 pub fn switch_trae_account(info: &TraeLoginInfo, machine_id: Option<&str>) -> Result<()> {
+    ensure_trae_installed()?;
+    let trae_path = get_trae_data_path()?;
+    ensure_trae_profile_safe(&trae_path)?;
+
     // 0. 先关闭 Trae IDE
     kill_trae()?;
 
-    let trae_path = get_trae_data_path()?;
-
     // 1. 设置机器码（如果提供则使用，否则生成新的）
     let new_machine_id = match machine_id {
         Some(mid) => mid.to_string(),
@@ -630,9 +1055,17 @@ pub fn switch_trae_account(info: &TraeLoginInfo, machine_id: Option<&str>) -> Re
     // 11. 写入新的登录信息
     write_trae_login_info(info)?;
 
+    // 12. 按账号偏好设置界面语言
+    if let Some(locale) = &info.locale {
+        match set_trae_locale(locale) {
+            Ok(()) => println!("[INFO] 已设置 Trae 界面语言: {}", locale),
+            Err(e) => println!("[WARN] 设置 Trae 界面语言失败: {}", e),
+        }
+    }
+
     println!("[INFO] 已切换 Trae IDE 到账号: {}", info.email);
 
-    // 12. 自动打开 Trae IDE
+    // 13. 自动打开 Trae IDE
     if let Err(e) = open_trae() {
         println!("[WARN] 自动打开 Trae IDE 失败: {}", e);
     }
@@ -640,9 +1073,66 @@ pub fn switch_trae_account(info: &TraeLoginInfo, machine_id: Option<&str>) -> Re
     Ok(())
 }
 
+/// Trae 启动参数文件（~/.trae/argv.json，其中的 locale 决定界面语言）
+fn get_trae_argv_path() -> Result<PathBuf> {
+    let dirs = directories::UserDirs::new().ok_or_else(|| anyhow!("无法获取用户目录"))?;
+    Ok(dirs.home_dir().join(".trae").join("argv.json"))
+}
+
+/// 设置 Trae 的界面语言（写入 argv.json 的 locale 字段，下次启动生效）
+pub fn set_trae_locale(locale: &str) -> Result<()> {
+    let path = get_trae_argv_path()?;
+    let content = if path.exists() {
+        fs::read_to_string(&path).map_err(|e| anyhow!("读取 argv.json 失败: {}", e))?
+    } else {
+        "{\n}\n".to_string()
+    };
+
+    // 没有注释时直接按 JSON 修改
+    let new_content = if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(&content) {
+        let obj = json.as_object_mut().ok_or_else(|| anyhow!("argv.json 格式错误"))?;
+        obj.insert("locale".to_string(), serde_json::Value::String(locale.to_string()));
+        serde_json::to_string_pretty(&json).map_err(|e| anyhow!("序列化 JSON 失败: {}", e))?
+    } else {
+        // argv.json 默认带注释（JSONC），按行替换以保留注释
+        let entry = format!("\"locale\": \"{}\"", locale);
+        let mut replaced = false;
+        let mut lines: Vec<String> = content
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start();
+                if replaced || !trimmed.starts_with("\"locale\"") {
+                    return line.to_string();
+                }
+                replaced = true;
+                let indent = &line[..line.len() - trimmed.len()];
+                let comma = if trimmed.trim_end().ends_with(',') { "," } else { "" };
+                format!("{}{}{}", indent, entry, comma)
+            })
+            .collect();
+        if !replaced {
+            let open = lines
+                .iter()
+                .position(|l| l.trim() == "{")
+                .ok_or_else(|| anyhow!("argv.json 格式无法识别"))?;
+            let has_more = lines[open + 1..].iter().any(|l| l.trim_start().starts_with('"'));
+            lines.insert(open + 1, format!("\t{}{}", entry, if has_more { "," } else { "" }));
+        }
+        lines.join("\n")
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| anyhow!("创建目录失败: {}", e))?;
+    }
+    fs::write(&path, new_content + "\n").map_err(|e| anyhow!("写入 argv.json 失败: {}", e))?;
+    Ok(())
+}
+
 /// 清除 Trae IDE 的登录状态（让 IDE 变成全新安装状态）
 pub fn clear_trae_login_state() -> Result<()> {
+    ensure_trae_installed()?;
     let trae_path = get_trae_data_path()?;
+    ensure_trae_profile_safe(&trae_path)?;
 
     // 1. 生成新的机器码
     let new_machine_id = generate_machine_guid();
@@ -787,18 +1277,69 @@ pub fn reset_machine_guid() -> Result<String> {
     Err(anyhow!("macOS 不支持重置系统机器码"))
 }
 
-// 非 Windows/macOS 平台的占位实现
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+// Linux 平台实现：不修改系统的 /etc/machine-id（会影响 systemd、D-Bus 等其他程序），
+// 机器码读写 Trae 自己数据目录中的 machineid 文件
+#[cfg(target_os = "linux")]
+const LINUX_MACHINE_ID_PATHS: [&str; 2] = ["/etc/machine-id", "/var/lib/dbus/machine-id"];
+
+#[cfg(target_os = "linux")]
+fn trae_machine_id_path() -> Result<PathBuf> {
+    Ok(get_trae_data_path()?.join("machineid"))
+}
+
+/// 读取 Trae 的机器码，尚未生成时使用系统的 /etc/machine-id
+#[cfg(target_os = "linux")]
+pub fn get_machine_guid() -> Result<String> {
+    let trae_id = trae_machine_id_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| content.trim().to_string())
+        .filter(|id| !id.is_empty());
+    if let Some(id) = trae_id {
+        return Ok(id);
+    }
+    LINUX_MACHINE_ID_PATHS
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|content| content.trim().to_string())
+        .find(|id| !id.is_empty())
+        .ok_or_else(|| anyhow!("无法读取机器码"))
+}
+
+/// 写入 Trae 数据目录中的 machineid（不需要 root 权限）
+#[cfg(target_os = "linux")]
+pub fn set_machine_guid(new_guid: &str) -> Result<()> {
+    let new_guid = new_guid.trim();
+    if new_guid.is_empty() {
+        return Err(coded(ErrorCode::InvalidInput, "机器码格式无效"));
+    }
+    let path = trae_machine_id_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, new_guid).map_err(|e| anyhow!("写入 Trae 机器码失败: {}", e))?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn reset_machine_guid() -> Result<String> {
+    let new_guid = generate_machine_guid();
+    set_machine_guid(&new_guid)?;
+    Ok(new_guid)
+}
+
+// 其他平台的占位实现
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn get_machine_guid() -> Result<String> {
-    Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"))
+    Err(anyhow!("此功能仅支持 Windows、macOS 和 Linux 系统"))
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn set_machine_guid(_new_guid: &str) -> Result<()> {
-    Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"))
+    Err(anyhow!("此功能仅支持 Windows、macOS 和 Linux 系统"))
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn reset_machine_guid() -> Result<String> {
-    Err(anyhow!("此功能仅支持 Windows 和 macOS 系统"))
+    Err(anyhow!("此功能仅支持 Windows、macOS 和 Linux 系统"))
 }
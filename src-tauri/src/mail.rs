@@ -0,0 +1,971 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::api::network::{client_builder, SendExt};
+
+pub const MAIL_CX_API_BASE: &str = "https://api.mail.cx/api/v1";
+const MAIL_TM_API_BASE: &str = "https://api.mail.tm";
+const ONE_SEC_MAIL_API_BASE: &str = "https://www.1secmail.com/api/v1/";
+const GUERRILLA_MAIL_API_BASE: &str = "https://api.guerrillamail.com/ajax.php";
+const MAIL_DOMAINS: [&str; 3] = ["uuf.me", "nqmo.com", "end.tw"];
+const ONE_SEC_MAIL_DOMAINS: [&str; 3] = ["1secmail.com", "1secmail.org", "1secmail.net"];
+const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+pub type MailFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// 临时邮箱服务
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MailProviderKind {
+    #[default]
+    MailCx,
+    MailTm,
+    OneSecMail,
+    GuerrillaMail,
+}
+
+impl MailProviderKind {
+    const ALL: [MailProviderKind; 4] = [
+        MailProviderKind::MailCx,
+        MailProviderKind::MailTm,
+        MailProviderKind::OneSecMail,
+        MailProviderKind::GuerrillaMail,
+    ];
+
+    /// 连通性检测使用的地址
+    pub fn health_url(self) -> String {
+        match self {
+            MailProviderKind::MailCx => format!("{}/auth/authorize_token", MAIL_CX_API_BASE),
+            MailProviderKind::MailTm => format!("{}/domains", MAIL_TM_API_BASE),
+            MailProviderKind::OneSecMail => format!("{}?action=getDomainList", ONE_SEC_MAIL_API_BASE),
+            MailProviderKind::GuerrillaMail => format!("{}?f=get_email_address", GUERRILLA_MAIL_API_BASE),
+        }
+    }
+
+    fn create(self, client: Client, domains: &[String]) -> Arc<dyn MailProvider> {
+        match self {
+            MailProviderKind::MailCx => Arc::new(MailCxProvider {
+                client,
+                api_token: StdMutex::new(None),
+                domains: domains.to_vec(),
+            }),
+            MailProviderKind::MailTm => Arc::new(MailTmProvider { client, tokens: Sessions::default() }),
+            MailProviderKind::OneSecMail => Arc::new(OneSecMailProvider { client }),
+            MailProviderKind::GuerrillaMail => Arc::new(GuerrillaMailProvider { client, sids: Sessions::default() }),
+        }
+    }
+}
+
+/// 临时邮箱设置
+#[derive(Debug, Clone, Default)]
+pub struct MailOptions {
+    /// 优先使用的服务
+    pub provider: MailProviderKind,
+    /// 自定义收件域名，为空时使用服务自带的域名
+    pub domains: Vec<String>,
+    /// 验证码识别规则
+    pub code: CodeExtraction,
+    /// 使用指定代理（快速注册代理），为 None 时按全局网络设置
+    pub proxy: Option<String>,
+}
+
+/// 验证码的字符类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodePattern {
+    /// 纯数字
+    #[default]
+    Digits,
+    /// 字母和数字（至少包含一个数字）
+    Alphanumeric,
+}
+
+impl CodePattern {
+    fn matches(self, c: char) -> bool {
+        match self {
+            CodePattern::Digits => c.is_ascii_digit(),
+            CodePattern::Alphanumeric => c.is_ascii_alphanumeric(),
+        }
+    }
+}
+
+/// 验证码识别规则：在邮件正文中查找长度恰好为 length 的连续字符，
+/// 优先选择紧跟在关键词（如 "verification code"）之后的匹配，没有关键词时取第一个匹配
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodeExtraction {
+    pub length: usize,
+    pub pattern: CodePattern,
+    /// 关键词（不区分大小写）
+    pub keywords: Vec<String>,
+}
+
+impl Default for CodeExtraction {
+    fn default() -> Self {
+        Self {
+            length: 6,
+            pattern: CodePattern::Digits,
+            keywords: ["verification code", "验证码", "code", "verify"]
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// 关键词之后超过该字符数的匹配不视为关键词附近
+const KEYWORD_WINDOW: usize = 200;
+
+impl CodeExtraction {
+    /// 从邮件正文中识别验证码
+    pub fn extract(&self, content: &str) -> Option<String> {
+        let length = self.length.clamp(4, 12);
+        let chars: Vec<char> = content.chars().collect();
+        let lower: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+
+        // 长度恰好为 length 的连续匹配字符（更长的数字串如订单号不会被截取）
+        let mut candidates = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            if !self.pattern.matches(chars[start]) {
+                start += 1;
+                continue;
+            }
+            let end = (start..chars.len())
+                .find(|&i| !self.pattern.matches(chars[i]))
+                .unwrap_or(chars.len());
+            let run = &chars[start..end];
+            if run.len() == length && run.iter().any(|c| c.is_ascii_digit()) {
+                candidates.push((start, run.iter().collect::<String>()));
+            }
+            start = end;
+        }
+
+        let keyword_ends: Vec<usize> = self
+            .keywords
+            .iter()
+            .map(|k| k.trim().chars().map(|c| c.to_ascii_lowercase()).collect::<Vec<char>>())
+            .filter(|k| !k.is_empty() && k.len() <= lower.len())
+            .flat_map(|k| {
+                lower
+                    .windows(k.len())
+                    .enumerate()
+                    .filter(|(_, window)| *window == k.as_slice())
+                    .map(|(i, _)| i + k.len())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let near_keyword = candidates
+            .iter()
+            .filter_map(|(pos, code)| {
+                keyword_ends
+                    .iter()
+                    .filter(|&&end| end <= *pos && *pos - end <= KEYWORD_WINDOW)
+                    .map(|&end| *pos - end)
+                    .min()
+                    .map(|distance| (distance, code))
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, code)| code.clone());
+
+        near_keyword.or_else(|| candidates.into_iter().next().map(|(_, code)| code))
+    }
+}
+
+/// 规范化收件域名（去掉空白和开头的 @，转为小写），无效时返回 None
+pub fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim().trim_start_matches('@').trim_end_matches('.').to_lowercase();
+    let valid = domain.contains('.')
+        && !domain.starts_with('.')
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+    valid.then_some(domain)
+}
+
+/// 收件箱中的一封邮件
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MailSummary {
+    pub id: String,
+    pub from: String,
+    pub subject: String,
+}
+
+/// 临时邮箱服务接口
+pub trait MailProvider: Send + Sync {
+    /// 服务名称（用于日志）
+    fn name(&self) -> &'static str;
+
+    fn kind(&self) -> MailProviderKind;
+
+    /// 创建新的收件地址（需要会话的服务按地址保存会话）
+    fn create_address(&self) -> MailFuture<'_, String>;
+
+    /// 收件箱中的邮件，最新的在前
+    fn list_messages<'a>(&'a self, email: &'a str) -> MailFuture<'a, Vec<MailSummary>>;
+
+    /// 邮件正文
+    fn message_content<'a>(&'a self, email: &'a str, id: &'a str) -> MailFuture<'a, MailContent>;
+
+    /// 地址的会话凭据（用于重启后继续收信），无需会话的服务返回 None
+    fn session(&self, _email: &str) -> Option<String> {
+        None
+    }
+
+    /// 恢复 session 返回的会话凭据
+    fn restore_session(&self, _email: &str, _credential: String) {}
+}
+
+/// 邮件正文（纯文本和 HTML，服务未提供的部分为空）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MailContent {
+    pub text: String,
+    pub html: String,
+}
+
+impl MailContent {
+    /// 纯文本正文，没有时由 HTML 转换
+    pub fn plain_text(&self) -> String {
+        if self.text.trim().is_empty() {
+            html_to_text(&self.html)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// 按 HTML 标签分隔文本的块级元素
+const BLOCK_TAGS: [&str; 14] = [
+    "br", "p", "div", "tr", "td", "th", "li", "table", "h1", "h2", "h3", "h4", "blockquote", "hr",
+];
+
+/// HTML 转纯文本：去掉标签、脚本和样式，解码实体；行内标签（如 span）不插入空白，
+/// 被拆到多个标签中的验证码会重新连在一起
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..open]));
+        let after = &rest[open + 1..];
+        if let Some(comment) = after.strip_prefix("!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(close) = after.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = after[..close].trim();
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        rest = &after[close + 1..];
+
+        if !closing && matches!(name.as_str(), "script" | "style" | "head") {
+            // 跳过到对应的结束标签
+            let end_tag = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(&end_tag) {
+                Some(end) => rest[end..].find('>').map_or("", |gt| &rest[end + gt + 1..]),
+                None => "",
+            };
+        } else if BLOCK_TAGS.contains(&name.as_str()) {
+            text.push('\n');
+        }
+    }
+    text.push_str(&decode_entities(rest));
+
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 解码常见的命名实体和数字实体
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &after[..end];
+            let ch = match entity {
+                "nbsp" => Some(' '),
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            ch.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 临时邮箱客户端：使用设置中选择的服务，创建地址失败时依次切换到其他服务
+pub struct MailClient {
+    providers: Vec<Arc<dyn MailProvider>>,
+    /// 当前使用的服务
+    current: usize,
+    email: String,
+    processed_ids: HashSet<String>,
+    code: CodeExtraction,
+}
+
+impl MailClient {
+    pub fn new(options: &MailOptions) -> Result<Self> {
+        let mut builder = client_builder().user_agent(BROWSER_USER_AGENT);
+        if let Some(proxy) = &options.proxy {
+            builder = builder.proxy(crate::api::network::parse_proxy_url(proxy)?);
+        }
+        let client = builder.build()?;
+        let preferred = options.provider;
+        let providers = std::iter::once(preferred)
+            .chain(MailProviderKind::ALL.into_iter().filter(|kind| *kind != preferred))
+            .map(|kind| kind.create(client.clone(), &options.domains))
+            .collect();
+        Ok(Self {
+            providers,
+            current: 0,
+            email: String::new(),
+            processed_ids: HashSet::new(),
+            code: options.code.clone(),
+        })
+    }
+
+    /// 当前使用的服务名称
+    pub fn provider_name(&self) -> &'static str {
+        self.providers[self.current].name()
+    }
+
+    /// 当前地址的会话，尚未创建地址时为 None
+    pub fn session(&self) -> Option<MailSession> {
+        if self.email.is_empty() {
+            return None;
+        }
+        let provider = &self.providers[self.current];
+        Some(MailSession {
+            provider: provider.kind(),
+            email: self.email.clone(),
+            credential: provider.session(&self.email),
+        })
+    }
+
+    /// 恢复之前创建的地址继续收信；收件箱中已有的邮件不再识别验证码
+    pub async fn resume(options: &MailOptions, session: &MailSession) -> Result<Self> {
+        let options = MailOptions {
+            provider: session.provider,
+            ..options.clone()
+        };
+        let mut client = Self::new(&options)?;
+        let provider = client.providers[client.current].clone();
+        if let Some(credential) = &session.credential {
+            provider.restore_session(&session.email, credential.clone());
+        }
+        let existing = provider.list_messages(&session.email).await?;
+        remember_mailbox(&session.email, provider);
+        client.email = session.email.clone();
+        client.processed_ids = existing.into_iter().map(|m| m.id).collect();
+        Ok(client)
+    }
+
+    /// 创建新的收件地址；当前服务不可用时切换到下一个服务
+    pub async fn new_address(&mut self) -> Result<String> {
+        let mut errors = Vec::new();
+        for offset in 0..self.providers.len() {
+            let index = (self.current + offset) % self.providers.len();
+            let provider = &self.providers[index];
+            match provider.create_address().await {
+                Ok(email) => {
+                    if index != self.current {
+                        println!("[WARN] 临时邮箱已切换到 {}", provider.name());
+                    }
+                    remember_mailbox(&email, provider.clone());
+                    self.current = index;
+                    self.email = email.clone();
+                    self.processed_ids.clear();
+                    return Ok(email);
+                }
+                Err(e) => {
+                    println!("[WARN] 临时邮箱 {} 不可用: {}", provider.name(), e);
+                    errors.push(format!("{}: {}", provider.name(), e));
+                }
+            }
+        }
+        Err(anyhow!("所有临时邮箱服务均不可用（{}）", errors.join("；")))
+    }
+
+    pub async fn check_for_code(&mut self) -> Result<Option<String>> {
+        if self.email.is_empty() {
+            return Ok(None);
+        }
+
+        let provider = &self.providers[self.current];
+        let messages = provider.list_messages(&self.email).await?;
+        let Some(latest) = messages.first() else {
+            return Ok(None);
+        };
+        if latest.id.is_empty() || self.processed_ids.contains(&latest.id) {
+            return Ok(None);
+        }
+
+        let content = provider.message_content(&self.email, &latest.id).await?;
+        self.processed_ids.insert(latest.id.clone());
+        // 纯文本中没有识别到时再从 HTML 正文中查找
+        Ok(self
+            .code
+            .extract(&content.plain_text())
+            .or_else(|| self.code.extract(&html_to_text(&content.html))))
+    }
+}
+
+/// 临时邮箱会话（保存到注册会话中，重启后继续收信）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailSession {
+    pub provider: MailProviderKind,
+    pub email: String,
+    /// 服务的会话凭据（Token / sid），无需会话的服务为 None
+    pub credential: Option<String>,
+}
+
+/// 按地址保存的会话凭据（Token / sid）
+#[derive(Default)]
+struct Sessions(StdMutex<HashMap<String, String>>);
+
+impl Sessions {
+    fn insert(&self, email: &str, credential: String) {
+        if let Ok(mut map) = self.0.lock() {
+            map.insert(email.to_lowercase(), credential);
+        }
+    }
+
+    fn get(&self, email: &str) -> Result<String> {
+        self.0
+            .lock()
+            .ok()
+            .and_then(|map| map.get(&email.to_lowercase()).cloned())
+            .ok_or_else(|| anyhow!("邮箱 {} 的会话已失效", email))
+    }
+}
+
+/// 保留最近创建的邮箱数量（供查看收件箱）
+const MAX_RECENT_MAILBOXES: usize = 100;
+
+/// 邮箱地址及其所属服务
+type RecentMailbox = (String, Arc<dyn MailProvider>);
+
+/// 最近创建的邮箱及其所属服务
+static RECENT_MAILBOXES: Lazy<StdMutex<VecDeque<RecentMailbox>>> = Lazy::new(|| StdMutex::new(VecDeque::new()));
+
+fn remember_mailbox(email: &str, provider: Arc<dyn MailProvider>) {
+    if let Ok(mut recent) = RECENT_MAILBOXES.lock() {
+        recent.retain(|(e, _)| !e.eq_ignore_ascii_case(email));
+        recent.push_front((email.to_string(), provider));
+        recent.truncate(MAX_RECENT_MAILBOXES);
+    }
+}
+
+/// 邮箱所属的服务：优先使用本次运行中创建该邮箱的服务，其次按域名判断无需会话的服务
+fn mailbox_provider(email: &str, options: &MailOptions) -> Result<Arc<dyn MailProvider>> {
+    let email = email.trim();
+    if let Some(provider) = RECENT_MAILBOXES.lock().ok().and_then(|recent| {
+        recent
+            .iter()
+            .find(|(e, _)| e.eq_ignore_ascii_case(email))
+            .map(|(_, p)| p.clone())
+    }) {
+        return Ok(provider);
+    }
+
+    let (_, domain) = split_address(email)?;
+    let domain = domain.to_lowercase();
+    let client = client_builder().user_agent(BROWSER_USER_AGENT).build()?;
+    if MAIL_DOMAINS.contains(&domain.as_str()) || options.domains.contains(&domain) {
+        Ok(MailProviderKind::MailCx.create(client, &options.domains))
+    } else if ONE_SEC_MAIL_DOMAINS.contains(&domain.as_str()) {
+        Ok(MailProviderKind::OneSecMail.create(client, &options.domains))
+    } else {
+        Err(crate::error::coded(
+            crate::error::ErrorCode::NotFound,
+            format!("未找到邮箱 {} 的会话，只能查看本次运行中创建的邮箱", email),
+        ))
+    }
+}
+
+/// 邮件详情（get_mailbox_message 返回值）
+#[derive(Debug, Clone, Serialize)]
+pub struct MailMessageDetail {
+    pub id: String,
+    pub content: MailContent,
+    /// 纯文本正文（HTML 已转换）
+    pub plain_text: String,
+    /// 按当前规则识别出的验证码
+    pub code: Option<String>,
+}
+
+/// 列出邮箱中的邮件（最新的在前）
+pub async fn list_mailbox_messages(email: &str, options: &MailOptions) -> Result<Vec<MailSummary>> {
+    let provider = mailbox_provider(email, options)?;
+    provider.list_messages(email.trim()).await
+}
+
+/// 读取一封邮件，并按当前规则识别验证码
+pub async fn get_mailbox_message(email: &str, id: &str, options: &MailOptions) -> Result<MailMessageDetail> {
+    let provider = mailbox_provider(email, options)?;
+    let content = provider.message_content(email.trim(), id).await?;
+    let plain_text = content.plain_text();
+    let code = options
+        .code
+        .extract(&plain_text)
+        .or_else(|| options.code.extract(&html_to_text(&content.html)));
+    Ok(MailMessageDetail {
+        id: id.to_string(),
+        content,
+        plain_text,
+        code,
+    })
+}
+
+fn value_to_id(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn value_str(value: &Value, key: &str) -> String {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+}
+
+/// 邮件列表：数组本身，或 messages / hydra:member / list 字段
+fn message_list(data: &Value) -> Vec<Value> {
+    data.as_array()
+        .or_else(|| data.get("messages").and_then(|v| v.as_array()))
+        .or_else(|| data.get("hydra:member").and_then(|v| v.as_array()))
+        .or_else(|| data.get("list").and_then(|v| v.as_array()))
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn random_username() -> String {
+    Uuid::new_v4().simple().to_string()[..10].to_string()
+}
+
+/// 生成随机收件地址，domains 为空时使用 mail.cx 自带的域名
+pub fn generate_email_address(domains: &[String]) -> String {
+    let raw = Uuid::new_v4().simple().to_string();
+    let username = raw[..8].to_string();
+    let seed = raw.as_bytes()[0] as usize;
+    if domains.is_empty() {
+        format!("{}@{}", username, MAIL_DOMAINS[seed % MAIL_DOMAINS.len()])
+    } else {
+        format!("{}@{}", username, domains[seed % domains.len()])
+    }
+}
+
+/// api.mail.cx
+struct MailCxProvider {
+    client: Client,
+    api_token: StdMutex<Option<String>>,
+    /// 自定义收件域名（需将域名的邮件交由 mail.cx 接收）
+    domains: Vec<String>,
+}
+
+impl MailCxProvider {
+    async fn authorize(&self) -> Result<String> {
+        let url = format!("{MAIL_CX_API_BASE}/auth/authorize_token");
+        let resp = self.client.post(&url).json(&serde_json::json!({})).send_checked().await?;
+        let value: Value = resp.json().await?;
+        let token = match value {
+            Value::String(val) => Some(val),
+            Value::Object(map) => map
+                .get("token")
+                .or_else(|| map.get("access_token"))
+                .or_else(|| map.get("data"))
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string()),
+            _ => None,
+        };
+        token.ok_or_else(|| anyhow!("邮箱认证失败，未获取到 token"))
+    }
+
+    /// 读取 API Token，尚未认证时先申请
+    async fn token(&self) -> Result<String> {
+        if let Some(token) = self.api_token.lock().ok().and_then(|t| t.clone()) {
+            return Ok(token);
+        }
+        let token = self.authorize().await?;
+        if let Ok(mut slot) = self.api_token.lock() {
+            *slot = Some(token.clone());
+        }
+        Ok(token)
+    }
+}
+
+impl MailProvider for MailCxProvider {
+    fn name(&self) -> &'static str {
+        "mail.cx"
+    }
+
+    fn kind(&self) -> MailProviderKind {
+        MailProviderKind::MailCx
+    }
+
+    fn create_address(&self) -> MailFuture<'_, String> {
+        Box::pin(async move {
+            self.token().await?;
+            Ok(generate_email_address(&self.domains))
+        })
+    }
+
+    fn list_messages<'a>(&'a self, email: &'a str) -> MailFuture<'a, Vec<MailSummary>> {
+        Box::pin(async move {
+            let url = format!("{MAIL_CX_API_BASE}/mailbox/{}", email);
+            let resp = self.client.get(&url).bearer_auth(self.token().await?).send_checked().await?;
+            let data: Value = resp.json().await?;
+            Ok(message_list(&data)
+                .iter()
+                .map(|m| MailSummary {
+                    id: value_to_id(m.get("id")),
+                    from: value_str(m, "from"),
+                    subject: value_str(m, "subject"),
+                })
+                .collect())
+        })
+    }
+
+    fn message_content<'a>(&'a self, email: &'a str, id: &'a str) -> MailFuture<'a, MailContent> {
+        Box::pin(async move {
+            let url = format!("{MAIL_CX_API_BASE}/mailbox/{}/{}", email, id);
+            let resp = self.client.get(&url).bearer_auth(self.token().await?).send_checked().await?;
+            let data: Value = resp.json().await?;
+            let body = data.get("body").cloned().unwrap_or(Value::Null);
+            Ok(MailContent {
+                text: value_str(&body, "text"),
+                html: value_str(&body, "html"),
+            })
+        })
+    }
+}
+
+/// mail.tm（每个地址需要注册账号并申请 Token）
+struct MailTmProvider {
+    client: Client,
+    /// 地址 -> Token
+    tokens: Sessions,
+}
+
+impl MailProvider for MailTmProvider {
+    fn name(&self) -> &'static str {
+        "mail.tm"
+    }
+
+    fn kind(&self) -> MailProviderKind {
+        MailProviderKind::MailTm
+    }
+
+    fn create_address(&self) -> MailFuture<'_, String> {
+        Box::pin(async move {
+            let resp = self
+                .client
+                .get(format!("{MAIL_TM_API_BASE}/domains"))
+                .send_checked()
+                .await?;
+            let data: Value = resp.json().await?;
+            let domain = message_list(&data)
+                .iter()
+                .find(|d| d.get("isActive").and_then(|v| v.as_bool()).unwrap_or(true))
+                .map(|d| value_str(d, "domain"))
+                .filter(|d| !d.is_empty())
+                .ok_or_else(|| anyhow!("mail.tm 没有可用的域名"))?;
+
+            let email = format!("{}@{}", random_username(), domain);
+            let password = Uuid::new_v4().simple().to_string();
+            let credentials = serde_json::json!({ "address": email, "password": password });
+            let resp = self
+                .client
+                .post(format!("{MAIL_TM_API_BASE}/accounts"))
+                .json(&credentials)
+                .send_checked()
+                .await?;
+            if !resp.status().is_success() {
+                return Err(crate::error::status_error("mail.tm 创建邮箱失败", resp.status()));
+            }
+            let resp = self
+                .client
+                .post(format!("{MAIL_TM_API_BASE}/token"))
+                .json(&credentials)
+                .send_checked()
+                .await?;
+            let data: Value = resp.json().await?;
+            let token = value_str(&data, "token");
+            if token.is_empty() {
+                return Err(anyhow!("mail.tm 未返回 token"));
+            }
+            self.tokens.insert(&email, token);
+            Ok(email)
+        })
+    }
+
+    fn list_messages<'a>(&'a self, email: &'a str) -> MailFuture<'a, Vec<MailSummary>> {
+        Box::pin(async move {
+            let token = self.tokens.get(email)?;
+            let resp = self
+                .client
+                .get(format!("{MAIL_TM_API_BASE}/messages"))
+                .bearer_auth(token)
+                .send_checked()
+                .await?;
+            let data: Value = resp.json().await?;
+            Ok(message_list(&data)
+                .iter()
+                .map(|m| MailSummary {
+                    id: value_to_id(m.get("id")),
+                    from: m
+                        .get("from")
+                        .map(|f| value_str(f, "address"))
+                        .unwrap_or_default(),
+                    subject: value_str(m, "subject"),
+                })
+                .collect())
+        })
+    }
+
+    fn message_content<'a>(&'a self, email: &'a str, id: &'a str) -> MailFuture<'a, MailContent> {
+        Box::pin(async move {
+            let token = self.tokens.get(email)?;
+            let resp = self
+                .client
+                .get(format!("{MAIL_TM_API_BASE}/messages/{}", id))
+                .bearer_auth(token)
+                .send_checked()
+                .await?;
+            let data: Value = resp.json().await?;
+            let html = match data.get("html") {
+                Some(Value::Array(parts)) => parts.iter().filter_map(|p| p.as_str()).collect::<Vec<_>>().join("\n"),
+                Some(Value::String(html)) => html.clone(),
+                _ => String::new(),
+            };
+            Ok(MailContent {
+                text: value_str(&data, "text"),
+                html,
+            })
+        })
+    }
+
+    fn session(&self, email: &str) -> Option<String> {
+        self.tokens.get(email).ok()
+    }
+
+    fn restore_session(&self, email: &str, credential: String) {
+        self.tokens.insert(email, credential);
+    }
+}
+
+/// 1secmail（无需认证，任意用户名均可收信）
+struct OneSecMailProvider {
+    client: Client,
+}
+
+impl OneSecMailProvider {
+    async fn get(&self, query: &[(&str, &str)]) -> Result<Value> {
+        let resp = self
+            .client
+            .get(ONE_SEC_MAIL_API_BASE)
+            .query(query)
+            .send_checked()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(crate::error::status_error("1secmail 请求失败", resp.status()));
+        }
+        Ok(resp.json().await?)
+    }
+}
+
+fn split_address(email: &str) -> Result<(&str, &str)> {
+    email.split_once('@').ok_or_else(|| anyhow!("邮箱地址无效: {}", email))
+}
+
+impl MailProvider for OneSecMailProvider {
+    fn name(&self) -> &'static str {
+        "1secmail"
+    }
+
+    fn kind(&self) -> MailProviderKind {
+        MailProviderKind::OneSecMail
+    }
+
+    fn create_address(&self) -> MailFuture<'_, String> {
+        Box::pin(async move {
+            let data = self.get(&[("action", "getDomainList")]).await?;
+            let domains: Vec<String> = data
+                .as_array()
+                .map(|list| list.iter().filter_map(|d| d.as_str().map(str::to_string)).collect())
+                .unwrap_or_default();
+            let raw = Uuid::new_v4().simple().to_string();
+            let domain = if domains.is_empty() {
+                ONE_SEC_MAIL_DOMAINS[(raw.as_bytes()[0] as usize) % ONE_SEC_MAIL_DOMAINS.len()].to_string()
+            } else {
+                domains[(raw.as_bytes()[0] as usize) % domains.len()].clone()
+            };
+            Ok(format!("{}@{}", &raw[..10], domain))
+        })
+    }
+
+    fn list_messages<'a>(&'a self, email: &'a str) -> MailFuture<'a, Vec<MailSummary>> {
+        Box::pin(async move {
+            let (login, domain) = split_address(email)?;
+            let data = self
+                .get(&[("action", "getMessages"), ("login", login), ("domain", domain)])
+                .await?;
+            Ok(message_list(&data)
+                .iter()
+                .map(|m| MailSummary {
+                    id: value_to_id(m.get("id")),
+                    from: value_str(m, "from"),
+                    subject: value_str(m, "subject"),
+                })
+                .collect())
+        })
+    }
+
+    fn message_content<'a>(&'a self, email: &'a str, id: &'a str) -> MailFuture<'a, MailContent> {
+        Box::pin(async move {
+            let (login, domain) = split_address(email)?;
+            let data = self
+                .get(&[("action", "readMessage"), ("login", login), ("domain", domain), ("id", id)])
+                .await?;
+            let html = Some(value_str(&data, "htmlBody"))
+                .filter(|html| !html.is_empty())
+                .unwrap_or_else(|| value_str(&data, "body"));
+            Ok(MailContent {
+                text: value_str(&data, "textBody"),
+                html,
+            })
+        })
+    }
+}
+
+/// Guerrilla Mail（地址由服务端分配，按会话收信）
+struct GuerrillaMailProvider {
+    client: Client,
+    /// 地址 -> sid_token
+    sids: Sessions,
+}
+
+impl GuerrillaMailProvider {
+    async fn call(&self, query: &[(&str, &str)], sid: Option<&str>) -> Result<Value> {
+        let mut request = self.client.get(GUERRILLA_MAIL_API_BASE).query(query);
+        if let Some(sid) = sid {
+            request = request.query(&[("sid_token", sid)]);
+        }
+        let resp = request.send_checked().await?;
+        if !resp.status().is_success() {
+            return Err(crate::error::status_error("Guerrilla Mail 请求失败", resp.status()));
+        }
+        Ok(resp.json().await?)
+    }
+}
+
+impl MailProvider for GuerrillaMailProvider {
+    fn name(&self) -> &'static str {
+        "Guerrilla Mail"
+    }
+
+    fn kind(&self) -> MailProviderKind {
+        MailProviderKind::GuerrillaMail
+    }
+
+    fn create_address(&self) -> MailFuture<'_, String> {
+        Box::pin(async move {
+            let data = self.call(&[("f", "get_email_address")], None).await?;
+            let sid = value_str(&data, "sid_token");
+            let email = value_str(&data, "email_addr");
+            if sid.is_empty() || email.is_empty() {
+                return Err(anyhow!("Guerrilla Mail 未返回邮箱地址"));
+            }
+            self.sids.insert(&email, sid);
+            Ok(email)
+        })
+    }
+
+    fn list_messages<'a>(&'a self, email: &'a str) -> MailFuture<'a, Vec<MailSummary>> {
+        Box::pin(async move {
+            let sid = self.sids.get(email)?;
+            let data = self.call(&[("f", "check_email"), ("seq", "0")], Some(&sid)).await?;
+            Ok(message_list(&data)
+                .iter()
+                .map(|m| MailSummary {
+                    id: value_to_id(m.get("mail_id")),
+                    from: value_str(m, "mail_from"),
+                    subject: value_str(m, "mail_subject"),
+                })
+                .collect())
+        })
+    }
+
+    fn message_content<'a>(&'a self, email: &'a str, id: &'a str) -> MailFuture<'a, MailContent> {
+        Box::pin(async move {
+            let sid = self.sids.get(email)?;
+            let data = self.call(&[("f", "fetch_email"), ("email_id", id)], Some(&sid)).await?;
+            // mail_body 为 HTML
+            Ok(MailContent {
+                text: String::new(),
+                html: value_str(&data, "mail_body"),
+            })
+        })
+    }
+
+    fn session(&self, email: &str) -> Option<String> {
+        self.sids.get(email).ok()
+    }
+
+    fn restore_session(&self, email: &str, credential: String) {
+        self.sids.insert(email, credential);
+    }
+}
+
+/// 持续轮询收件箱直到收到验证码（超时由调用方控制，人机验证期间会暂停计时）
+pub async fn wait_for_verification_code(client: &mut MailClient) -> Result<String> {
+    loop {
+        if let Some(code) = client.check_for_code().await? {
+            return Ok(code);
+        }
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
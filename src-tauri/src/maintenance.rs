@@ -0,0 +1,229 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::AppState;
+
+/// Token 剩余有效期低于该值时在维护中刷新
+const TOKEN_REFRESH_WINDOW_SECS: i64 = 24 * 3600;
+
+static MAINTENANCE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 维护步骤
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceStep {
+    RefreshTokens,
+    RefreshProfiles,
+    FetchUsage,
+    ClaimGifts,
+    /// 清理过期数据（旧安装包、已删除账号的 WebView 数据、过期备份和轮转日志）
+    Cleanup,
+    /// 轮转调试日志和窗口控制台日志
+    RotateLogs,
+}
+
+/// 维护进度事件（maintenance_progress）
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceProgress {
+    pub step: MaintenanceStep,
+    pub current: usize,
+    pub total: usize,
+    pub account_id: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceFailure {
+    pub step: MaintenanceStep,
+    pub account_id: String,
+    pub email: String,
+    pub error: String,
+}
+
+/// 维护结果汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub tokens_refreshed: usize,
+    pub profiles_refreshed: usize,
+    pub usage_fetched: usize,
+    pub gifts_claimed: usize,
+    /// 清理的文件和目录数
+    pub cleaned_items: usize,
+    pub reclaimed_bytes: u64,
+    pub logs_rotated: usize,
+    pub failures: Vec<MaintenanceFailure>,
+}
+
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        MAINTENANCE_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+fn emit_progress(app: &AppHandle, progress: MaintenanceProgress) {
+    let _ = app.emit("maintenance_progress", progress);
+}
+
+/// 单个账号的维护操作，返回是否实际处理了该账号
+type StepFuture<'a> = std::pin::Pin<Box<dyn std::future::Future<Output = Result<bool>> + Send + 'a>>;
+
+/// 按步骤依次处理账号，每个账号单独加锁，避免长时间阻塞其他操作
+async fn run_step<F>(
+    app: &AppHandle,
+    step: MaintenanceStep,
+    accounts: Vec<(String, String)>,
+    report: &mut MaintenanceReport,
+    op: F,
+) -> usize
+where
+    F: for<'a> Fn(&'a mut crate::account::AccountManager, &'a str) -> StepFuture<'a>,
+{
+    let state = app.state::<AppState>();
+    let total = accounts.len();
+    let mut done = 0;
+    for (index, (account_id, email)) in accounts.into_iter().enumerate() {
+        emit_progress(app, MaintenanceProgress {
+            step,
+            current: index + 1,
+            total,
+            account_id: Some(account_id.clone()),
+            email: Some(email.clone()),
+        });
+        let mut manager = state.account_manager.lock().await;
+        match op(&mut manager, &account_id).await {
+            Ok(true) => done += 1,
+            Ok(false) => {}
+            Err(e) => report.failures.push(MaintenanceFailure {
+                step,
+                account_id,
+                email,
+                error: e.to_string(),
+            }),
+        }
+    }
+    done
+}
+
+/// 轮转调试日志和窗口控制台日志，返回轮转的日志数
+fn rotate_logs() -> usize {
+    let results = [
+        ("api-debug.log", crate::api::debug_log::rotate()),
+        ("webview-console.log", crate::webview_log::rotate()),
+    ];
+    let mut rotated = 0;
+    for (name, result) in results {
+        match result {
+            Ok(true) => rotated += 1,
+            Ok(false) => {}
+            Err(e) => println!("[WARN] 轮转日志 {} 失败: {}", name, e),
+        }
+    }
+    rotated
+}
+
+fn emit_step(app: &AppHandle, step: MaintenanceStep) {
+    emit_progress(app, MaintenanceProgress {
+        step,
+        current: 1,
+        total: 1,
+        account_id: None,
+        email: None,
+    });
+}
+
+/// 执行一次完整维护：刷新即将过期的 Token、刷新资料、查询使用量、领取礼包、清理过期数据、轮转日志
+pub async fn run_maintenance(app: &AppHandle) -> Result<MaintenanceReport> {
+    if MAINTENANCE_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(anyhow!("维护任务正在运行中"));
+    }
+    let _guard = RunningGuard;
+
+    let state = app.state::<AppState>();
+    let profile_interval_hours = state.settings.lock().await.profile_refresh_interval_hours;
+
+    let mut report = MaintenanceReport {
+        started_at: chrono::Utc::now().timestamp(),
+        ..Default::default()
+    };
+
+    let (accounts, expiring, stale) = {
+        let manager = state.account_manager.lock().await;
+        let accounts: Vec<(String, String)> = manager
+            .get_accounts()
+            .into_iter()
+            .filter(|a| !a.unverified)
+            .map(|a| (a.id, a.email))
+            .collect();
+        let expiring = manager.accounts_with_expiring_token(TOKEN_REFRESH_WINDOW_SECS);
+        let stale = if profile_interval_hours == 0 {
+            Vec::new()
+        } else {
+            manager.accounts_with_stale_profile((profile_interval_hours * 3600) as i64)
+        };
+        (accounts, expiring, stale)
+    };
+    let pick = |ids: &[String]| -> Vec<(String, String)> {
+        accounts.iter().filter(|(id, _)| ids.contains(id)).cloned().collect()
+    };
+
+    let tokens_refreshed = run_step(app, MaintenanceStep::RefreshTokens, pick(&expiring), &mut report, |m, id| {
+        Box::pin(async move { m.refresh_token(id).await.map(|_| true) })
+    })
+    .await;
+
+    let profiles_refreshed = run_step(app, MaintenanceStep::RefreshProfiles, pick(&stale), &mut report, |m, id| {
+        Box::pin(async move { m.refresh_account_profile(id).await.map(|_| true) })
+    })
+    .await;
+
+    let usage_fetched = run_step(app, MaintenanceStep::FetchUsage, accounts.clone(), &mut report, |m, id| {
+        Box::pin(async move { m.get_account_usage(id).await.map(|_| true) })
+    })
+    .await;
+
+    let gifts_claimed = run_step(app, MaintenanceStep::ClaimGifts, accounts, &mut report, |m, id| {
+        Box::pin(async move {
+            match m.claim_birthday_bonus(id).await {
+                Ok(()) => Ok(true),
+                // 已领取过不算失败
+                Err(e) if e.to_string().contains("已领取") => Ok(false),
+                Err(e) => Err(e),
+            }
+        })
+    })
+    .await;
+
+    emit_step(app, MaintenanceStep::Cleanup);
+    let cleanup = crate::run_cleanup_with_state(&state).await;
+
+    emit_step(app, MaintenanceStep::RotateLogs);
+    report.logs_rotated = rotate_logs();
+
+    report.cleaned_items = cleanup.items.len();
+    report.reclaimed_bytes = cleanup.reclaimed_bytes;
+    report.tokens_refreshed = tokens_refreshed;
+    report.profiles_refreshed = profiles_refreshed;
+    report.usage_fetched = usage_fetched;
+    report.gifts_claimed = gifts_claimed;
+    report.finished_at = chrono::Utc::now().timestamp();
+    println!(
+        "[INFO] 维护完成: 刷新 Token {} 个, 刷新资料 {} 个, 查询使用量 {} 个, 领取礼包 {} 个, 清理 {} 项 ({} 字节), 轮转日志 {} 个, 失败 {} 项",
+        report.tokens_refreshed,
+        report.profiles_refreshed,
+        report.usage_fetched,
+        report.gifts_claimed,
+        report.cleaned_items,
+        report.reclaimed_bytes,
+        report.logs_rotated,
+        report.failures.len()
+    );
+    let _ = app.emit("maintenance_finished", &report);
+    Ok(report)
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::AppState;
+
+/// 推送请求超时时间
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+/// Telegram 单条消息的最大长度（字符）
+const TELEGRAM_MAX_CHARS: usize = 4000;
+
+/// 通知事件类型，按类型路由到不同渠道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// 额度不足提醒
+    QuotaAlert,
+    /// Token 即将过期
+    TokenExpiring,
+    /// 快速注册结果
+    Registration,
+    /// 周报
+    WeeklyReport,
+}
+
+impl NotifyEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            NotifyEvent::QuotaAlert => "quota_alert",
+            NotifyEvent::TokenExpiring => "token_expiring",
+            NotifyEvent::Registration => "registration",
+            NotifyEvent::WeeklyReport => "weekly_report",
+        }
+    }
+}
+
+/// 通知渠道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkKind {
+    /// 应用内通知（发出 app_notification 事件，由前端展示）
+    Desktop,
+    Webhook,
+    Telegram,
+}
+
+/// 通知设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Webhook 地址，以 JSON（event、title、text 字段）POST
+    pub webhook_url: String,
+    pub telegram_bot_token: String,
+    pub telegram_chat_id: String,
+    /// 事件类型 -> 发送渠道，未配置的事件不发送
+    pub routes: HashMap<NotifyEvent, Vec<SinkKind>>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: String::new(),
+            telegram_bot_token: String::new(),
+            telegram_chat_id: String::new(),
+            routes: HashMap::from([
+                (NotifyEvent::QuotaAlert, vec![SinkKind::Desktop]),
+                (NotifyEvent::TokenExpiring, vec![SinkKind::Desktop]),
+                (NotifyEvent::Registration, vec![SinkKind::Desktop]),
+                (NotifyEvent::WeeklyReport, vec![SinkKind::Webhook]),
+            ]),
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// 构建指定渠道的发送器，渠道未配置时返回 None
+    fn notifier(&self, kind: SinkKind, app: &AppHandle) -> Option<Box<dyn Notifier>> {
+        match kind {
+            SinkKind::Desktop => Some(Box::new(DesktopNotifier { app: app.clone() })),
+            SinkKind::Webhook => {
+                let url = self.webhook_url.trim();
+                (!url.is_empty()).then(|| Box::new(WebhookNotifier { url: url.to_string() }) as Box<dyn Notifier>)
+            }
+            SinkKind::Telegram => {
+                let bot_token = self.telegram_bot_token.trim();
+                let chat_id = self.telegram_chat_id.trim();
+                (!bot_token.is_empty() && !chat_id.is_empty()).then(|| {
+                    Box::new(TelegramNotifier {
+                        bot_token: bot_token.to_string(),
+                        chat_id: chat_id.to_string(),
+                    }) as Box<dyn Notifier>
+                })
+            }
+        }
+    }
+}
+
+/// 一条通知
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub event: NotifyEvent,
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn new(event: NotifyEvent, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            event,
+            title: title.into(),
+            body: body.into(),
+        }
+    }
+}
+
+type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// 通知渠道，新增渠道时实现该 trait 并在 NotifyConfig::notifier 中构建
+pub trait Notifier: Send + Sync {
+    fn kind(&self) -> SinkKind;
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a>;
+}
+
+struct DesktopNotifier {
+    app: AppHandle,
+}
+
+impl Notifier for DesktopNotifier {
+    fn kind(&self) -> SinkKind {
+        SinkKind::Desktop
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            self.app.emit("app_notification", notification)?;
+            Ok(())
+        })
+    }
+}
+
+struct WebhookNotifier {
+    url: String,
+}
+
+impl Notifier for WebhookNotifier {
+    fn kind(&self) -> SinkKind {
+        SinkKind::Webhook
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            crate::api::network::client_builder()
+                .timeout(SEND_TIMEOUT)
+                .build()?
+                .post(&self.url)
+                .json(&serde_json::json!({
+                    "event": notification.event.as_str(),
+                    "title": notification.title,
+                    "text": notification.body,
+                }))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+struct TelegramNotifier {
+    bot_token: String,
+    chat_id: String,
+}
+
+impl Notifier for TelegramNotifier {
+    fn kind(&self) -> SinkKind {
+        SinkKind::Telegram
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            let text: String = format!("{}\n\n{}", notification.title, notification.body)
+                .chars()
+                .take(TELEGRAM_MAX_CHARS)
+                .collect();
+            let response = crate::api::network::client_builder()
+                .timeout(SEND_TIMEOUT)
+                .build()?
+                .post(format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token))
+                .json(&serde_json::json!({ "chat_id": self.chat_id, "text": text }))
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(anyhow!("Telegram 返回 {}: {}", status, body));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// 按路由规则发送通知，单个渠道失败只记录日志
+pub async fn dispatch(app: &AppHandle, notification: Notification) {
+    let config = app.state::<AppState>().settings.lock().await.notify.clone();
+    let kinds = config.routes.get(&notification.event).cloned().unwrap_or_default();
+    for kind in kinds {
+        let Some(notifier) = config.notifier(kind, app) else {
+            println!("[WARN] 通知渠道 {:?} 未配置，跳过 {} 通知", kind, notification.event.as_str());
+            continue;
+        };
+        if let Err(e) = notifier.send(&notification).await {
+            println!("[WARN] 通过 {:?} 发送通知失败: {}", notifier.kind(), e);
+        }
+    }
+}
+
+/// 在后台发送通知（用于同步上下文）
+pub fn spawn_dispatch(app: &AppHandle, notification: Notification) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        dispatch(&app, notification).await;
+    });
+}
+
+/// 向指定渠道发送测试通知（忽略路由规则）
+pub async fn send_test(app: &AppHandle, kind: SinkKind) -> Result<()> {
+    let config = app.state::<AppState>().settings.lock().await.notify.clone();
+    let notifier = config
+        .notifier(kind, app)
+        .ok_or_else(|| anyhow!("该通知渠道尚未配置"))?;
+    let notification = Notification::new(
+        NotifyEvent::QuotaAlert,
+        "测试通知",
+        "这是一条来自 Trae Account Manager 的测试通知",
+    );
+    notifier.send(&notification).await
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::{self, ErrorCode};
+use crate::notify::{self, Notification, NotifyEvent};
+use crate::AppState;
+
+/// 检查是否到达设定间隔的频率
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+/// 单次补充最多添加的注册任务数
+const MAX_TOPUP_PER_ROUND: usize = 10;
+
+/// 账号池自动补充设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolTopUpConfig {
+    pub enabled: bool,
+    /// 可用账号（已验证、Token 有效、额度未用完）少于该数量时开始补充
+    pub min_available: usize,
+    /// 补充到的目标数量
+    pub target_available: usize,
+    /// 检查间隔（分钟）
+    pub check_interval_minutes: u64,
+    /// 补充注册时显示浏览器窗口
+    pub show_window: bool,
+}
+
+impl Default for PoolTopUpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_available: 3,
+            target_available: 5,
+            check_interval_minutes: 30,
+            show_window: false,
+        }
+    }
+}
+
+impl PoolTopUpConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.target_available < self.min_available {
+            return Err(error::coded(ErrorCode::InvalidInput, "账号池目标数量不能小于最低数量"));
+        }
+        if self.check_interval_minutes == 0 {
+            return Err(error::coded(ErrorCode::InvalidInput, "账号池检查间隔必须大于 0"));
+        }
+        Ok(())
+    }
+}
+
+/// 自动补充账号池（pool_topup_enqueued 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolTopUpEvent {
+    /// 当前可用账号数
+    pub available: usize,
+    /// 队列中已有的未完成任务数
+    pub pending: usize,
+    /// 本次添加的注册任务数
+    pub enqueued: usize,
+    pub target: usize,
+}
+
+/// 启动账号池补充：按设定间隔统计可用账号，少于最低数量时向注册队列添加任务，直到（含排队中的任务）达到目标数量
+///
+/// 队列暂停时不添加任务，避免任务堆积
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_check: Option<i64> = None;
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            let state = app.state::<AppState>();
+            let config = state.settings.lock().await.pool_topup.clone();
+            if !config.enabled {
+                last_check = None;
+                continue;
+            }
+            let now = chrono::Utc::now().timestamp();
+            let interval = i64::try_from(config.check_interval_minutes.max(1)).unwrap_or(i64::MAX).saturating_mul(60);
+            if last_check.is_some_and(|at| now - at < interval) {
+                continue;
+            }
+            last_check = Some(now);
+            top_up(&app, &state, &config, now).await;
+        }
+    });
+}
+
+async fn top_up(app: &AppHandle, state: &AppState, config: &PoolTopUpConfig, now: i64) {
+    let available = state.account_manager.lock().await.available_account_count(now);
+    if available >= config.min_available {
+        return;
+    }
+
+    let (pending, enqueued) = {
+        let mut queue = state.register_queue.lock().await;
+        if queue.is_paused() {
+            println!("[INFO] 可用账号 {} 个，低于 {} 个，但注册队列已暂停", available, config.min_available);
+            return;
+        }
+        let pending = queue.pending_count();
+        let missing = config.target_available.saturating_sub(available + pending).min(MAX_TOPUP_PER_ROUND);
+        if missing == 0 {
+            return;
+        }
+        match queue.enqueue(missing, config.show_window) {
+            Ok(ids) => (pending, ids.len()),
+            Err(e) => {
+                println!("[WARN] 自动补充账号池失败: {}", e);
+                return;
+            }
+        }
+    };
+    println!(
+        "[INFO] 可用账号 {} 个，低于 {} 个，已添加 {} 个注册任务（目标 {} 个）",
+        available, config.min_available, enqueued, config.target_available
+    );
+    crate::register_queue::ensure_worker(app).await;
+
+    let event = PoolTopUpEvent {
+        available,
+        pending,
+        enqueued,
+        target: config.target_available,
+    };
+    let _ = app.emit("pool_topup_enqueued", &event);
+    notify::dispatch(
+        app,
+        Notification::new(
+            NotifyEvent::Registration,
+            "自动补充账号",
+            format!("可用账号仅剩 {} 个，已添加 {} 个注册任务", available, enqueued),
+        ),
+    )
+    .await;
+}
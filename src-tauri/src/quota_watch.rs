@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::account::Account;
+use crate::api::UsageSummary;
+use crate::AppState;
+
+/// 检查剩余额度的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// 剩余快速请求低于阈值（quota_low 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaLowEvent {
+    pub account_id: String,
+    pub email: String,
+    /// 剩余快速请求（含额外礼包）
+    pub left: f64,
+    pub threshold: f64,
+}
+
+/// 当前账号额度用完后自动切换（account_auto_switched 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoSwitchEvent {
+    pub from_account_id: String,
+    pub from_email: String,
+    pub account_id: String,
+    pub email: String,
+}
+
+fn remaining(summary: &UsageSummary) -> f64 {
+    summary.fast_request_left + summary.extra_fast_request_left
+}
+
+/// 读取账号使用量：缓存有效时直接使用，否则请求接口并更新账号信息
+async fn load_usage(state: &AppState, account_id: &str, ttl: u64) -> Option<(Account, UsageSummary)> {
+    let (account, cached) = {
+        let manager = state.account_manager.lock().await;
+        let account = manager.get_account(account_id).ok()?;
+        let cached = manager.cached_usage(account_id, ttl);
+        (account, cached)
+    };
+    if let Some(summary) = cached {
+        return Some((account, summary));
+    }
+
+    let result = crate::api::request_log::scope(account_id, crate::fetch_usage_for_account(&account)).await;
+    let mut manager = state.account_manager.lock().await;
+    match result {
+        Ok((summary, new_token)) => {
+            let _ = manager.update_account_info_after_usage_check(account_id, &summary, new_token);
+            Some((account, summary))
+        }
+        Err(e) => {
+            println!("[WARN] 检查账号 {} 剩余额度失败: {}", account.email, e);
+            manager.record_account_error(account_id, &e);
+            None
+        }
+    }
+}
+
+/// 启动额度监控：对设置了提醒阈值的账号定时检查剩余快速请求，低于阈值时发出 quota_low 事件并按路由发送通知；
+/// 开启自动切换时，当前账号额度用完后切换到下一个可用账号
+///
+/// 每次跌破阈值只提醒一次，恢复到阈值以上（如额度重置）后再次跌破会重新提醒
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut alerted: HashSet<String> = HashSet::new();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let state = app.state::<AppState>();
+            let (thresholds, ttl, auto_switch) = {
+                let settings = state.settings.lock().await;
+                (
+                    settings.quota_thresholds.clone(),
+                    settings.usage_cache_ttl_secs,
+                    settings.auto_switch_on_exhausted,
+                )
+            };
+            alerted.retain(|id| thresholds.contains_key(id));
+
+            for (account_id, threshold) in thresholds.into_iter().filter(|(_, t)| *t > 0.0) {
+                let Some((account, summary)) = load_usage(&state, &account_id, ttl).await else {
+                    continue;
+                };
+                let left = remaining(&summary);
+                if left >= threshold {
+                    alerted.remove(&account_id);
+                    continue;
+                }
+                if !alerted.insert(account_id.clone()) {
+                    continue;
+                }
+                println!("[INFO] 账号 {} 剩余快速请求 {} 低于阈值 {}", account.email, left, threshold);
+                let event = QuotaLowEvent {
+                    account_id,
+                    email: account.email.clone(),
+                    left,
+                    threshold,
+                };
+                let _ = app.emit("quota_low", &event);
+                crate::notify::dispatch(
+                    &app,
+                    crate::notify::Notification::new(
+                        crate::notify::NotifyEvent::QuotaAlert,
+                        "额度不足",
+                        format!("账号 {} 剩余快速请求 {}，低于提醒阈值 {}", account.display_name(), left, threshold),
+                    ),
+                )
+                .await;
+            }
+
+            if auto_switch {
+                switch_if_exhausted(&app, &state, ttl).await;
+            }
+        }
+    });
+}
+
+/// 当前账号额度用完时切换到下一个可用账号
+async fn switch_if_exhausted(app: &AppHandle, state: &AppState, ttl: u64) {
+    let Some(current_id) = state.account_manager.lock().await.current_account_id() else {
+        return;
+    };
+    let Some((current, summary)) = load_usage(state, &current_id, ttl).await else {
+        return;
+    };
+    if remaining(&summary) > 0.0 {
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let Some(next) = state.account_manager.lock().await.next_healthy_account(now) else {
+        println!("[WARN] 账号 {} 额度已用完，但没有可切换的账号", current.email);
+        return;
+    };
+    println!("[INFO] 账号 {} 额度已用完，自动切换到 {}", current.email, next.email);
+    if let Err(e) = crate::switch_account_with_state(state, next.id.clone(), false).await {
+        println!("[WARN] 自动切换账号失败: {}", e.message);
+        return;
+    }
+
+    let event = AutoSwitchEvent {
+        from_account_id: current.id.clone(),
+        from_email: current.email.clone(),
+        account_id: next.id.clone(),
+        email: next.email.clone(),
+    };
+    let _ = app.emit("account_auto_switched", &event);
+    crate::notify::dispatch(
+        app,
+        crate::notify::Notification::new(
+            crate::notify::NotifyEvent::QuotaAlert,
+            "已自动切换账号",
+            format!("账号 {} 额度已用完，已切换到 {}", current.display_name(), next.display_name()),
+        ),
+    )
+    .await;
+}
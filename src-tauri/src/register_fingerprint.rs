@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// 随机选择使用的 Chrome 版本
+const CHROME_VERSIONS: [&str; 6] = [
+    "120.0.0.0",
+    "121.0.0.0",
+    "122.0.0.0",
+    "123.0.0.0",
+    "124.0.0.0",
+    "125.0.0.0",
+];
+/// 随机选择使用的系统
+const PLATFORMS: [&str; 4] = [
+    "Windows NT 10.0; Win64; x64",
+    "Macintosh; Intel Mac OS X 10_15_7",
+    "X11; Linux x86_64",
+    "Windows NT 11.0; Win64; x64",
+];
+/// 随机选择使用的语言（首选语言，后续为备选）
+const LANGUAGES: [&[&str]; 5] = [
+    &["en-US", "en"],
+    &["en-GB", "en"],
+    &["zh-CN", "zh", "en"],
+    &["de-DE", "de", "en"],
+    &["ja-JP", "ja", "en"],
+];
+/// 随机选择使用的窗口大小
+const VIEWPORTS: [(u32, u32); 5] = [(1000, 720), (1100, 760), (1200, 800), (1280, 820), (960, 700)];
+
+/// 注册窗口的浏览器特征（User-Agent、语言、窗口大小），每次注册随机生成以降低批量注册账号之间的关联
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrowserFingerprint {
+    pub user_agent: String,
+    /// navigator.languages
+    pub languages: Vec<String>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BrowserFingerprint {
+    pub fn random() -> Self {
+        let bytes = *uuid::Uuid::new_v4().as_bytes();
+        let pick = |index: usize, len: usize| bytes[index] as usize % len;
+        let platform = PLATFORMS[pick(0, PLATFORMS.len())];
+        let version = CHROME_VERSIONS[pick(1, CHROME_VERSIONS.len())];
+        let (width, height) = VIEWPORTS[pick(3, VIEWPORTS.len())];
+        Self {
+            user_agent: format!(
+                "Mozilla/5.0 ({}) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{} Safari/537.36",
+                platform, version
+            ),
+            languages: LANGUAGES[pick(2, LANGUAGES.len())].iter().map(|l| l.to_string()).collect(),
+            width,
+            height,
+        }
+    }
+
+    /// 在页面脚本执行前覆盖 navigator.language / languages
+    pub fn init_script(&self) -> String {
+        let languages = serde_json::to_string(&self.languages).unwrap_or_else(|_| "[]".to_string());
+        format!(
+            r#"(function() {{
+  const languages = {languages};
+  if (!languages.length) return;
+  try {{
+    Object.defineProperty(Navigator.prototype, "languages", {{ get: () => languages.slice(), configurable: true }});
+    Object.defineProperty(Navigator.prototype, "language", {{ get: () => languages[0], configurable: true }});
+  }} catch (e) {{}}
+}})();"#
+        )
+    }
+}
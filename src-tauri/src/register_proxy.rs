@@ -0,0 +1,97 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tauri::Url;
+
+use crate::error::{self, ErrorCode};
+
+/// 轮询代理池的位置
+static NEXT_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+/// 代理池的选择方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyRotation {
+    /// 按顺序轮流使用
+    #[default]
+    RoundRobin,
+    /// 每次随机选择
+    Random,
+}
+
+/// 快速注册代理设置：每次注册从代理池中选择一个代理，注册窗口和临时邮箱请求都通过该代理
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegisterProxyConfig {
+    pub enabled: bool,
+    /// 代理地址（http:// 或 socks5://，省略协议时按 HTTP 代理处理）
+    pub urls: Vec<String>,
+    pub rotation: ProxyRotation,
+}
+
+impl RegisterProxyConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let urls: Vec<&str> = self.urls.iter().map(|u| u.trim()).filter(|u| !u.is_empty()).collect();
+        if urls.is_empty() {
+            return Err(error::coded(ErrorCode::InvalidInput, "请至少填写一个注册代理"));
+        }
+        for url in urls {
+            webview_proxy_url(url)?;
+            crate::api::network::parse_proxy_url(url)?;
+        }
+        Ok(())
+    }
+
+    /// 为一次注册选择代理，未开启时返回 None
+    pub fn pick(&self) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let urls: Vec<&str> = self.urls.iter().map(|u| u.trim()).filter(|u| !u.is_empty()).collect();
+        if urls.is_empty() {
+            return None;
+        }
+        let index = match self.rotation {
+            ProxyRotation::RoundRobin => NEXT_INDEX.fetch_add(1, Ordering::Relaxed),
+            ProxyRotation::Random => uuid::Uuid::new_v4().as_bytes()[0] as usize,
+        };
+        Some(urls[index % urls.len()].to_string())
+    }
+}
+
+/// 注册窗口使用的代理地址：WebView 只支持不带认证的 http / socks5 代理
+pub fn webview_proxy_url(proxy: &str) -> Result<Url> {
+    let proxy = proxy.trim();
+    let raw = if proxy.contains("://") { proxy.to_string() } else { format!("http://{}", proxy) };
+    let url = Url::parse(&raw)
+        .map_err(|e| error::coded(ErrorCode::InvalidInput, format!("代理地址无效 {}: {}", proxy, e)))?;
+    if !matches!(url.scheme(), "http" | "socks5") || url.host_str().is_none() {
+        return Err(error::coded(
+            ErrorCode::InvalidInput,
+            format!("注册代理只支持 http:// 和 socks5://: {}", proxy),
+        ));
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(error::coded(
+            ErrorCode::InvalidInput,
+            format!("注册窗口不支持需要认证的代理: {}", proxy),
+        ));
+    }
+    Ok(url)
+}
+
+/// 使用代理的注册窗口的数据目录：WebView2 同一数据目录只能使用一种代理，按代理分开保存
+pub fn webview_data_dir(proxy: &str) -> Result<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    proxy.trim().hash(&mut hasher);
+    Ok(crate::account::AccountManager::data_dir()?
+        .join("register-webview")
+        .join(format!("{:016x}", hasher.finish())))
+}
@@ -0,0 +1,266 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::oneshot;
+
+use crate::error::{self, ErrorCode};
+use crate::mail::MailClient;
+use crate::{AppState, RegisterNotifier};
+
+/// 队列注册使用的窗口
+const QUEUE_WINDOW_LABEL: &str = "trae-register-queue";
+/// 队列中最多保留的任务数（含已结束的任务）
+const MAX_QUEUE_JOBS: usize = 200;
+
+/// 注册任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterJobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl RegisterJobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// 注册任务
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisterJob {
+    pub id: u64,
+    pub status: RegisterJobStatus,
+    pub show_window: bool,
+    /// 注册成功后导入的账号
+    pub account_id: Option<String>,
+    pub email: Option<String>,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
+
+/// 注册队列状态（register_queue_updated 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisterQueueStatus {
+    /// 暂停后正在执行的任务会继续完成，之后不再开始新任务
+    pub paused: bool,
+    /// 是否有任务正在执行
+    pub running: bool,
+    pub jobs: Vec<RegisterJob>,
+}
+
+/// 注册队列（保存在 AppState 中）
+#[derive(Default)]
+pub struct RegisterQueue {
+    jobs: Vec<RegisterJob>,
+    paused: bool,
+    /// 后台任务是否正在处理队列
+    worker_active: bool,
+    next_id: u64,
+    /// 取消正在执行的任务
+    cancel: Option<(u64, oneshot::Sender<()>)>,
+}
+
+impl RegisterQueue {
+    pub fn status(&self) -> RegisterQueueStatus {
+        RegisterQueueStatus {
+            paused: self.paused,
+            running: self.jobs.iter().any(|j| j.status == RegisterJobStatus::Running),
+            jobs: self.jobs.clone(),
+        }
+    }
+
+    /// 添加 count 个注册任务，返回新任务的 ID
+    pub fn enqueue(&mut self, count: usize, show_window: bool) -> anyhow::Result<Vec<u64>> {
+        let now = chrono::Utc::now().timestamp();
+        let pending = self.pending_count();
+        if count == 0 {
+            return Err(error::coded(ErrorCode::InvalidInput, "注册数量必须大于 0"));
+        }
+        if pending + count > MAX_QUEUE_JOBS {
+            return Err(error::coded(
+                ErrorCode::InvalidInput,
+                format!("队列中最多保留 {} 个未完成的任务", MAX_QUEUE_JOBS),
+            ));
+        }
+        self.prune();
+        let ids: Vec<u64> = (0..count)
+            .map(|_| {
+                self.next_id += 1;
+                self.next_id
+            })
+            .collect();
+        self.jobs.extend(ids.iter().map(|&id| RegisterJob {
+            id,
+            status: RegisterJobStatus::Queued,
+            show_window,
+            account_id: None,
+            email: None,
+            error: None,
+            created_at: now,
+            started_at: None,
+            finished_at: None,
+        }));
+        Ok(ids)
+    }
+
+    /// 排队中和正在执行的任务数
+    pub fn pending_count(&self) -> usize {
+        self.jobs.iter().filter(|j| !j.status.is_finished()).count()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// 取消任务：排队中的任务直接取消，正在执行的任务会关闭注册窗口并中止
+    pub fn cancel(&mut self, job_id: u64) -> anyhow::Result<()> {
+        let job = self
+            .jobs
+            .iter_mut()
+            .find(|j| j.id == job_id)
+            .ok_or_else(|| error::coded(ErrorCode::NotFound, "注册任务不存在"))?;
+        match job.status {
+            RegisterJobStatus::Queued => {
+                job.status = RegisterJobStatus::Cancelled;
+                job.finished_at = Some(chrono::Utc::now().timestamp());
+            }
+            RegisterJobStatus::Running if matches!(&self.cancel, Some((id, _)) if *id == job_id) => {
+                if let Some((_, tx)) = self.cancel.take() {
+                    let _ = tx.send(());
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 移除已结束的任务
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(|j| !j.status.is_finished());
+    }
+
+    /// 任务过多时移除最早结束的任务
+    fn prune(&mut self) {
+        let mut excess = self.jobs.len().saturating_sub(MAX_QUEUE_JOBS / 2);
+        self.jobs.retain(|j| {
+            if excess > 0 && j.status.is_finished() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// 取出下一个待执行的任务并标记为执行中；暂停或队列为空时返回 None
+    fn start_next(&mut self, cancel: oneshot::Sender<()>) -> Option<(u64, bool)> {
+        if self.paused {
+            return None;
+        }
+        let job = self.jobs.iter_mut().find(|j| j.status == RegisterJobStatus::Queued)?;
+        job.status = RegisterJobStatus::Running;
+        job.started_at = Some(chrono::Utc::now().timestamp());
+        self.cancel = Some((job.id, cancel));
+        Some((job.id, job.show_window))
+    }
+
+    fn finish(&mut self, job_id: u64, result: Option<Result<crate::account::Account, String>>) {
+        self.cancel = None;
+        let Some(job) = self.jobs.iter_mut().find(|j| j.id == job_id) else {
+            return;
+        };
+        job.finished_at = Some(chrono::Utc::now().timestamp());
+        match result {
+            Some(Ok(account)) => {
+                job.status = RegisterJobStatus::Succeeded;
+                job.account_id = Some(account.id);
+                job.email = Some(account.email);
+            }
+            Some(Err(message)) => {
+                job.status = RegisterJobStatus::Failed;
+                job.error = Some(message);
+            }
+            None => job.status = RegisterJobStatus::Cancelled,
+        }
+    }
+}
+
+fn emit_status(app: &AppHandle, queue: &RegisterQueue) {
+    let _ = app.emit("register_queue_updated", &queue.status());
+}
+
+/// 队列有待执行的任务且未暂停时启动后台任务，依次执行注册（同一时间只执行一个）
+pub async fn ensure_worker(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    {
+        let mut queue = state.register_queue.lock().await;
+        emit_status(app, &queue);
+        if queue.worker_active || queue.paused {
+            return;
+        }
+        queue.worker_active = true;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        loop {
+            let (cancel_tx, cancel_rx) = oneshot::channel();
+            let next = {
+                let mut queue = state.register_queue.lock().await;
+                let next = queue.start_next(cancel_tx);
+                if next.is_none() {
+                    queue.worker_active = false;
+                }
+                emit_status(&app, &queue);
+                next
+            };
+            let Some((job_id, show_window)) = next else {
+                break;
+            };
+            println!("[quick-register] 开始执行注册任务 #{}", job_id);
+
+            let (level, mail_options) = {
+                let settings = state.settings.lock().await;
+                (settings.quick_register_notice_level, settings.mail_options())
+            };
+            let mut notifier = RegisterNotifier::new(&app, level, show_window);
+            // 每个任务使用新的邮箱客户端，避免上一个任务的会话和 Cookie 影响本次注册
+            let result = match MailClient::new(&mail_options) {
+                Ok(mut client) => {
+                    tokio::select! {
+                        result = crate::run_quick_register(
+                            &app,
+                            &state,
+                            show_window,
+                            QUEUE_WINDOW_LABEL,
+                            &mut notifier,
+                            &mut client,
+                        ) => Some(result.map_err(|e| e.message)),
+                        _ = cancel_rx => None,
+                    }
+                }
+                Err(e) => Some(Err(e.to_string())),
+            };
+            if result.is_none() {
+                if let Some(window) = app.get_webview_window(QUEUE_WINDOW_LABEL) {
+                    let _ = window.close();
+                }
+                println!("[quick-register] 注册任务 #{} 已取消", job_id);
+            }
+
+            let mut queue = state.register_queue.lock().await;
+            queue.finish(job_id, result);
+            emit_status(&app, &queue);
+        }
+    });
+}
@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex as StdMutex;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{self, ErrorCode};
+use crate::mail::MailSession;
+use crate::register_fingerprint::BrowserFingerprint;
+use crate::register_steps::RegisterStep;
+
+const SESSIONS_FILE: &str = "register-sessions.json";
+/// 超过该时间的未完成注册不再提示继续
+const MAX_SESSION_AGE_SECS: i64 = 7 * 24 * 3600;
+
+/// 读写会话文件时加锁，避免同时进行的注册互相覆盖
+static FILE_LOCK: StdMutex<()> = StdMutex::new(());
+/// 本次运行中正在进行的注册
+static ACTIVE: Lazy<StdMutex<HashSet<String>>> = Lazy::new(|| StdMutex::new(HashSet::new()));
+
+/// 进行中的注册（保存到磁盘，应用中途退出后可继续）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterSession {
+    pub id: String,
+    pub email: String,
+    pub password: String,
+    /// 中断时所在的步骤
+    pub step: RegisterStep,
+    /// 临时邮箱会话，使用自有邮箱注册时为 None
+    pub mail: Option<MailSession>,
+    /// 注册使用的代理，继续注册时沿用
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// 注册窗口的浏览器特征，继续注册时沿用
+    #[serde(default)]
+    pub fingerprint: Option<BrowserFingerprint>,
+    pub started_at: i64,
+    pub updated_at: i64,
+}
+
+impl RegisterSession {
+    pub fn new(email: &str, password: String, mail: Option<MailSession>, proxy: Option<String>) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            email: email.to_string(),
+            password,
+            step: RegisterStep::OpenForm,
+            mail,
+            proxy,
+            fingerprint: None,
+            started_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// 注册进行期间保留会话，注册成功、失败或取消时自动删除
+pub struct SessionGuard {
+    id: String,
+}
+
+impl SessionGuard {
+    pub fn new(session: &RegisterSession) -> Self {
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.insert(session.id.clone());
+        }
+        save(session);
+        Self { id: session.id.clone() }
+    }
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        remove(&self.id);
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.remove(&self.id);
+        }
+    }
+}
+
+fn is_active(id: &str) -> bool {
+    ACTIVE.lock().map(|active| active.contains(id)).unwrap_or(false)
+}
+
+fn sessions_path() -> Result<PathBuf> {
+    Ok(crate::account::AccountManager::data_dir()?.join(SESSIONS_FILE))
+}
+
+fn read_all() -> Vec<RegisterSession> {
+    sessions_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_all(sessions: &[RegisterSession]) -> Result<()> {
+    let path = sessions_path()?;
+    if sessions.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    fs::write(path, serde_json::to_string_pretty(sessions)?)?;
+    Ok(())
+}
+
+fn update(apply: impl FnOnce(&mut Vec<RegisterSession>)) {
+    let _lock = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut sessions = read_all();
+    apply(&mut sessions);
+    if let Err(e) = write_all(&sessions) {
+        println!("[WARN] 保存注册会话失败: {}", e);
+    }
+}
+
+/// 保存（或更新）注册会话
+pub fn save(session: &RegisterSession) {
+    let mut session = session.clone();
+    session.updated_at = chrono::Utc::now().timestamp();
+    update(|sessions| {
+        sessions.retain(|s| s.id != session.id);
+        sessions.push(session);
+    });
+}
+
+/// 删除注册会话
+pub fn remove(id: &str) {
+    update(|sessions| sessions.retain(|s| s.id != id));
+}
+
+/// 中断的注册（不含正在进行的注册，最近的在前），过期的会话会被清理
+pub fn list() -> Vec<RegisterSession> {
+    let cutoff = chrono::Utc::now().timestamp() - MAX_SESSION_AGE_SECS;
+    let _lock = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut sessions = read_all();
+    let total = sessions.len();
+    sessions.retain(|s| s.updated_at >= cutoff);
+    if sessions.len() != total {
+        if let Err(e) = write_all(&sessions) {
+            println!("[WARN] 清理过期注册会话失败: {}", e);
+        }
+    }
+    sessions.retain(|s| !is_active(&s.id));
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
+    sessions
+}
+
+/// 读取注册会话
+pub fn get(id: &str) -> Result<RegisterSession> {
+    list()
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| error::coded(ErrorCode::NotFound, "未找到该注册，可能已完成或已过期"))
+}
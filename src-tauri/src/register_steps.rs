@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, WebviewWindow};
+use tokio::sync::{mpsc, watch};
+use tokio::time::Instant;
+
+use crate::error::{self, ErrorCode};
+use crate::mail::MailClient;
+
+/// 每个步骤自动尝试的次数
+pub const STEP_ATTEMPTS: u32 = 3;
+/// 步骤自动重试用尽后等待用户选择重试或放弃的时间
+const RESUME_WINDOW: Duration = Duration::from_secs(120);
+/// 等待用户完成人机验证的最长时间
+const CAPTCHA_WAIT: Duration = Duration::from_secs(300);
+/// 等待用户提交验证码的时间
+pub const MANUAL_CODE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// 快速注册的步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterStep {
+    /// 打开注册页
+    OpenForm,
+    /// 填写邮箱并点击发送验证码
+    SendCode,
+    /// 等待验证码邮件
+    AwaitCode,
+    /// 填写验证码和密码并提交
+    Submit,
+    /// 等待登录 Token
+    AwaitToken,
+}
+
+impl RegisterStep {
+    pub fn label(self) -> &'static str {
+        match self {
+            RegisterStep::OpenForm => "打开注册页",
+            RegisterStep::SendCode => "发送验证码",
+            RegisterStep::AwaitCode => "接收验证码",
+            RegisterStep::Submit => "提交注册",
+            RegisterStep::AwaitToken => "等待登录",
+        }
+    }
+
+    pub fn next(self) -> Option<Self> {
+        match self {
+            RegisterStep::OpenForm => Some(RegisterStep::SendCode),
+            RegisterStep::SendCode => Some(RegisterStep::AwaitCode),
+            RegisterStep::AwaitCode => Some(RegisterStep::Submit),
+            RegisterStep::Submit => Some(RegisterStep::AwaitToken),
+            RegisterStep::AwaitToken => None,
+        }
+    }
+
+    /// 单次尝试的超时时间
+    pub fn timeout(self) -> Duration {
+        match self {
+            RegisterStep::OpenForm => Duration::from_secs(30),
+            RegisterStep::SendCode | RegisterStep::Submit => Duration::from_secs(25),
+            RegisterStep::AwaitCode | RegisterStep::AwaitToken => Duration::from_secs(60),
+        }
+    }
+}
+
+/// 快速注册进度阶段（quick_register_progress 事件中的 stage）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterStage {
+    /// 邮箱已就绪
+    MailReady,
+    /// 注册窗口已创建
+    Init,
+    PageOpened,
+    CodeSent,
+    /// 注册页出现人机验证
+    Captcha,
+    CodeReceived,
+    Submitted,
+    TokenCaptured,
+    LoginOk,
+    AccountAdded,
+    /// 步骤失败后重试
+    Retrying,
+    Done,
+    Failed,
+}
+
+impl RegisterStage {
+    pub fn id(self) -> &'static str {
+        match self {
+            RegisterStage::MailReady => "mail_ready",
+            RegisterStage::Init => "init",
+            RegisterStage::PageOpened => "page_opened",
+            RegisterStage::CodeSent => "code_sent",
+            RegisterStage::Captcha => "captcha",
+            RegisterStage::CodeReceived => "code_received",
+            RegisterStage::Submitted => "submitted",
+            RegisterStage::TokenCaptured => "token_captured",
+            RegisterStage::LoginOk => "login_ok",
+            RegisterStage::AccountAdded => "account_added",
+            RegisterStage::Retrying => "retrying",
+            RegisterStage::Done => "done",
+            RegisterStage::Failed => "failed",
+        }
+    }
+
+    /// 到达该阶段时的进度百分比；人机验证、重试和失败不改变进度
+    pub fn percent(self) -> Option<u8> {
+        match self {
+            RegisterStage::MailReady => Some(5),
+            RegisterStage::Init => Some(10),
+            RegisterStage::PageOpened => Some(20),
+            RegisterStage::CodeSent => Some(35),
+            RegisterStage::CodeReceived => Some(55),
+            RegisterStage::Submitted => Some(70),
+            RegisterStage::TokenCaptured => Some(80),
+            RegisterStage::LoginOk => Some(90),
+            RegisterStage::AccountAdded => Some(95),
+            RegisterStage::Done => Some(100),
+            RegisterStage::Captcha | RegisterStage::Retrying | RegisterStage::Failed => None,
+        }
+    }
+}
+
+/// 用户对正在进行的注册的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepControl {
+    /// 重新执行失败的步骤
+    Retry,
+    /// 放弃本次注册
+    Abort,
+    /// 提交验证码（使用自有邮箱注册时）
+    Code(String),
+}
+
+/// 验证码来源
+pub enum CodeSource<'a> {
+    /// 从临时邮箱收件箱读取
+    TempMail(&'a mut MailClient),
+    /// 由用户通过 submit_verification_code 提交
+    Manual,
+}
+
+/// 等待用户提交验证码（verification_code_required 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeRequiredEvent {
+    pub window_label: String,
+    pub email: String,
+    /// 等待提交的秒数
+    pub timeout_secs: u64,
+}
+
+/// 步骤自动重试用尽（quick_register_step_failed 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct StepFailedEvent {
+    /// 注册窗口标识，重试或放弃时传回
+    pub window_label: String,
+    pub step: RegisterStep,
+    pub step_label: String,
+    pub message: String,
+    /// 等待用户选择的秒数，超时后放弃本次注册
+    pub resume_secs: u64,
+}
+
+/// 注册页出现人机验证（captcha_required 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptchaEvent {
+    pub window_label: String,
+    pub step: RegisterStep,
+    pub step_label: String,
+    /// 等待用户完成验证的秒数
+    pub wait_secs: u64,
+}
+
+/// 窗口标识 -> 正在进行的注册的控制通道
+static CONTROLS: Lazy<StdMutex<HashMap<String, mpsc::UnboundedSender<StepControl>>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+
+/// 注册进行期间保留控制通道，结束时自动移除
+pub struct ControlGuard {
+    window_label: String,
+}
+
+impl Drop for ControlGuard {
+    fn drop(&mut self) {
+        if let Ok(mut controls) = CONTROLS.lock() {
+            controls.remove(&self.window_label);
+        }
+    }
+}
+
+/// 登记一次注册的控制通道
+pub fn register(window_label: &str) -> (ControlGuard, mpsc::UnboundedReceiver<StepControl>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    if let Ok(mut controls) = CONTROLS.lock() {
+        controls.insert(window_label.to_string(), tx);
+    }
+    (ControlGuard { window_label: window_label.to_string() }, rx)
+}
+
+/// 向正在进行的注册发送重试或放弃
+pub fn send(window_label: &str, control: StepControl) -> anyhow::Result<()> {
+    let sent = CONTROLS
+        .lock()
+        .ok()
+        .and_then(|controls| controls.get(window_label).map(|tx| tx.send(control).is_ok()))
+        .unwrap_or(false);
+    if sent {
+        Ok(())
+    } else {
+        Err(error::coded(ErrorCode::NotFound, "该注册已结束"))
+    }
+}
+
+/// 步骤自动重试用尽后推送 quick_register_step_failed 事件，等待用户选择；选择重试时返回 true
+pub async fn wait_for_resume(
+    app: &AppHandle,
+    controls: &mut mpsc::UnboundedReceiver<StepControl>,
+    window_label: &str,
+    step: RegisterStep,
+    message: &str,
+) -> bool {
+    while controls.try_recv().is_ok() {}
+    let event = StepFailedEvent {
+        window_label: window_label.to_string(),
+        step,
+        step_label: step.label().to_string(),
+        message: message.to_string(),
+        resume_secs: RESUME_WINDOW.as_secs(),
+    };
+    let _ = app.emit("quick_register_step_failed", &event);
+    matches!(
+        tokio::time::timeout(RESUME_WINDOW, controls.recv()).await,
+        Ok(Some(StepControl::Retry))
+    )
+}
+
+/// 在超时时间内执行一个步骤；注册页出现人机验证时暂停计时并调用 on_captcha，
+/// 等待用户完成验证后继续（步骤本身不会中断）
+pub async fn run_step<T, F>(
+    timeout: Duration,
+    future: F,
+    captcha: &mut watch::Receiver<bool>,
+    mut on_captcha: impl FnMut(),
+) -> Result<T, String>
+where
+    F: Future<Output = Result<T, String>>,
+{
+    let mut future = std::pin::pin!(future);
+    let mut deadline = Instant::now() + timeout;
+    let mut watching = true;
+    loop {
+        if watching && *captcha.borrow_and_update() {
+            on_captcha();
+            let paused_at = Instant::now();
+            let solved = async {
+                loop {
+                    if captcha.changed().await.is_err() {
+                        return false;
+                    }
+                    if !*captcha.borrow_and_update() {
+                        return true;
+                    }
+                }
+            };
+            tokio::select! {
+                result = &mut future => return result,
+                solved = tokio::time::timeout(CAPTCHA_WAIT, solved) => {
+                    if !matches!(solved, Ok(true)) {
+                        return Err(format!("人机验证未在 {} 分钟内完成", CAPTCHA_WAIT.as_secs() / 60));
+                    }
+                }
+            }
+            deadline += paused_at.elapsed();
+            continue;
+        }
+        if !watching {
+            return tokio::select! {
+                result = &mut future => result,
+                _ = tokio::time::sleep_until(deadline) => Err(format!("{} 秒内未完成", timeout.as_secs())),
+            };
+        }
+        tokio::select! {
+            result = &mut future => return result,
+            _ = tokio::time::sleep_until(deadline) => return Err(format!("{} 秒内未完成", timeout.as_secs())),
+            changed = captcha.changed() => watching = changed.is_ok(),
+        }
+    }
+}
+
+/// 把人机验证交给用户：显示注册窗口并推送 captcha_required 事件
+pub fn hand_off_captcha(app: &AppHandle, webview: &WebviewWindow, window_label: &str, step: RegisterStep) {
+    let _ = webview.show();
+    let _ = webview.set_focus();
+    let event = CaptchaEvent {
+        window_label: window_label.to_string(),
+        step,
+        step_label: step.label().to_string(),
+        wait_secs: CAPTCHA_WAIT.as_secs(),
+    };
+    let _ = app.emit("captcha_required", &event);
+}
+
+/// 推送 verification_code_required 事件后等待用户提交验证码
+pub async fn wait_for_manual_code(
+    app: &AppHandle,
+    controls: &mut mpsc::UnboundedReceiver<StepControl>,
+    window_label: &str,
+    email: &str,
+) -> Result<String, String> {
+    let event = CodeRequiredEvent {
+        window_label: window_label.to_string(),
+        email: email.to_string(),
+        timeout_secs: MANUAL_CODE_TIMEOUT.as_secs(),
+    };
+    let _ = app.emit("verification_code_required", &event);
+    loop {
+        match controls.recv().await {
+            Some(StepControl::Code(code)) => return Ok(code),
+            Some(_) => {}
+            None => return Err("注册已结束".to_string()),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+/// 周报周期
+const REPORT_PERIOD_SECS: i64 = 7 * 24 * 3600;
+/// 检查是否需要生成周报的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+const SNAPSHOT_FILE: &str = "weekly-snapshot.json";
+
+/// 上一次生成周报时的快照，用于计算本周变化
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ReportSnapshot {
+    generated_at: i64,
+    /// 账号 ID -> 已用快速请求（含额外礼包）
+    usage_used: HashMap<String, f64>,
+    /// 账号 ID -> 累计切换次数
+    switch_counts: HashMap<String, u32>,
+}
+
+/// 生成的周报
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyReport {
+    pub path: String,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub content: String,
+}
+
+struct AccountRow {
+    email: String,
+    used: Option<f64>,
+    delta: Option<f64>,
+    left: Option<f64>,
+    switches: u32,
+}
+
+fn reports_dir() -> Result<PathBuf> {
+    let dir = crate::account::AccountManager::data_dir()?.join("reports");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn load_snapshot() -> Option<ReportSnapshot> {
+    let content = fs::read_to_string(reports_dir().ok()?.join(SNAPSHOT_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn format_time(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_number(value: Option<f64>) -> String {
+    value.map(|v| format!("{}", (v * 100.0).round() / 100.0)).unwrap_or_else(|| "-".to_string())
+}
+
+/// 汇总本周使用量变化、新增账号、失败和账号切换，保存为 Markdown 到数据目录下的 reports 文件夹
+pub async fn generate_weekly_report(app: &AppHandle) -> Result<WeeklyReport> {
+    let state = app.state::<AppState>();
+    let now = chrono::Utc::now().timestamp();
+    let previous = load_snapshot();
+    let period_start = previous.as_ref().map(|s| s.generated_at).unwrap_or(now - REPORT_PERIOD_SECS);
+
+    let accounts = state.account_manager.lock().await.get_accounts();
+
+    // 每个账号单独加锁查询使用量，避免长时间阻塞其他操作
+    let mut snapshot = ReportSnapshot {
+        generated_at: now,
+        ..Default::default()
+    };
+    let mut rows = Vec::new();
+    for account in &accounts {
+        let usage = if account.unverified {
+            None
+        } else {
+            let mut manager = state.account_manager.lock().await;
+            match manager.get_account_usage(&account.id).await {
+                Ok(usage) => Some(usage),
+                Err(e) => {
+                    println!("[WARN] 周报查询账号 {} 使用量失败: {}", account.email, e);
+                    None
+                }
+            }
+        };
+        let used = usage.as_ref().map(|u| u.fast_request_used + u.extra_fast_request_used);
+        let left = usage.as_ref().map(|u| u.fast_request_left + u.extra_fast_request_left);
+        let delta = match (used, previous.as_ref().and_then(|p| p.usage_used.get(&account.id))) {
+            (Some(used), Some(before)) => Some((used - before).max(0.0)),
+            (Some(used), None) if account.created_at >= period_start => Some(used),
+            _ => None,
+        };
+        let switches_before = previous
+            .as_ref()
+            .and_then(|p| p.switch_counts.get(&account.id).copied())
+            .unwrap_or(0);
+
+        if let Some(used) = used {
+            snapshot.usage_used.insert(account.id.clone(), used);
+        }
+        snapshot.switch_counts.insert(account.id.clone(), account.switch_count);
+        rows.push(AccountRow {
+            email: account.email.clone(),
+            used,
+            delta,
+            left,
+            switches: account.switch_count.saturating_sub(switches_before),
+        });
+    }
+
+    let new_accounts: Vec<_> = accounts.iter().filter(|a| a.created_at >= period_start).collect();
+    let failures: Vec<_> = accounts
+        .iter()
+        .filter_map(|a| a.last_error.as_ref().map(|e| (a, e)))
+        .filter(|(_, e)| e.occurred_at >= period_start)
+        .collect();
+    let total_delta: f64 = rows.iter().filter_map(|r| r.delta).sum();
+    let total_left: f64 = rows.iter().filter_map(|r| r.left).sum();
+    let total_switches: u32 = rows.iter().map(|r| r.switches).sum();
+
+    let mut md = String::new();
+    md.push_str("# Trae 账号周报\n\n");
+    md.push_str(&format!("统计周期: {} ~ {}\n\n", format_time(period_start), format_time(now)));
+    if previous.is_none() {
+        md.push_str("> 首次生成周报，使用量变化仅统计本周新增的账号。\n\n");
+    }
+    md.push_str("## 概览\n\n");
+    md.push_str(&format!("- 账号总数: {}\n", accounts.len()));
+    md.push_str(&format!("- 本周新增账号: {}\n", new_accounts.len()));
+    md.push_str(&format!("- 本周消耗快速请求: {}\n", format_number(Some(total_delta))));
+    md.push_str(&format!("- 剩余快速请求合计: {}\n", format_number(Some(total_left))));
+    md.push_str(&format!("- 账号切换次数: {}\n", total_switches));
+    md.push_str(&format!("- 出错账号: {}\n\n", failures.len()));
+
+    md.push_str("## 使用量\n\n");
+    md.push_str("| 邮箱 | 本周消耗 | 累计已用 | 剩余 | 切换次数 |\n");
+    md.push_str("| --- | --- | --- | --- | --- |\n");
+    for row in &rows {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            row.email,
+            format_number(row.delta),
+            format_number(row.used),
+            format_number(row.left),
+            row.switches
+        ));
+    }
+
+    if !new_accounts.is_empty() {
+        md.push_str("\n## 新增账号\n\n");
+        for account in &new_accounts {
+            md.push_str(&format!("- {}（{}）\n", account.email, format_time(account.created_at)));
+        }
+    }
+
+    if !failures.is_empty() {
+        md.push_str("\n## 失败记录\n\n");
+        for (account, error) in &failures {
+            md.push_str(&format!(
+                "- {}: [{}] {}（{}）\n",
+                account.email,
+                error.code,
+                error.message.replace('\n', " "),
+                format_time(error.occurred_at)
+            ));
+        }
+    }
+
+    let dir = reports_dir()?;
+    let path = dir.join(format!("weekly-{}.md", chrono::Local::now().format("%Y-%m-%d")));
+    fs::write(&path, &md)?;
+    fs::write(dir.join(SNAPSHOT_FILE), serde_json::to_string_pretty(&snapshot)?)?;
+    println!("[INFO] 周报已生成: {}", path.display());
+
+    crate::notify::dispatch(
+        app,
+        crate::notify::Notification::new(crate::notify::NotifyEvent::WeeklyReport, "Trae 账号周报", md.clone()),
+    )
+    .await;
+
+    Ok(WeeklyReport {
+        path: path.to_string_lossy().to_string(),
+        period_start,
+        period_end: now,
+        content: md,
+    })
+}
+
+/// 启动周报定时任务：开启后距上次生成超过 7 天时自动生成
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let enabled = app.state::<AppState>().settings.lock().await.weekly_report_enabled;
+            if !enabled {
+                continue;
+            }
+            let due = !matches!(
+                load_snapshot(),
+                Some(s) if chrono::Utc::now().timestamp() - s.generated_at < REPORT_PERIOD_SECS
+            );
+            if due {
+                if let Err(e) = generate_weekly_report(&app).await {
+                    println!("[WARN] 生成周报失败: {}", e);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::{self, ErrorCode};
+use crate::notify::{self, Notification, NotifyEvent};
+use crate::AppState;
+
+/// 检查是否到达设定间隔的频率
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 额度用完的账号的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetirementAction {
+    /// 归档：保留账号，不再参与自动切换，额度恢复后自动取消归档
+    #[default]
+    Archive,
+    /// 直接删除账号
+    Delete,
+}
+
+/// 自动清理额度用完的免费账号
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetirementConfig {
+    pub enabled: bool,
+    pub action: RetirementAction,
+    /// 为清理掉的账号添加同样数量的注册任务
+    pub replace: bool,
+    /// 补充注册时显示浏览器窗口
+    pub show_window: bool,
+    /// 检查间隔（分钟）
+    pub check_interval_minutes: u64,
+}
+
+impl Default for RetirementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: RetirementAction::Archive,
+            replace: false,
+            show_window: false,
+            check_interval_minutes: 60,
+        }
+    }
+}
+
+impl RetirementConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.check_interval_minutes == 0 {
+            return Err(error::coded(ErrorCode::InvalidInput, "清理检查间隔必须大于 0"));
+        }
+        Ok(())
+    }
+}
+
+/// 被清理的账号
+#[derive(Debug, Clone, Serialize)]
+pub struct RetiredAccount {
+    pub id: String,
+    pub email: String,
+}
+
+/// 自动清理结果（accounts_retired 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct RetirementEvent {
+    pub action: RetirementAction,
+    pub accounts: Vec<RetiredAccount>,
+    /// 添加的补充注册任务数
+    pub enqueued: usize,
+}
+
+/// 启动自动清理：按设定间隔查找额度用完的免费账号（以缓存的使用量为准），归档或删除，并按设置添加补充注册任务
+///
+/// Trae IDE 当前使用的账号不会被清理
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_check: Option<i64> = None;
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            let state = app.state::<AppState>();
+            let config = state.settings.lock().await.retirement.clone();
+            if !config.enabled {
+                last_check = None;
+                continue;
+            }
+            let now = chrono::Utc::now().timestamp();
+            let interval = i64::try_from(config.check_interval_minutes.max(1)).unwrap_or(i64::MAX).saturating_mul(60);
+            if last_check.is_some_and(|at| now - at < interval) {
+                continue;
+            }
+            last_check = Some(now);
+            retire(&app, &state, &config, now).await;
+        }
+    });
+}
+
+async fn retire(app: &AppHandle, state: &AppState, config: &RetirementConfig, now: i64) {
+    let accounts = {
+        let mut manager = state.account_manager.lock().await;
+        let mut retired = Vec::new();
+        for account in manager.exhausted_free_accounts() {
+            let result = match config.action {
+                RetirementAction::Archive => manager.archive_account(&account.id, now),
+                RetirementAction::Delete => manager.remove_account(&account.id),
+            };
+            match result {
+                Ok(()) => retired.push(RetiredAccount { id: account.id, email: account.email }),
+                Err(e) => println!("[WARN] 清理额度用完的账号 {} 失败: {}", account.email, e),
+            }
+        }
+        retired
+    };
+    if accounts.is_empty() {
+        return;
+    }
+    let verb = match config.action {
+        RetirementAction::Archive => "归档",
+        RetirementAction::Delete => "删除",
+    };
+    println!("[INFO] 已{} {} 个额度用完的免费账号", verb, accounts.len());
+
+    let enqueued = if config.replace { enqueue_replacements(app, state, accounts.len(), config.show_window).await } else { 0 };
+
+    let mut body = format!("已{} {} 个额度用完的免费账号", verb, accounts.len());
+    if enqueued > 0 {
+        body.push_str(&format!("，并添加了 {} 个注册任务", enqueued));
+    }
+    let event = RetirementEvent {
+        action: config.action,
+        accounts,
+        enqueued,
+    };
+    let _ = app.emit("accounts_retired", &event);
+    notify::dispatch(app, Notification::new(NotifyEvent::QuotaAlert, "自动清理账号", body)).await;
+}
+
+/// 为清理掉的账号添加补充注册任务，队列暂停时不添加
+async fn enqueue_replacements(app: &AppHandle, state: &AppState, count: usize, show_window: bool) -> usize {
+    let enqueued = {
+        let mut queue = state.register_queue.lock().await;
+        if queue.is_paused() {
+            println!("[INFO] 注册队列已暂停，未添加补充注册任务");
+            return 0;
+        }
+        match queue.enqueue(count, show_window) {
+            Ok(ids) => ids.len(),
+            Err(e) => {
+                println!("[WARN] 添加补充注册任务失败: {}", e);
+                return 0;
+            }
+        }
+    };
+    crate::register_queue::ensure_worker(app).await;
+    enqueued
+}
@@ -0,0 +1,202 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Local, TimeZone, Timelike};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::AppState;
+
+/// 检查是否到达轮换时间的间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+/// Trae 状态数据库在该时间内有写入时视为正在使用
+const BUSY_WINDOW: Duration = Duration::from_secs(180);
+
+/// 轮换时间表
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RotationSchedule {
+    /// 从开始轮换起每隔 minutes 分钟切换一次
+    Interval { minutes: u64 },
+    /// 每 N 小时整点切换一次（按本地时间对齐，如 N = 6 时在 0/6/12/18 点切换）
+    EveryHours { hours: u64 },
+}
+
+impl Default for RotationSchedule {
+    fn default() -> Self {
+        RotationSchedule::EveryHours { hours: 4 }
+    }
+}
+
+impl RotationSchedule {
+    /// 下一次轮换时间（Unix 时间戳，秒）
+    fn next_after(self, now: i64) -> i64 {
+        match self {
+            RotationSchedule::Interval { minutes } => {
+                let secs = i64::try_from(minutes.max(1)).unwrap_or(i64::MAX).saturating_mul(60);
+                now.saturating_add(secs)
+            }
+            RotationSchedule::EveryHours { hours } => {
+                let hours = hours.clamp(1, 24) as u32;
+                let hour_start = now - now.rem_euclid(3600);
+                (1..=48)
+                    .map(|offset| hour_start + offset * 3600)
+                    .find(|ts| {
+                        Local.timestamp_opt(*ts, 0).single().is_some_and(|dt| dt.hour() % hours == 0)
+                    })
+                    .unwrap_or(hour_start + i64::from(hours) * 3600)
+            }
+        }
+    }
+}
+
+/// 轮换设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RotationConfig {
+    /// 是否正在轮换（由 start_rotation / stop_rotation 控制）
+    pub enabled: bool,
+    /// 参与轮换的账号，按顺序依次切换
+    pub account_ids: Vec<String>,
+    pub schedule: RotationSchedule,
+    /// Trae IDE 正在使用时跳过本次轮换
+    pub skip_if_busy: bool,
+}
+
+/// 轮换运行状态
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RotationStatus {
+    pub enabled: bool,
+    /// 下一次轮换时间
+    pub next_at: Option<i64>,
+    pub last_switched_at: Option<i64>,
+    pub last_account_id: Option<String>,
+    /// 最近一次跳过或失败的原因
+    pub last_skipped: Option<String>,
+}
+
+/// 轮换切换账号（rotation_switched 事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct RotationSwitchEvent {
+    pub account_id: String,
+    pub email: String,
+}
+
+struct RotationState {
+    status: RotationStatus,
+    /// 计算 next_at 时使用的时间表，设置变化后重新计算
+    schedule: Option<RotationSchedule>,
+}
+
+static STATE: Lazy<Mutex<RotationState>> = Lazy::new(|| {
+    Mutex::new(RotationState {
+        status: RotationStatus::default(),
+        schedule: None,
+    })
+});
+
+/// 当前轮换状态
+pub fn status() -> RotationStatus {
+    STATE.lock().map(|s| s.status.clone()).unwrap_or_default()
+}
+
+/// 重置时间表（开始或停止轮换时调用），开始时从现在起重新计时
+pub fn reset(config: &RotationConfig) {
+    if let Ok(mut state) = STATE.lock() {
+        let now = chrono::Utc::now().timestamp();
+        state.status.enabled = config.enabled;
+        state.status.last_skipped = None;
+        state.status.next_at = config.enabled.then(|| config.schedule.next_after(now));
+        state.schedule = config.enabled.then_some(config.schedule);
+    }
+}
+
+/// 轮换池中当前账号之后的下一个账号（跳过已删除和未验证的账号）
+fn next_account_id(manager: &crate::account::AccountManager, pool: &[String], current: Option<&str>) -> Option<String> {
+    let start = current
+        .and_then(|id| pool.iter().position(|p| p == id))
+        .map_or(0, |index| index + 1);
+    (0..pool.len())
+        .map(|offset| &pool[(start + offset) % pool.len()])
+        .filter(|id| Some(id.as_str()) != current)
+        .find(|id| manager.get_account(id).is_ok_and(|a| !a.unverified))
+        .cloned()
+}
+
+/// 启动轮换任务：开启后按时间表在轮换池中依次切换 Trae IDE 的账号
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            let state = app.state::<AppState>();
+            let config = state.settings.lock().await.rotation.clone();
+            let now = chrono::Utc::now().timestamp();
+
+            let due = {
+                let Ok(mut rotation) = STATE.lock() else {
+                    continue;
+                };
+                rotation.status.enabled = config.enabled;
+                if !config.enabled {
+                    rotation.status.next_at = None;
+                    rotation.schedule = None;
+                    continue;
+                }
+                if rotation.schedule != Some(config.schedule) || rotation.status.next_at.is_none() {
+                    rotation.schedule = Some(config.schedule);
+                    rotation.status.next_at = Some(config.schedule.next_after(now));
+                }
+                if rotation.status.next_at.is_some_and(|at| now >= at) {
+                    rotation.status.next_at = Some(config.schedule.next_after(now));
+                    true
+                } else {
+                    false
+                }
+            };
+            if !due {
+                continue;
+            }
+
+            let skipped = rotate(&app, &state, &config).await.err();
+            if let Some(reason) = &skipped {
+                println!("[INFO] 跳过本次账号轮换: {}", reason);
+            }
+            if let Ok(mut rotation) = STATE.lock() {
+                rotation.status.last_skipped = skipped;
+            }
+        }
+    });
+}
+
+/// 执行一次轮换，返回跳过或失败的原因
+async fn rotate(app: &AppHandle, state: &AppState, config: &RotationConfig) -> Result<(), String> {
+    if config.skip_if_busy && crate::machine::is_trae_busy(BUSY_WINDOW) {
+        return Err("Trae IDE 正在使用中".to_string());
+    }
+    let next_id = {
+        let manager = state.account_manager.lock().await;
+        let current = manager.current_account_id();
+        next_account_id(&manager, &config.account_ids, current.as_deref())
+    }
+    .ok_or_else(|| "轮换池中没有可切换的账号".to_string())?;
+
+    crate::switch_account_with_state(state, next_id.clone(), false)
+        .await
+        .map_err(|e| format!("切换账号失败: {}", e.message))?;
+
+    let email = state
+        .account_manager
+        .lock()
+        .await
+        .get_account(&next_id)
+        .map(|a| a.email)
+        .unwrap_or_default();
+    println!("[INFO] 账号轮换：已切换到 {}", email);
+    if let Ok(mut rotation) = STATE.lock() {
+        rotation.status.last_switched_at = Some(chrono::Utc::now().timestamp());
+        rotation.status.last_account_id = Some(next_id.clone());
+    }
+    let _ = app.emit("rotation_switched", &RotationSwitchEvent { account_id: next_id, email });
+    Ok(())
+}
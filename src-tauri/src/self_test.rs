@@ -0,0 +1,134 @@
+use std::fs;
+use std::future::Future;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Url, WebviewUrl, WebviewWindowBuilder};
+use tokio::sync::oneshot;
+
+use crate::machine::{EntitlementMode, TraeLoginInfo};
+use crate::mail::{MailClient, MailOptions};
+
+/// 注册页加载超时时间
+const PAGE_LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const WINDOW_LABEL: &str = "trae-self-test";
+
+/// 单项自检结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub passed: bool,
+    pub duration_ms: u64,
+    /// 成功时为说明，失败时为错误信息
+    pub detail: String,
+}
+
+async fn run_check<F>(name: &str, fut: F) -> SelfTestCheck
+where
+    F: Future<Output = Result<String>>,
+{
+    let started = Instant::now();
+    let result = fut.await;
+    let duration_ms = crate::millis(started.elapsed());
+    match result {
+        Ok(detail) => SelfTestCheck { name: name.to_string(), passed: true, duration_ms, detail },
+        Err(e) => {
+            println!("[WARN] 自检项 {} 失败: {}", name, e);
+            SelfTestCheck { name: name.to_string(), passed: false, duration_ms, detail: e.to_string() }
+        }
+    }
+}
+
+/// 依次检查注册页、临时邮箱和 IDE 写入，返回每项的结果（不会创建账号，也不会修改真实的 Trae 配置）
+pub async fn run(app: &AppHandle, register_url: Option<String>, mail_options: MailOptions) -> Vec<SelfTestCheck> {
+    let register_url = register_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| crate::QUICK_REGISTER_URL.to_string());
+
+    vec![
+        run_check("register_page", check_register_page(app, &register_url)).await,
+        run_check("mail_provider", check_mail_provider(&mail_options)).await,
+        run_check("ide_write", async { check_ide_write() }).await,
+    ]
+}
+
+/// 在隐藏的 WebView 中打开注册页，确认页面能加载完成
+async fn check_register_page(app: &AppHandle, url: &str) -> Result<String> {
+    let url = Url::parse(url).map_err(|e| anyhow!("注册页地址无效: {}", e))?;
+    if let Some(existing) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = existing.close();
+    }
+
+    let (loaded_tx, loaded_rx) = oneshot::channel::<String>();
+    let loaded_sender = Arc::new(StdMutex::new(Some(loaded_tx)));
+    let webview = WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::External("about:blank".parse().unwrap()))
+        .title("Trae 自检")
+        .visible(false)
+        .on_page_load(move |_, payload| {
+            if payload.event() == PageLoadEvent::Finished && payload.url().scheme() != "about" {
+                if let Some(tx) = loaded_sender.lock().unwrap().take() {
+                    let _ = tx.send(payload.url().to_string());
+                }
+            }
+        })
+        .build()
+        .map_err(|e| anyhow!("无法创建自检窗口: {}", e))?;
+
+    let _ = webview.clear_all_browsing_data();
+    let _ = webview.navigate(url);
+    let result = tokio::time::timeout(PAGE_LOAD_TIMEOUT, loaded_rx).await;
+    let _ = webview.close();
+    match result {
+        Ok(Ok(final_url)) => Ok(format!("页面已加载: {}", final_url)),
+        Ok(Err(_)) => Err(anyhow!("自检窗口已关闭")),
+        Err(_) => Err(anyhow!("注册页在 {} 秒内未加载完成", PAGE_LOAD_TIMEOUT.as_secs())),
+    }
+}
+
+/// 申请临时邮箱并读取一次收件箱
+async fn check_mail_provider(options: &MailOptions) -> Result<String> {
+    let mut mail_client = MailClient::new(options)?;
+    let email = mail_client.new_address().await?;
+    mail_client.check_for_code().await?;
+    Ok(format!("收件箱可用（{}）: {}", mail_client.provider_name(), email))
+}
+
+/// 向临时目录写入一份测试登录信息并读回校验
+fn check_ide_write() -> Result<String> {
+    let dir = std::env::temp_dir().join(format!("trae-self-test-{}", uuid::Uuid::new_v4().simple()));
+    let info = TraeLoginInfo {
+        token: "self-test-token".to_string(),
+        refresh_token: None,
+        user_id: "self-test".to_string(),
+        email: "self-test@example.com".to_string(),
+        username: "self-test".to_string(),
+        avatar_url: String::new(),
+        host: String::new(),
+        region: "sg".to_string(),
+        locale: None,
+        plan_type: "Free".to_string(),
+        entitlement_mode: EntitlementMode::Free,
+    };
+
+    let result = write_and_verify(&dir, &info);
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+fn write_and_verify(dir: &std::path::Path, info: &TraeLoginInfo) -> Result<String> {
+    crate::machine::write_trae_login_info_to(dir, info)?;
+    let storage_path = dir.join("User").join("globalStorage").join("storage.json");
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&storage_path)?)?;
+    let auth = json
+        .get("iCubeAuthInfo://icube.cloudide")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("写入后未找到登录信息"))?;
+    if !auth.contains(&info.token) {
+        return Err(anyhow!("写入的登录信息与预期不一致"));
+    }
+    Ok(format!("已写入并校验 {}", storage_path.display()))
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::integrity::IntegrityIssue;
+use crate::AppState;
+
+/// 检查 accounts.json 是否被外部修改的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// accounts.json 被外部修改并重新加载后的事件（accounts_changed）
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountsChangedEvent {
+    pub account_count: usize,
+    /// 重新加载后发现的完整性问题（例如手动编辑导致签名不匹配）
+    pub integrity_issue: Option<IntegrityIssue>,
+}
+
+/// 启动账号数据文件监听：文件被同步工具或手动编辑替换时重新加载，避免下次保存覆盖外部修改
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let state = app.state::<AppState>();
+            let mut manager = state.account_manager.lock().await;
+            match manager.reload_if_changed_externally() {
+                Ok(true) => {
+                    let event = AccountsChangedEvent {
+                        account_count: manager.get_accounts().len(),
+                        integrity_issue: manager.integrity_issue(),
+                    };
+                    let _ = app.emit("accounts_changed", &event);
+                    // 签名不匹配会让后续保存被拒绝，交给界面询问用户是否信任外部修改
+                    if let Some(issue) = event.integrity_issue.filter(|i| i.trustable) {
+                        let _ = app.emit("accounts_external_change", &issue);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => println!("[WARN] 重新加载 accounts.json 失败: {}", e),
+            }
+        }
+    });
+}
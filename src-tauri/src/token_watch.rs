@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::AppState;
+
+/// 检查 Token 过期时间的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(600);
+/// 启动检查的延迟（等待前端注册事件监听）
+const SWEEP_DELAY: Duration = Duration::from_secs(3);
+/// Cookies 超过该时间未更新时提醒
+const COOKIE_MAX_AGE_SECS: i64 = 30 * 24 * 3600;
+
+/// 启动时本地检查各账号的 Token 过期时间和 Cookies 更新时间，有需要处理的账号时发出 token_sweep 事件
+pub fn spawn_startup_sweep(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SWEEP_DELAY).await;
+        let state = app.state::<AppState>();
+        let warning_hours = state.settings.lock().await.token_expiry_warning_hours;
+        let within_secs = i64::try_from(warning_hours.saturating_mul(3600)).unwrap_or(i64::MAX);
+        let now = chrono::Utc::now().timestamp();
+        let summary = state.account_manager.lock().await.token_sweep(now, within_secs, COOKIE_MAX_AGE_SECS);
+        if summary.accounts.is_empty() {
+            return;
+        }
+        println!("[INFO] 启动检查: {} 个账号中有 {} 个需要处理", summary.checked, summary.accounts.len());
+        let _ = app.emit("token_sweep", &summary);
+    });
+}
+
+/// 启动 Token 过期检查：Token 进入提醒窗口时标记账号、发出 token_expiring 事件并按路由发送通知（每次进入窗口只提醒一次）
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app.state::<AppState>();
+            let warning_hours = state.settings.lock().await.token_expiry_warning_hours;
+            if warning_hours > 0 {
+                let within_secs = i64::try_from(warning_hours.saturating_mul(3600)).unwrap_or(i64::MAX);
+                let expiring = state.account_manager.lock().await.mark_expiring_tokens(within_secs);
+                for event in expiring {
+                    println!("[INFO] 账号 {} 的 Token 即将过期", event.email);
+                    let _ = app.emit("token_expiring", &event);
+                    let expires = chrono::DateTime::from_timestamp(event.expires_at, 0)
+                        .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default();
+                    crate::notify::dispatch(
+                        &app,
+                        crate::notify::Notification::new(
+                            crate::notify::NotifyEvent::TokenExpiring,
+                            "Token 即将过期",
+                            format!("账号 {} 的 Token 将于 {} 过期，请及时重新登录", event.email, expires),
+                        ),
+                    )
+                    .await;
+                }
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
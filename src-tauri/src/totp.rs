@@ -0,0 +1,144 @@
+use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha1::Sha1;
+use tauri::{AppHandle, Manager};
+
+use crate::error::{self, ErrorCode};
+use crate::AppState;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// 验证码有效期（秒）和位数，与常见的身份验证器应用一致
+const PERIOD: u64 = 30;
+const DIGITS: u32 = 6;
+
+/// 当前的两步验证码
+#[derive(Debug, Clone, Serialize)]
+pub struct TotpCode {
+    pub code: String,
+    /// 验证码剩余有效时间（秒）
+    pub remaining_secs: u64,
+}
+
+/// 注入脚本中检测两步验证输入框并请求验证码的代码片段，需要页面脚本中已定义 send
+///
+/// 只识别明确标注为身份验证器 / 两步验证的输入框，不会填写邮箱验证码
+pub const JS_TOTP: &str = r#"
+  (() => {
+    const TOTP_HINTS = ["totp", "2fa", "mfa", "authenticator", "two-factor", "two factor", "身份验证器", "两步验证", "二次验证", "双重验证"];
+    const isTotpInput = (input) => {
+      if (!input || !input.getAttribute || input.type === "hidden" || input.disabled) return false;
+      const attrs = ["name", "id", "placeholder", "aria-label", "autocomplete"]
+        .map((key) => (input.getAttribute(key) || "").toLowerCase())
+        .join(" ");
+      if (TOTP_HINTS.some((hint) => attrs.includes(hint)) && !attrs.includes("email")) return true;
+      const form = input.closest("form, [role='dialog']");
+      const text = ((form && form.innerText) || "").toLowerCase();
+      return attrs.includes("one-time-code") && TOTP_HINTS.some((hint) => text.includes(hint));
+    };
+    const findTotpInput = () => Array.from(document.querySelectorAll("input")).find(isTotpInput);
+    let lastRequest = 0;
+    window.__traeFillTotp = (code) => {
+      const input = findTotpInput();
+      if (!input || !code) return;
+      const setter = Object.getOwnPropertyDescriptor(HTMLInputElement.prototype, "value");
+      if (setter && setter.set) setter.set.call(input, code);
+      else input.value = code;
+      input.dispatchEvent(new Event("input", { bubbles: true }));
+      input.dispatchEvent(new Event("change", { bubbles: true }));
+    };
+    setInterval(() => {
+      const input = findTotpInput();
+      if (!input || input.value || Date.now() - lastRequest < 10000) return;
+      lastRequest = Date.now();
+      send({ step: "totp_required" });
+    }, 1000);
+  })();
+"#;
+
+/// 解析两步验证密钥：支持 Base32 密钥（可含空格）或 otpauth:// 链接，返回规范化的 Base32 密钥
+pub fn normalize_secret(raw: &str) -> Result<String> {
+    let raw = raw.trim();
+    let secret = if raw.to_lowercase().starts_with("otpauth://") {
+        let url = tauri::Url::parse(raw).map_err(|_| error::coded(ErrorCode::InvalidInput, "无法解析 otpauth 链接"))?;
+        url.query_pairs()
+            .find(|(key, _)| key == "secret")
+            .map(|(_, value)| value.into_owned())
+            .ok_or_else(|| error::coded(ErrorCode::InvalidInput, "otpauth 链接中没有 secret 参数"))?
+    } else {
+        raw.to_string()
+    };
+    let secret: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '=')
+        .collect::<String>()
+        .to_uppercase();
+    if decode_base32(&secret).is_none_or(|key| key.is_empty()) {
+        return Err(error::coded(ErrorCode::InvalidInput, "两步验证密钥不是有效的 Base32 字符串"));
+    }
+    Ok(secret)
+}
+
+/// 按 RFC 6238 生成指定时间的验证码（HMAC-SHA1，30 秒，6 位）
+pub fn generate(secret: &str, unix_time: u64) -> Result<String> {
+    let key = decode_base32(secret)
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| error::coded(ErrorCode::InvalidInput, "两步验证密钥不是有效的 Base32 字符串"))?;
+    let mut mac = HmacSha1::new_from_slice(&key).map_err(|e| anyhow!("HMAC 密钥无效: {}", e))?;
+    mac.update(&(unix_time / PERIOD).to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    // RFC 4226 动态截断
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]]) & 0x7fff_ffff;
+    Ok(format!("{:0width$}", binary % 10u32.pow(DIGITS), width = DIGITS as usize))
+}
+
+/// 当前的验证码
+pub fn current(secret: &str) -> Result<TotpCode> {
+    let now = u64::try_from(chrono::Utc::now().timestamp()).unwrap_or_default();
+    Ok(TotpCode {
+        code: generate(secret, now)?,
+        remaining_secs: PERIOD - now % PERIOD,
+    })
+}
+
+fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+    for c in input.bytes() {
+        let value = ALPHABET.iter().position(|&a| a == c.to_ascii_uppercase())? as u64;
+        bits = (bits << 5) | value;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            output.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    Some(output)
+}
+
+/// 页面显示两步验证输入框时填写验证码：优先使用指定账号的密钥，否则按邮箱查找账号
+pub async fn fill(app: AppHandle, window_label: String, account_id: Option<String>, email: String) {
+    let secret = {
+        let state = app.state::<AppState>();
+        let manager = state.account_manager.lock().await;
+        manager.totp_secret_for(account_id.as_deref(), &email)
+    };
+    let Some(secret) = secret else {
+        println!("[INFO] 页面需要两步验证码，但账号没有保存两步验证密钥");
+        return;
+    };
+    match current(&secret) {
+        Ok(totp) => {
+            if let Some(window) = app.get_webview_window(&window_label) {
+                let _ = window.eval(format!("window.__traeFillTotp && window.__traeFillTotp({:?});", totp.code));
+                println!("[INFO] 已填写两步验证码");
+            }
+        }
+        Err(e) => println!("[WARN] 生成两步验证码失败: {}", e),
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api::network::SendExt;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/S-Trespassing/Trae-Account-Manager/releases/latest";
+
+/// 各架构在安装包文件名中的写法（x86_64 会先统一为 x64）
+const X64_TOKENS: [&str; 2] = ["x64", "amd64"];
+const ARM64_TOKENS: [&str; 2] = ["arm64", "aarch64"];
+
+/// GitHub Release 中的附件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseResponse {
+    #[serde(default)]
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+/// 最新版本信息
+#[derive(Debug, Clone, Serialize)]
+pub struct LatestRelease {
+    pub version: String,
+    pub notes: String,
+    /// 适用于当前系统和架构的安装包，没有时为 None
+    pub installer: Option<ReleaseAsset>,
+    /// 当前运行环境（如 windows-aarch64），用于提示
+    pub platform: String,
+}
+
+/// 当前系统可用的安装包扩展名（按优先级）
+fn installer_extensions() -> &'static [&'static str] {
+    match std::env::consts::OS {
+        "windows" => &[".msi", "-setup.exe"],
+        "macos" => &[".dmg"],
+        _ => &[".appimage", ".deb"],
+    }
+}
+
+/// 安装包与当前架构的匹配程度：Some(2) 架构一致，Some(1) 通用包，Some(0) 未标注架构，None 不匹配
+fn arch_score(name: &str) -> Option<u8> {
+    let name = name.to_lowercase().replace("x86_64", "x64");
+    let tokens: Vec<&str> = name.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+    let is_x64 = tokens.iter().any(|t| X64_TOKENS.contains(t));
+    let is_arm64 = tokens.iter().any(|t| ARM64_TOKENS.contains(t));
+    let current_is_arm64 = std::env::consts::ARCH == "aarch64";
+
+    if tokens.contains(&"universal") {
+        return (std::env::consts::OS == "macos").then_some(1);
+    }
+    match (is_x64, is_arm64) {
+        (true, false) => (!current_is_arm64).then_some(2),
+        (false, true) => current_is_arm64.then_some(2),
+        // 旧版本只发布 x64 安装包且不标注架构
+        (false, false) => (!current_is_arm64).then_some(0),
+        (true, true) => None,
+    }
+}
+
+/// 选择适用于当前系统和架构的安装包
+pub fn select_installer(assets: &[ReleaseAsset]) -> Option<ReleaseAsset> {
+    let extensions = installer_extensions();
+    assets
+        .iter()
+        .filter_map(|asset| {
+            let name = asset.name.to_lowercase();
+            let ext_rank = extensions.iter().position(|ext| name.ends_with(ext))?;
+            let score = arch_score(&name)?;
+            Some((score, std::cmp::Reverse(ext_rank), asset))
+        })
+        .max_by_key(|(score, ext_rank, _)| (*score, *ext_rank))
+        .map(|(_, _, asset)| asset.clone())
+}
+
+/// 拒绝与当前系统或架构不匹配的安装包
+pub fn ensure_installer_matches(file_name: &str) -> Result<()> {
+    let name = file_name.to_lowercase();
+    if !installer_extensions().iter().any(|ext| name.ends_with(ext)) {
+        return Err(anyhow!("安装包 {} 不适用于当前系统 ({})", file_name, platform()));
+    }
+    if arch_score(&name).is_none() {
+        return Err(anyhow!("安装包 {} 与当前架构不匹配 ({})", file_name, platform()));
+    }
+    Ok(())
+}
+
+fn platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// 查询 GitHub 上的最新版本
+pub async fn fetch_latest_release() -> Result<LatestRelease> {
+    let release: ReleaseResponse = crate::api::network::client_builder()
+        .user_agent("Trae Account Manager Updater")
+        .build()?
+        .get(LATEST_RELEASE_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send_checked()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let version = Some(release.tag_name.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .or_else(|| release.name.map(|n| n.trim().to_string()))
+        .ok_or_else(|| anyhow!("最新版本信息缺少版本号"))?;
+    Ok(LatestRelease {
+        version,
+        notes: release.body.unwrap_or_default().trim().to_string(),
+        installer: select_installer(&release.assets),
+        platform: platform(),
+    })
+}
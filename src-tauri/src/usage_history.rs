@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::TimeZone;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::api::{UsageSummary, UserStatisticResult};
+
+const DB_FILE: &str = "usage-history.db";
+/// 已用量没有变化时，两次采样的最小间隔（秒）
+const UNCHANGED_SAMPLE_INTERVAL: i64 = 3600;
+/// 采样保留时间
+const RETENTION_SECS: i64 = 180 * 24 * 3600;
+
+/// 趋势查询范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendRange {
+    #[default]
+    Week,
+    Month,
+    Quarter,
+}
+
+impl TrendRange {
+    fn days(self) -> i64 {
+        match self {
+            TrendRange::Week => 7,
+            TrendRange::Month => 30,
+            TrendRange::Quarter => 90,
+        }
+    }
+}
+
+/// 每日使用量（按本地日期汇总）
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageTrendPoint {
+    /// 本地日期（YYYY-MM-DD）
+    pub date: String,
+    /// 当天最后一次采样的已用快速请求（含额外礼包）
+    pub used: f64,
+    /// 当天消耗量（额度重置当天按重置后的已用量计算）
+    pub delta: f64,
+    /// 当天最后一次采样的剩余快速请求（含额外礼包）
+    pub left: f64,
+}
+
+/// 额度耗尽预测
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaForecast {
+    /// 近 30 天平均每日消耗（按第一次到最后一次采样的天数平均）
+    pub daily_burn: f64,
+    /// 有采样的天数
+    pub days_sampled: usize,
+    /// 最近一次采样的剩余快速请求（含额外礼包）
+    pub left: f64,
+    /// 预计耗尽时间，没有消耗记录时为 None
+    pub exhausts_at: Option<i64>,
+    /// 额度重置时间（来自最近一次查询的使用量）
+    pub resets_at: Option<i64>,
+    /// 预计在额度重置前耗尽
+    pub exhausts_before_reset: bool,
+}
+
+/// 每日统计数据采样（每天保留最后一次查询的结果）
+#[derive(Debug, Clone, Serialize)]
+pub struct StatisticPoint {
+    /// 本地日期（YYYY-MM-DD）
+    pub date: String,
+    /// 近一年 AI 请求总数
+    pub ai_requests: i64,
+    /// 近 7 天代码补全次数
+    pub completions: i64,
+    /// 近 7 天采纳 AI 代码次数
+    pub accepts: i64,
+    /// 与上一个有采样的日期相比的变化量（第一天为 0）
+    pub ai_requests_delta: i64,
+    pub completions_delta: i64,
+    pub accepts_delta: i64,
+}
+
+fn open() -> Result<Connection> {
+    let path = crate::account::AccountManager::data_dir()?.join(DB_FILE);
+    let conn = Connection::open(path)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS usage_samples (
+            account_id TEXT NOT NULL,
+            sampled_at INTEGER NOT NULL,
+            used REAL NOT NULL,
+            left_amount REAL NOT NULL,
+            plan_type TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_usage_samples_account ON usage_samples (account_id, sampled_at);
+        CREATE TABLE IF NOT EXISTS statistic_samples (
+            account_id TEXT NOT NULL,
+            date TEXT NOT NULL,
+            sampled_at INTEGER NOT NULL,
+            ai_requests INTEGER NOT NULL,
+            completions INTEGER NOT NULL,
+            accepts INTEGER NOT NULL,
+            PRIMARY KEY (account_id, date)
+        );",
+    )?;
+    Ok(conn)
+}
+
+/// 记录一次使用量采样（已用量未变化且距上次采样不足 1 小时时跳过）
+pub fn record_sample(account_id: &str, summary: &UsageSummary, now: i64) -> Result<()> {
+    let conn = open()?;
+    let used = summary.fast_request_used + summary.extra_fast_request_used;
+    let left = summary.fast_request_left + summary.extra_fast_request_left;
+
+    let last: Option<(i64, f64)> = conn
+        .query_row(
+            "SELECT sampled_at, used FROM usage_samples WHERE account_id = ?1 ORDER BY sampled_at DESC LIMIT 1",
+            params![account_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    if let Some((sampled_at, last_used)) = last {
+        if (last_used - used).abs() < f64::EPSILON && now - sampled_at < UNCHANGED_SAMPLE_INTERVAL {
+            return Ok(());
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO usage_samples (account_id, sampled_at, used, left_amount, plan_type) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![account_id, now, used, left, summary.plan_type],
+    )?;
+    conn.execute(
+        "DELETE FROM usage_samples WHERE account_id = ?1 AND sampled_at < ?2",
+        params![account_id, now - RETENTION_SECS],
+    )?;
+    Ok(())
+}
+
+/// 按近 30 天的使用量历史估算平均每日消耗和预计耗尽时间
+pub fn estimate_exhaustion(account_id: &str, resets_at: Option<i64>, now: i64) -> Result<QuotaForecast> {
+    let points = get_usage_trend(account_id, TrendRange::Month)?;
+    let span_days = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => {
+            let parse = |date: &str| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+            match (parse(&first.date), parse(&last.date)) {
+                (Some(start), Some(end)) => (end - start).num_days() + 1,
+                _ => i64::try_from(points.len()).unwrap_or(i64::MAX),
+            }
+        }
+        _ => 0,
+    };
+    let total: f64 = points.iter().map(|p| p.delta).sum();
+    let daily_burn = if span_days > 0 { total / span_days as f64 } else { 0.0 };
+    let left = points.last().map_or(0.0, |p| p.left);
+
+    let exhausts_at = (daily_burn > 0.0).then(|| now + (left / daily_burn * 86400.0) as i64);
+    let resets_at = resets_at.filter(|t| *t > 0);
+    Ok(QuotaForecast {
+        daily_burn,
+        days_sampled: points.len(),
+        left,
+        exhausts_at,
+        resets_at,
+        exhausts_before_reset: matches!((exhausts_at, resets_at), (Some(exhausts), Some(resets)) if exhausts < resets),
+    })
+}
+
+/// 记录当天的统计数据采样（同一天多次查询时覆盖）
+pub fn record_statistic_sample(account_id: &str, stats: &UserStatisticResult, now: i64) -> Result<()> {
+    let conn = open()?;
+    let ai_requests: i64 = stats.ai_cnt_365d.values().map(|v| i64::from(*v)).sum();
+    conn.execute(
+        "INSERT OR REPLACE INTO statistic_samples (account_id, date, sampled_at, ai_requests, completions, accepts)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            account_id,
+            local_date(now),
+            now,
+            ai_requests,
+            i64::from(stats.code_comp_cnt_7d),
+            i64::from(stats.code_ai_accept_cnt_7d)
+        ],
+    )?;
+    conn.execute(
+        "DELETE FROM statistic_samples WHERE account_id = ?1 AND sampled_at < ?2",
+        params![account_id, now - RETENTION_SECS],
+    )?;
+    Ok(())
+}
+
+/// 查询账号在指定范围内每天的统计数据采样及变化量（没有采样的日期不返回）
+pub fn get_statistic_history(account_id: &str, range: TrendRange) -> Result<Vec<StatisticPoint>> {
+    let conn = open()?;
+    let now = chrono::Utc::now().timestamp();
+    // 多取一天作为第一天的基准
+    let since = now - (range.days() + 1) * 24 * 3600;
+
+    let mut stmt = conn.prepare(
+        "SELECT date, ai_requests, completions, accepts FROM statistic_samples
+         WHERE account_id = ?1 AND sampled_at >= ?2 ORDER BY date",
+    )?;
+    let samples = stmt
+        .query_map(params![account_id, since], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?, row.get::<_, i64>(3)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let first_date = local_date(now - (range.days() - 1) * 24 * 3600);
+    let mut points = Vec::new();
+    let mut previous: Option<(i64, i64, i64)> = None;
+    for (date, ai_requests, completions, accepts) in samples {
+        let (ai_before, comp_before, accepts_before) = previous.unwrap_or((ai_requests, completions, accepts));
+        previous = Some((ai_requests, completions, accepts));
+        if date >= first_date {
+            points.push(StatisticPoint {
+                date,
+                ai_requests,
+                completions,
+                accepts,
+                ai_requests_delta: ai_requests - ai_before,
+                completions_delta: completions - comp_before,
+                accepts_delta: accepts - accepts_before,
+            });
+        }
+    }
+    Ok(points)
+}
+
+/// 删除账号的全部采样
+pub fn delete_account(account_id: &str) -> Result<()> {
+    let conn = open()?;
+    conn.execute("DELETE FROM usage_samples WHERE account_id = ?1", params![account_id])?;
+    conn.execute("DELETE FROM statistic_samples WHERE account_id = ?1", params![account_id])?;
+    Ok(())
+}
+
+fn local_date(ts: i64) -> String {
+    chrono::Local
+        .timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// 查询账号在指定范围内每天的消耗量（没有采样的日期不返回）
+pub fn get_usage_trend(account_id: &str, range: TrendRange) -> Result<Vec<UsageTrendPoint>> {
+    let conn = open()?;
+    let now = chrono::Utc::now().timestamp();
+    // 多取一天作为第一天的基准
+    let since = now - (range.days() + 1) * 24 * 3600;
+
+    let mut stmt = conn.prepare(
+        "SELECT sampled_at, used, left_amount FROM usage_samples WHERE account_id = ?1 AND sampled_at >= ?2 ORDER BY sampled_at",
+    )?;
+    let samples = stmt
+        .query_map(params![account_id, since], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?, row.get::<_, f64>(2)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // 每天取最后一次采样
+    let mut days: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+    for (sampled_at, used, left) in samples {
+        days.insert(local_date(sampled_at), (used, left));
+    }
+
+    let first_date = local_date(now - (range.days() - 1) * 24 * 3600);
+    let mut points = Vec::new();
+    let mut previous: Option<f64> = None;
+    for (date, (used, left)) in days {
+        // 用量下降说明额度已重置，当天消耗按重置后的已用量计算
+        let delta = match previous {
+            Some(before) if used >= before => used - before,
+            Some(_) => used,
+            None => 0.0,
+        };
+        previous = Some(used);
+        if date >= first_date {
+            points.push(UsageTrendPoint { date, used, delta, left });
+        }
+    }
+    Ok(points)
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::AppState;
+
+/// 检查是否有账号需要刷新的间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+/// 每轮最多刷新的账号数，避免集中请求
+const MAX_PER_TICK: usize = 5;
+
+/// 启动使用量自动刷新：当前账号和最近活跃的账号刷新更频繁，闲置账号每小时刷新一次
+///
+/// 每个账号刷新后发出 account_usage 事件
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            let state = app.state::<AppState>();
+            if !state.settings.lock().await.auto_refresh_enabled {
+                continue;
+            }
+
+            let now = chrono::Utc::now().timestamp();
+            let due = state.account_manager.lock().await.accounts_due_for_usage_poll(now);
+            for account in due.into_iter().take(MAX_PER_TICK) {
+                let result = crate::api::request_log::scope(&account.id, crate::fetch_usage_for_account(&account)).await;
+                let item = {
+                    let mut manager = state.account_manager.lock().await;
+                    match result {
+                        Ok((summary, new_token)) => {
+                            let _ = manager.update_account_info_after_usage_check(&account.id, &summary, new_token);
+                            crate::AccountUsageResult { account_id: account.id.clone(), usage: Some(summary), error: None }
+                        }
+                        Err(e) => {
+                            println!("[WARN] 自动刷新账号 {} 使用量失败: {}", account.email, e);
+                            manager.record_account_error(&account.id, &e);
+                            crate::AccountUsageResult { account_id: account.id.clone(), usage: None, error: Some(e.to_string()) }
+                        }
+                    }
+                };
+                let _ = app.emit("account_usage", &item);
+            }
+        }
+    });
+}
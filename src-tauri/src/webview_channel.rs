@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::sync::Mutex as StdMutex;
+
+use once_cell::sync::Lazy;
+use tokio::sync::mpsc;
+
+/// 注入脚本回传的一条消息（键值对，与原先回调地址的查询参数相同）
+pub type CallbackMessage = HashMap<String, String>;
+
+/// 窗口标识 -> 接收该窗口注入脚本回传消息的通道
+static CHANNELS: Lazy<StdMutex<HashMap<String, mpsc::UnboundedSender<CallbackMessage>>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+
+/// 注入脚本中回传消息的函数：通过应用 IPC 调用 webview_callback，不需要本地监听端口
+pub const JS_SEND: &str = r#"(query) => {
+    const ipc = window.__TAURI_INTERNALS__;
+    if (ipc && typeof ipc.invoke === "function") {
+      ipc.invoke("webview_callback", { query }).catch(() => {});
+    }
+  }"#;
+
+/// 窗口使用期间保留回传通道，结束时自动移除
+pub struct ChannelGuard {
+    window_label: String,
+    sender: mpsc::UnboundedSender<CallbackMessage>,
+}
+
+impl Drop for ChannelGuard {
+    fn drop(&mut self) {
+        if let Ok(mut channels) = CHANNELS.lock() {
+            // 同一窗口已重新登记时保留新通道
+            if channels.get(&self.window_label).is_some_and(|tx| tx.same_channel(&self.sender)) {
+                channels.remove(&self.window_label);
+            }
+        }
+    }
+}
+
+/// 登记窗口的回传通道（同一窗口再次登记时替换旧通道）
+pub fn register(window_label: &str) -> (ChannelGuard, mpsc::UnboundedReceiver<CallbackMessage>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    if let Ok(mut channels) = CHANNELS.lock() {
+        channels.insert(window_label.to_string(), tx.clone());
+    }
+    (ChannelGuard { window_label: window_label.to_string(), sender: tx }, rx)
+}
+
+/// 把消息转给窗口的回传通道，窗口没有登记时忽略
+pub fn dispatch(window_label: &str, message: CallbackMessage) {
+    if let Ok(channels) = CHANNELS.lock() {
+        if let Some(tx) = channels.get(window_label) {
+            let _ = tx.send(message);
+        }
+    }
+}
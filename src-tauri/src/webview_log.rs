@@ -0,0 +1,165 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+const LOG_FILE: &str = "webview-console.log";
+/// 日志超过该大小时轮转为 .1 文件（只保留一份旧日志）
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// 单条日志的最大长度（字符）
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// 串行写入，避免多个窗口的日志行交错
+static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 注入脚本回传的一条控制台输出或页面状态
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConsoleEntry {
+    /// log / info / warn / error / dom 等
+    pub level: String,
+    pub message: String,
+}
+
+/// 注入登录 / 注册窗口的脚本：转发控制台输出、页面错误，并在页面状态变化时回传关键 DOM 状态
+///
+/// 页面状态只包含地址、标题、输入框类型和是否已填写、按钮文字和错误提示，不包含输入框的内容
+pub const JS_CAPTURE: &str = r#"(function() {
+  if (window.__traeConsoleCaptured) return;
+  window.__traeConsoleCaptured = true;
+  const ipc = window.__TAURI_INTERNALS__;
+  if (!ipc || typeof ipc.invoke !== "function") return;
+  let queue = [];
+  const push = (level, message) => {
+    queue.push({ level, message: String(message).slice(0, 2000) });
+    if (queue.length > 200) queue = queue.slice(-200);
+  };
+  const format = (args) => Array.from(args).map((arg) => {
+    if (arg instanceof Error) return arg.stack || arg.message;
+    if (typeof arg === "object") {
+      try { return JSON.stringify(arg); } catch { return String(arg); }
+    }
+    return String(arg);
+  }).join(" ");
+  ["log", "info", "warn", "error", "debug"].forEach((level) => {
+    const orig = console[level];
+    console[level] = function(...args) {
+      try { push(level, format(args)); } catch {}
+      return orig && orig.apply(this, args);
+    };
+  });
+  window.addEventListener("error", (event) => {
+    push("error", (event.message || "error") + " @ " + (event.filename || "") + ":" + (event.lineno || 0));
+  });
+  window.addEventListener("unhandledrejection", (event) => {
+    push("error", "unhandledrejection: " + format([event.reason]));
+  });
+  const visible = (el) => !!(el && (el.offsetWidth || el.offsetHeight || el.getClientRects().length));
+  const text = (el) => (el.innerText || el.textContent || el.value || "").trim().replace(/\s+/g, " ").slice(0, 40);
+  let lastDom = "";
+  const snapshotDom = () => {
+    try {
+      const inputs = Array.from(document.querySelectorAll("input, textarea")).filter(visible).slice(0, 10)
+        .map((el) => (el.type || "text") + ":" + (el.name || el.id || el.placeholder || "?") + (el.value ? "=filled" : "=empty"));
+      const buttons = Array.from(document.querySelectorAll("button, [role='button'], input[type='submit']")).filter(visible).slice(0, 10)
+        .map((el) => text(el) + (el.disabled ? "(disabled)" : ""));
+      const errors = Array.from(document.querySelectorAll("[role='alert'], [class*='error'], [class*='Error']")).filter(visible).slice(0, 5)
+        .map(text).filter(Boolean);
+      const captcha = !!document.querySelector("iframe[src*='captcha'], iframe[src*='turnstile'], iframe[src*='recaptcha'], iframe[src*='hcaptcha']");
+      const dom = JSON.stringify({ url: location.href, title: document.title, inputs, buttons, errors, captcha });
+      if (dom !== lastDom) {
+        lastDom = dom;
+        push("dom", dom);
+      }
+    } catch {}
+  };
+  const flush = () => {
+    snapshotDom();
+    if (!queue.length) return;
+    const entries = queue;
+    queue = [];
+    ipc.invoke("webview_console", { entries }).catch(() => {});
+  };
+  setInterval(flush, 2000);
+  window.addEventListener("pagehide", flush);
+})();"#;
+
+/// 开启或关闭窗口控制台日志（由设置控制）
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 窗口控制台日志文件路径
+pub fn log_path() -> Result<PathBuf> {
+    let dir = crate::account::AccountManager::data_dir()?.join("logs");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(LOG_FILE))
+}
+
+/// 记录窗口回传的控制台输出（未开启时忽略）
+pub fn record(window_label: &str, entries: &[ConsoleEntry]) {
+    if !ENABLED.load(Ordering::Relaxed) || entries.is_empty() {
+        return;
+    }
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    let lines: String = entries
+        .iter()
+        .map(|entry| {
+            let message: String = entry.message.chars().take(MAX_MESSAGE_CHARS).collect();
+            format!("{} [{}] {} {}\n", now, window_label, entry.level, message.replace(['\r', '\n'], " "))
+        })
+        .collect();
+    if let Err(e) = append(&lines) {
+        println!("[WARN] 写入窗口控制台日志失败: {}", e);
+    }
+}
+
+/// 记录注入脚本自身的日志（未开启时忽略）
+pub fn record_line(window_label: &str, level: &str, message: &str) {
+    record(
+        window_label,
+        &[ConsoleEntry {
+            level: level.to_string(),
+            message: message.to_string(),
+        }],
+    );
+}
+
+fn append(lines: &str) -> Result<()> {
+    let _guard = WRITE_LOCK.lock().map_err(|_| anyhow::anyhow!("窗口控制台日志锁已损坏"))?;
+    let path = log_path()?;
+    if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let _ = fs::rename(&path, path.with_extension("log.1"));
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(lines.as_bytes())?;
+    Ok(())
+}
+
+/// 将当前的窗口控制台日志轮转为 .1 文件（定时维护时调用），返回是否发生了轮转
+pub fn rotate() -> Result<bool> {
+    let _guard = WRITE_LOCK.lock().map_err(|_| anyhow::anyhow!("窗口控制台日志锁已损坏"))?;
+    let path = log_path()?;
+    if !fs::metadata(&path).is_ok_and(|m| m.len() > 0) {
+        return Ok(false);
+    }
+    fs::rename(&path, path.with_extension("log.1"))?;
+    Ok(true)
+}
+
+/// 清空窗口控制台日志（包括轮转的旧日志）
+pub fn clear() -> Result<()> {
+    let _guard = WRITE_LOCK.lock().map_err(|_| anyhow::anyhow!("窗口控制台日志锁已损坏"))?;
+    let path = log_path()?;
+    for file in [path.clone(), path.with_extension("log.1")] {
+        if file.exists() {
+            fs::remove_file(&file)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use tauri::{Manager, Runtime, WebviewWindowBuilder};
+use uuid::Uuid;
+
+/// 保留第三方登录状态时使用的共享登录数据目录名
+pub const SHARED_LOGIN_PROFILE: &str = "shared-login";
+
+/// 转移登录数据时的重试次数（关闭窗口后 WebView 进程可能仍短暂占用目录）
+const ADOPT_ATTEMPTS: u32 = 10;
+const ADOPT_RETRY_DELAY: Duration = Duration::from_millis(300);
+
+/// 窗口使用的独立 WebView 数据（Cookie、LocalStorage 等），关闭窗口后保留
+///
+/// 每个账号一份，保存在数据目录的 webview-profiles/<账号 ID> 下，账号删除后由清理任务回收
+pub struct WebviewProfile {
+    id: Uuid,
+    dir: PathBuf,
+    /// 多次登录共用的数据，登录成功后不转为账号的数据
+    shared: bool,
+}
+
+impl WebviewProfile {
+    /// 账号的 WebView 数据
+    pub fn for_account(account_id: &str) -> Result<Self> {
+        let id = Uuid::parse_str(account_id).unwrap_or_else(|_| {
+            let mut hasher = DefaultHasher::new();
+            account_id.hash(&mut hasher);
+            let hash = hasher.finish();
+            Uuid::from_u64_pair(hash, hash.rotate_left(32))
+        });
+        Ok(Self {
+            id,
+            dir: crate::gc::webview_profiles_dir()?.join(account_id),
+            shared: false,
+        })
+    }
+
+    /// 新的空白数据，登录新账号时使用，登录成功后通过 adopt 转为账号的数据
+    pub fn fresh() -> Result<Self> {
+        let id = Uuid::new_v4();
+        Ok(Self {
+            id,
+            dir: crate::gc::webview_profiles_dir()?.join(id.to_string()),
+            shared: false,
+        })
+    }
+
+    /// 多次登录共用的数据，保留 Google / GitHub 等第三方网站的登录状态
+    pub fn shared_login() -> Result<Self> {
+        Ok(Self {
+            shared: true,
+            ..Self::for_account(SHARED_LOGIN_PROFILE)?
+        })
+    }
+
+    /// 让窗口使用该数据目录（macOS 上使用对应的数据存储）
+    pub fn apply<'a, R: Runtime, M: Manager<R>>(
+        &self,
+        builder: WebviewWindowBuilder<'a, R, M>,
+    ) -> WebviewWindowBuilder<'a, R, M> {
+        let builder = builder.data_directory(self.dir.clone());
+        #[cfg(target_os = "macos")]
+        let builder = builder.data_store_identifier(*self.id.as_bytes());
+        builder
+    }
+
+    /// 登录成功后把数据转为账号的数据，替换账号原有的数据；需在窗口关闭后调用
+    ///
+    /// 转移失败时保留账号原有的数据，临时数据由清理任务回收；共享的登录数据保持不动
+    pub async fn adopt(self, account_id: &str) {
+        if self.shared || self.id.to_string() == account_id || !self.dir.exists() {
+            return;
+        }
+        let target = match Self::for_account(account_id) {
+            Ok(profile) => profile.dir,
+            Err(e) => {
+                println!("[WARN] 保存账号的浏览器数据失败: {}", e);
+                return;
+            }
+        };
+        // 先确认临时数据已不再被占用，再替换账号原有的数据
+        let staging = target.with_extension("adopting");
+        for attempt in 1..=ADOPT_ATTEMPTS {
+            match fs::rename(&self.dir, &staging) {
+                Ok(()) => break,
+                Err(e) if attempt == ADOPT_ATTEMPTS => {
+                    println!("[WARN] 保存账号的浏览器数据失败: {}", e);
+                    return;
+                }
+                Err(_) => tokio::time::sleep(ADOPT_RETRY_DELAY).await,
+            }
+        }
+        let _ = fs::remove_dir_all(&target);
+        if let Err(e) = fs::rename(&staging, &target) {
+            println!("[WARN] 保存账号的浏览器数据失败: {}", e);
+        }
+    }
+}
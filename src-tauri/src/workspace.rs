@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::AppState;
+
+/// 检查 Trae 工作区的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 工作区匹配到映射账号时的事件（workspace_switch_suggested / workspace_switched）
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceSwitchEvent {
+    pub folder: String,
+    pub mapping_id: String,
+    pub account_id: String,
+    pub email: String,
+}
+
+/// 启动工作区监听：Trae 最近活动的工作区变化时，按映射提示或自动切换账号
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_workspace: Option<PathBuf> = None;
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let state = app.state::<AppState>();
+            if !state.settings.lock().await.workspace_watch_enabled {
+                continue;
+            }
+
+            let workspace = match tokio::task::spawn_blocking(crate::machine::get_trae_open_workspaces).await {
+                Ok(Ok(folders)) => folders.into_iter().next(),
+                _ => continue,
+            };
+            if workspace == last_workspace {
+                continue;
+            }
+            last_workspace = workspace.clone();
+            let Some(workspace) = workspace else {
+                continue;
+            };
+
+            let (mapping, current, email) = {
+                let manager = state.account_manager.lock().await;
+                let Some(mapping) = manager.find_workspace_mapping(&workspace) else {
+                    continue;
+                };
+                let email = manager
+                    .get_account(&mapping.account_id)
+                    .map(|a| a.email)
+                    .unwrap_or_default();
+                (mapping, manager.current_account_id(), email)
+            };
+            if current.as_deref() == Some(mapping.account_id.as_str()) {
+                continue;
+            }
+
+            let event = WorkspaceSwitchEvent {
+                folder: workspace.to_string_lossy().to_string(),
+                mapping_id: mapping.id.clone(),
+                account_id: mapping.account_id.clone(),
+                email,
+            };
+            if !mapping.auto_switch {
+                println!("[INFO] 工作区 {} 映射到账号 {}，等待用户确认切换", event.folder, event.email);
+                let _ = app.emit("workspace_switch_suggested", &event);
+                continue;
+            }
+
+            println!("[INFO] 工作区 {} 映射到账号 {}，自动切换", event.folder, event.email);
+            match crate::switch_account_with_state(&state, mapping.account_id, false).await {
+                Ok(()) => {
+                    let _ = app.emit("workspace_switched", &event);
+                }
+                Err(e) => println!("[WARN] 工作区自动切换账号失败: {}", e.message),
+            }
+        }
+    });
+}
@@ -12,22 +12,26 @@ import { AccountLoginModal } from "./components/AccountLoginModal";
 import { Toast, ToastMessage } from "./components/Toast";
 import { ConfirmModal } from "./components/ConfirmModal";
 import { UpdateModal } from "./components/UpdateModal";
+import { PassphraseModal } from "./components/PassphraseModal";
+import { describeRegisterProgress } from "./components/RegisterProgressBar";
 import { Dashboard } from "./pages/Dashboard";
 import { Stats } from "./pages/Stats";
 import { Settings } from "./pages/Settings";
 import { About } from "./pages/About";
 import * as api from "./api";
-import type { Account, AccountBrief, AppSettings, UsageSummary } from "./types";
+import type { Account, AccountBrief, AccountUsageResult, AccountsChangedEvent, AppNotification, AppSettings, AttentionReason, CaptchaEvent, IntegrityIssue, PoolTopUpEvent, RegisterProgress, RetirementEvent, RegisterQueueStatus, RotationSwitchEvent, TokenExpiringEvent, TokenSweepSummary, TraeInstallStatus, UsageSummary, WorkspaceSwitchEvent } from "./types";
 import "./App.css";
 
 interface AccountWithUsage extends AccountBrief {
   usage?: UsageSummary | null;
   password?: string | null;
+  locale?: string | null;
+  api_host?: string | null;
+  totp_secret?: string | null;
 }
 
 type ViewMode = "grid" | "list";
 const USAGE_CACHE_KEY = "trae_usage_cache_v1";
-const UPDATE_CHECK_URL = "https://api.github.com/repos/S-Trespassing/Trae-Account-Manager/releases/latest";
 const UPDATE_IGNORE_KEY = "trae_update_ignore_version_v1";
 
 const normalizeVersion = (value: string) => value.trim().replace(/^v/i, "");
@@ -85,6 +89,12 @@ function App() {
     onConfirm: () => void;
   } | null>(null);
 
+  // 导出/导入密码弹窗状态
+  const [passphraseModal, setPassphraseModal] = useState<{
+    mode: "export" | "import";
+    onSubmit: (passphrase: string | null) => Promise<void>;
+  } | null>(null);
+
   // 右键菜单状态
   const [contextMenu, setContextMenu] = useState<{
     x: number;
@@ -95,6 +105,10 @@ function App() {
   // 详情弹窗状态
   const [detailAccount, setDetailAccount] = useState<AccountWithUsage | null>(null);
 
+  // Trae IDE 安装状态（未安装时停用切换账号等功能）
+  const [traeStatus, setTraeStatus] = useState<TraeInstallStatus | null>(null);
+  const traeMissing = traeStatus?.installed === false;
+
   // 刷新中的账号 ID
   const [refreshingIds, setRefreshingIds] = useState<Set<string>>(new Set());
 
@@ -108,6 +122,7 @@ function App() {
   const quickRegisterNoticeRef = useRef<Map<string, number>>(new Map());
   const toastDedupRef = useRef<Map<string, number>>(new Map());
   const updateCheckRef = useRef(false);
+  const importedJobsRef = useRef<Set<number>>(new Set());
   const quickRegisterShowWindow = appSettings?.quick_register_show_window ?? false;
 
   // 网络状态监听
@@ -144,21 +159,8 @@ function App() {
     if (!navigator.onLine) return;
     if (updateInfo) return;
     try {
-      const response = await fetch(UPDATE_CHECK_URL, {
-        headers: {
-          Accept: "application/vnd.github+json",
-        },
-      });
-      if (!response.ok) {
-        return;
-      }
-      const data = (await response.json()) as {
-        tag_name?: string;
-        name?: string;
-        body?: string;
-        assets?: Array<{ name?: string; browser_download_url?: string }>;
-      };
-      const latestTag = String(data.tag_name || data.name || "").trim();
+      const release = await api.checkForUpdate();
+      const latestTag = release.version.trim();
       if (!latestTag) return;
       const currentVersion = await getVersion();
       if (!currentVersion) return;
@@ -173,14 +175,8 @@ function App() {
           }
         } catch {}
 
-        const assets = Array.isArray(data.assets) ? data.assets : [];
-        const msiAsset = assets.find((asset) =>
-          String(asset.name || "")
-            .toLowerCase()
-            .endsWith(".msi")
-        );
-        const installerUrl = String(msiAsset?.browser_download_url || "").trim() || null;
-        const notes = typeof data.body === "string" ? data.body.trim() : "";
+        const installerUrl = release.installer?.browser_download_url.trim() || null;
+        const notes = release.notes.trim();
         setUpdateInfo({
           currentVersion,
           latestVersion: latestTag,
@@ -210,7 +206,7 @@ function App() {
   const handleOneClickUpdate = useCallback(async () => {
     if (!updateInfo || updating) return;
     if (!updateInfo.installerUrl) {
-      addToast("error", "未找到可用的 Windows 安装包（.msi），请前往 GitHub 手动下载。", 5000, "update-missing-msi");
+      addToast("error", "未找到适用于当前系统架构的安装包，请前往 GitHub 手动下载。", 5000, "update-missing-msi");
       return;
     }
     setUpdating(true);
@@ -288,6 +284,12 @@ function App() {
     [readUsageCache]
   );
 
+  useEffect(() => {
+    api.getTraeInstallStatus()
+      .then(setTraeStatus)
+      .catch(() => setTraeStatus(null));
+  }, []);
+
   useEffect(() => {
     let active = true;
     api.getSettings()
@@ -319,12 +321,11 @@ function App() {
 
   useEffect(() => {
     let unlisten: (() => void) | null = null;
-    listen<{ id?: string; message: string }>("quick_register_notice", (event) => {
-      if (quickRegisterShowWindow) {
-        return;
-      }
-      const { id, message } = event.payload || {};
-      if (!message) return;
+    listen<RegisterProgress>("quick_register_progress", (event) => {
+      const progress = event.payload;
+      if (!progress?.toast) return;
+      const { id, milestone, elapsed_ms } = progress;
+      const message = describeRegisterProgress(progress);
       const key = id || message;
       const now = Date.now();
       const last = quickRegisterNoticeRef.current.get(key);
@@ -332,46 +333,93 @@ function App() {
         return;
       }
       quickRegisterNoticeRef.current.set(key, now);
-      addToast("success", message, 2500);
+      // 通知级别由后端按设置标记，这里只区分展示样式
+      const text = milestone ? message : `${message}（${(elapsed_ms / 1000).toFixed(1)}s）`;
+      addToast(milestone ? "success" : "info", text, 2500);
     })
       .then((fn) => {
         unlisten = fn;
       })
       .catch(() => {});
+    // 注册页面出现人机验证：注册窗口已自动显示，提示用户完成验证
+    let unlistenCaptcha: (() => void) | null = null;
+    listen<CaptchaEvent>("captcha_required", (event) => {
+      const minutes = Math.round(event.payload.wait_secs / 60);
+      addToast("warning", `「${event.payload.step_label}」时出现人机验证，请在注册窗口中 ${minutes} 分钟内完成，完成后将自动继续`, 8000);
+    })
+      .then((fn) => {
+        unlistenCaptcha = fn;
+      })
+      .catch(() => {});
 
     return () => {
       if (unlisten) {
         unlisten();
       }
+      unlistenCaptcha?.();
     };
-  }, [addToast, quickRegisterShowWindow]);
+  }, [addToast]);
 
   const refreshUsageForAccounts = useCallback(
     async (list: AccountBrief[]) => {
       if (list.length === 0) return;
+      const applyUsage = (updates: Record<string, UsageSummary>) => {
+        setAccounts((prev) =>
+          prev.map((account) =>
+            updates[account.id] ? { ...account, usage: updates[account.id] } : account
+          )
+        );
+      };
+
+      // 已验证账号由后端并发查询，每个账号完成后通过 account_usage 事件更新界面
+      const updates: Record<string, UsageSummary> = {};
+      try {
+        const results = await api.getAllAccountsUsage();
+        results.forEach((result) => {
+          if (result.usage) updates[result.account_id] = result.usage;
+        });
+      } catch (err) {
+        console.error("批量获取使用量失败:", err);
+      }
+
+      // 未验证账号在单独查询时完成校验
+      const unverified = list.filter((account) => account.unverified);
       const results = await Promise.allSettled(
-        list.map((account) => api.getAccountUsage(account.id))
+        unverified.map((account) => api.getAccountUsage(account.id))
       );
-      const updates: Record<string, UsageSummary> = {};
       results.forEach((result, index) => {
         if (result.status === "fulfilled") {
-          updates[list[index].id] = result.value;
+          updates[unverified[index].id] = result.value;
         }
       });
       if (Object.keys(updates).length > 0) {
-        setAccounts((prev) =>
-          prev.map((account) =>
-            updates[account.id] ? { ...account, usage: updates[account.id] } : account
-          )
-        );
-        updateUsageCache(updates, list.map((a) => a.id));
-      } else {
-        updateUsageCache({}, list.map((a) => a.id));
+        applyUsage(updates);
       }
+      updateUsageCache(updates, list.map((a) => a.id));
     },
     [updateUsageCache]
   );
 
+  // 后端查询到账号使用量（批量查询或按活跃程度自动刷新）
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    listen<AccountUsageResult>("account_usage", (event) => {
+      const { account_id, usage } = event.payload;
+      if (!usage) return;
+      setAccounts((prev) =>
+        prev.map((account) => (account.id === account_id ? { ...account, usage } : account))
+      );
+      updateUsageCache({ [account_id]: usage });
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch(() => {});
+    return () => {
+      unlisten?.();
+    };
+  }, [updateUsageCache]);
+
   // 加载账号列表
   const loadAccounts = useCallback(async () => {
     setLoading(true);
@@ -400,6 +448,256 @@ function App() {
     loadAccounts();
   }, [loadAccounts]);
 
+  // Trae 打开了映射的工作区：提示切换或在自动切换后刷新列表
+  useEffect(() => {
+    const unlisteners: Array<() => void> = [];
+    listen<WorkspaceSwitchEvent>("workspace_switch_suggested", (event) => {
+      const { folder, account_id, email } = event.payload;
+      setConfirmModal({
+        isOpen: true,
+        title: "切换工作区账号",
+        message: `Trae 打开了工作区 "${folder}"，该工作区映射到账号 "${email}"。\n\n是否切换到该账号？系统将自动关闭 Trae IDE 并切换登录信息。`,
+        type: "info",
+        confirmText: "切换",
+        onConfirm: async () => {
+          setConfirmModal(null);
+          addToast("info", "正在切换账号，请稍候...");
+          try {
+            await api.switchAccount(account_id);
+            await loadAccounts();
+            addToast("success", "账号切换成功，请重新打开 Trae IDE");
+          } catch (err: any) {
+            addToast("error", err.message || "切换账号失败");
+          }
+        },
+      });
+    })
+      .then((fn) => unlisteners.push(fn))
+      .catch(() => {});
+    listen<WorkspaceSwitchEvent>("workspace_switched", (event) => {
+      addToast("success", `已按工作区映射切换到账号 ${event.payload.email}`);
+      void loadAccounts();
+    })
+      .then((fn) => unlisteners.push(fn))
+      .catch(() => {});
+
+    listen<RotationSwitchEvent>("rotation_switched", (event) => {
+      addToast("info", `账号轮换：已切换到 ${event.payload.email}`);
+      void loadAccounts();
+    })
+      .then((fn) => unlisteners.push(fn))
+      .catch(() => {});
+
+    listen<PoolTopUpEvent>("pool_topup_enqueued", (event) => {
+      addToast("info", `可用账号仅剩 ${event.payload.available} 个，已添加 ${event.payload.enqueued} 个注册任务`);
+    })
+      .then((fn) => unlisteners.push(fn))
+      .catch(() => {});
+
+    listen<RetirementEvent>("accounts_retired", (event) => {
+      const verb = event.payload.action === "delete" ? "删除" : "归档";
+      const replaced = event.payload.enqueued > 0 ? `，已添加 ${event.payload.enqueued} 个注册任务` : "";
+      addToast("info", `已${verb} ${event.payload.accounts.length} 个额度用完的账号${replaced}`);
+      void loadAccounts();
+    })
+      .then((fn) => unlisteners.push(fn))
+      .catch(() => {});
+
+    // 额度用完自动切换（提醒由通知路由发送）
+    listen("account_auto_switched", () => {
+      void loadAccounts();
+    })
+      .then((fn) => unlisteners.push(fn))
+      .catch(() => {});
+
+    return () => {
+      unlisteners.forEach((fn) => fn());
+    };
+  }, [addToast, loadAccounts]);
+
+  // 启动时提示上次退出时未完成的注册
+  useEffect(() => {
+    api.listInterruptedRegistrations()
+      .then((sessions) => {
+        if (sessions.length > 0) {
+          addToast("info", `有 ${sessions.length} 个注册在上次退出时未完成，可在「添加账号」中继续`, 8000);
+        }
+      })
+      .catch(() => {});
+  }, [addToast]);
+
+  // 注册队列中有任务导入成功后刷新账号列表
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<RegisterQueueStatus>("register_queue_updated", (event) => {
+      const imported = event.payload.jobs.filter(
+        (job) => job.status === "succeeded" && !importedJobsRef.current.has(job.id)
+      );
+      if (imported.length === 0) return;
+      imported.forEach((job) => importedJobsRef.current.add(job.id));
+      addToast("success", `注册队列已导入账号: ${imported.map((job) => job.email).join("、")}`);
+      void loadAccounts();
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+
+    return () => unlisten?.();
+  }, [addToast, loadAccounts]);
+
+  // accounts.json 被外部修改（同步工具、手动编辑）后已重新加载
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<AccountsChangedEvent>("accounts_changed", (event) => {
+      const { account_count, integrity_issue } = event.payload;
+      if (integrity_issue) {
+        addToast("warning", `账号数据已被外部修改且校验失败：${integrity_issue.reason}`, 8000);
+      } else {
+        addToast("info", `账号数据已被外部修改，已重新加载 ${account_count} 个账号`);
+      }
+      void loadAccounts();
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+
+    return () => unlisten?.();
+  }, [addToast, loadAccounts]);
+
+  // 外部修改导致签名不匹配时，询问是否信任并重新签名（否则数据会一直拒绝保存）
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<IntegrityIssue>("accounts_external_change", (event) => {
+      const issue = event.payload;
+      setConfirmModal({
+        isOpen: true,
+        title: "账号数据已被外部修改",
+        message: issue.latest_valid_backup
+          ? `accounts.json 被其他程序修改，签名不匹配。是否信任这次修改并重新签名？\n如果不是你本人的操作，请选择稍后处理并在设置中从备份恢复：\n${issue.latest_valid_backup}`
+          : "accounts.json 被其他程序修改，签名不匹配。是否信任这次修改并重新签名？",
+        type: "danger",
+        confirmText: "信任外部修改",
+        cancelText: "稍后处理",
+        onConfirm: async () => {
+          setConfirmModal(null);
+          try {
+            const count = await api.trustExternalAccountsChange();
+            addToast("success", `已信任外部修改，当前共 ${count} 个账号`);
+            await loadAccounts();
+          } catch (err: any) {
+            addToast("error", err.message || "重新签名失败");
+          }
+        },
+      });
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+
+    return () => unlisten?.();
+  }, [addToast, loadAccounts]);
+
+  // Token 即将过期时刷新账号标记（提醒由通知路由发送）
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<TokenExpiringEvent>("token_expiring", () => {
+      void loadAccounts();
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+
+    return () => unlisten?.();
+  }, [loadAccounts]);
+
+  // 启动时检查出需要处理的账号（Token 过期、即将过期或 Cookies 过旧）
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<TokenSweepSummary>("token_sweep", (event) => {
+      const { accounts: attention } = event.payload;
+      const count = (reason: AttentionReason) => attention.filter((a) => a.reasons.includes(reason)).length;
+      const parts = [
+        count("token_expired") && `${count("token_expired")} 个 Token 已过期`,
+        count("token_expiring") && `${count("token_expiring")} 个 Token 即将过期`,
+        count("no_token") && `${count("no_token")} 个没有 Token`,
+        count("cookies_stale") && `${count("cookies_stale")} 个 Cookies 长时间未更新`,
+      ].filter(Boolean);
+      const names = attention.slice(0, 3).map((a) => a.email).join("、") + (attention.length > 3 ? " 等" : "");
+      addToast("warning", `${attention.length} 个账号需要处理（${parts.join("，")}）：${names}`, 10000);
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+
+    return () => unlisten?.();
+  }, [addToast]);
+
+  // 路由到应用内通知渠道的通知
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<AppNotification>("app_notification", (event) => {
+      const { event: kind, title, body } = event.payload;
+      const alert = kind === "quota_alert" || kind === "token_expiring";
+      // 周报正文较长，只提示标题
+      const text = kind === "weekly_report" ? `${title}已生成` : `${title}：${body}`;
+      addToast(alert ? "warning" : "info", text, alert ? 8000 : 4000);
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+
+    return () => unlisten?.();
+  }, [addToast]);
+
+  // 另一个实例持有账号数据锁时提示只读，锁释放后自动重新加载
+  useEffect(() => {
+    api.getStoreReadOnly()
+      .then((readOnly) => {
+        if (readOnly) {
+          addToast("warning", "另一个程序实例正在使用账号数据，当前为只读模式，修改将无法保存", 8000);
+        }
+      })
+      .catch(() => {});
+
+    let unlisten: (() => void) | undefined;
+    listen("store_lock_acquired", () => {
+      addToast("success", "已退出只读模式，账号数据已重新加载");
+      void loadAccounts();
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+
+    return () => unlisten?.();
+  }, [addToast, loadAccounts]);
+
+  // 启动时检查账号数据完整性
+  useEffect(() => {
+    api.getIntegrityStatus()
+      .then((issue) => {
+        if (!issue) return;
+        const backup = issue.latest_valid_backup;
+        setConfirmModal({
+          isOpen: true,
+          title: "账号数据校验失败",
+          message: backup
+            ? `${issue.reason}。是否从最近的有效备份恢复？\n${backup}`
+            : `${issue.reason}。没有找到可用的备份，是否信任当前数据？`,
+          type: "danger",
+          confirmText: backup ? "从备份恢复" : "信任当前数据",
+          cancelText: "稍后处理",
+          onConfirm: async () => {
+            setConfirmModal(null);
+            try {
+              if (backup) {
+                const count = await api.restoreIntegrityBackup(backup);
+                addToast("success", `已从备份恢复 ${count} 个账号`);
+              } else {
+                await api.dismissIntegrityIssue();
+              }
+              await loadAccounts();
+            } catch (err: any) {
+              addToast("error", err.message || "恢复失败");
+            }
+          },
+        });
+      })
+      .catch(() => {});
+  }, [addToast, loadAccounts]);
+
   // 删除账号
   const handleDeleteAccount = async (accountId: string) => {
     setConfirmModal({
@@ -438,7 +736,7 @@ function App() {
     setRefreshingIds((prev) => new Set(prev).add(accountId));
 
     try {
-      const usage = await api.getAccountUsage(accountId);
+      const usage = await api.getAccountUsage(accountId, { force: true });
       setAccounts((prev) =>
         prev.map((a) => (a.id === accountId ? { ...a, usage } : a))
       );
@@ -482,8 +780,12 @@ function App() {
       setError(null);
       setHasLoaded(true);
       void handleRefreshAccount(account.id, { silent: true });
+      // 注册后自动切换时当前账号已变化，重新加载列表
+      if (appSettings?.quick_register_auto_switch) {
+        void loadAccounts();
+      }
     },
-    [handleRefreshAccount]
+    [handleRefreshAccount, appSettings?.quick_register_auto_switch, loadAccounts]
   );
 
   // 选择账号
@@ -537,6 +839,10 @@ function App() {
   ) => {
     const account = accounts.find((a) => a.id === accountId);
     if (!account) return;
+    if (traeMissing) {
+      addToast("warning", traeStatus?.reason || "未检测到 Trae IDE，无法切换账号");
+      return;
+    }
 
     const mode = options?.mode ?? "switch";
     const force = options?.force ?? mode === "relogin";
@@ -578,6 +884,12 @@ function App() {
         ...account,
         email: full.email,
         password: full.password ?? null,
+        locale: full.locale ?? null,
+        api_host: full.api_host ?? null,
+        // 详情中展示服务器返回的用户名，别名单独编辑
+        name: full.name,
+        alias: full.alias ?? null,
+        totp_secret: full.totp_secret ?? null,
       });
     } catch (err: any) {
       addToast("error", err.message || "获取账号详情失败");
@@ -587,12 +899,14 @@ function App() {
 
   const handleUpdateCredentials = async (
     accountId: string,
-    updates: { email?: string; password?: string }
+    updates: { email?: string; password?: string; locale?: string; apiHost?: string }
   ) => {
     try {
       const updated = await api.updateAccountProfile(accountId, {
         email: updates.email ?? null,
         password: updates.password ?? null,
+        locale: updates.locale ?? null,
+        apiHost: updates.apiHost ?? null,
       });
       setAccounts((prev) =>
         prev.map((account) =>
@@ -603,7 +917,13 @@ function App() {
       );
       setDetailAccount((prev) =>
         prev && prev.id === accountId
-          ? { ...prev, email: updated.email, password: updated.password ?? null }
+          ? {
+              ...prev,
+              email: updated.email,
+              password: updated.password ?? null,
+              locale: updated.locale ?? null,
+              api_host: updated.api_host ?? null,
+            }
           : prev
       );
       addToast("success", "账号信息已更新", 1000);
@@ -613,6 +933,59 @@ function App() {
     }
   };
 
+  const handleUpdateAlias = async (accountId: string, alias: string) => {
+    try {
+      const updated = await api.updateAccountAlias(accountId, alias || null);
+      const displayName = updated.alias || updated.name;
+      setAccounts((prev) =>
+        prev.map((account) =>
+          account.id === accountId ? { ...account, name: displayName, alias: updated.alias ?? null } : account
+        )
+      );
+      setDetailAccount((prev) =>
+        prev && prev.id === accountId ? { ...prev, alias: updated.alias ?? null } : prev
+      );
+      addToast("success", updated.alias ? "显示名称已更新" : "已恢复使用用户名", 1000);
+    } catch (err: any) {
+      addToast("error", err.message || "更新显示名称失败");
+      throw err;
+    }
+  };
+
+  const handleUpdateTotpSecret = async (accountId: string, secret: string) => {
+    try {
+      const updated = await api.updateAccountTotpSecret(accountId, secret || null);
+      setDetailAccount((prev) =>
+        prev && prev.id === accountId ? { ...prev, totp_secret: updated.totp_secret ?? null } : prev
+      );
+      addToast("success", updated.totp_secret ? "两步验证密钥已保存" : "已清除两步验证密钥", 1000);
+    } catch (err: any) {
+      addToast("error", err.message || "保存两步验证密钥失败");
+      throw err;
+    }
+  };
+
+  const handleCopyTotpCode = async (accountId: string) => {
+    try {
+      const totp = await api.getAccountTotpCode(accountId);
+      await navigator.clipboard.writeText(totp.code);
+      addToast("success", `已复制验证码 ${totp.code}（${totp.remaining_secs} 秒内有效）`);
+    } catch (err: any) {
+      addToast("error", err.message || "生成两步验证码失败");
+    }
+  };
+
+  const handleUpdateQuotaThreshold = async (accountId: string, threshold: number | null) => {
+    try {
+      const saved = await api.setQuotaThreshold(accountId, threshold);
+      setAppSettings(saved);
+      addToast("success", threshold ? `剩余额度低于 ${threshold} 时提醒` : "已关闭低额度提醒", 1000);
+    } catch (err: any) {
+      addToast("error", err.message || "设置低额度提醒失败");
+      throw err;
+    }
+  };
+
   const handleRelogin = async (
     accountId: string,
     options?: { forceManual?: boolean; source?: "update-token" | "relogin"; suppressToast?: boolean }
@@ -675,6 +1048,18 @@ function App() {
     }
   };
 
+  const handleBrowserRelogin = async (accountId: string) => {
+    try {
+      addToast("info", "已打开登录窗口，请在窗口中完成登录", 3000);
+      await api.startBrowserRelogin(accountId);
+      await api.finishBrowserLogin();
+      await handleRefreshAccount(accountId, { silent: true });
+      addToast("success", "已更新账号的登录信息");
+    } catch (err: any) {
+      addToast("error", err.message || "浏览器重新登录失败");
+    }
+  };
+
   const handleUpdateToken = async (accountId: string) => {
     if (refreshingIds.has(accountId)) {
       return;
@@ -683,7 +1068,7 @@ function App() {
     setRefreshingIds((prev) => new Set(prev).add(accountId));
     addToast("info", "正在更新 Token...", 2000, "update-token-progress");
     try {
-      const usage = await api.getAccountUsage(accountId);
+      const usage = await api.getAccountUsage(accountId, { force: true });
       setAccounts((prev) =>
         prev.map((a) => (a.id === accountId ? { ...a, usage } : a))
       );
@@ -765,27 +1150,72 @@ function App() {
         filters: [{ name: "JSON", extensions: ["json"] }],
       });
       if (!path) return;
-      await api.exportAccountsToPath(path as string);
-      addToast("success", `已导出 ${accounts.length} 个账号`);
+      setPassphraseModal({
+        mode: "export",
+        onSubmit: async (passphrase) => {
+          await api.exportAccountsToPath(
+            path as string,
+            passphrase ? { passphrase } : { plaintext: true }
+          );
+          addToast("success", `已导出 ${accounts.length} 个账号${passphrase ? "（已加密）" : ""}`);
+        },
+      });
     } catch (err: any) {
       addToast("error", err.message || "导出失败");
     }
   };
 
-  // 导入账号
-  const handleImportAccounts = () => {
+  // 导出号池概览（Markdown，不含凭据）
+  const handleExportPoolSummary = async () => {
+    try {
+      const date = new Date().toISOString().split("T")[0];
+      const path = await save({
+        defaultPath: `trae-pool-${date}.md`,
+        filters: [{ name: "Markdown", extensions: ["md"] }],
+      });
+      if (!path) return;
+      const count = await api.exportPoolSummaryMd(path as string);
+      addToast("success", `已导出 ${count} 个账号的号池概览`);
+    } catch (err: any) {
+      addToast("error", err.message || "导出概览失败");
+    }
+  };
+
+  // 导入账号（merge 为 true 时完整导入：保留 Token、密码和机器码，按 user_id 合并到已有账号）
+  const handleImportAccounts = (merge = false) => {
+    const runImport = async (text: string, passphrase?: string) => {
+      if (merge) {
+        const report = await api.importAccountsMerge(text, passphrase);
+        addToast(
+          "success",
+          `完整导入完成：新增 ${report.added} 个，合并 ${report.merged} 个${report.skipped ? `，跳过 ${report.skipped} 个` : ""}`
+        );
+      } else {
+        const count = await api.importAccounts(text, { passphrase });
+        addToast("success", `成功导入 ${count} 个账号`);
+      }
+      await loadAccounts();
+    };
+
     const input = document.createElement("input");
     input.type = "file";
-    input.accept = ".json";
+    input.accept = ".json,.txt";
     input.onchange = async (e) => {
       const file = (e.target as HTMLInputElement).files?.[0];
       if (!file) return;
 
       try {
         const text = await file.text();
-        const count = await api.importAccounts(text);
-        addToast("success", `成功导入 ${count} 个账号`);
-        await loadAccounts();
+        if (api.isEncryptedExport(text)) {
+          setPassphraseModal({
+            mode: "import",
+            onSubmit: async (passphrase) => {
+              await runImport(text, passphrase ?? undefined);
+            },
+          });
+          return;
+        }
+        await runImport(text);
       } catch (err: any) {
         addToast("error", err.message || "导入失败");
       }
@@ -807,6 +1237,45 @@ function App() {
     }
   };
 
+  // 批量领取选中账号的礼包
+  const handleClaimGiftAll = async () => {
+    addToast("info", `正在为全部 ${accounts.length} 个账号领取礼包...`);
+    try {
+      const report = await api.claimGiftAll();
+      const firstError = report.results.find((r) => r.status === "failed")?.error;
+      addToast(
+        report.failed === 0 ? "success" : "warning",
+        `已领取 ${report.claimed} 个，已领取过 ${report.skipped} 个` +
+          (report.failed > 0 ? `，${report.failed} 个失败${firstError ? `（${firstError}）` : ""}` : "")
+      );
+      await loadAccounts();
+    } catch (err: any) {
+      addToast("error", err.message || "领取礼包失败");
+    }
+  };
+
+  const handleBatchClaimGift = async () => {
+    if (selectedIds.size === 0) {
+      addToast("warning", "请先选择要领取礼包的账号");
+      return;
+    }
+
+    addToast("info", `正在为 ${selectedIds.size} 个账号领取礼包...`);
+    try {
+      const results = await api.batchAccountAction("claim_gift", Array.from(selectedIds));
+      const claimed = results.filter((r) => r.success).length;
+      const firstError = results.find((r) => !r.success)?.error;
+      addToast(
+        claimed > 0 ? "success" : "warning",
+        `已领取 ${claimed} 个账号的礼包` +
+          (claimed < results.length ? `，${results.length - claimed} 个失败${firstError ? `（${firstError}）` : ""}` : "")
+      );
+      await loadAccounts();
+    } catch (err: any) {
+      addToast("error", err.message || "领取礼包失败");
+    }
+  };
+
   // 批量删除选中账号
   const handleBatchDelete = () => {
     if (selectedIds.size === 0) {
@@ -821,11 +1290,13 @@ function App() {
       type: "danger",
       onConfirm: async () => {
         try {
-          for (const id of selectedIds) {
-            await api.removeAccount(id);
-          }
+          const results = await api.batchAccountAction("remove", Array.from(selectedIds));
+          const removed = results.filter((r) => r.success).length;
           setSelectedIds(new Set());
-          addToast("success", `已删除 ${selectedIds.size} 个账号`);
+          addToast(
+            removed === results.length ? "success" : "warning",
+            `已删除 ${removed} 个账号` + (removed < results.length ? `，${results.length - removed} 个失败` : "")
+          );
           await loadAccounts();
         } catch (err: any) {
           addToast("error", err.message || "删除失败");
@@ -854,6 +1325,12 @@ function App() {
           </div>
         )}
 
+        {traeMissing && (
+          <div className="error-banner trae-missing-banner">
+            未检测到 Trae IDE：账号管理、使用量查询和注册可正常使用，切换账号等依赖 IDE 的功能已停用
+          </div>
+        )}
+
         {currentPage === "dashboard" && (
           <Dashboard 
             accounts={accounts} 
@@ -945,6 +1422,19 @@ function App() {
                           </svg>
                           刷新
                         </button>
+                        <button className="batch-btn" onClick={handleBatchClaimGift}>
+                          <svg
+                            viewBox="0 0 24 24"
+                            fill="none"
+                            stroke="currentColor"
+                            strokeWidth="2"
+                            width="14"
+                            height="14"
+                          >
+                            <path d="M20 12v10H4V12M2 7h20v5H2zM12 22V7M12 7H7.5a2.5 2.5 0 0 1 0-5C11 2 12 7 12 7zM12 7h4.5a2.5 2.5 0 0 0 0-5C13 2 12 7 12 7z"/>
+                          </svg>
+                          礼包
+                        </button>
                         <button className="batch-btn danger" onClick={handleBatchDelete}>
                           <svg
                             viewBox="0 0 24 24"
@@ -962,18 +1452,36 @@ function App() {
                     )}
                   </div>
                   <div className="toolbar-right">
-                    <button className="header-btn" onClick={handleImportAccounts} title="导入账号" style={{padding: '8px 14px'}}>
+                    <button className="header-btn" onClick={handleClaimGiftAll} title="为所有账号领取礼包（已领取过的跳过）" disabled={accounts.length === 0} style={{padding: '8px 14px'}}>
+                      <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" width="14" height="14">
+                        <path d="M20 12v10H4V12M2 7h20v5H2zM12 22V7M12 7H7.5a2.5 2.5 0 0 1 0-5C11 2 12 7 12 7zM12 7h4.5a2.5 2.5 0 0 0 0-5C13 2 12 7 12 7z"/>
+                      </svg>
+                      全部礼包
+                    </button>
+                    <button className="header-btn" onClick={() => handleImportAccounts()} title="导入账号" style={{padding: '8px 14px'}}>
                       <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" width="14" height="14">
                         <path d="M21 15v4a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2v-4M17 8l-5-5-5 5M12 3v12"/>
                       </svg>
                       导入
                     </button>
+                    <button className="header-btn" onClick={() => handleImportAccounts(true)} title="完整导入：保留 Token、密码和机器码，按 user_id 合并到已有账号" style={{padding: '8px 14px'}}>
+                      <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" width="14" height="14">
+                        <path d="M8 3H5a2 2 0 0 0-2 2v3M21 8V5a2 2 0 0 0-2-2h-3M3 16v3a2 2 0 0 0 2 2h3M16 21h3a2 2 0 0 0 2-2v-3M12 8v8M8 12h8"/>
+                      </svg>
+                      合并导入
+                    </button>
                     <button className="header-btn" onClick={handleExportAccounts} title="导出账号" disabled={accounts.length === 0} style={{padding: '8px 14px'}}>
                       <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" width="14" height="14">
                         <path d="M21 15v4a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2v-4M7 10l5 5 5-5M12 15V3"/>
                       </svg>
                       导出
                     </button>
+                    <button className="header-btn" onClick={handleExportPoolSummary} title="导出号池概览（Markdown，不含凭据）" disabled={accounts.length === 0} style={{padding: '8px 14px'}}>
+                      <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" width="14" height="14">
+                        <path d="M14 2H6a2 2 0 0 0-2 2v16a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V8zM14 2v6h6M8 13h8M8 17h8"/>
+                      </svg>
+                      概览
+                    </button>
                     <button className="add-btn" onClick={() => setShowAddModal(true)} style={{padding: '8px 16px', fontSize: '13px'}}>
                       <span>+</span> 添加账号
                     </button>
@@ -996,7 +1504,7 @@ function App() {
                     <button className="empty-btn primary" onClick={() => setShowAddModal(true)}>
                       添加账号
                     </button>
-                    <button className="empty-btn" onClick={handleImportAccounts}>
+                    <button className="empty-btn" onClick={() => handleImportAccounts()}>
                       导入账号
                     </button>
                   </div>
@@ -1047,6 +1555,7 @@ function App() {
             onToast={addToast}
             settings={appSettings}
             onSettingsChange={setAppSettings}
+            onAccountsRestored={loadAccounts}
           />
         )}
 
@@ -1070,6 +1579,16 @@ function App() {
         />
       )}
 
+      {/* 导出/导入密码弹窗 */}
+      <PassphraseModal
+        isOpen={!!passphraseModal}
+        mode={passphraseModal?.mode ?? "export"}
+        onClose={() => setPassphraseModal(null)}
+        onSubmit={async (passphrase) => {
+          await passphraseModal?.onSubmit(passphrase);
+        }}
+      />
+
       {/* 右键菜单 */}
       {/* 自动更新弹窗 */}
       <UpdateModal
@@ -1092,6 +1611,10 @@ function App() {
             handleSwitchAccount(contextMenu.accountId, { mode: "relogin" });
             setContextMenu(null);
           }}
+          onBrowserRelogin={() => {
+            void handleBrowserRelogin(contextMenu.accountId);
+            setContextMenu(null);
+          }}
           onViewDetail={() => {
             void handleViewDetail(contextMenu.accountId);
             setContextMenu(null);
@@ -1144,6 +1667,11 @@ function App() {
         account={detailAccount}
         usage={detailAccount?.usage || null}
         onUpdateCredentials={handleUpdateCredentials}
+        onUpdateAlias={handleUpdateAlias}
+        onUpdateTotpSecret={handleUpdateTotpSecret}
+        onCopyTotpCode={handleCopyTotpCode}
+        quotaThreshold={detailAccount ? appSettings?.quota_thresholds?.[detailAccount.id] ?? null : null}
+        onUpdateQuotaThreshold={handleUpdateQuotaThreshold}
       />
 
       <AccountLoginModal
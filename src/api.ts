@@ -1,5 +1,53 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Account, AccountBrief, AppSettings, UsageSummary, UsageEventsResponse, UserStatisticData } from "./types";
+import type {
+  Account,
+  AccountBrief,
+  AccountUsageResult,
+  AppSettings,
+  BackupEntry,
+  CleanupReport,
+  BatchAction,
+  BatchActionResult,
+  GiftClaimReport,
+  CookieEntry,
+  ImportMergeReport,
+  ImportPreview,
+  IntegrityIssue,
+  LatestRelease,
+  MaintenanceReport,
+  ProfileRefreshReport,
+  SinkKind,
+  RequestLogEntry,
+  RotationStatus,
+  SelfTestCheck,
+  SubscriptionInfo,
+  Promotion,
+  SystemProxy,
+  TrendRange,
+  TraeAuthSnapshot,
+  TraeInstallStatus,
+  CertificateFingerprint,
+  HealthReport,
+  Browser,
+  BrowserImportReport,
+  UsageSummary,
+  UsageAggregate,
+  UsageEventsResponse,
+  UsageTrendPoint,
+  StatisticPoint,
+  QuotaForecast,
+  UserStatisticData,
+  ValidationReport,
+  WeeklyReport,
+  WorkspaceMapping,
+  BatchRegisterReport,
+  RegisterSession,
+  CredentialRecord,
+  RegisterQueueStatus,
+  MailMessageDetail,
+  MailSummary,
+  TotpCode,
+} from "./types";
 
 function checkNetwork() {
   if (typeof navigator !== 'undefined' && !navigator.onLine) {
@@ -18,11 +66,88 @@ export async function addAccount(cookies: string): Promise<Account> {
 }
 
 // 添加账号（通过 Token，可选 Cookies）
-export async function addAccountByToken(token: string, cookies?: string): Promise<Account> {
+export async function addAccountByToken(
+  token: string,
+  cookies?: string,
+  options?: { verify?: boolean }
+): Promise<Account> {
+  if (options?.verify === false) {
+    return invoke("add_account_by_token", { token, cookies, verify: false });
+  }
   return invokeNetwork("add_account_by_token", { token, cookies });
 }
 
+// 添加账号（粘贴 Cookie 请求头或 cookies.txt 内容）
+export async function addAccountByCookieHeader(raw: string): Promise<Account> {
+  return invokeNetwork("add_account_by_cookie_header", { raw });
+}
+
+// 添加未验证账号（不调用 API，稍后校验）
+export async function addAccountUnverified(options: { token?: string; cookies?: string }): Promise<Account> {
+  return invoke("add_account_unverified", { token: options.token, cookies: options.cookies });
+}
+
+// 校验单个未验证账号
+export async function validateAccount(accountId: string): Promise<Account> {
+  return invokeNetwork("validate_account", { accountId });
+}
+
+// 校验所有未验证账号
+export async function validateUnverifiedAccounts(): Promise<ValidationReport> {
+  return invokeNetwork("validate_unverified_accounts");
+}
+
+// 刷新账号资料（名称/头像/区域/套餐）
+export async function refreshAccountProfile(accountId: string): Promise<Account> {
+  return invokeNetwork("refresh_account_profile", { accountId });
+}
+
+// 刷新所有账号资料
+export async function refreshAllAccountProfiles(): Promise<ProfileRefreshReport> {
+  return invokeNetwork("refresh_all_account_profiles");
+}
+
+// 对多个账号执行同一操作，返回每个账号的结果
+export async function batchAccountAction(action: BatchAction, ids: string[]): Promise<BatchActionResult[]> {
+  return invokeNetwork("batch_account_action", { action, ids });
+}
+
+// 为所有账号领取礼包，已领取过的账号跳过
+export async function claimGiftAll(): Promise<GiftClaimReport> {
+  return invokeNetwork("claim_gift_all");
+}
+
+// 执行一次完整维护（进度通过 maintenance_progress 事件推送）
+export async function runMaintenance(): Promise<MaintenanceReport> {
+  return invokeNetwork("run_maintenance");
+}
+
+// 立即生成账号周报
+export async function generateWeeklyReport(): Promise<WeeklyReport> {
+  return invokeNetwork("generate_weekly_report");
+}
+
+// 环境自检（注册页、临时邮箱、IDE 写入），registerUrl 可指定测试环境地址
+export async function selfTest(registerUrl?: string): Promise<SelfTestCheck[]> {
+  return invokeNetwork("self_test", { registerUrl: registerUrl || null });
+}
+
+// 向指定通知渠道发送测试通知
+export async function sendTestNotification(sink: SinkKind): Promise<void> {
+  return invokeNetwork("send_test_notification", { sink });
+}
+
+// 清理过期数据（旧安装包、临时文件、已删除账号的 WebView 数据、过期备份和日志）
+export async function runCleanup(): Promise<CleanupReport> {
+  return invoke("run_cleanup");
+}
+
 // 添加账号（通过邮箱密码登录）
+// 从已安装浏览器（Chrome/Edge/Firefox）读取 Trae Cookie 并创建账号
+export async function importBrowserCookies(browsers?: Browser[]): Promise<BrowserImportReport> {
+  return invokeNetwork("import_browser_cookies", { browsers });
+}
+
 export async function addAccountByEmail(email: string, password: string): Promise<Account> {
   return invokeNetwork("add_account_by_email", { email, password });
 }
@@ -34,19 +159,121 @@ export async function quickRegister(showWindow?: boolean): Promise<Account> {
   return invokeNetwork("quick_register");
 }
 
-export async function startBrowserLogin(): Promise<void> {
-  return invokeNetwork("start_browser_login");
+// 连续快速注册多个账号（parallel 为同时打开的窗口数）
+export async function quickRegisterBatch(count: number, showWindow: boolean, parallel?: number): Promise<BatchRegisterReport> {
+  return invokeNetwork("quick_register_batch", { count, showWindow, parallel: parallel ?? null });
+}
+
+// 使用自有邮箱注册（验证码通过 submitVerificationCode 提交）
+export async function quickRegisterWithEmail(email: string, showWindow: boolean): Promise<Account> {
+  return invokeNetwork("quick_register_with_email", { email, showWindow });
+}
+
+// 提交自有邮箱注册收到的验证码
+export async function submitVerificationCode(windowLabel: string, code: string): Promise<void> {
+  return invoke("submit_verification_code", { windowLabel, code });
+}
+
+// 应用中途退出时未完成的注册
+export async function listInterruptedRegistrations(): Promise<RegisterSession[]> {
+  return invoke("list_interrupted_registrations");
+}
+
+// 继续中断的注册
+export async function resumeRegistration(sessionId: string, showWindow: boolean): Promise<Account> {
+  return invokeNetwork("resume_registration", { sessionId, showWindow });
+}
+
+// 放弃中断的注册
+export async function discardRegistration(sessionId: string): Promise<void> {
+  return invoke("discard_registration", { sessionId });
+}
+
+// 读取注册凭据日志（最新的在前）
+export async function getCredentialJournal(): Promise<CredentialRecord[]> {
+  return invoke("get_credential_journal");
+}
+
+// 列出临时邮箱中的邮件
+export async function listMailboxMessages(email: string): Promise<MailSummary[]> {
+  return invokeNetwork("list_mailbox_messages", { email });
+}
+
+// 读取临时邮箱中的一封邮件
+export async function getMailboxMessage(email: string, id: string): Promise<MailMessageDetail> {
+  return invokeNetwork("get_mailbox_message", { email, id });
+}
+
+// 向注册队列添加快速注册任务
+export async function enqueueRegisterJobs(count: number, showWindow: boolean): Promise<RegisterQueueStatus> {
+  return invoke("enqueue_register_jobs", { count, showWindow });
+}
+
+// 获取注册队列状态
+export async function getRegisterQueue(): Promise<RegisterQueueStatus> {
+  return invoke("get_register_queue");
+}
+
+// 暂停注册队列（正在执行的任务会继续完成）
+export async function pauseRegisterQueue(): Promise<RegisterQueueStatus> {
+  return invoke("pause_register_queue");
+}
+
+// 继续执行注册队列
+export async function resumeRegisterQueue(): Promise<RegisterQueueStatus> {
+  return invoke("resume_register_queue");
+}
+
+// 取消注册任务
+export async function cancelRegisterJob(jobId: number): Promise<RegisterQueueStatus> {
+  return invoke("cancel_register_job", { jobId });
+}
+
+// 移除注册队列中已结束的任务
+export async function clearRegisterQueue(): Promise<RegisterQueueStatus> {
+  return invoke("clear_register_queue");
+}
+
+// 重新执行快速注册中失败的步骤
+export async function retryQuickRegisterStep(windowLabel: string): Promise<void> {
+  return invoke("retry_quick_register_step", { windowLabel });
+}
+
+// 放弃步骤失败的快速注册
+export async function abortQuickRegister(windowLabel: string): Promise<void> {
+  return invoke("abort_quick_register", { windowLabel });
+}
+
+// incognito 为 false 时保留 Google / GitHub 等第三方网站的登录状态（默认每次使用空白的浏览器数据）
+// startUrl 为登录窗口的起始页，未指定时使用设置中的起始页
+export async function startBrowserLogin(incognito = true, startUrl?: string): Promise<void> {
+  return invokeNetwork("start_browser_login", { incognito, startUrl });
+}
+
+// 在浏览器窗口中重新登录已有账号，完成后（finishBrowserLogin）更新该账号的登录信息
+export async function startBrowserRelogin(accountId: string): Promise<void> {
+  return invokeNetwork("start_browser_relogin", { accountId });
 }
 
 export async function finishBrowserLogin(): Promise<Account> {
   return invokeNetwork("finish_browser_login");
 }
 
+// 延长浏览器登录的等待时间（默认 2 分钟）
+export async function extendBrowserLogin(secs?: number): Promise<void> {
+  return invoke("extend_browser_login", { secs });
+}
+
 export async function cancelBrowserLogin(): Promise<void> {
   return invoke("cancel_browser_login");
 }
 
-// 下载并运行更新安装包（Windows: .msi）
+// 查询最新版本及适用于当前系统架构的安装包
+export async function checkForUpdate(): Promise<LatestRelease> {
+  return invokeNetwork("check_for_update");
+}
+
+// 下载并运行更新安装包（会校验是否匹配当前系统架构）
 export async function downloadAndRunInstaller(url: string): Promise<string> {
   return invokeNetwork("download_and_run_installer", { url });
 }
@@ -82,9 +309,34 @@ export async function switchAccount(
   return invoke("switch_account", { accountId, force: options?.force });
 }
 
-// 获取账号使用量
-export async function getAccountUsage(accountId: string): Promise<UsageSummary> {
-  return invokeNetwork("get_account_usage", { accountId });
+// 获取账号使用量（缓存未过期时返回缓存，force 为 true 时强制重新查询）
+export async function getAccountUsage(accountId: string, options?: { force?: boolean }): Promise<UsageSummary> {
+  return invokeNetwork("get_account_usage", { accountId, force: options?.force ?? null });
+}
+
+// 并发获取所有已验证账号的使用量（每个账号完成时会推送 account_usage 事件）
+export async function getAllAccountsUsage(options?: { force?: boolean }): Promise<AccountUsageResult[]> {
+  return invokeNetwork("get_all_accounts_usage", { force: options?.force ?? null });
+}
+
+// 获取账号订阅信息（续费时间、自动续费、付款记录）
+export async function getSubscriptionInfo(accountId: string): Promise<SubscriptionInfo> {
+  return invokeNetwork("get_subscription_info", { accountId });
+}
+
+// 汇总所有账号的快速请求用量（按套餐分组）
+export async function getUsageAggregate(options?: { force?: boolean }): Promise<UsageAggregate> {
+  return invokeNetwork("get_usage_aggregate", { force: options?.force ?? null });
+}
+
+// 获取账号最近的 API 请求记录（仅保存在内存中）
+export async function getAccountRequestLog(accountId: string): Promise<RequestLogEntry[]> {
+  return invoke("get_account_request_log", { accountId });
+}
+
+// 获取账号每日消耗趋势
+export async function getUsageTrend(accountId: string, range: TrendRange): Promise<UsageTrendPoint[]> {
+  return invoke("get_usage_trend", { accountId, range });
 }
 
 // 更新账号 Token
@@ -111,32 +363,217 @@ export async function loginAccountWithEmail(
 
 export async function updateAccountProfile(
   accountId: string,
-  updates: { email?: string | null; password?: string | null }
+  updates: {
+    email?: string | null;
+    password?: string | null;
+    region?: string | null;
+    apiHost?: string | null;
+    locale?: string | null;
+  }
 ): Promise<Account> {
   return invokeNetwork("update_account_profile", {
     accountId,
     email: updates.email ?? null,
     password: updates.password ?? null,
+    region: updates.region ?? null,
+    apiHost: updates.apiHost ?? null,
+    locale: updates.locale ?? null,
   });
 }
 
+// 获取账号 Cookies 的各个条目
+export async function getAccountCookiesParsed(accountId: string): Promise<CookieEntry[]> {
+  return invoke("get_account_cookies_parsed", { accountId });
+}
+
+// 修改账号的单个 Cookie（值为空时删除），保存前会校验 Cookies 是否仍然有效
+export async function setAccountCookie(accountId: string, name: string, value: string): Promise<CookieEntry[]> {
+  return invokeNetwork("set_account_cookie", { accountId, name, value });
+}
+
 // 更新 Cookies
 export async function updateCookies(accountId: string, cookies: string): Promise<void> {
   return invokeNetwork("update_cookies", { accountId, cookies });
 }
 
-// 导出账号
-export async function exportAccounts(): Promise<string> {
-  return invoke("export_accounts");
+// 获取账号数据完整性问题（无问题时返回 null）
+export async function getIntegrityStatus(): Promise<IntegrityIssue | null> {
+  return invoke("get_integrity_status");
+}
+
+// 账号数据是否处于只读模式（另一个实例正在使用）
+export async function getStoreReadOnly(): Promise<boolean> {
+  return invoke("get_store_read_only");
+}
+
+// 重新尝试获取数据锁，返回是否仍为只读
+export async function retryStoreLock(): Promise<boolean> {
+  return invoke("retry_store_lock");
+}
+
+// 获取所有工作区映射
+export async function listWorkspaceMappings(): Promise<WorkspaceMapping[]> {
+  return invoke("list_workspace_mappings");
+}
+
+// 添加或更新工作区映射
+export async function setWorkspaceMapping(
+  folder: string,
+  accountId: string,
+  autoSwitch = false
+): Promise<WorkspaceMapping> {
+  return invoke("set_workspace_mapping", { folder, accountId, autoSwitch });
+}
+
+// 删除工作区映射
+export async function removeWorkspaceMapping(mappingId: string): Promise<void> {
+  return invoke("remove_workspace_mapping", { mappingId });
+}
+
+// 获取 Trae IDE 当前打开的工作区
+export async function getTraeOpenWorkspaces(): Promise<string[]> {
+  return invoke("get_trae_open_workspaces");
+}
+
+// 读取 Trae IDE 当前的登录信息（只读，敏感字段已打码）
+export async function getTraeAuthSnapshot(): Promise<TraeAuthSnapshot> {
+  return invoke("get_trae_auth_snapshot");
+}
+
+// 立即备份账号数据和设置
+export async function createBackup(): Promise<BackupEntry> {
+  return invoke("create_backup");
+}
+
+// 列出所有备份
+export async function listBackups(): Promise<BackupEntry[]> {
+  return invoke("list_backups");
+}
+
+// 从指定备份恢复（恢复前会先备份当前数据）
+export async function restoreBackup(backupId: string, restoreSettings = false): Promise<number> {
+  return invoke("restore_backup", { backupId, restoreSettings });
+}
+
+// 上传备份到云端（不传 ID 时上传最近一个备份），返回远程位置
+export async function uploadBackup(backupId?: string): Promise<string> {
+  return invokeNetwork("upload_backup", { backupId: backupId ?? null });
+}
+
+// 从备份恢复账号数据（不传路径时使用最近一个有效备份）
+export async function restoreIntegrityBackup(backupPath?: string): Promise<number> {
+  return invoke("restore_integrity_backup", { backupPath: backupPath ?? null });
+}
+
+// 信任当前账号数据并重新签名
+export async function dismissIntegrityIssue(): Promise<void> {
+  return invoke("dismiss_integrity_issue");
+}
+
+// 信任外部对账号数据文件的修改并重新签名，返回账号数量
+export async function trustExternalAccountsChange(): Promise<number> {
+  return invoke("trust_external_accounts_change");
+}
+
+// 导出账号（提供 passphrase 时加密，否则需显式传 plaintext: true）
+export interface ExportOptions {
+  passphrase?: string;
+  plaintext?: boolean;
+}
+
+export async function exportAccounts(options?: ExportOptions): Promise<string> {
+  return invoke("export_accounts", {
+    passphrase: options?.passphrase ?? null,
+    plaintext: options?.plaintext ?? null,
+  });
+}
+
+export async function exportAccountsToPath(path: string, options?: ExportOptions): Promise<void> {
+  return invoke("export_accounts_to_path", {
+    path,
+    passphrase: options?.passphrase ?? null,
+    plaintext: options?.plaintext ?? null,
+  });
+}
+
+// 导出号池概览 Markdown（不含凭据），返回账号数
+export async function exportPoolSummaryMd(path: string): Promise<number> {
+  return invokeNetwork("export_pool_summary_md", { path });
+}
+
+// 设置账号的两步验证密钥（Base32 密钥或 otpauth:// 链接），传 null 或空字符串清除
+export async function updateAccountTotpSecret(accountId: string, secret: string | null): Promise<Account> {
+  return invoke("update_account_totp_secret", { accountId, secret });
+}
+
+// 生成账号当前的两步验证码
+export async function getAccountTotpCode(accountId: string): Promise<TotpCode> {
+  return invoke("get_account_totp_code", { accountId });
+}
+
+// 设置账号本地显示名称，传 null 或空字符串恢复服务器名称
+export async function updateAccountAlias(accountId: string, alias: string | null): Promise<Account> {
+  return invoke("update_account_alias", { accountId, alias });
+}
+
+// 开始账号轮换（使用设置中的轮换池和时间表）
+export async function startRotation(): Promise<RotationStatus> {
+  return invoke("start_rotation");
+}
+
+// 停止账号轮换
+export async function stopRotation(): Promise<RotationStatus> {
+  return invoke("stop_rotation");
+}
+
+// 获取账号轮换状态
+export async function getRotationStatus(): Promise<RotationStatus> {
+  return invoke("get_rotation_status");
+}
+
+// 设置账号剩余额度提醒阈值（null 表示不监控），返回更新后的设置
+export async function setQuotaThreshold(accountId: string, threshold: number | null): Promise<AppSettings> {
+  return invoke("set_quota_threshold", { accountId, threshold });
+}
+
+// 更新账号标签和备注
+export async function updateAccountNotes(accountId: string, tags: string[], notes: string | null): Promise<Account> {
+  return invoke("update_account_notes", { accountId, tags, notes });
 }
 
-export async function exportAccountsToPath(path: string): Promise<void> {
-  return invoke("export_accounts_to_path", { path });
+// 完整导入：保留 Token、密码、机器码等全部字段，按 user_id 合并到已有账号
+export async function importAccountsMerge(data: string, passphrase?: string): Promise<ImportMergeReport> {
+  return invoke("import_accounts_merge", { data, passphrase: passphrase ?? null });
+}
+
+// 判断导入文件是否为加密导出格式
+export function isEncryptedExport(data: string): boolean {
+  try {
+    return JSON.parse(data)?.format === "trae-account-manager/encrypted";
+  } catch {
+    return false;
+  }
+}
+
+// 预览导入结果（不写入数据，verify 为 true 时逐个通过 API 校验）
+export async function previewImport(
+  data: string,
+  options?: { verify?: boolean; passphrase?: string }
+): Promise<ImportPreview> {
+  const args = { data, verify: options?.verify ?? false, passphrase: options?.passphrase ?? null };
+  return options?.verify ? invokeNetwork("preview_import", args) : invoke("preview_import", args);
 }
 
 // 导入账号
-export async function importAccounts(data: string): Promise<number> {
-  return invoke("import_accounts", { data });
+export async function importAccounts(
+  data: string,
+  options?: { deferValidation?: boolean; passphrase?: string }
+): Promise<number> {
+  return invoke("import_accounts", {
+    data,
+    deferValidation: options?.deferValidation,
+    passphrase: options?.passphrase ?? null,
+  });
 }
 
 export async function clearAccounts(): Promise<number> {
@@ -151,6 +588,41 @@ export async function updateSettings(settings: AppSettings): Promise<AppSettings
   return invoke("update_settings", { settings });
 }
 
+// 检测系统代理设置
+export async function getSystemProxy(): Promise<SystemProxy> {
+  return invoke("get_system_proxy");
+}
+
+// 获取 Trae 各端点当前的证书指纹（用于证书固定）
+export async function getApiCertificateFingerprints(): Promise<CertificateFingerprint[]> {
+  return invokeNetwork("get_api_certificate_fingerprints");
+}
+
+// 检测 Trae 接口和临时邮箱服务的可达性和延迟
+export async function checkApiHealth(): Promise<HealthReport> {
+  return invokeNetwork("check_api_health");
+}
+
+// 获取调试日志文件路径
+export async function getApiDebugLogPath(): Promise<string> {
+  return invoke("get_api_debug_log_path");
+}
+
+// 清空调试日志
+export async function clearApiDebugLog(): Promise<void> {
+  return invoke("clear_api_debug_log");
+}
+
+// 获取窗口控制台日志文件路径
+export async function getWebviewConsoleLogPath(): Promise<string> {
+  return invoke("get_webview_console_log_path");
+}
+
+// 清空窗口控制台日志
+export async function clearWebviewConsoleLog(): Promise<void> {
+  return invoke("clear_webview_console_log");
+}
+
 // 获取使用事件
 export async function getUsageEvents(
   accountId: string,
@@ -168,6 +640,11 @@ export async function getUsageEvents(
   });
 }
 
+// 导出时间范围内的全部使用事件为 CSV，返回事件数
+export async function exportUsageEvents(accountId: string, startTime: number, endTime: number, path: string): Promise<number> {
+  return invokeNetwork("export_usage_events", { accountId, startTime, endTime, path });
+}
+
 // 从 Trae IDE 读取当前登录账号
 export async function readTraeAccount(): Promise<Account | null> {
   return invoke("read_trae_account");
@@ -214,6 +691,11 @@ export async function clearTraeLoginState(): Promise<void> {
 
 // ============ Trae IDE 路径相关 API ============
 
+// 检测 Trae IDE 安装状态
+export async function getTraeInstallStatus(): Promise<TraeInstallStatus> {
+  return invoke("get_trae_install_status");
+}
+
 // 获取保存的 Trae IDE 路径
 export async function getTraePath(): Promise<string> {
   return invoke("get_trae_path");
@@ -236,9 +718,29 @@ export async function claimGift(accountId: string): Promise<void> {
   return invokeNetwork("claim_gift", { accountId });
 }
 
+// 列出账号当前可领取的活动礼包
+export async function listPromotions(accountId: string): Promise<Promotion[]> {
+  return invokeNetwork("list_promotions", { accountId });
+}
+
+// 领取指定活动礼包
+export async function claimPromotion(accountId: string, promoId: string): Promise<void> {
+  return invokeNetwork("claim_promotion", { accountId, promoId });
+}
+
 // 获取用户统计数据
-export async function getUserStatistics(accountId: string): Promise<UserStatisticData> {
-  return invokeNetwork("get_user_statistics", { accountId });
+export async function getUserStatistics(accountId: string, options?: { force?: boolean }): Promise<UserStatisticData> {
+  return invokeNetwork("get_user_statistics", { accountId, force: options?.force ?? null });
+}
+
+// 按使用量历史估算平均每日消耗和预计耗尽时间
+export async function estimateQuotaExhaustion(accountId: string): Promise<QuotaForecast> {
+  return invoke("estimate_quota_exhaustion", { accountId });
+}
+
+// 获取账号每天的统计数据采样及变化量
+export async function getStatisticsHistory(accountId: string, range: TrendRange): Promise<StatisticPoint[]> {
+  return invoke("get_statistics_history", { accountId, range });
 }
 
 // 打开购买页面（内置浏览器，携带账号 Cookies）
@@ -1,4 +1,4 @@
-import type { UsageSummary } from "../types";
+import type { AccountError, UsageSummary } from "../types";
 
 interface AccountCardProps {
   account: {
@@ -9,6 +9,12 @@ interface AccountCardProps {
     plan_type: string;
     created_at: number;
     is_current?: boolean;
+    last_error?: AccountError | null;
+    token_expires_at?: number | null;
+    token_expiring?: boolean;
+    plan_expires_at?: number | null;
+    plan_expiring?: boolean;
+    archived_at?: number | null;
   };
   usage: UsageSummary | null;
   selected: boolean;
@@ -50,7 +56,7 @@ export function AccountCard({ account, usage, selected, onSelect, onContextMenu
   const usagePercent = totalLimit > 0 ? Math.round((totalUsed / totalLimit) * 100) : 0;
   const usageLevel = getUsageLevel(totalUsed, totalLimit);
 
-  const isTokenExpired = false; // TODO: 根据实际 token 过期时间判断
+  const isTokenExpired = !!account.token_expires_at && account.token_expires_at * 1000 < Date.now();
 
   const handleCopy = (e: React.MouseEvent) => {
     e.stopPropagation();
@@ -122,6 +128,38 @@ export function AccountCard({ account, usage, selected, onSelect, onContextMenu
             礼包
           </span>
         )}
+        {account.last_error && (
+          <span
+            className="tag error"
+            title={`${account.last_error.message}（${new Date(account.last_error.occurred_at * 1000).toLocaleString()}）`}
+          >
+            错误
+          </span>
+        )}
+        {account.token_expiring && !isTokenExpired && account.token_expires_at && (
+          <span
+            className="tag extra"
+            title={`Token 将于 ${new Date(account.token_expires_at * 1000).toLocaleString()} 过期`}
+          >
+            即将过期
+          </span>
+        )}
+        {account.plan_expiring && account.plan_expires_at && (
+          <span
+            className="tag extra"
+            title={`${account.plan_type} 套餐将于 ${new Date(account.plan_expires_at * 1000).toLocaleString()} 到期，未开启自动续费`}
+          >
+            套餐到期
+          </span>
+        )}
+        {account.archived_at && (
+          <span
+            className="tag extra"
+            title={`额度用完，已于 ${new Date(account.archived_at * 1000).toLocaleString()} 自动归档，不参与自动切换`}
+          >
+            已归档
+          </span>
+        )}
         {account.is_current && (
           <span className="tag current">
             <svg width="12" height="12" viewBox="0 0 24 24" fill="currentColor">
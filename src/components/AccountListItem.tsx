@@ -8,6 +8,8 @@ interface AccountListItemProps {
     avatar_url: string;
     plan_type: string;
     created_at: number;
+    token_expires_at?: number | null;
+    token_expiring?: boolean;
   };
   usage: UsageSummary | null;
   selected: boolean;
@@ -43,7 +45,7 @@ export function AccountListItem({ account, usage, selected, onSelect, onContextM
     return `${Math.floor(diffDays / 365)}年前`;
   };
 
-  const isTokenExpired = false; // TODO: 根据实际 token 过期时间判断
+  const isTokenExpired = !!account.token_expires_at && account.token_expires_at * 1000 < Date.now();
 
   return (
     <div
@@ -110,7 +112,7 @@ export function AccountListItem({ account, usage, selected, onSelect, onContextM
 
       <div className="list-item-status">
         <span className={`status-dot ${isTokenExpired ? "expired" : "normal"}`}></span>
-        <span>{isTokenExpired ? "过期" : "正常"}</span>
+        <span>{isTokenExpired ? "过期" : account.token_expiring ? "即将过期" : "正常"}</span>
       </div>
 
       <div className="list-item-actions">
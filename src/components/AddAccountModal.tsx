@@ -1,6 +1,12 @@
 import { useRef, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
 import * as api from "../api";
-import type { Account } from "../types";
+import { InterruptedRegistrations } from "./InterruptedRegistrations";
+import { OwnEmailRegister } from "./OwnEmailRegister";
+import { RegisterProgressBar } from "./RegisterProgressBar";
+import { RegisterQueue } from "./RegisterQueue";
+import { RegisterStepFailures } from "./RegisterStepFailures";
+import type { Account, BatchRegisterProgress, BrowserLoginProgress, BrowserLoginWaiting } from "../types";
 
 interface AddAccountModalProps {
   isOpen: boolean;
@@ -25,6 +31,13 @@ export function AddAccountModal({
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState("");
   const browserRunRef = useRef(0);
+  const [registerCount, setRegisterCount] = useState(1);
+  const [registerParallel, setRegisterParallel] = useState(1);
+  const [batchProgress, setBatchProgress] = useState<BatchRegisterProgress | null>(null);
+  const [cookieInput, setCookieInput] = useState("");
+  const [keepThirdPartyLogin, setKeepThirdPartyLogin] = useState(false);
+  const [loginWaiting, setLoginWaiting] = useState<BrowserLoginWaiting | null>(null);
+  const [loginProgress, setLoginProgress] = useState<BrowserLoginProgress | null>(null);
 
   if (!isOpen) return null;
 
@@ -53,13 +66,21 @@ export function AddAccountModal({
     setError("");
     const runId = browserRunRef.current + 1;
     browserRunRef.current = runId;
+    setLoginProgress(null);
+    // 窗口打开事件在 startBrowserLogin 返回前发出，需提前监听
+    const unlistenProgress = await listen<BrowserLoginProgress>("browser_login_progress", (event) => {
+      if (browserRunRef.current === runId) setLoginProgress(event.payload);
+    }).catch(() => undefined);
 
     try {
-      await api.startBrowserLogin();
+      await api.startBrowserLogin(!keepThirdPartyLogin);
       setBrowserStarted(true);
       setBrowserWaiting(true);
       onToast?.("info", "已打开登录窗口，完成登录后将自动导入。");
       void (async () => {
+        const unlisten = await listen<BrowserLoginWaiting>("browser_login_waiting", (event) => {
+          if (browserRunRef.current === runId) setLoginWaiting(event.payload);
+        }).catch(() => undefined);
         try {
           const account = await api.finishBrowserLogin();
           if (browserRunRef.current !== runId) return;
@@ -69,7 +90,7 @@ export function AddAccountModal({
         } catch (err: any) {
           if (browserRunRef.current !== runId) return;
           
-          if (err.message && err.message.includes("浏览器被主动关闭")) {
+          if (err.code === "cancelled") {
             setBrowserStarted(false);
             setBrowserWaiting(false);
             onToast?.("error", "导入失败,浏览器被主动关闭");
@@ -78,12 +99,16 @@ export function AddAccountModal({
 
           setError(err.message || "等待浏览器登录失败");
         } finally {
+          unlisten?.();
+          unlistenProgress?.();
           if (browserRunRef.current === runId) {
             setBrowserWaiting(false);
+            setLoginWaiting(null);
           }
         }
       })();
     } catch (err: any) {
+      unlistenProgress?.();
       if (browserRunRef.current === runId) {
         setError(err.message || "打开浏览器登录窗口失败");
         setBrowserStarted(false);
@@ -94,6 +119,64 @@ export function AddAccountModal({
     }
   };
 
+  const handleExtendBrowserLogin = async () => {
+    try {
+      await api.extendBrowserLogin();
+      onToast?.("info", "已延长登录等待时间 2 分钟");
+    } catch (err: any) {
+      setError(err.message || "延长等待时间失败");
+    }
+  };
+
+  const formatRemaining = (secs: number) =>
+    `${Math.floor(secs / 60)}:${String(secs % 60).padStart(2, "0")}`;
+
+  // 粘贴开发者工具中复制的 Cookie 请求头或 cookies.txt
+  const handleImportCookieHeader = async () => {
+    if (!cookieInput.trim()) return;
+    setLoading(true);
+    setError("");
+
+    try {
+      const account = await api.addAccountByCookieHeader(cookieInput);
+      onToast?.("success", `成功添加账号: ${account.email}`);
+      onAccountAdded?.(account);
+      setCookieInput("");
+      handleClose();
+    } catch (err: any) {
+      setError(err.message || "导入 Cookie 失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  // 直接读取已安装浏览器中的登录状态
+  const handleImportFromBrowsers = async () => {
+    setLoading(true);
+    setError("");
+
+    try {
+      const report = await api.importBrowserCookies();
+      report.imported.forEach((account) => onAccountAdded?.(account));
+      if (report.imported.length > 0) {
+        onToast?.(
+          "success",
+          `已从浏览器导入 ${report.imported.length} 个账号` +
+            (report.skipped.length > 0 ? `，${report.skipped.length} 个已存在` : "")
+        );
+        handleClose();
+      } else if (report.failed.length > 0) {
+        setError(report.failed.map((f) => `${f.browser} (${f.profile}): ${f.error}`).join("；"));
+      } else {
+        setError("浏览器中的账号均已存在");
+      }
+    } catch (err: any) {
+      setError(err.message || "读取浏览器 Cookie 失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
   const handleBrowserCancel = async () => {
     browserRunRef.current += 1;
     try {
@@ -101,10 +184,15 @@ export function AddAccountModal({
     } catch {} finally {
       setBrowserStarted(false);
       setBrowserWaiting(false);
+      setLoginWaiting(null);
     }
   };
 
   const handleQuickRegister = async () => {
+    if (registerCount > 1) {
+      await handleQuickRegisterBatch();
+      return;
+    }
     setLoading(true);
     setError("");
 
@@ -120,6 +208,43 @@ export function AddAccountModal({
     }
   };
 
+  const handleQuickRegisterBatch = async () => {
+    setLoading(true);
+    setError("");
+    setBatchProgress(null);
+    const unlisten = await listen<BatchRegisterProgress>("quick_register_batch_progress", (event) => {
+      setBatchProgress(event.payload);
+    }).catch(() => undefined);
+
+    try {
+      const report = await api.quickRegisterBatch(registerCount, quickRegisterShowWindow, registerParallel);
+      report.accounts.forEach((account) => onAccountAdded?.(account));
+      if (report.failures.length === 0) {
+        onToast?.("success", `批量注册完成，已导入 ${report.accounts.length} 个账号`);
+        handleClose();
+      } else {
+        onToast?.("warning", `已导入 ${report.accounts.length} 个账号，${report.failures.length} 个失败`);
+        setError(report.failures.join("；"));
+      }
+    } catch (err: any) {
+      setError(err.message || "批量注册失败");
+    } finally {
+      unlisten?.();
+      setBatchProgress(null);
+      setLoading(false);
+    }
+  };
+
+  const handleEnqueueRegister = async () => {
+    setError("");
+    try {
+      await api.enqueueRegisterJobs(registerCount, quickRegisterShowWindow);
+      onToast?.("info", `已添加 ${registerCount} 个注册任务到队列，可关闭窗口在后台完成`);
+    } catch (err: any) {
+      setError(err.message || "添加注册任务失败");
+    }
+  };
+
   const handleClose = () => {
     browserRunRef.current += 1;
     setError("");
@@ -211,8 +336,55 @@ export function AddAccountModal({
               </svg>
               <h3>使用浏览器登录并自动导入</h3>
               <p>在新窗口完成登录后，系统会自动获取账号信息</p>
+              <p>也可以直接读取 Chrome / Edge / Firefox 中已登录的 trae.ai 会话</p>
             </div>
 
+            {!browserStarted && (
+              <div style={{ display: 'flex', gap: '8px', marginBottom: '12px' }}>
+                <textarea
+                  className="setting-input"
+                  style={{ flex: 1, minHeight: '56px', resize: 'vertical', fontFamily: 'monospace', fontSize: '12px' }}
+                  placeholder="或粘贴 Cookie 请求头 / cookies.txt 内容"
+                  value={cookieInput}
+                  onChange={(e) => setCookieInput(e.target.value)}
+                  disabled={loading}
+                />
+                <button type="button" onClick={handleImportCookieHeader} disabled={loading || !cookieInput.trim()}>
+                  导入 Cookie
+                </button>
+              </div>
+            )}
+
+            {!browserStarted && (
+              <label
+                style={{ display: 'flex', alignItems: 'center', gap: '6px', marginBottom: '12px', fontSize: '12px', color: 'var(--text-muted)' }}
+                title="登录窗口使用共享的浏览器数据，下次登录时无需重新登录 Google / GitHub；Trae 的登录状态每次都会清除"
+              >
+                <input
+                  type="checkbox"
+                  checked={keepThirdPartyLogin}
+                  onChange={(e) => setKeepThirdPartyLogin(e.target.checked)}
+                  disabled={loading}
+                />
+                保留 Google / GitHub 登录状态
+              </label>
+            )}
+
+            {browserWaiting && loginWaiting && (
+              <div style={{ display: 'flex', alignItems: 'center', gap: '8px', marginBottom: '12px', fontSize: '12px', color: 'var(--text-muted)' }}>
+                <span style={{ flex: 1, textAlign: 'left', overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }} title={loginWaiting.url ?? undefined}>
+                  剩余 {formatRemaining(loginWaiting.remaining_secs)}
+                  {loginProgress
+                    ? ` · ${loginProgress.message}${loginProgress.email ? `（${loginProgress.email}）` : ""}`
+                    : ""}
+                  {loginWaiting.url && ` · ${loginWaiting.url}`}
+                </span>
+                <button type="button" onClick={handleExtendBrowserLogin}>
+                  延长 2 分钟
+                </button>
+              </div>
+            )}
+
             {error && <div className="error-message">{error}</div>}
 
             <div className="modal-actions">
@@ -220,9 +392,14 @@ export function AddAccountModal({
                 取消
               </button>
               {!browserStarted ? (
-                <button type="button" className="primary" onClick={handleBrowserStart} disabled={loading}>
-                  {loading ? "正在打开..." : "打开登录窗口"}
-                </button>
+                <>
+                  <button type="button" onClick={handleImportFromBrowsers} disabled={loading}>
+                    从已安装浏览器读取
+                  </button>
+                  <button type="button" className="primary" onClick={handleBrowserStart} disabled={loading}>
+                    {loading ? "处理中..." : "打开登录窗口"}
+                  </button>
+                </>
               ) : (
                 <>
                   <button type="button" onClick={handleBrowserCancel} disabled={loading}>
@@ -246,14 +423,80 @@ export function AddAccountModal({
               <p>系统自动生成邮箱完成注册，并导入到列表</p>
             </div>
 
+            <div style={{ display: 'flex', gap: '12px', justifyContent: 'center', alignItems: 'center', marginBottom: '12px', fontSize: '13px' }}>
+              <label>
+                注册数量{" "}
+                <input
+                  type="number"
+                  className="setting-input"
+                  min={1}
+                  max={50}
+                  value={registerCount}
+                  onChange={(e) => setRegisterCount(Math.min(50, Math.max(1, Number(e.target.value) || 1)))}
+                  disabled={loading}
+                  style={{ width: '64px' }}
+                />
+              </label>
+              {registerCount > 1 && (
+                <label>
+                  同时注册{" "}
+                  <select
+                    className="setting-select"
+                    value={registerParallel}
+                    onChange={(e) => setRegisterParallel(Number(e.target.value))}
+                    disabled={loading}
+                  >
+                    <option value={1}>1 个</option>
+                    <option value={2}>2 个</option>
+                    <option value={3}>3 个</option>
+                  </select>
+                </label>
+              )}
+            </div>
+            <RegisterProgressBar />
+            {batchProgress && (
+              <div style={{ textAlign: 'center', fontSize: '12px', color: 'var(--text-muted)', marginBottom: '12px' }}>
+                已完成 {batchProgress.completed} / {batchProgress.total}
+                {batchProgress.success ? `（${batchProgress.email} 已导入）` : `（第 ${batchProgress.index + 1} 个失败：${batchProgress.error}）`}
+              </div>
+            )}
+
+            <OwnEmailRegister
+              showWindow={quickRegisterShowWindow}
+              disabled={loading}
+              onBusyChange={setLoading}
+              onSuccess={(account) => {
+                onToast?.("success", `注册成功，已导入账号: ${account.email}`);
+                onAccountAdded?.(account);
+                handleClose();
+              }}
+              onError={setError}
+            />
+            <InterruptedRegistrations
+              showWindow={quickRegisterShowWindow}
+              disabled={loading}
+              onBusyChange={setLoading}
+              onSuccess={(account) => {
+                onToast?.("success", `注册成功，已导入账号: ${account.email}`);
+                onAccountAdded?.(account);
+                handleClose();
+              }}
+              onError={setError}
+            />
+            <RegisterStepFailures onError={setError} />
+            <RegisterQueue onError={setError} />
+
             {error && <div className="error-message">{error}</div>}
 
             <div className="modal-actions">
               <button type="button" onClick={handleClose} disabled={loading}>
                 取消
               </button>
+              <button type="button" onClick={handleEnqueueRegister} disabled={loading}>
+                加入队列
+              </button>
               <button type="button" className="primary" onClick={handleQuickRegister} disabled={loading}>
-                {loading ? "注册中..." : "快速注册并导入"}
+                {loading ? "注册中..." : registerCount > 1 ? `批量注册 ${registerCount} 个` : "快速注册并导入"}
               </button>
             </div>
           </div>
@@ -5,6 +5,7 @@ interface ContextMenuProps {
   y: number;
   onClose: () => void;
   onRelogin: () => void;
+  onBrowserRelogin: () => void;
   onViewDetail: () => void;
   onRefresh: () => void;
   onUpdateToken: () => void;
@@ -21,6 +22,7 @@ export function ContextMenu({
   y,
   onClose,
   onRelogin,
+  onBrowserRelogin,
   onViewDetail,
   onRefresh,
   onUpdateToken,
@@ -79,6 +81,10 @@ export function ContextMenu({
           <span className="icon">🔐</span>
           更新 Token
         </div>
+        <div className="context-menu-item" onClick={onBrowserRelogin}>
+          <span className="icon">🌐</span>
+          浏览器重新登录
+        </div>
         <div className="context-menu-item" onClick={onCopyToken}>
           <span className="icon">🔑</span>
           复制 Token
@@ -0,0 +1,147 @@
+import { useEffect, useState } from "react";
+import * as api from "../api";
+import type { CookieEntry } from "../types";
+
+interface CookieEditorProps {
+  accountId: string;
+}
+
+const inputStyle = {
+  padding: '6px 10px',
+  borderRadius: '6px',
+  border: '1px solid var(--border)',
+  background: 'var(--bg-input)',
+  color: 'var(--text-primary)',
+  fontSize: '13px',
+  outline: 'none',
+};
+
+// 逐条查看和修改账号 Cookies（值留空保存即删除该条）
+export function CookieEditor({ accountId }: CookieEditorProps) {
+  const [expanded, setExpanded] = useState(false);
+  const [cookies, setCookies] = useState<CookieEntry[]>([]);
+  const [drafts, setDrafts] = useState<Record<string, string>>({});
+  const [newName, setNewName] = useState("");
+  const [newValue, setNewValue] = useState("");
+  const [saving, setSaving] = useState<string | null>(null);
+  const [error, setError] = useState("");
+
+  const applyCookies = (entries: CookieEntry[]) => {
+    setCookies(entries);
+    setDrafts(Object.fromEntries(entries.map((c) => [c.name, c.value])));
+  };
+
+  useEffect(() => {
+    setExpanded(false);
+    setCookies([]);
+    setDrafts({});
+    setError("");
+  }, [accountId]);
+
+  useEffect(() => {
+    if (!expanded) return;
+    api.getAccountCookiesParsed(accountId)
+      .then(applyCookies)
+      .catch((err: any) => setError(err.message || "读取 Cookies 失败"));
+  }, [expanded, accountId]);
+
+  const saveCookie = async (name: string, value: string) => {
+    setSaving(name);
+    setError("");
+    try {
+      applyCookies(await api.setAccountCookie(accountId, name, value));
+      return true;
+    } catch (err: any) {
+      setError(`${name}: ${err.message || "保存失败"}`);
+      return false;
+    } finally {
+      setSaving(null);
+    }
+  };
+
+  const handleAdd = async () => {
+    if (!newName.trim() || !newValue.trim()) return;
+    if (await saveCookie(newName.trim(), newValue)) {
+      setNewName("");
+      setNewValue("");
+    }
+  };
+
+  return (
+    <div className="detail-section">
+      <h3
+        style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', cursor: 'pointer' }}
+        onClick={() => setExpanded((prev) => !prev)}
+      >
+        Cookies
+        <span style={{ fontSize: '12px', fontWeight: 'normal', color: 'var(--text-muted)' }}>
+          {expanded ? "收起" : "编辑"}
+        </span>
+      </h3>
+      {expanded && (
+        <>
+          {cookies.map((cookie) => {
+            const draft = drafts[cookie.name] ?? "";
+            const changed = draft !== cookie.value;
+            return (
+              <div className="detail-row" key={cookie.name} style={{ alignItems: 'center', gap: '8px' }}>
+                <span className="detail-label" style={{ wordBreak: 'break-all' }}>{cookie.name}</span>
+                <span className="detail-value" style={{ display: 'flex', gap: '6px', justifyContent: 'flex-end' }}>
+                  <input
+                    type="text"
+                    value={draft}
+                    onChange={(e) => setDrafts((prev) => ({ ...prev, [cookie.name]: e.target.value }))}
+                    disabled={saving !== null}
+                    style={{ ...inputStyle, width: '240px' }}
+                  />
+                  <button
+                    type="button"
+                    className="setting-btn"
+                    onClick={() => saveCookie(cookie.name, draft)}
+                    disabled={!changed || saving !== null}
+                    title={draft.trim() ? "保存" : "删除该 Cookie"}
+                  >
+                    {saving === cookie.name ? "保存中..." : draft.trim() ? "保存" : "删除"}
+                  </button>
+                </span>
+              </div>
+            );
+          })}
+          <div className="detail-row" style={{ alignItems: 'center', gap: '8px' }}>
+            <input
+              type="text"
+              value={newName}
+              placeholder="名称"
+              onChange={(e) => setNewName(e.target.value)}
+              disabled={saving !== null}
+              style={{ ...inputStyle, width: '120px' }}
+            />
+            <span className="detail-value" style={{ display: 'flex', gap: '6px', justifyContent: 'flex-end' }}>
+              <input
+                type="text"
+                value={newValue}
+                placeholder="值"
+                onChange={(e) => setNewValue(e.target.value)}
+                disabled={saving !== null}
+                style={{ ...inputStyle, width: '240px' }}
+              />
+              <button
+                type="button"
+                className="setting-btn"
+                onClick={handleAdd}
+                disabled={!newName.trim() || !newValue.trim() || saving !== null}
+              >
+                添加
+              </button>
+            </span>
+          </div>
+          {error && (
+            <div style={{ color: 'var(--danger)', fontSize: '12px', marginTop: '6px', wordBreak: 'break-all' }}>
+              {error}
+            </div>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,74 @@
+import { useState } from "react";
+import * as api from "../api";
+import type { CredentialRecord, CredentialStatus } from "../types";
+
+const STATUS_LABELS: Record<CredentialStatus, string> = {
+  signed_up: "已注册",
+  added: "已导入",
+  failed: "失败",
+};
+
+// 注册凭据日志：每次快速注册的邮箱、密码和结果，导入账号失败时可据此手动登录
+export function CredentialJournal() {
+  const [records, setRecords] = useState<CredentialRecord[] | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = async () => {
+    setLoading(true);
+    try {
+      setRecords(await api.getCredentialJournal());
+      setError(null);
+    } catch (err: any) {
+      setError(err.message || "读取注册凭据日志失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const copy = (record: CredentialRecord) => {
+    void navigator.clipboard.writeText(`${record.email}\t${record.password}`);
+  };
+
+  return (
+    <div style={{ marginTop: '8px' }}>
+      <div style={{ display: 'flex', gap: '8px' }}>
+        <button type="button" className="setting-btn" onClick={load} disabled={loading}>
+          {loading ? "读取中..." : records ? "刷新" : "查看记录"}
+        </button>
+        {records && (
+          <button type="button" className="setting-btn" onClick={() => setRecords(null)}>
+            隐藏
+          </button>
+        )}
+      </div>
+      {error && <div style={{ fontSize: '12px', color: 'var(--danger)', marginTop: '6px' }}>{error}</div>}
+      {records && records.length === 0 && (
+        <div style={{ fontSize: '12px', color: 'var(--text-muted)', marginTop: '6px' }}>暂无记录</div>
+      )}
+      {records && records.length > 0 && (
+        <div style={{ maxHeight: '240px', overflowY: 'auto', marginTop: '6px' }}>
+          {records.map((record, index) => (
+            <div
+              key={`${record.at}-${index}`}
+              className="setting-desc"
+              style={{ display: 'flex', gap: '8px', alignItems: 'center', padding: '4px 0' }}
+            >
+              <span style={{ minWidth: '140px' }}>{new Date(record.at * 1000).toLocaleString()}</span>
+              <span style={{ color: record.status === "failed" ? 'var(--danger)' : undefined, minWidth: '48px' }}>
+                {STATUS_LABELS[record.status] ?? record.status}
+              </span>
+              <span style={{ flex: 1, wordBreak: 'break-all' }}>
+                {record.email}
+                {record.reason ? `（${record.reason}）` : ""}
+              </span>
+              <button type="button" className="setting-btn" onClick={() => copy(record)}>
+                复制账号密码
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,6 +1,32 @@
 import { useEffect, useState } from "react";
 import type { KeyboardEvent } from "react";
-import type { UsageSummary } from "../types";
+import type { AccountRegistration, AccountSource, UsageSummary } from "../types";
+import { CookieEditor } from "./CookieEditor";
+import { RequestLog } from "./RequestLog";
+import { Subscription } from "./Subscription";
+import { Promotions } from "./Promotions";
+import { UsageTrend } from "./UsageTrend";
+
+const SOURCE_LABELS: Record<AccountSource, string> = {
+  unknown: "未记录",
+  quick_register: "快速注册",
+  browser_login: "浏览器登录",
+  trae_ide: "读取 Trae IDE",
+  browser_cookies: "读取浏览器 Cookie",
+  token: "Token / Cookies",
+  email_login: "邮箱密码登录",
+  import: "导入",
+};
+
+// 来源说明：快速注册时附带邮箱服务、域名和注册时间
+function describeRegistration(registration?: AccountRegistration): string {
+  if (!registration) return SOURCE_LABELS.unknown;
+  const parts = [SOURCE_LABELS[registration.source] ?? registration.source];
+  const mail = [registration.mail_provider, registration.mail_domain].filter(Boolean).join(" · ");
+  if (mail) parts.push(mail);
+  if (registration.registered_at) parts.push(new Date(registration.registered_at * 1000).toLocaleString("zh-CN"));
+  return parts.join("，");
+}
 
 interface DetailModalProps {
   isOpen: boolean;
@@ -12,18 +38,46 @@ interface DetailModalProps {
     avatar_url: string;
     plan_type: string;
     password?: string | null;
+    locale?: string | null;
+    api_host?: string | null;
+    alias?: string | null;
+    registration?: AccountRegistration;
+    totp_secret?: string | null;
   } | null;
   usage: UsageSummary | null;
-  onUpdateCredentials: (accountId: string, updates: { email?: string; password?: string }) => Promise<void>;
+  onUpdateCredentials: (
+    accountId: string,
+    updates: { email?: string; password?: string; locale?: string; apiHost?: string }
+  ) => Promise<void>;
+  onUpdateAlias: (accountId: string, alias: string) => Promise<void>;
+  onUpdateTotpSecret: (accountId: string, secret: string) => Promise<void>;
+  onCopyTotpCode: (accountId: string) => Promise<void>;
+  quotaThreshold?: number | null;
+  onUpdateQuotaThreshold: (accountId: string, threshold: number | null) => Promise<void>;
 }
 
-export function DetailModal({ isOpen, onClose, account, usage, onUpdateCredentials }: DetailModalProps) {
+export function DetailModal({
+  isOpen,
+  onClose,
+  account,
+  usage,
+  onUpdateCredentials,
+  onUpdateAlias,
+  onUpdateTotpSecret,
+  onCopyTotpCode,
+  quotaThreshold,
+  onUpdateQuotaThreshold,
+}: DetailModalProps) {
   if (!isOpen || !account) return null;
   const [showPassword, setShowPassword] = useState(false);
   const [editingField, setEditingField] = useState<"email" | "password" | null>(null);
   const [emailDraft, setEmailDraft] = useState(account.email || "");
   const [passwordDraft, setPasswordDraft] = useState(account.password || "");
   const [isSaving, setIsSaving] = useState(false);
+  const [apiHostDraft, setApiHostDraft] = useState(account.api_host || "");
+  const [aliasDraft, setAliasDraft] = useState(account.alias || "");
+  const [totpDraft, setTotpDraft] = useState(account.totp_secret || "");
+  const [thresholdDraft, setThresholdDraft] = useState(quotaThreshold ? String(quotaThreshold) : "");
 
   useEffect(() => {
     setEmailDraft(account.email || "");
@@ -32,6 +86,79 @@ export function DetailModal({ isOpen, onClose, account, usage, onUpdateCredentia
     setShowPassword(false);
   }, [account.id, account.email, account.password]);
 
+  useEffect(() => {
+    setApiHostDraft(account.api_host || "");
+  }, [account.id, account.api_host]);
+
+  useEffect(() => {
+    setAliasDraft(account.alias || "");
+  }, [account.id, account.alias]);
+
+  useEffect(() => {
+    setTotpDraft(account.totp_secret || "");
+  }, [account.id, account.totp_secret]);
+
+  useEffect(() => {
+    setThresholdDraft(quotaThreshold ? String(quotaThreshold) : "");
+  }, [account.id, quotaThreshold]);
+
+  // 保存剩余额度提醒阈值（留空或 0 表示不提醒）
+  const saveThreshold = async () => {
+    const value = Number(thresholdDraft.trim());
+    const next = thresholdDraft.trim() && Number.isFinite(value) && value > 0 ? value : null;
+    if (isSaving || next === (quotaThreshold || null)) return;
+    setIsSaving(true);
+    try {
+      await onUpdateQuotaThreshold(account.id, next);
+    } catch {
+      setThresholdDraft(quotaThreshold ? String(quotaThreshold) : "");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  // 保存本地显示名称（留空恢复服务器返回的名称）
+  const saveAlias = async () => {
+    const next = aliasDraft.trim();
+    if (isSaving || next === (account.alias || "")) return;
+    setIsSaving(true);
+    try {
+      await onUpdateAlias(account.id, next);
+    } catch {
+      setAliasDraft(account.alias || "");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  // 保存两步验证密钥（留空清除）
+  const saveTotpSecret = async () => {
+    const next = totpDraft.trim();
+    if (isSaving || next === (account.totp_secret || "")) return;
+    setIsSaving(true);
+    try {
+      await onUpdateTotpSecret(account.id, next);
+    } catch {
+      setTotpDraft(account.totp_secret || "");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  // 保存自定义 API 地址（留空恢复按区域自动选择）
+  const saveApiHost = async () => {
+    const next = apiHostDraft.trim();
+    if (isSaving || next === (account.api_host || "")) return;
+    setIsSaving(true);
+    try {
+      await onUpdateCredentials(account.id, { apiHost: next });
+    } catch {
+      setApiHostDraft(account.api_host || "");
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
   const formatDate = (timestamp: number) => {
     if (!timestamp) return "-";
     return new Date(timestamp * 1000).toLocaleString("zh-CN");
@@ -143,6 +270,45 @@ export function DetailModal({ isOpen, onClose, account, usage, onUpdateCredentia
             <span className="detail-label">用户名</span>
             <span className="detail-value">{account.name}</span>
           </div>
+          <div className="detail-row">
+            <span className="detail-label">来源</span>
+            <span className="detail-value">{describeRegistration(account.registration)}</span>
+          </div>
+          {account.registration?.user_agent && (
+            <div className="detail-row">
+              <span className="detail-label">注册 UA</span>
+              <span className="detail-value" style={{ fontSize: '12px', wordBreak: 'break-all' }}>
+                {account.registration.user_agent}
+              </span>
+            </div>
+          )}
+          <div className="detail-row" style={{ alignItems: 'center' }}>
+            <span className="detail-label">显示名称</span>
+            <span className="detail-value">
+              <input
+                type="text"
+                value={aliasDraft}
+                placeholder="使用用户名"
+                disabled={isSaving}
+                onChange={(e) => setAliasDraft(e.target.value)}
+                onBlur={saveAlias}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") e.currentTarget.blur();
+                }}
+                title="仅保存在本地，用于列表中显示，不会修改服务器上的资料"
+                style={{
+                  width: '240px',
+                  padding: '6px 10px',
+                  borderRadius: '6px',
+                  border: '1px solid var(--border)',
+                  background: 'var(--bg-input)',
+                  color: 'var(--text-primary)',
+                  fontSize: '13px',
+                  outline: 'none',
+                }}
+              />
+            </span>
+          </div>
           <div className="detail-row" style={{ alignItems: 'center' }}>
             <span className="detail-label">邮箱</span>
             <span
@@ -369,6 +535,90 @@ export function DetailModal({ isOpen, onClose, account, usage, onUpdateCredentia
               )}
             </span>
           </div>
+          <div className="detail-row" style={{ alignItems: 'center' }}>
+            <span className="detail-label">两步验证</span>
+            <span className="detail-value" style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
+              <input
+                type={showPassword ? "text" : "password"}
+                value={totpDraft}
+                placeholder="Base32 密钥或 otpauth:// 链接"
+                disabled={isSaving}
+                onChange={(e) => setTotpDraft(e.target.value)}
+                onBlur={saveTotpSecret}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") e.currentTarget.blur();
+                }}
+                title="登录或注册页面要求两步验证码时自动生成并填写"
+                style={{
+                  width: '240px',
+                  padding: '6px 10px',
+                  borderRadius: '6px',
+                  border: '1px solid var(--border)',
+                  background: 'var(--bg-input)',
+                  color: 'var(--text-primary)',
+                  fontSize: '13px',
+                  outline: 'none',
+                }}
+              />
+              {account.totp_secret && (
+                <button type="button" className="setting-btn" onClick={() => void onCopyTotpCode(account.id)}>
+                  复制验证码
+                </button>
+              )}
+            </span>
+          </div>
+          <div className="detail-row">
+            <span className="detail-label">IDE 语言</span>
+            <span className="detail-value">
+              <select
+                className="setting-select"
+                value={account.locale || ""}
+                disabled={isSaving}
+                onChange={async (e) => {
+                  setIsSaving(true);
+                  try {
+                    await onUpdateCredentials(account.id, { locale: e.target.value });
+                  } catch {
+                    // 错误提示由上层处理
+                  } finally {
+                    setIsSaving(false);
+                  }
+                }}
+                title="切换到该账号时写入 Trae 的界面语言"
+              >
+                <option value="">不修改</option>
+                <option value="zh-cn">简体中文</option>
+                <option value="en">English</option>
+              </select>
+            </span>
+          </div>
+          <div className="detail-row">
+            <span className="detail-label">API 地址</span>
+            <span className="detail-value">
+              <input
+                type="text"
+                value={apiHostDraft}
+                placeholder="按区域自动选择"
+                disabled={isSaving}
+                onChange={(e) => setApiHostDraft(e.target.value)}
+                onBlur={saveApiHost}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") e.currentTarget.blur();
+                }}
+                title="自建反向代理或区域网关地址，请求和写入 Trae 的 host 都会使用该地址"
+                style={{
+                  width: '240px',
+                  padding: '6px 10px',
+                  borderRadius: '6px',
+                  border: '1px solid var(--border)',
+                  background: 'var(--bg-input)',
+                  color: 'var(--text-primary)',
+                  fontSize: '13px',
+                  outline: 'none',
+                }}
+              />
+            </span>
+          </div>
           <div className="detail-row">
             <span className="detail-label">套餐类型</span>
             <span className="detail-value">{usage?.plan_type || account.plan_type || "Free"}</span>
@@ -395,6 +645,34 @@ export function DetailModal({ isOpen, onClose, account, usage, onUpdateCredentia
                 <span className="detail-label">剩余</span>
                 <span className="detail-value success">{formatNumber(usage.fast_request_left)}</span>
               </div>
+              <div className="detail-row" style={{ alignItems: 'center' }}>
+                <span className="detail-label">低额度提醒</span>
+                <span className="detail-value">
+                  <input
+                    type="number"
+                    min={0}
+                    value={thresholdDraft}
+                    placeholder="不提醒"
+                    disabled={isSaving}
+                    onChange={(e) => setThresholdDraft(e.target.value)}
+                    onBlur={saveThreshold}
+                    onKeyDown={(e) => {
+                      if (e.key === "Enter") e.currentTarget.blur();
+                    }}
+                    title="剩余快速请求（含额外礼包）低于该值时提醒，每 10 分钟检查一次"
+                    style={{
+                      width: '120px',
+                      padding: '6px 10px',
+                      borderRadius: '6px',
+                      border: '1px solid var(--border)',
+                      background: 'var(--bg-input)',
+                      color: 'var(--text-primary)',
+                      fontSize: '13px',
+                      outline: 'none',
+                    }}
+                  />
+                </span>
+              </div>
             </div>
 
             {usage.extra_fast_request_limit > 0 && (
@@ -443,6 +721,12 @@ export function DetailModal({ isOpen, onClose, account, usage, onUpdateCredentia
           </>
         )}
 
+        <UsageTrend accountId={account.id} />
+        <Subscription accountId={account.id} />
+        <Promotions accountId={account.id} />
+        <CookieEditor accountId={account.id} />
+        <RequestLog accountId={account.id} />
+
         <div className="modal-actions">
           <button onClick={handleClose}>关闭</button>
         </div>
@@ -0,0 +1,137 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import * as api from "../api";
+import type { Account, CodeRequiredEvent, RegisterSession, RegisterStep } from "../types";
+
+const STEP_LABELS: Record<RegisterStep, string> = {
+  open_form: "打开注册页",
+  send_code: "发送验证码",
+  await_code: "接收验证码",
+  submit: "提交注册",
+  await_token: "等待登录",
+};
+
+interface InterruptedRegistrationsProps {
+  showWindow: boolean;
+  disabled?: boolean;
+  onBusyChange?: (busy: boolean) => void;
+  onSuccess: (account: Account) => void;
+  onError: (message: string) => void;
+}
+
+// 应用中途退出时未完成的注册：使用原来的邮箱和密码继续，或放弃
+export function InterruptedRegistrations({ showWindow, disabled, onBusyChange, onSuccess, onError }: InterruptedRegistrationsProps) {
+  const [sessions, setSessions] = useState<RegisterSession[]>([]);
+  const [resumingId, setResumingId] = useState<string | null>(null);
+  const [pending, setPending] = useState<CodeRequiredEvent | null>(null);
+  const [code, setCode] = useState("");
+
+  const load = () => {
+    api.listInterruptedRegistrations().then(setSessions).catch(() => {});
+  };
+
+  useEffect(load, []);
+
+  const resume = async (session: RegisterSession) => {
+    setResumingId(session.id);
+    onBusyChange?.(true);
+    // 自有邮箱注册需要用户重新提交验证码
+    const unlisten = session.mail
+      ? undefined
+      : await listen<CodeRequiredEvent>("verification_code_required", (event) => {
+          if (event.payload.email !== session.email) return;
+          setPending(event.payload);
+          setCode("");
+        }).catch(() => undefined);
+    try {
+      const account = await api.resumeRegistration(session.id, showWindow);
+      onSuccess(account);
+    } catch (err: any) {
+      onError(err.message || "继续注册失败");
+    } finally {
+      unlisten?.();
+      setPending(null);
+      setResumingId(null);
+      onBusyChange?.(false);
+      load();
+    }
+  };
+
+  const discard = async (session: RegisterSession) => {
+    try {
+      await api.discardRegistration(session.id);
+      setSessions((prev) => prev.filter((s) => s.id !== session.id));
+    } catch (err: any) {
+      onError(err.message || "操作失败");
+    }
+  };
+
+  const submit = async () => {
+    if (!pending || !code.trim()) return;
+    try {
+      await api.submitVerificationCode(pending.window_label, code.trim());
+      setPending(null);
+    } catch (err: any) {
+      onError(err.message || "提交验证码失败");
+    }
+  };
+
+  if (sessions.length === 0) return null;
+
+  return (
+    <div className="detail-section" style={{ textAlign: 'left', marginBottom: '12px' }}>
+      <h3>未完成的注册</h3>
+      {sessions.map((session) => (
+        <div className="detail-row" key={session.id}>
+          <span className="detail-label">
+            {session.email}
+            <span style={{ fontSize: '12px', color: 'var(--text-muted)' }}>
+              {" "}· 中断于「{STEP_LABELS[session.step] ?? session.step}」· {new Date(session.updated_at * 1000).toLocaleString()}
+            </span>
+          </span>
+          <span className="detail-value" style={{ display: 'flex', gap: '6px' }}>
+            <button
+              type="button"
+              className="setting-btn"
+              onClick={() => resume(session)}
+              disabled={disabled || resumingId !== null}
+            >
+              {resumingId === session.id ? "注册中..." : "继续注册"}
+            </button>
+            <button
+              type="button"
+              className="setting-btn"
+              onClick={() => discard(session)}
+              disabled={resumingId !== null}
+            >
+              放弃
+            </button>
+          </span>
+        </div>
+      ))}
+      {pending && (
+        <div style={{ marginTop: '8px' }}>
+          <div style={{ fontSize: '12px', color: 'var(--text-muted)', marginBottom: '6px' }}>
+            验证码已发送到 {pending.email}，请在 {Math.round(pending.timeout_secs / 60)} 分钟内填写
+          </div>
+          <div style={{ display: 'flex', gap: '8px' }}>
+            <input
+              className="setting-input"
+              style={{ flex: 1 }}
+              placeholder="邮件中的验证码"
+              value={code}
+              onChange={(e) => setCode(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") void submit();
+              }}
+              autoFocus
+            />
+            <button type="button" className="setting-btn" onClick={submit} disabled={!code.trim()}>
+              提交验证码
+            </button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,86 @@
+import { useState } from "react";
+import * as api from "../api";
+import type { MailMessageDetail, MailSummary } from "../types";
+
+// 查看临时邮箱收件箱：排查快速注册卡在等待验证码时，验证码邮件是否送达及其内容
+export function MailboxInspector() {
+  const [email, setEmail] = useState("");
+  const [messages, setMessages] = useState<MailSummary[] | null>(null);
+  const [detail, setDetail] = useState<MailMessageDetail | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = async () => {
+    if (!email.trim()) return;
+    setLoading(true);
+    setDetail(null);
+    try {
+      setMessages(await api.listMailboxMessages(email.trim()));
+      setError(null);
+    } catch (err: any) {
+      setMessages(null);
+      setError(err.message || "读取收件箱失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const open = async (id: string) => {
+    setLoading(true);
+    try {
+      setDetail(await api.getMailboxMessage(email.trim(), id));
+      setError(null);
+    } catch (err: any) {
+      setError(err.message || "读取邮件失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div style={{ marginTop: '8px' }}>
+      <div style={{ display: 'flex', gap: '8px' }}>
+        <input
+          className="setting-input"
+          style={{ flex: 1 }}
+          placeholder="注册时使用的临时邮箱地址"
+          value={email}
+          onChange={(e) => setEmail(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") void load();
+          }}
+        />
+        <button type="button" className="setting-btn" onClick={load} disabled={loading || !email.trim()}>
+          {loading ? "读取中..." : "查看收件箱"}
+        </button>
+      </div>
+      {error && <div style={{ fontSize: '12px', color: 'var(--danger)', marginTop: '6px' }}>{error}</div>}
+      {messages && messages.length === 0 && (
+        <div style={{ fontSize: '12px', color: 'var(--text-muted)', marginTop: '6px' }}>收件箱中没有邮件</div>
+      )}
+      {messages && messages.length > 0 && (
+        <div style={{ marginTop: '6px', fontSize: '12px' }}>
+          {messages.map((msg) => (
+            <div
+              key={msg.id}
+              className="detail-row"
+              style={{ cursor: 'pointer', fontWeight: detail?.id === msg.id ? 600 : undefined }}
+              onClick={() => open(msg.id)}
+            >
+              <span className="detail-label">{msg.subject || "(无主题)"}</span>
+              <span className="detail-value">{msg.from}</span>
+            </div>
+          ))}
+        </div>
+      )}
+      {detail && (
+        <div style={{ marginTop: '6px', fontSize: '12px' }}>
+          <div>识别到的验证码：{detail.code ?? "未识别"}</div>
+          <pre style={{ whiteSpace: 'pre-wrap', maxHeight: '200px', overflowY: 'auto', marginTop: '4px' }}>
+            {detail.plain_text || "(正文为空)"}
+          </pre>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,93 @@
+import { useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import * as api from "../api";
+import type { Account, CodeRequiredEvent } from "../types";
+
+interface OwnEmailRegisterProps {
+  showWindow: boolean;
+  disabled?: boolean;
+  onBusyChange?: (busy: boolean) => void;
+  onSuccess: (account: Account) => void;
+  onError: (message: string) => void;
+}
+
+// 使用自有邮箱注册：自动填写注册页，收到验证码后由用户填写提交
+export function OwnEmailRegister({ showWindow, disabled, onBusyChange, onSuccess, onError }: OwnEmailRegisterProps) {
+  const [email, setEmail] = useState("");
+  const [running, setRunning] = useState(false);
+  const [pending, setPending] = useState<CodeRequiredEvent | null>(null);
+  const [code, setCode] = useState("");
+
+  const start = async () => {
+    if (!email.trim()) return;
+    setRunning(true);
+    onBusyChange?.(true);
+    const unlisten = await listen<CodeRequiredEvent>("verification_code_required", (event) => {
+      setPending(event.payload);
+      setCode("");
+    }).catch(() => undefined);
+    try {
+      const account = await api.quickRegisterWithEmail(email.trim(), showWindow);
+      onSuccess(account);
+    } catch (err: any) {
+      onError(err.message || "注册失败");
+    } finally {
+      unlisten?.();
+      setPending(null);
+      setRunning(false);
+      onBusyChange?.(false);
+    }
+  };
+
+  const submit = async () => {
+    if (!pending || !code.trim()) return;
+    try {
+      await api.submitVerificationCode(pending.window_label, code.trim());
+      setPending(null);
+    } catch (err: any) {
+      onError(err.message || "提交验证码失败");
+    }
+  };
+
+  return (
+    <div className="detail-section" style={{ textAlign: 'left', marginBottom: '12px' }}>
+      <h3>使用自有邮箱注册</h3>
+      <div style={{ display: 'flex', gap: '8px' }}>
+        <input
+          className="setting-input"
+          style={{ flex: 1 }}
+          placeholder="your@example.com"
+          value={email}
+          onChange={(e) => setEmail(e.target.value)}
+          disabled={running || disabled}
+        />
+        <button type="button" className="setting-btn" onClick={start} disabled={running || disabled || !email.trim()}>
+          {running ? "注册中..." : "开始注册"}
+        </button>
+      </div>
+      {pending && (
+        <div style={{ marginTop: '8px' }}>
+          <div style={{ fontSize: '12px', color: 'var(--text-muted)', marginBottom: '6px' }}>
+            验证码已发送到 {pending.email}，请在 {Math.round(pending.timeout_secs / 60)} 分钟内填写
+          </div>
+          <div style={{ display: 'flex', gap: '8px' }}>
+            <input
+              className="setting-input"
+              style={{ flex: 1 }}
+              placeholder="邮件中的验证码"
+              value={code}
+              onChange={(e) => setCode(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") void submit();
+              }}
+              autoFocus
+            />
+            <button type="button" className="setting-btn" onClick={submit} disabled={!code.trim()}>
+              提交验证码
+            </button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
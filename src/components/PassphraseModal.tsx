@@ -0,0 +1,125 @@
+import { useState } from "react";
+
+interface PassphraseModalProps {
+  isOpen: boolean;
+  mode: "export" | "import";
+  onClose: () => void;
+  onSubmit: (passphrase: string | null) => Promise<void>;
+}
+
+export function PassphraseModal({ isOpen, mode, onClose, onSubmit }: PassphraseModalProps) {
+  const [passphrase, setPassphrase] = useState("");
+  const [confirmValue, setConfirmValue] = useState("");
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState("");
+
+  if (!isOpen) return null;
+
+  const reset = () => {
+    setPassphrase("");
+    setConfirmValue("");
+    setError("");
+  };
+
+  const handleClose = () => {
+    reset();
+    onClose();
+  };
+
+  const run = async (value: string | null) => {
+    setLoading(true);
+    setError("");
+    try {
+      await onSubmit(value);
+      reset();
+      onClose();
+    } catch (err: any) {
+      setError(err.message || (mode === "export" ? "导出失败" : "导入失败"));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!passphrase) {
+      setError("请输入密码");
+      return;
+    }
+    if (mode === "export" && passphrase !== confirmValue) {
+      setError("两次输入的密码不一致");
+      return;
+    }
+    await run(passphrase);
+  };
+
+  return (
+    <div className="modal-overlay" onClick={handleClose}>
+      <div className="modal-content" onClick={(e) => e.stopPropagation()}>
+        <h2>{mode === "export" ? "加密导出" : "输入导出密码"}</h2>
+
+        <p className="modal-desc">
+          {mode === "export" ? (
+            <>
+              导出文件包含 Token、Cookies 与机器码，建议设置密码加密。
+              <br />
+              <small>忘记密码将无法恢复导出文件。</small>
+            </>
+          ) : (
+            "该文件已加密，请输入导出时设置的密码。"
+          )}
+        </p>
+
+        <form onSubmit={handleSubmit}>
+          <div className="form-section">
+            <label className="form-label">
+              密码<span className="required">*</span>
+            </label>
+            <input
+              className="modal-input"
+              type="password"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              placeholder="请输入导出密码"
+              autoComplete="new-password"
+              disabled={loading}
+            />
+          </div>
+
+          {mode === "export" && (
+            <div className="form-section">
+              <label className="form-label">
+                确认密码<span className="required">*</span>
+              </label>
+              <input
+                className="modal-input"
+                type="password"
+                value={confirmValue}
+                onChange={(e) => setConfirmValue(e.target.value)}
+                placeholder="请再次输入导出密码"
+                autoComplete="new-password"
+                disabled={loading}
+              />
+            </div>
+          )}
+
+          {error && <div className="error-message">{error}</div>}
+
+          <div className="modal-actions">
+            <button type="button" onClick={handleClose} disabled={loading}>
+              取消
+            </button>
+            {mode === "export" && (
+              <button type="button" onClick={() => run(null)} disabled={loading}>
+                明文导出
+              </button>
+            )}
+            <button type="submit" className="primary" disabled={loading}>
+              {loading ? "处理中..." : mode === "export" ? "加密导出" : "导入"}
+            </button>
+          </div>
+        </form>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,84 @@
+import { useEffect, useState } from "react";
+import * as api from "../api";
+import type { Promotion } from "../types";
+
+interface PromotionsProps {
+  accountId: string;
+}
+
+const formatDate = (ts: number | null) =>
+  ts ? new Date(ts * 1000).toLocaleDateString("zh-CN") : "";
+
+// 账号当前可领取的活动礼包（目前只支持生日礼包）
+export function Promotions({ accountId }: PromotionsProps) {
+  const [promotions, setPromotions] = useState<Promotion[] | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [claiming, setClaiming] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setPromotions(null);
+    setError(null);
+  }, [accountId]);
+
+  const load = async () => {
+    setLoading(true);
+    try {
+      setPromotions(await api.listPromotions(accountId));
+      setError(null);
+    } catch (err: any) {
+      setError(err.message || "获取活动礼包失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const claim = async (promoId: string) => {
+    setClaiming(promoId);
+    try {
+      await api.claimPromotion(accountId, promoId);
+      setPromotions((prev) => prev?.map((p) => (p.id === promoId ? { ...p, claimed: true } : p)) ?? null);
+      setError(null);
+    } catch (err: any) {
+      setError(err.message || "领取失败");
+    } finally {
+      setClaiming(null);
+    }
+  };
+
+  return (
+    <div className="detail-section">
+      <h3 style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between' }}>
+        活动礼包
+        <button type="button" className="setting-btn" onClick={load} disabled={loading}>
+          {loading ? "查询中..." : promotions ? "刷新" : "查询"}
+        </button>
+      </h3>
+      {error && <div style={{ fontSize: '12px', color: 'var(--danger)' }}>{error}</div>}
+      {promotions && promotions.map((promo) => (
+        <div className="detail-row" key={promo.id}>
+          <span className="detail-label" title={promo.description || undefined}>
+            {promo.name}
+            {promo.expires_at && (
+              <span style={{ fontSize: '12px', color: 'var(--text-muted)' }}> · 至 {formatDate(promo.expires_at)}</span>
+            )}
+          </span>
+          <span className="detail-value">
+            {promo.claimed ? (
+              "已领取"
+            ) : (
+              <button
+                type="button"
+                className="setting-btn"
+                onClick={() => claim(promo.id)}
+                disabled={claiming !== null}
+              >
+                {claiming === promo.id ? "领取中..." : "领取"}
+              </button>
+            )}
+          </span>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,84 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import type { RegisterProgress, RegisterStage } from "../types";
+
+const STAGE_LABELS: Record<RegisterStage, string> = {
+  mail_ready: "邮箱已就绪",
+  init: "初始化完成，正在打开注册页",
+  page_opened: "已打开注册页面",
+  code_sent: "已发送验证码，等待接收邮件",
+  captcha: "注册页面需要人机验证",
+  code_received: "邮箱验证码获取成功，正在登录",
+  submitted: "已提交注册，等待登录",
+  token_captured: "已获取登录 Token，正在读取 Cookie",
+  login_ok: "登录成功，正在导入账号",
+  account_added: "账号已保存",
+  retrying: "正在重试",
+  done: "导入成功",
+  failed: "注册失败",
+};
+
+// 进度说明：按阶段显示，重试和失败时使用后端给出的详细信息
+export function describeRegisterProgress(progress: RegisterProgress): string {
+  let text = STAGE_LABELS[progress.stage] ?? progress.message;
+  if (progress.stage === "retrying") {
+    text = progress.message;
+  } else if (progress.stage === "failed" && progress.error) {
+    text = progress.error;
+  } else if (progress.stage === "mail_ready" && progress.email) {
+    text = `${text}：${progress.email}`;
+  }
+  if (progress.batch_index !== null && progress.batch_total !== null) {
+    text = `[${progress.batch_index + 1}/${progress.batch_total}] ${text}`;
+  }
+  return text;
+}
+
+// 快速注册进度条：显示进行中（和刚结束）的注册进度
+export function RegisterProgressBar() {
+  const [items, setItems] = useState<Record<string, RegisterProgress>>({});
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<RegisterProgress>("quick_register_progress", (event) => {
+      const progress = event.payload;
+      const key = String(progress.batch_index ?? "single");
+      setItems((prev) => {
+        // 新一轮注册从邮箱就绪开始，清掉上一轮的结果
+        const next = progress.stage === "mail_ready" && progress.batch_index === null ? {} : { ...prev };
+        next[key] = progress;
+        return next;
+      });
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+    return () => unlisten?.();
+  }, []);
+
+  const list = Object.values(items).sort((a, b) => (a.batch_index ?? 0) - (b.batch_index ?? 0));
+  if (list.length === 0) return null;
+
+  return (
+    <div style={{ marginBottom: '12px', textAlign: 'left' }}>
+      {list.map((progress) => (
+        <div key={progress.batch_index ?? "single"} style={{ marginBottom: '6px' }}>
+          <div style={{ display: 'flex', justifyContent: 'space-between', fontSize: '12px', marginBottom: '4px' }}>
+            <span style={{ color: progress.stage === "failed" ? 'var(--danger)' : 'var(--text-muted)' }}>
+              {describeRegisterProgress(progress)}
+            </span>
+            <span style={{ color: 'var(--text-muted)' }}>{progress.percent}%</span>
+          </div>
+          <div className="progress-bar-bg">
+            <div
+              className="progress-bar-fill"
+              style={{
+                width: `${progress.percent}%`,
+                backgroundColor: progress.stage === "failed" ? 'var(--danger)' : progress.stage === "done" ? 'var(--success)' : undefined,
+              }}
+            />
+          </div>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,92 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import * as api from "../api";
+import type { RegisterJobStatus, RegisterQueueStatus } from "../types";
+
+const STATUS_LABELS: Record<RegisterJobStatus, string> = {
+  queued: "排队中",
+  running: "注册中",
+  succeeded: "已导入",
+  failed: "失败",
+  cancelled: "已取消",
+};
+
+interface RegisterQueueProps {
+  onError?: (message: string) => void;
+}
+
+// 快速注册队列：查看任务进度，暂停、继续或取消任务
+export function RegisterQueue({ onError }: RegisterQueueProps) {
+  const [queue, setQueue] = useState<RegisterQueueStatus | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    api.getRegisterQueue().then(setQueue).catch(() => {});
+    listen<RegisterQueueStatus>("register_queue_updated", (event) => setQueue(event.payload))
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+    return () => unlisten?.();
+  }, []);
+
+  const run = async (action: () => Promise<RegisterQueueStatus>) => {
+    setBusy(true);
+    try {
+      setQueue(await action());
+    } catch (err: any) {
+      onError?.(err.message || "操作失败");
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  if (!queue || queue.jobs.length === 0) return null;
+
+  const pending = queue.jobs.filter((j) => j.status === "queued" || j.status === "running").length;
+
+  return (
+    <div className="detail-section" style={{ textAlign: 'left', marginBottom: '12px' }}>
+      <h3 style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', gap: '8px' }}>
+        <span>注册队列{queue.paused ? "（已暂停）" : ""}{pending > 0 ? ` · 剩余 ${pending} 个` : ""}</span>
+        <span style={{ display: 'flex', gap: '6px' }}>
+          {queue.paused ? (
+            <button type="button" className="setting-btn" onClick={() => run(api.resumeRegisterQueue)} disabled={busy}>
+              继续
+            </button>
+          ) : (
+            <button type="button" className="setting-btn" onClick={() => run(api.pauseRegisterQueue)} disabled={busy || pending === 0}>
+              暂停
+            </button>
+          )}
+          <button type="button" className="setting-btn" onClick={() => run(api.clearRegisterQueue)} disabled={busy}>
+            清除已结束
+          </button>
+        </span>
+      </h3>
+      <div style={{ maxHeight: '160px', overflowY: 'auto' }}>
+        {queue.jobs.map((job) => (
+          <div className="detail-row" key={job.id}>
+            <span className="detail-label" title={job.error || undefined}>
+              #{job.id} {job.email || ""}
+              {job.error && <span style={{ fontSize: '12px', color: 'var(--danger)' }}> · {job.error}</span>}
+            </span>
+            <span className="detail-value">
+              {STATUS_LABELS[job.status]}
+              {(job.status === "queued" || job.status === "running") && (
+                <button
+                  type="button"
+                  className="setting-btn"
+                  style={{ marginLeft: '6px' }}
+                  onClick={() => run(() => api.cancelRegisterJob(job.id))}
+                  disabled={busy}
+                >
+                  取消
+                </button>
+              )}
+            </span>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
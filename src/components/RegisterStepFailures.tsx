@@ -0,0 +1,74 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import * as api from "../api";
+import type { StepFailedEvent } from "../types";
+
+interface PendingStep extends StepFailedEvent {
+  expiresAt: number;
+}
+
+interface RegisterStepFailuresProps {
+  onError?: (message: string) => void;
+}
+
+// 快速注册步骤自动重试用尽后，由用户选择重试该步骤或放弃本次注册
+export function RegisterStepFailures({ onError }: RegisterStepFailuresProps) {
+  const [pending, setPending] = useState<PendingStep[]>([]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<StepFailedEvent>("quick_register_step_failed", (event) => {
+      const entry = { ...event.payload, expiresAt: Date.now() + event.payload.resume_secs * 1000 };
+      setPending((prev) => [...prev.filter((p) => p.window_label !== entry.window_label), entry]);
+    })
+      .then((fn) => { unlisten = fn; })
+      .catch(() => {});
+    // 超过等待时间后注册已自动放弃，移除提示
+    const timer = setInterval(() => {
+      setPending((prev) => {
+        const now = Date.now();
+        return prev.some((p) => p.expiresAt <= now) ? prev.filter((p) => p.expiresAt > now) : prev;
+      });
+    }, 1000);
+    return () => {
+      unlisten?.();
+      clearInterval(timer);
+    };
+  }, []);
+
+  const resolve = async (entry: PendingStep, retry: boolean) => {
+    setPending((prev) => prev.filter((p) => p.window_label !== entry.window_label));
+    try {
+      if (retry) {
+        await api.retryQuickRegisterStep(entry.window_label);
+      } else {
+        await api.abortQuickRegister(entry.window_label);
+      }
+    } catch (err: any) {
+      onError?.(err.message || "操作失败");
+    }
+  };
+
+  if (pending.length === 0) return null;
+
+  return (
+    <div className="detail-section" style={{ textAlign: 'left', marginBottom: '12px' }}>
+      {pending.map((entry) => (
+        <div className="detail-row" key={entry.window_label}>
+          <span className="detail-label">
+            「{entry.step_label}」失败
+            <span style={{ fontSize: '12px', color: 'var(--danger)' }}> · {entry.message}</span>
+          </span>
+          <span className="detail-value" style={{ display: 'flex', gap: '6px' }}>
+            <button type="button" className="setting-btn" onClick={() => resolve(entry, true)}>
+              重试此步骤
+            </button>
+            <button type="button" className="setting-btn" onClick={() => resolve(entry, false)}>
+              放弃
+            </button>
+          </span>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,82 @@
+import { useEffect, useState } from "react";
+import * as api from "../api";
+import type { RequestLogEntry } from "../types";
+
+interface RequestLogProps {
+  accountId: string;
+}
+
+const formatTime = (ms: number) =>
+  new Date(ms).toLocaleTimeString("zh-CN", { hour12: false });
+
+// 账号最近的 API 请求记录（用于排查单个账号反复失败的原因）
+export function RequestLog({ accountId }: RequestLogProps) {
+  const [expanded, setExpanded] = useState(false);
+  const [entries, setEntries] = useState<RequestLogEntry[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  const load = async () => {
+    setLoading(true);
+    try {
+      setEntries(await api.getAccountRequestLog(accountId));
+    } catch {
+      setEntries([]);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    setExpanded(false);
+    setEntries([]);
+  }, [accountId]);
+
+  useEffect(() => {
+    if (expanded) void load();
+  }, [expanded, accountId]);
+
+  return (
+    <div className="detail-section">
+      <h3
+        style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', cursor: 'pointer' }}
+        onClick={() => setExpanded((prev) => !prev)}
+      >
+        请求记录
+        <span style={{ fontSize: '12px', fontWeight: 'normal', color: 'var(--text-muted)' }}>
+          {expanded ? "收起" : "查看"}
+        </span>
+      </h3>
+      {expanded && (
+        <>
+          <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', marginBottom: '6px' }}>
+            <span style={{ fontSize: '12px', color: 'var(--text-muted)' }}>
+              最近 {entries.length} 条，重启应用后清空
+            </span>
+            <button type="button" className="setting-btn" onClick={load} disabled={loading}>
+              {loading ? "加载中..." : "刷新"}
+            </button>
+          </div>
+          {entries.length === 0 && !loading && (
+            <div style={{ fontSize: '12px', color: 'var(--text-muted)' }}>暂无请求记录</div>
+          )}
+          {entries.map((entry, index) => {
+            const failed = entry.error !== null || entry.status === null || entry.status >= 400;
+            return (
+              <div className="detail-row" key={`${entry.at}-${index}`} style={{ fontSize: '12px', gap: '8px' }}>
+                <span className="detail-label" style={{ whiteSpace: 'nowrap' }}>{formatTime(entry.at)}</span>
+                <span
+                  className="detail-value"
+                  style={{ wordBreak: 'break-all', textAlign: 'right', color: failed ? 'var(--danger)' : undefined }}
+                  title={entry.host}
+                >
+                  {entry.method} {entry.path} · {entry.status ?? "ERR"} · {entry.latency_ms} ms
+                  {entry.error && <div>{entry.error}</div>}
+                </span>
+              </div>
+            );
+          })}
+        </>
+      )}
+    </div>
+  );
+}
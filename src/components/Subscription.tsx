@@ -0,0 +1,79 @@
+import { useEffect, useState } from "react";
+import * as api from "../api";
+import type { SubscriptionInfo } from "../types";
+
+interface SubscriptionProps {
+  accountId: string;
+}
+
+const formatDate = (ts: number | null) =>
+  ts ? new Date(ts * 1000).toLocaleDateString("zh-CN") : "-";
+
+// 金额按最小货币单位返回
+const formatAmount = (amount: number) => (amount / 100).toFixed(2);
+
+// 账号订阅信息（续费时间、自动续费、付款记录），查询后保存到期时间用于到期提醒
+export function Subscription({ accountId }: SubscriptionProps) {
+  const [info, setInfo] = useState<SubscriptionInfo | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setInfo(null);
+    setError(null);
+  }, [accountId]);
+
+  const load = async () => {
+    setLoading(true);
+    try {
+      setInfo(await api.getSubscriptionInfo(accountId));
+      setError(null);
+    } catch (err: any) {
+      setError(err.message || "获取订阅信息失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div className="detail-section">
+      <h3 style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between' }}>
+        订阅
+        <button type="button" className="setting-btn" onClick={load} disabled={loading}>
+          {loading ? "查询中..." : info ? "刷新" : "查询"}
+        </button>
+      </h3>
+      {error && <div style={{ fontSize: '12px', color: 'var(--danger)' }}>{error}</div>}
+      {info && (
+        <>
+          <div className="detail-row">
+            <span className="detail-label">套餐</span>
+            <span className="detail-value">{info.plan_type}</span>
+          </div>
+          <div className="detail-row">
+            <span className="detail-label">到期时间</span>
+            <span className="detail-value">{formatDate(info.expires_at)}</span>
+          </div>
+          <div className="detail-row">
+            <span className="detail-label">自动续费</span>
+            <span className="detail-value">
+              {info.auto_renew ? `已开启（${formatDate(info.renews_at)} 续费）` : "未开启"}
+            </span>
+          </div>
+          {info.payments.length === 0 ? (
+            <div style={{ fontSize: '12px', color: 'var(--text-muted)' }}>暂无付款记录</div>
+          ) : (
+            info.payments.map((payment) => (
+              <div className="detail-row" key={payment.entitlement_id} style={{ fontSize: '12px' }}>
+                <span className="detail-label">{formatDate(payment.start_time)}</span>
+                <span className="detail-value">
+                  {payment.product_type === 2 ? "礼包" : "订阅"} · {formatAmount(payment.amount)} · 至 {formatDate(payment.end_time)}
+                </span>
+              </div>
+            ))
+          )}
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,67 @@
+import { useEffect, useState } from "react";
+import * as api from "../api";
+import type { UsageAggregate as UsageAggregateData, UsageTotals } from "../types";
+
+interface UsageAggregateProps {
+  // 账号列表变化时重新汇总
+  accountKey: string;
+}
+
+const formatNumber = (value: number) =>
+  Number.isInteger(value) ? value.toString() : value.toFixed(1);
+
+// 所有账号的快速请求汇总（按套餐分组），用于查看整个账号池还剩多少额度
+export function UsageAggregate({ accountKey }: UsageAggregateProps) {
+  const [data, setData] = useState<UsageAggregateData | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = async (force = false) => {
+    setLoading(true);
+    try {
+      setData(await api.getUsageAggregate({ force }));
+      setError(null);
+    } catch (err: any) {
+      setError(err.message || "汇总使用量失败");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    void load();
+  }, [accountKey]);
+
+  const row = (totals: UsageTotals, label: string) => (
+    <div className="detail-row" key={label}>
+      <span className="detail-label">{label}（{totals.accounts} 个）</span>
+      <span className="detail-value">
+        剩余 {formatNumber(totals.left)} / {formatNumber(totals.limit)} · 已用 {formatNumber(totals.used)}
+      </span>
+    </div>
+  );
+
+  return (
+    <div className="dashboard-widgets-section" style={{ marginBottom: "24px", padding: "16px 20px", background: "var(--bg-card)", borderRadius: "16px" }}>
+      <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center", marginBottom: "8px" }}>
+        <h3 style={{ margin: 0 }}>账号池额度</h3>
+        <button type="button" className="setting-btn" onClick={() => load(true)} disabled={loading}>
+          {loading ? "刷新中..." : "刷新"}
+        </button>
+      </div>
+      {error && !data && <div style={{ color: "var(--danger)", fontSize: "13px" }}>{error}</div>}
+      {data && (
+        <>
+          {row(data.total, "全部")}
+          {data.by_plan.map((group) => row(group, group.plan_type))}
+          {(data.stale > 0 || data.missing > 0) && (
+            <div style={{ marginTop: "6px", fontSize: "12px", color: "var(--text-muted)" }}>
+              {data.stale > 0 && `${data.stale} 个账号本次查询失败，使用上次的数据；`}
+              {data.missing > 0 && `${data.missing} 个账号没有使用量数据，未计入`}
+            </div>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
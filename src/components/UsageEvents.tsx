@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
+import { save } from '@tauri-apps/plugin-dialog';
 import type { UsageEvent } from '../types';
-import { getUsageEvents } from '../api';
+import { exportUsageEvents, getUsageEvents } from '../api';
 
 interface UsageEventsProps {
   accountId: string;
@@ -17,6 +18,7 @@ export function UsageEvents({ accountId, onError }: UsageEventsProps) {
   const [endDate, setEndDate] = useState('');
   const [showDatePicker, setShowDatePicker] = useState(false);
   const [total, setTotal] = useState(0);
+  const [exporting, setExporting] = useState(false);
 
   // 计算时间戳范围
   const getTimeRange = (filter: TimeFilter): { startTime: number; endTime: number } => {
@@ -90,6 +92,25 @@ export function UsageEvents({ accountId, onError }: UsageEventsProps) {
     loadEvents();
   }, [accountId, timeFilter, startDate, endDate]);
 
+  // 导出当前时间范围内的全部事件（由后端分页拉取）
+  const handleExportCsv = async () => {
+    if (!accountId || exporting) return;
+    const path = await save({
+      defaultPath: `trae-usage-${new Date().toISOString().split('T')[0]}.csv`,
+      filters: [{ name: 'CSV', extensions: ['csv'] }],
+    });
+    if (!path) return;
+    setExporting(true);
+    try {
+      const { startTime, endTime } = getTimeRange(timeFilter);
+      await exportUsageEvents(accountId, startTime, endTime, path as string);
+    } catch (error: any) {
+      onError?.(error?.message || '导出使用事件失败');
+    } finally {
+      setExporting(false);
+    }
+  };
+
   const handleTimeFilterChange = (filter: TimeFilter) => {
     setTimeFilter(filter);
     if (filter !== 'custom') {
@@ -141,6 +162,14 @@ export function UsageEvents({ accountId, onError }: UsageEventsProps) {
               <path d="M4 6l4 4 4-4" stroke="currentColor" strokeWidth="2" strokeLinecap="round"/>
             </svg>
           </button>
+          <button
+            className="date-range-btn"
+            onClick={handleExportCsv}
+            disabled={exporting || total === 0}
+            title="导出当前时间范围内的全部事件"
+          >
+            {exporting ? '导出中...' : '导出 CSV'}
+          </button>
         </div>
       </div>
 
@@ -0,0 +1,119 @@
+import { useEffect, useState } from "react";
+import { BarChart, Bar, XAxis, YAxis, Tooltip, ResponsiveContainer } from "recharts";
+import * as api from "../api";
+import type { QuotaForecast, TrendRange, UsageTrendPoint } from "../types";
+import { useThemeColors } from "../hooks/useThemeColors";
+
+interface UsageTrendProps {
+  accountId: string;
+}
+
+const RANGE_OPTIONS: { value: TrendRange; label: string }[] = [
+  { value: "week", label: "7 天" },
+  { value: "month", label: "30 天" },
+  { value: "quarter", label: "90 天" },
+];
+
+// 账号每日消耗趋势（来自本地使用量历史）
+export function UsageTrend({ accountId }: UsageTrendProps) {
+  const theme = useThemeColors();
+  const [range, setRange] = useState<TrendRange>("week");
+  const [points, setPoints] = useState<UsageTrendPoint[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [forecast, setForecast] = useState<QuotaForecast | null>(null);
+
+  useEffect(() => {
+    let active = true;
+    api.estimateQuotaExhaustion(accountId)
+      .then((data) => {
+        if (active) setForecast(data);
+      })
+      .catch(() => {
+        if (active) setForecast(null);
+      });
+    return () => {
+      active = false;
+    };
+  }, [accountId]);
+
+  useEffect(() => {
+    let active = true;
+    setLoading(true);
+    api.getUsageTrend(accountId, range)
+      .then((data) => {
+        if (active) setPoints(data);
+      })
+      .catch(() => {
+        if (active) setPoints([]);
+      })
+      .finally(() => {
+        if (active) setLoading(false);
+      });
+    return () => {
+      active = false;
+    };
+  }, [accountId, range]);
+
+  const total = points.reduce((sum, point) => sum + point.delta, 0);
+
+  return (
+    <div className="detail-section">
+      <h3 style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between' }}>
+        消耗趋势
+        <select
+          className="setting-select"
+          value={range}
+          onChange={(e) => setRange(e.target.value as TrendRange)}
+          style={{ fontSize: '12px', fontWeight: 'normal' }}
+        >
+          {RANGE_OPTIONS.map((option) => (
+            <option key={option.value} value={option.value}>{option.label}</option>
+          ))}
+        </select>
+      </h3>
+      {points.length === 0 ? (
+        <div style={{ fontSize: '12px', color: 'var(--text-muted)' }}>
+          {loading ? "加载中..." : "暂无历史数据，查询使用量后会自动记录"}
+        </div>
+      ) : (
+        <>
+          <div style={{ fontSize: '12px', color: 'var(--text-muted)', marginBottom: '6px' }}>
+            合计消耗 {Math.round(total * 100) / 100}，当前剩余 {points[points.length - 1].left}
+          </div>
+          {forecast && forecast.exhausts_at && (
+            <div
+              style={{
+                fontSize: '12px',
+                color: forecast.exhausts_before_reset ? 'var(--warning)' : 'var(--text-muted)',
+                marginBottom: '6px',
+              }}
+            >
+              日均消耗 {Math.round(forecast.daily_burn * 100) / 100}，预计
+              {new Date(forecast.exhausts_at * 1000).toLocaleDateString("zh-CN")} 用完
+              {forecast.exhausts_before_reset ? "（早于额度重置）" : ""}
+            </div>
+          )}
+          <div style={{ width: '100%', height: 160 }}>
+            <ResponsiveContainer>
+              <BarChart data={points} margin={{ left: 0, right: 10, top: 5, bottom: 0 }}>
+                <XAxis
+                  dataKey="date"
+                  tickFormatter={(date: string) => date.slice(5)}
+                  tick={{ fontSize: 11, fill: theme.textMuted }}
+                  axisLine={false}
+                  tickLine={false}
+                />
+                <YAxis width={32} tick={{ fontSize: 11, fill: theme.textMuted }} axisLine={false} tickLine={false} />
+                <Tooltip
+                  formatter={(value) => [value, "消耗"]}
+                  contentStyle={{ background: theme.tooltipBg, color: theme.tooltipText, border: 'none', fontSize: 12 }}
+                />
+                <Bar dataKey="delta" fill={theme.primary} radius={[4, 4, 0, 0]} />
+              </BarChart>
+            </ResponsiveContainer>
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
@@ -1,25 +1,138 @@
 import { useEffect, useMemo, useState } from "react";
 import { open } from "@tauri-apps/plugin-dialog";
+import { revealItemInDir } from "@tauri-apps/plugin-opener";
 import * as api from "../api";
-import type { AppSettings } from "../types";
+import { CredentialJournal } from "../components/CredentialJournal";
+import { MailboxInspector } from "../components/MailboxInspector";
+import type { AccountBrief, AppSettings, BackupEntry, CaptchaSolverConfig, CloudBackupConfig, CodeExtraction, EntitlementMode, HealthReport, MailProviderKind, NoticeLevel, NotifyConfig, NotifyEvent, PoolTopUpConfig, ProxyMode, ProxyRotation, RetirementAction, RetirementConfig, RotationConfig, RotationSchedule, RotationStatus, SelfTestCheck, SinkKind, SystemProxy, TraeAuthSnapshot, TraeInstallStatus, WorkspaceMapping } from "../types";
+
+const EMPTY_CLOUD_BACKUP: Record<CloudBackupConfig["kind"], CloudBackupConfig> = {
+  disabled: { kind: "disabled" },
+  webdav: { kind: "webdav", url: "", username: "", password: "" },
+  s3: { kind: "s3", endpoint: "", region: "", bucket: "", prefix: "", access_key_id: "", secret_access_key: "" },
+  gist: { kind: "gist", token: "", gist_id: "" },
+};
+
+const DEFAULT_CODE_EXTRACTION: CodeExtraction = {
+  length: 6,
+  pattern: "digits",
+  keywords: ["verification code", "验证码", "code", "verify"],
+};
+
+const DEFAULT_NOTIFY: NotifyConfig = {
+  webhook_url: "",
+  telegram_bot_token: "",
+  telegram_chat_id: "",
+  routes: {},
+};
+
+const NOTIFY_EVENTS: { event: NotifyEvent; label: string }[] = [
+  { event: "quota_alert", label: "额度提醒" },
+  { event: "token_expiring", label: "Token 过期" },
+  { event: "registration", label: "注册结果" },
+  { event: "weekly_report", label: "周报" },
+];
+
+const SELF_TEST_LABELS: Record<SelfTestCheck["name"], string> = {
+  register_page: "注册页",
+  mail_provider: "临时邮箱",
+  ide_write: "IDE 写入",
+};
+
+const DEFAULT_ROTATION: RotationConfig = {
+  enabled: false,
+  account_ids: [],
+  schedule: { kind: "every_hours", hours: 4 },
+  skip_if_busy: true,
+};
+
+const DEFAULT_POOL_TOPUP: PoolTopUpConfig = {
+  enabled: false,
+  min_available: 3,
+  target_available: 5,
+  check_interval_minutes: 30,
+  show_window: false,
+};
+
+const DEFAULT_RETIREMENT: RetirementConfig = {
+  enabled: false,
+  action: "archive",
+  replace: false,
+  show_window: false,
+  check_interval_minutes: 60,
+};
+
+const ROTATION_SCHEDULES: { value: string; label: string; schedule: RotationSchedule }[] = [
+  { value: "interval:30", label: "每 30 分钟", schedule: { kind: "interval", minutes: 30 } },
+  { value: "interval:60", label: "每 1 小时", schedule: { kind: "interval", minutes: 60 } },
+  { value: "every_hours:2", label: "每 2 小时整点", schedule: { kind: "every_hours", hours: 2 } },
+  { value: "every_hours:4", label: "每 4 小时整点", schedule: { kind: "every_hours", hours: 4 } },
+  { value: "every_hours:6", label: "每 6 小时整点", schedule: { kind: "every_hours", hours: 6 } },
+  { value: "every_hours:12", label: "每 12 小时整点", schedule: { kind: "every_hours", hours: 12 } },
+  { value: "every_hours:24", label: "每天 0 点", schedule: { kind: "every_hours", hours: 24 } },
+];
+
+const scheduleValue = (schedule: RotationSchedule) =>
+  schedule.kind === "interval" ? `interval:${schedule.minutes}` : `every_hours:${schedule.hours}`;
+
+const NOTIFY_SINKS: { sink: SinkKind; label: string }[] = [
+  { sink: "desktop", label: "应用内" },
+  { sink: "webhook", label: "Webhook" },
+  { sink: "telegram", label: "Telegram" },
+];
 
 interface SettingsProps {
   onToast?: (type: "success" | "error" | "warning" | "info", message: string, duration?: number) => void;
   settings?: AppSettings | null;
   onSettingsChange?: (settings: AppSettings) => void;
+  onAccountsRestored?: () => void;
 }
 
 export function Settings({
   onToast,
   settings,
   onSettingsChange,
+  onAccountsRestored,
 }: SettingsProps) {
   const [traeMachineId, setTraeMachineId] = useState<string>("");
   const [traeRefreshing, setTraeRefreshing] = useState(false);
   const [clearingTrae, setClearingTrae] = useState(false);
+  const [authSnapshot, setAuthSnapshot] = useState<TraeAuthSnapshot | null>(null);
+  const [loadingSnapshot, setLoadingSnapshot] = useState(false);
   const [traePath, setTraePath] = useState<string>("");
+  const [installStatus, setInstallStatus] = useState<TraeInstallStatus | null>(null);
   const [traePathLoading, setTraePathLoading] = useState(false);
   const [scanning, setScanning] = useState(false);
+  const [pinning, setPinning] = useState(false);
+  const [healthChecking, setHealthChecking] = useState(false);
+  const [healthReport, setHealthReport] = useState<HealthReport | null>(null);
+  const [backups, setBackups] = useState<BackupEntry[]>([]);
+  const [backingUp, setBackingUp] = useState(false);
+  const [cloudDraft, setCloudDraft] = useState<CloudBackupConfig>(EMPTY_CLOUD_BACKUP.disabled);
+  const [cloudPassphrase, setCloudPassphrase] = useState("");
+  const [uploading, setUploading] = useState(false);
+  const [notifyDraft, setNotifyDraft] = useState<NotifyConfig>(DEFAULT_NOTIFY);
+  const [proxyUrlDraft, setProxyUrlDraft] = useState("");
+  const [systemProxy, setSystemProxy] = useState<SystemProxy | null>(null);
+  const [apiBaseDraft, setApiBaseDraft] = useState("");
+  const [loginUrlDraft, setLoginUrlDraft] = useState("");
+  const [mailDomainsDraft, setMailDomainsDraft] = useState("");
+  const [codeKeywordsDraft, setCodeKeywordsDraft] = useState("");
+  const [captchaDraft, setCaptchaDraft] = useState<CaptchaSolverConfig>({ kind: "disabled" });
+  const [registerProxyMode, setRegisterProxyMode] = useState<"disabled" | ProxyRotation>("disabled");
+  const [registerProxyDraft, setRegisterProxyDraft] = useState("");
+  const [testingSink, setTestingSink] = useState<SinkKind | null>(null);
+  const [selfTestUrl, setSelfTestUrl] = useState("");
+  const [rotationStatus, setRotationStatus] = useState<RotationStatus | null>(null);
+  const [rotationBusy, setRotationBusy] = useState(false);
+  const [selfTesting, setSelfTesting] = useState(false);
+  const [selfTestResults, setSelfTestResults] = useState<SelfTestCheck[]>([]);
+  const [generatingReport, setGeneratingReport] = useState(false);
+  const [cleaning, setCleaning] = useState(false);
+  const [mappings, setMappings] = useState<WorkspaceMapping[]>([]);
+  const [mappingAccounts, setMappingAccounts] = useState<AccountBrief[]>([]);
+  const [newMappingFolder, setNewMappingFolder] = useState("");
+  const [newMappingAccountId, setNewMappingAccountId] = useState("");
   const defaultSettings = useMemo<AppSettings>(
     () => ({
       quick_register_show_window: false,
@@ -60,9 +173,33 @@ export function Settings({
     }
   };
 
+  // 加载备份列表
+  const loadBackups = async () => {
+    try {
+      setBackups(await api.listBackups());
+    } catch (err: any) {
+      console.error("获取备份列表失败:", err);
+    }
+  };
+
+  // 加载工作区映射
+  const loadMappings = async () => {
+    try {
+      const [list, accounts] = await Promise.all([api.listWorkspaceMappings(), api.getAccounts()]);
+      setMappings(list);
+      setMappingAccounts(accounts);
+    } catch (err: any) {
+      console.error("获取工作区映射失败:", err);
+    }
+  };
+
   useEffect(() => {
+    api.getRotationStatus().then(setRotationStatus).catch(() => setRotationStatus(null));
+    api.getTraeInstallStatus().then(setInstallStatus).catch(() => setInstallStatus(null));
     loadTraeMachineId();
     loadTraePath();
+    loadBackups();
+    loadMappings();
   }, []);
 
   useEffect(() => {
@@ -71,6 +208,16 @@ export function Settings({
     }
   }, [settings]);
 
+  // 自动 / 系统代理模式下显示检测到的系统代理（包括不会执行的 PAC 脚本）
+  const proxyMode = appSettings?.proxy_mode ?? "default";
+  useEffect(() => {
+    if (proxyMode !== "default" && proxyMode !== "system") {
+      setSystemProxy(null);
+      return;
+    }
+    api.getSystemProxy().then(setSystemProxy).catch(() => setSystemProxy(null));
+  }, [proxyMode]);
+
   useEffect(() => {
     if (appSettings) return;
     api.getSettings()
@@ -106,6 +253,22 @@ export function Settings({
     }
   };
 
+  // 查看 Trae IDE 当前登录信息（再次点击收起）
+  const handleToggleAuthSnapshot = async () => {
+    if (authSnapshot) {
+      setAuthSnapshot(null);
+      return;
+    }
+    setLoadingSnapshot(true);
+    try {
+      setAuthSnapshot(await api.getTraeAuthSnapshot());
+    } catch (err: any) {
+      onToast?.("error", err.message || "读取登录信息失败");
+    } finally {
+      setLoadingSnapshot(false);
+    }
+  };
+
   // 自动扫描 Trae IDE 路径
   const handleScanTraePath = async () => {
     setScanning(true);
@@ -123,13 +286,15 @@ export function Settings({
   // 手动设置 Trae IDE 路径
   const handleSetTraePath = async () => {
     try {
+      // Linux 上的 Trae 可执行文件没有扩展名
+      const isLinux = navigator.userAgent.includes("Linux");
       const selected = await open({
         multiple: false,
-        filters: [{
+        filters: isLinux ? [] : [{
           name: "Trae IDE",
           extensions: ["exe"]
         }],
-        title: "选择 Trae.exe 文件"
+        title: isLinux ? "选择 Trae 可执行文件" : "选择 Trae.exe 文件"
       });
 
       if (selected) {
@@ -156,7 +321,374 @@ export function Settings({
     }
   };
 
+  // 在文件管理器中显示调试日志
+  const handleRevealDebugLog = async () => {
+    try {
+      await revealItemInDir(await api.getApiDebugLogPath());
+    } catch (err: any) {
+      onToast?.("error", err.message || "打开调试日志失败");
+    }
+  };
+
+  const handleClearDebugLog = async () => {
+    try {
+      await api.clearApiDebugLog();
+      onToast?.("success", "已清空调试日志");
+    } catch (err: any) {
+      onToast?.("error", err.message || "清空调试日志失败");
+    }
+  };
+
+  // 在文件管理器中显示窗口控制台日志
+  const handleRevealConsoleLog = async () => {
+    try {
+      await revealItemInDir(await api.getWebviewConsoleLogPath());
+    } catch (err: any) {
+      onToast?.("error", err.message || "打开窗口控制台日志失败");
+    }
+  };
+
+  const handleClearConsoleLog = async () => {
+    try {
+      await api.clearWebviewConsoleLog();
+      onToast?.("success", "已清空窗口控制台日志");
+    } catch (err: any) {
+      onToast?.("error", err.message || "清空窗口控制台日志失败");
+    }
+  };
+
+  // 选择额外信任的 CA 证书
+  const handleSelectCaFile = async () => {
+    try {
+      const selected = await open({
+        multiple: false,
+        filters: [{
+          name: "CA 证书",
+          extensions: ["pem", "crt", "cer"]
+        }],
+        title: "选择 CA 证书文件（PEM 格式）"
+      });
+      if (selected) {
+        await updateSettings({ extra_ca_path: selected as string }, "已添加自定义 CA 证书");
+      }
+    } catch (err: any) {
+      onToast?.("error", err.message || "选择文件失败");
+    }
+  };
+
+  // 固定 Trae API 当前的服务器证书
+  const handleTogglePinning = async () => {
+    if (currentSettings.pinned_certs?.length) {
+      await updateSettings({ pinned_certs: [] }, "已关闭证书固定");
+      return;
+    }
+    setPinning(true);
+    try {
+      const fingerprints = await api.getApiCertificateFingerprints();
+      if (fingerprints.length === 0) {
+        onToast?.("error", "无法获取 Trae API 证书，请检查网络");
+        return;
+      }
+      const summary = fingerprints.map((f) => `${f.host}\n${f.sha256}`).join("\n\n");
+      if (!confirm(`将固定以下证书，证书更换后需要重新固定：\n\n${summary}`)) {
+        return;
+      }
+      await updateSettings(
+        { pinned_certs: Array.from(new Set(fingerprints.map((f) => f.sha256))) },
+        "已启用证书固定"
+      );
+    } catch (err: any) {
+      onToast?.("error", err.message || "获取证书失败");
+    } finally {
+      setPinning(false);
+    }
+  };
+
+  // 检测 Trae 接口和临时邮箱服务是否可用
+  const handleCheckApiHealth = async () => {
+    setHealthChecking(true);
+    try {
+      const report = await api.checkApiHealth();
+      setHealthReport(report);
+      onToast?.(report.healthy ? "success" : "warning", report.healthy ? "所有服务均可访问" : "部分服务无法访问");
+    } catch (err: any) {
+      onToast?.("error", err.message || "连接检测失败");
+    } finally {
+      setHealthChecking(false);
+    }
+  };
+
+  // 选择工作区目录
+  const handlePickMappingFolder = async () => {
+    try {
+      const selected = await open({ directory: true, multiple: false, title: "选择工作区目录" });
+      if (selected) {
+        setNewMappingFolder(selected as string);
+      }
+    } catch (err: any) {
+      onToast?.("error", err.message || "选择目录失败");
+    }
+  };
+
+  // 使用 Trae 当前打开的工作区
+  const handleUseCurrentWorkspace = async () => {
+    try {
+      const folders = await api.getTraeOpenWorkspaces();
+      if (folders.length === 0) {
+        onToast?.("warning", "Trae IDE 当前没有打开工作区");
+        return;
+      }
+      setNewMappingFolder(folders[0]);
+    } catch (err: any) {
+      onToast?.("error", err.message || "读取 Trae 工作区失败");
+    }
+  };
+
+  const handleSaveMapping = async (folder: string, accountId: string, autoSwitch: boolean) => {
+    try {
+      await api.setWorkspaceMapping(folder, accountId, autoSwitch);
+      setNewMappingFolder("");
+      await loadMappings();
+      onToast?.("success", "已保存工作区映射", 1000);
+    } catch (err: any) {
+      onToast?.("error", err.message || "保存工作区映射失败");
+    }
+  };
+
+  const handleRemoveMapping = async (mappingId: string) => {
+    try {
+      await api.removeWorkspaceMapping(mappingId);
+      await loadMappings();
+    } catch (err: any) {
+      onToast?.("error", err.message || "删除工作区映射失败");
+    }
+  };
+
+  const accountLabel = (accountId: string) => {
+    const account = mappingAccounts.find((a) => a.id === accountId);
+    return account ? account.email || account.name : "未知账号";
+  };
+
+  // 立即备份
+  const handleCreateBackup = async () => {
+    setBackingUp(true);
+    try {
+      const backup = await api.createBackup();
+      onToast?.("success", `已备份 ${backup.account_count} 个账号`);
+      await loadBackups();
+    } catch (err: any) {
+      onToast?.("error", err.message || "备份失败");
+    } finally {
+      setBackingUp(false);
+    }
+  };
+
+  // 保存云端备份配置
+  const handleSaveCloudBackup = async () => {
+    await updateSettings(
+      { cloud_backup: cloudDraft, cloud_backup_passphrase: cloudPassphrase },
+      "已保存云端备份设置"
+    );
+  };
+
+  // 上传最近一个备份到云端
+  const handleUploadBackup = async () => {
+    setUploading(true);
+    try {
+      const location = await api.uploadBackup();
+      onToast?.("success", `已上传备份：${location}`);
+    } catch (err: any) {
+      onToast?.("error", err.message || "上传备份失败");
+    } finally {
+      setUploading(false);
+    }
+  };
+
+  const updateCloudDraft = (field: string, value: string) => {
+    setCloudDraft((draft) => ({ ...draft, [field]: value }) as CloudBackupConfig);
+  };
+
+  const cloudField = (field: string, placeholder: string, secret = false) => (
+    <input
+      key={field}
+      className="setting-input"
+      type={secret ? "password" : "text"}
+      placeholder={placeholder}
+      value={(cloudDraft as unknown as Record<string, string>)[field] ?? ""}
+      onChange={(e) => updateCloudDraft(field, e.target.value)}
+    />
+  );
+
+  // 立即生成周报
+  const handleGenerateReport = async () => {
+    setGeneratingReport(true);
+    try {
+      const report = await api.generateWeeklyReport();
+      onToast?.("success", `周报已保存到 ${report.path}`);
+    } catch (err: any) {
+      onToast?.("error", err.message || "生成周报失败");
+    } finally {
+      setGeneratingReport(false);
+    }
+  };
+
+  // 保存通知渠道配置（失焦时）
+  const saveNotifyDraft = () => {
+    const current = currentSettings.notify ?? DEFAULT_NOTIFY;
+    const next = {
+      ...current,
+      webhook_url: notifyDraft.webhook_url.trim(),
+      telegram_bot_token: notifyDraft.telegram_bot_token.trim(),
+      telegram_chat_id: notifyDraft.telegram_chat_id.trim(),
+    };
+    if (
+      next.webhook_url !== current.webhook_url ||
+      next.telegram_bot_token !== current.telegram_bot_token ||
+      next.telegram_chat_id !== current.telegram_chat_id
+    ) {
+      void updateSettings({ notify: next }, "已更新通知渠道");
+    }
+  };
+
+  // 切换某类事件是否发送到某个渠道
+  const toggleNotifyRoute = (event: NotifyEvent, sink: SinkKind) => {
+    const current = currentSettings.notify ?? DEFAULT_NOTIFY;
+    const sinks = current.routes[event] ?? [];
+    const nextSinks = sinks.includes(sink) ? sinks.filter((s) => s !== sink) : [...sinks, sink];
+    void updateSettings(
+      { notify: { ...current, routes: { ...current.routes, [event]: nextSinks } } },
+      "已更新通知规则"
+    );
+  };
+
+  // 发送测试通知
+  const handleTestNotification = async (sink: SinkKind) => {
+    setTestingSink(sink);
+    try {
+      await api.sendTestNotification(sink);
+      onToast?.("success", "测试通知已发送");
+    } catch (err: any) {
+      onToast?.("error", err.message || "发送测试通知失败");
+    } finally {
+      setTestingSink(null);
+    }
+  };
+
+  // 修改轮换设置（不改变开始 / 停止状态）
+  const updateRotation = (updates: Partial<RotationConfig>, successMessage: string) => {
+    const current = currentSettings.rotation ?? DEFAULT_ROTATION;
+    void updateSettings({ rotation: { ...current, ...updates } }, successMessage);
+  };
+
+  // 修改账号池自动补充设置（目标数量不小于最低数量）
+  const updatePoolTopUp = (updates: Partial<PoolTopUpConfig>, successMessage: string) => {
+    const next = { ...(currentSettings.pool_topup ?? DEFAULT_POOL_TOPUP), ...updates };
+    if ("min_available" in updates) {
+      next.target_available = Math.max(next.target_available, next.min_available);
+    } else {
+      next.min_available = Math.min(next.min_available, next.target_available);
+    }
+    void updateSettings({ pool_topup: next }, successMessage);
+  };
+
+  // 修改额度用完账号的自动清理设置
+  const updateRetirement = (updates: Partial<RetirementConfig>, successMessage: string) => {
+    void updateSettings({ retirement: { ...(currentSettings.retirement ?? DEFAULT_RETIREMENT), ...updates } }, successMessage);
+  };
+
+  // 开始或停止账号轮换
+  const handleToggleRotation = async () => {
+    const running = currentSettings.rotation?.enabled ?? false;
+    setRotationBusy(true);
+    try {
+      const status = running ? await api.stopRotation() : await api.startRotation();
+      setRotationStatus(status);
+      const saved = await api.getSettings();
+      setAppSettings(saved);
+      onSettingsChange?.(saved);
+      onToast?.("success", running ? "已停止账号轮换" : "已开始账号轮换", 1000);
+    } catch (err: any) {
+      onToast?.("error", err.message || "操作失败");
+    } finally {
+      setRotationBusy(false);
+    }
+  };
+
+  // 环境自检
+  const handleSelfTest = async () => {
+    setSelfTesting(true);
+    setSelfTestResults([]);
+    try {
+      const results = await api.selfTest(selfTestUrl.trim() || undefined);
+      setSelfTestResults(results);
+      const failed = results.filter((r) => !r.passed).length;
+      onToast?.(failed === 0 ? "success" : "warning", failed === 0 ? "自检全部通过" : `${failed} 项自检未通过`);
+    } catch (err: any) {
+      onToast?.("error", err.message || "自检失败");
+    } finally {
+      setSelfTesting(false);
+    }
+  };
+
+  // 清理过期数据
+  const handleCleanup = async () => {
+    setCleaning(true);
+    try {
+      const report = await api.runCleanup();
+      if (report.items.length === 0) {
+        onToast?.("info", "没有需要清理的数据");
+      } else {
+        const mb = (report.reclaimed_bytes / 1024 / 1024).toFixed(1);
+        onToast?.("success", `已清理 ${report.items.length} 项，释放 ${mb} MB`);
+      }
+      await loadBackups();
+    } catch (err: any) {
+      onToast?.("error", err.message || "清理失败");
+    } finally {
+      setCleaning(false);
+    }
+  };
+
+  // 从备份恢复
+  const handleRestoreBackup = async (backup: BackupEntry) => {
+    const time = new Date(backup.created_at * 1000).toLocaleString();
+    if (!confirm(`确定要恢复 ${time} 的备份吗？\n\n当前账号数据将被替换（恢复前会自动备份当前数据）。`)) {
+      return;
+    }
+    const restoreSettings = backup.has_settings && confirm("是否同时恢复该备份中的设置？");
+    try {
+      const count = await api.restoreBackup(backup.id, restoreSettings);
+      if (restoreSettings) {
+        const saved = await api.getSettings();
+        setAppSettings(saved);
+        onSettingsChange?.(saved);
+      }
+      onAccountsRestored?.();
+      onToast?.("success", `已恢复 ${count} 个账号`);
+      await loadBackups();
+    } catch (err: any) {
+      onToast?.("error", err.message || "恢复失败");
+    }
+  };
+
+  useEffect(() => {
+    setCloudDraft(appSettings?.cloud_backup ?? EMPTY_CLOUD_BACKUP.disabled);
+    setCloudPassphrase(appSettings?.cloud_backup_passphrase ?? "");
+    setNotifyDraft(appSettings?.notify ?? DEFAULT_NOTIFY);
+    setProxyUrlDraft(appSettings?.proxy_url ?? "");
+    setApiBaseDraft(appSettings?.api_base_url ?? "");
+    setLoginUrlDraft(appSettings?.browser_login_url ?? "");
+    setMailDomainsDraft((appSettings?.mail_domains ?? []).join(", "));
+    setCodeKeywordsDraft((appSettings?.verification_code ?? DEFAULT_CODE_EXTRACTION).keywords.join(", "));
+    setCaptchaDraft(appSettings?.captcha_solver ?? { kind: "disabled" });
+    setRegisterProxyMode(appSettings?.register_proxy?.enabled ? appSettings.register_proxy.rotation : "disabled");
+    setRegisterProxyDraft((appSettings?.register_proxy?.urls ?? []).join("\n"));
+  }, [appSettings]);
+
   const currentSettings = appSettings ?? defaultSettings;
+  const poolTopUp = currentSettings.pool_topup ?? DEFAULT_POOL_TOPUP;
+  const retirement = currentSettings.retirement ?? DEFAULT_RETIREMENT;
+  const codeExtraction = currentSettings.verification_code ?? DEFAULT_CODE_EXTRACTION;
   const settingsDisabled = !appSettings;
   const handlePrivacyHelp = () => {
     const message =
@@ -173,7 +705,28 @@ export function Settings({
       {/* Trae IDE 设置 */}
       <div className="settings-section">
         <h3>Trae IDE 配置</h3>
-        
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">安装状态</div>
+            <div className="setting-desc">
+              {!installStatus
+                ? "检测中..."
+                : installStatus.installed
+                  ? `已检测到 Trae IDE${installStatus.running ? "（运行中）" : ""}：${installStatus.data_dir}`
+                  : `${installStatus.reason || "未检测到 Trae IDE"}。账号管理、使用量查询和注册不受影响，切换账号、机器码等功能已停用`}
+            </div>
+          </div>
+          <div className="setting-action">
+            <button
+              className="setting-btn"
+              onClick={() => api.getTraeInstallStatus().then(setInstallStatus).catch(() => setInstallStatus(null))}
+            >
+              重新检测
+            </button>
+          </div>
+        </div>
+
         {/* Machine ID */}
         <div className="setting-item" style={{ alignItems: 'flex-start' }}>
           <div className="setting-info" style={{ flex: 1, overflow: 'hidden' }}>
@@ -262,7 +815,7 @@ export function Settings({
             <button
               className="setting-btn danger"
               onClick={handleClearTraeLoginState}
-              disabled={clearingTrae || traeRefreshing}
+              disabled={clearingTrae || traeRefreshing || installStatus?.installed === false}
               style={{ whiteSpace: 'nowrap' }}
             >
               {clearingTrae ? "清除中..." : "清除登录状态"}
@@ -331,6 +884,90 @@ export function Settings({
           </div>
         </div>
 
+        {/* 权益信息 */}
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">权益信息</div>
+            <div className="setting-desc">切换账号时写入 Trae IDE 的套餐权益，写错可能导致 Pro 功能不可用</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.entitlement_mode ?? "from_plan"}
+              onChange={(e) =>
+                updateSettings(
+                  { entitlement_mode: e.target.value as EntitlementMode },
+                  "已更新权益信息设置"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value="from_plan">按账号套餐</option>
+              <option value="free">固定 Free</option>
+              <option value="skip">不写入</option>
+            </select>
+          </div>
+        </div>
+
+        {/* Token 过期提醒 */}
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">Token 过期提醒</div>
+            <div className="setting-desc">账号 Token 即将过期时提醒重新登录，避免 Trae IDE 突然掉线</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.token_expiry_warning_hours ?? 24}
+              onChange={(e) =>
+                updateSettings(
+                  { token_expiry_warning_hours: Number(e.target.value) },
+                  "已更新 Token 过期提醒设置"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={0}>关闭</option>
+              <option value={6}>提前 6 小时</option>
+              <option value={24}>提前 1 天</option>
+              <option value={72}>提前 3 天</option>
+            </select>
+          </div>
+        </div>
+
+        {/* 当前登录信息 */}
+        <div className="setting-item" style={{ alignItems: 'flex-start' }}>
+          <div className="setting-info" style={{ flex: 1, overflow: 'hidden' }}>
+            <div className="setting-label">当前登录信息</div>
+            <div className="setting-desc">只读查看 Trae IDE storage.json 中的登录数据，Token 等敏感字段已打码</div>
+            {authSnapshot && (
+              <pre style={{
+                marginTop: '8px',
+                padding: '10px 12px',
+                maxHeight: '320px',
+                overflow: 'auto',
+                background: 'var(--bg-secondary)',
+                border: '1px solid var(--border)',
+                borderRadius: '8px',
+                fontSize: '12px',
+                whiteSpace: 'pre-wrap',
+                wordBreak: 'break-all',
+              }}>
+                {JSON.stringify(authSnapshot, null, 2)}
+              </pre>
+            )}
+          </div>
+          <div className="setting-action">
+            <button
+              className="setting-btn"
+              onClick={handleToggleAuthSnapshot}
+              disabled={loadingSnapshot}
+            >
+              {loadingSnapshot ? "读取中..." : authSnapshot ? "收起" : "查看"}
+            </button>
+          </div>
+        </div>
+
         {/* 自动开启隐私模式 */}
         <div className="setting-item">
           <div className="setting-info">
@@ -392,22 +1029,22 @@ export function Settings({
 
         <div className="setting-item">
           <div className="setting-info">
-            <div className="setting-label">自动刷新</div>
-            <div className="setting-desc">定时自动刷新账号使用量数据</div>
+            <div className="setting-label">注册后绑定新机器码</div>
+            <div className="setting-desc">快速注册成功后为新账号生成独立的机器码，切换到该账号时写入</div>
           </div>
           <div className="setting-action">
             <button
               type="button"
-              className={`pill-toggle ${currentSettings.auto_refresh_enabled ? "on" : ""}`}
+              className={`pill-toggle ${currentSettings.quick_register_new_machine_id ? "on" : ""}`}
               onClick={() =>
                 updateSettings(
-                  { auto_refresh_enabled: !currentSettings.auto_refresh_enabled },
-                  "已更新自动刷新设置"
+                  { quick_register_new_machine_id: !currentSettings.quick_register_new_machine_id },
+                  "已更新注册后绑定机器码设置"
                 )
               }
               disabled={settingsDisabled}
               role="switch"
-              aria-checked={currentSettings.auto_refresh_enabled}
+              aria-checked={!!currentSettings.quick_register_new_machine_id}
             >
               <span className="pill-track"></span>
               <span className="pill-thumb"></span>
@@ -415,25 +1052,24 @@ export function Settings({
           </div>
         </div>
 
-
         <div className="setting-item">
           <div className="setting-info">
-            <div className="setting-label">自动检查更新</div>
-            <div className="setting-desc">启动时自动请求 GitHub Release 更新信息</div>
+            <div className="setting-label">注册后自动切换</div>
+            <div className="setting-desc">快速注册成功后立即切换到新账号（批量注册不切换）</div>
           </div>
           <div className="setting-action">
             <button
               type="button"
-              className={`pill-toggle ${currentSettings.auto_update_check ? "on" : ""}`}
+              className={`pill-toggle ${currentSettings.quick_register_auto_switch ? "on" : ""}`}
               onClick={() =>
                 updateSettings(
-                  { auto_update_check: !currentSettings.auto_update_check },
-                  "已更新自动检查更新设置"
+                  { quick_register_auto_switch: !currentSettings.quick_register_auto_switch },
+                  "已更新注册后自动切换设置"
                 )
               }
               disabled={settingsDisabled}
               role="switch"
-              aria-checked={currentSettings.auto_update_check}
+              aria-checked={!!currentSettings.quick_register_auto_switch}
             >
               <span className="pill-track"></span>
               <span className="pill-thumb"></span>
@@ -441,18 +1077,459 @@ export function Settings({
           </div>
         </div>
 
-
         <div className="setting-item">
           <div className="setting-info">
-            <div className="setting-label">
-              开机静默自动刷新 Token
-              <span style={{ 
-                fontSize: '11px', 
-                padding: '2px 6px', 
-                background: 'var(--success-bg)', 
-                color: 'var(--success)', 
-                borderRadius: '4px', 
-                marginLeft: '8px',
+            <div className="setting-label">随机浏览器特征</div>
+            <div className="setting-desc">每次注册随机使用不同的 User-Agent、语言和窗口大小，降低批量注册账号之间的关联</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${(currentSettings.quick_register_random_fingerprint ?? true) ? "on" : ""}`}
+              onClick={() =>
+                updateSettings(
+                  { quick_register_random_fingerprint: !(currentSettings.quick_register_random_fingerprint ?? true) },
+                  "已更新随机浏览器特征设置"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={currentSettings.quick_register_random_fingerprint ?? true}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">浏览器登录等待时长</div>
+            <div className="setting-desc">超过该时长仍未完成登录时关闭登录窗口，等待期间可在添加账号窗口中延长</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.browser_login_timeout_secs ?? 300}
+              onChange={(e) =>
+                updateSettings({ browser_login_timeout_secs: Number(e.target.value) }, "已更新浏览器登录等待时长")
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={180}>3 分钟</option>
+              <option value={300}>5 分钟</option>
+              <option value={600}>10 分钟</option>
+              <option value={1200}>20 分钟</option>
+              <option value={1800}>30 分钟</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">浏览器登录起始页</div>
+            <div className="setting-desc">登录窗口打开的页面，如国内版登录页或企业 SSO 入口；留空时打开 Trae 海外版登录页</div>
+            <input
+              className="setting-input"
+              style={{ marginTop: '8px' }}
+              placeholder="https://www.trae.ai/login"
+              value={loginUrlDraft}
+              onChange={(e) => setLoginUrlDraft(e.target.value)}
+              onBlur={() => {
+                if (loginUrlDraft.trim() !== (currentSettings.browser_login_url ?? "")) {
+                  void updateSettings({ browser_login_url: loginUrlDraft.trim() }, "已更新浏览器登录起始页");
+                }
+              }}
+              disabled={settingsDisabled}
+            />
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">快速注册通知</div>
+            <div className="setting-desc">详细模式会推送每个步骤及耗时，显示浏览器窗口时也会推送</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.quick_register_notice_level ?? "milestones"}
+              onChange={(e) =>
+                updateSettings(
+                  { quick_register_notice_level: e.target.value as NoticeLevel },
+                  "已更新快速注册通知设置"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value="silent">不通知</option>
+              <option value="milestones">关键节点</option>
+              <option value="verbose">详细</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">临时邮箱服务</div>
+            <div className="setting-desc">快速注册接收验证码使用的服务，不可用时自动切换到其他服务</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.mail_provider ?? "mail_cx"}
+              onChange={(e) =>
+                updateSettings(
+                  { mail_provider: e.target.value as MailProviderKind },
+                  "已更新临时邮箱服务"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value="mail_cx">mail.cx</option>
+              <option value="mail_tm">mail.tm</option>
+              <option value="one_sec_mail">1secmail</option>
+              <option value="guerrilla_mail">Guerrilla Mail</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">自定义收件域名</div>
+            <div className="setting-desc">
+              使用自有的 catch-all 域名接收验证码（需将域名邮件交由 mail.cx 接收），多个域名用逗号分隔，留空使用内置域名
+            </div>
+            <input
+              className="setting-input"
+              style={{ marginTop: '8px' }}
+              placeholder="example.com, mail.example.org"
+              value={mailDomainsDraft}
+              onChange={(e) => setMailDomainsDraft(e.target.value)}
+              onBlur={() => {
+                const domains = mailDomainsDraft
+                  .split(/[,，\s]+/)
+                  .map((d) => d.trim())
+                  .filter(Boolean);
+                if (domains.join(",") !== (currentSettings.mail_domains ?? []).join(",")) {
+                  void updateSettings({ mail_domains: domains }, "已更新收件域名");
+                }
+              }}
+              disabled={settingsDisabled}
+            />
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">验证码识别</div>
+            <div className="setting-desc">
+              查找长度恰好匹配的验证码，优先选择关键词之后的匹配，避免误取邮件中的日期或订单号
+            </div>
+            <div style={{ display: 'flex', gap: '8px', marginTop: '8px', alignItems: 'center' }}>
+              <input
+                type="number"
+                className="setting-input"
+                style={{ width: '72px' }}
+                min={4}
+                max={12}
+                value={codeExtraction.length}
+                onChange={(e) =>
+                  updateSettings(
+                    { verification_code: { ...codeExtraction, length: Math.min(12, Math.max(4, Number(e.target.value) || 6)) } },
+                    "已更新验证码识别规则"
+                  )
+                }
+                disabled={settingsDisabled}
+              />
+              <select
+                className="setting-select"
+                value={codeExtraction.pattern}
+                onChange={(e) =>
+                  updateSettings(
+                    { verification_code: { ...codeExtraction, pattern: e.target.value as CodeExtraction["pattern"] } },
+                    "已更新验证码识别规则"
+                  )
+                }
+                disabled={settingsDisabled}
+              >
+                <option value="digits">纯数字</option>
+                <option value="alphanumeric">字母和数字</option>
+              </select>
+              <input
+                className="setting-input"
+                style={{ flex: 1 }}
+                placeholder="关键词，用逗号分隔"
+                value={codeKeywordsDraft}
+                onChange={(e) => setCodeKeywordsDraft(e.target.value)}
+                onBlur={() => {
+                  const keywords = codeKeywordsDraft.split(/[,，]/).map((k) => k.trim()).filter(Boolean);
+                  if (keywords.join(",") !== codeExtraction.keywords.join(",")) {
+                    void updateSettings({ verification_code: { ...codeExtraction, keywords } }, "已更新验证码识别规则");
+                  }
+                }}
+                disabled={settingsDisabled}
+              />
+            </div>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">打码服务</div>
+            <div className="setting-desc">
+              注册页面出现 reCAPTCHA、hCaptcha 或 Turnstile 验证时自动调用打码服务，批量注册无需值守；未配置或处理失败时显示注册窗口手动完成
+            </div>
+            {captchaDraft.kind !== "disabled" && (
+              <input
+                className="setting-input"
+                style={{ marginTop: '8px' }}
+                type="password"
+                placeholder="API Key"
+                value={captchaDraft.api_key}
+                onChange={(e) => setCaptchaDraft({ ...captchaDraft, api_key: e.target.value })}
+                disabled={settingsDisabled}
+              />
+            )}
+          </div>
+          <div className="setting-action" style={{ flexDirection: 'column', gap: '8px' }}>
+            <select
+              className="setting-select"
+              value={captchaDraft.kind}
+              onChange={(e) => {
+                const kind = e.target.value as CaptchaSolverConfig["kind"];
+                setCaptchaDraft(
+                  kind === "disabled"
+                    ? { kind }
+                    : { kind, api_key: captchaDraft.kind === "disabled" ? "" : captchaDraft.api_key }
+                );
+              }}
+              disabled={settingsDisabled}
+            >
+              <option value="disabled">关闭</option>
+              <option value="two_captcha">2Captcha</option>
+              <option value="anti_captcha">Anti-Captcha</option>
+            </select>
+            <button
+              className="setting-btn"
+              onClick={() => updateSettings({ captcha_solver: captchaDraft }, "已保存打码服务设置")}
+              disabled={settingsDisabled}
+            >
+              保存
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">注册代理</div>
+            <div className="setting-desc">
+              每次快速注册从代理池中选择一个代理，注册窗口和临时邮箱请求都通过该代理，避免大量账号来自同一 IP；每行一个 http:// 或 socks5:// 地址，不支持需要认证的代理
+            </div>
+            {registerProxyMode !== "disabled" && (
+              <textarea
+                className="setting-input"
+                style={{ marginTop: '8px', minHeight: '72px', fontFamily: 'monospace' }}
+                placeholder={"http://127.0.0.1:7890\nsocks5://10.0.0.2:1080"}
+                value={registerProxyDraft}
+                onChange={(e) => setRegisterProxyDraft(e.target.value)}
+                disabled={settingsDisabled}
+              />
+            )}
+          </div>
+          <div className="setting-action" style={{ flexDirection: 'column', gap: '8px' }}>
+            <select
+              className="setting-select"
+              value={registerProxyMode}
+              onChange={(e) => setRegisterProxyMode(e.target.value as "disabled" | ProxyRotation)}
+              disabled={settingsDisabled}
+            >
+              <option value="disabled">不使用</option>
+              <option value="round_robin">轮流使用</option>
+              <option value="random">随机选择</option>
+            </select>
+            <button
+              className="setting-btn"
+              onClick={() =>
+                updateSettings(
+                  {
+                    register_proxy: {
+                      enabled: registerProxyMode !== "disabled",
+                      rotation: registerProxyMode === "random" ? "random" : "round_robin",
+                      urls: registerProxyDraft.split(/\r?\n/).map((u) => u.trim()).filter(Boolean),
+                    },
+                  },
+                  "已保存注册代理设置"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              保存
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">临时邮箱收件箱</div>
+            <div className="setting-desc">注册卡在等待验证码时，查看验证码邮件是否送达及其内容</div>
+            <MailboxInspector />
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">注册凭据记录</div>
+            <div className="setting-desc">
+              每次快速注册的邮箱、密码和结果都会加密追加到独立的日志（密钥保存在系统密钥链），注册完成但导入失败时可据此手动登录
+            </div>
+            <CredentialJournal />
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">环境自检</div>
+            <div className="setting-desc">检查注册页能否打开、临时邮箱是否可用、IDE 配置能否写入（写入临时目录，不创建账号）</div>
+            <input
+              className="setting-input"
+              style={{ marginTop: '8px' }}
+              placeholder="注册页地址（留空使用官方注册页）"
+              value={selfTestUrl}
+              onChange={(e) => setSelfTestUrl(e.target.value)}
+            />
+            {selfTestResults.map((check) => (
+              <div
+                key={check.name}
+                className="setting-desc"
+                style={{ marginTop: '4px', color: check.passed ? undefined : 'var(--danger)' }}
+                title={check.detail}
+              >
+                {check.passed ? "✓" : "✗"} {SELF_TEST_LABELS[check.name] ?? check.name}（{check.duration_ms} ms）：{check.detail}
+              </div>
+            ))}
+          </div>
+          <div className="setting-action">
+            <button className="setting-btn" onClick={handleSelfTest} disabled={selfTesting}>
+              {selfTesting ? "检查中..." : "开始自检"}
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">自动刷新</div>
+            <div className="setting-desc">定时自动刷新账号使用量：当前账号每 5 分钟，最近活跃的账号每 15 分钟，闲置账号每小时</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${currentSettings.auto_refresh_enabled ? "on" : ""}`}
+              onClick={() =>
+                updateSettings(
+                  { auto_refresh_enabled: !currentSettings.auto_refresh_enabled },
+                  "已更新自动刷新设置"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={currentSettings.auto_refresh_enabled}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">额度用完自动切换</div>
+            <div className="setting-desc">当前账号快速请求用完时，自动切换到下一个还有额度的账号并发送通知（每 10 分钟检查一次）</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${currentSettings.auto_switch_on_exhausted ? "on" : ""}`}
+              onClick={() =>
+                updateSettings(
+                  { auto_switch_on_exhausted: !currentSettings.auto_switch_on_exhausted },
+                  "已更新自动切换设置"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={!!currentSettings.auto_switch_on_exhausted}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">使用量缓存</div>
+            <div className="setting-desc">有效期内列表刷新直接使用缓存，手动刷新单个账号时总会重新查询</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.usage_cache_ttl_secs ?? 300}
+              onChange={(e) =>
+                updateSettings(
+                  { usage_cache_ttl_secs: Number(e.target.value) },
+                  "已更新使用量缓存设置"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={0}>不缓存</option>
+              <option value={60}>1 分钟</option>
+              <option value={300}>5 分钟</option>
+              <option value={900}>15 分钟</option>
+              <option value={3600}>1 小时</option>
+            </select>
+          </div>
+        </div>
+
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">自动检查更新</div>
+            <div className="setting-desc">启动时自动请求 GitHub Release 更新信息</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${currentSettings.auto_update_check ? "on" : ""}`}
+              onClick={() =>
+                updateSettings(
+                  { auto_update_check: !currentSettings.auto_update_check },
+                  "已更新自动检查更新设置"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={currentSettings.auto_update_check}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">
+              开机静默自动刷新 Token
+              <span style={{ 
+                fontSize: '11px', 
+                padding: '2px 6px', 
+                background: 'var(--success-bg)', 
+                color: 'var(--success)', 
+                borderRadius: '4px', 
+                marginLeft: '8px',
                 fontWeight: 'normal',
                 border: '1px solid var(--success-border)'
               }}>强烈推荐</span>
@@ -495,6 +1572,1031 @@ export function Settings({
             </select>
           </div>
         </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">网络代理</div>
+            <div className="setting-desc">
+              「自动」优先使用代理环境变量，未设置时读取 Windows Internet 选项 / WinHTTP、macOS 网络代理设置（不支持 PAC 自动配置脚本，请改用手动代理）
+            </div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.proxy_mode ?? "default"}
+              onChange={(e) =>
+                updateSettings(
+                  { proxy_mode: e.target.value as ProxyMode },
+                  "已更新网络代理设置"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value="default">自动</option>
+              <option value="system">系统代理</option>
+              <option value="direct">直连</option>
+              <option value="manual">手动代理</option>
+            </select>
+          </div>
+        </div>
+
+        {systemProxy && (systemProxy.pac_url || systemProxy.http || systemProxy.https) && (
+          <div className="setting-item">
+            <div className="setting-info" style={{ flex: 1 }}>
+              <div className="setting-label">检测到的系统代理</div>
+              <div className="setting-desc">
+                {systemProxy.http || systemProxy.https
+                  ? `HTTP：${systemProxy.http ?? "无"}，HTTPS：${systemProxy.https ?? "无"}`
+                  : "未设置静态代理，将直连"}
+              </div>
+              {systemProxy.pac_url && (
+                <div className="setting-desc" style={{ marginTop: '8px', color: 'var(--warning)', display: 'flex', alignItems: 'center', gap: '4px' }}>
+                  <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2"><circle cx="12" cy="12" r="10"/><line x1="12" y1="8" x2="12" y2="12"/><line x1="12" y1="16" x2="12" y2="16"/></svg>
+                  <span>系统使用了 PAC 自动配置脚本 {systemProxy.pac_url}，本工具不执行 PAC 脚本，访问 Trae 和临时邮箱不会按脚本选择代理；需要代理时请改用「手动代理」</span>
+                </div>
+              )}
+            </div>
+          </div>
+        )}
+
+        {currentSettings.proxy_mode === "manual" && (
+          <div className="setting-item">
+            <div className="setting-info" style={{ flex: 1 }}>
+              <div className="setting-label">代理地址</div>
+              <div className="setting-desc">
+                支持 http://、https://、socks5://（socks5h:// 由代理解析域名），需要认证时填写 user:pass@host:port
+              </div>
+              <input
+                className="setting-input"
+                style={{ marginTop: '8px' }}
+                placeholder="socks5://127.0.0.1:1080"
+                value={proxyUrlDraft}
+                onChange={(e) => setProxyUrlDraft(e.target.value)}
+                onBlur={() => {
+                  if (proxyUrlDraft.trim() !== (currentSettings.proxy_url ?? "")) {
+                    void updateSettings({ proxy_url: proxyUrlDraft.trim() }, "已更新代理地址");
+                  }
+                }}
+                disabled={settingsDisabled}
+              />
+            </div>
+          </div>
+        )}
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">自定义 API 地址</div>
+            <div className="setting-desc">
+              通过镜像或中转服务访问 Trae API，设置后替代内置的各区域端点；账号单独指定的 API 地址仍然优先
+            </div>
+            <input
+              className="setting-input"
+              style={{ marginTop: '8px' }}
+              placeholder="https://api-sg-central.trae.ai"
+              value={apiBaseDraft}
+              onChange={(e) => setApiBaseDraft(e.target.value)}
+              onBlur={() => {
+                if (apiBaseDraft.trim() !== (currentSettings.api_base_url ?? "")) {
+                  void updateSettings({ api_base_url: apiBaseDraft.trim() }, "已更新 API 地址");
+                }
+              }}
+              disabled={settingsDisabled}
+            />
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">DNS over HTTPS</div>
+            <div className="setting-desc">
+              部分网络环境下 trae.ai 域名解析被污染时，可通过 DoH 解析 API 地址，无需修改系统 DNS
+            </div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.doh_url ?? ""}
+              onChange={(e) =>
+                updateSettings({ doh_url: e.target.value }, "已更新 DNS 解析设置")
+              }
+              disabled={settingsDisabled}
+            >
+              <option value="">系统 DNS</option>
+              <option value="https://1.1.1.1/dns-query">Cloudflare</option>
+              <option value="https://8.8.8.8/resolve">Google</option>
+              <option value="https://223.5.5.5/resolve">阿里 DNS</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">请求超时</div>
+            <div className="setting-desc">单次网络请求的超时时间，下载更新时为连接和读取超时</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.http_timeout_secs ?? 30}
+              onChange={(e) =>
+                updateSettings(
+                  { http_timeout_secs: Number(e.target.value) },
+                  "已更新请求超时设置"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={10}>10 秒</option>
+              <option value={30}>30 秒</option>
+              <option value={60}>60 秒</option>
+              <option value={120}>2 分钟</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">失败重试</div>
+            <div className="setting-desc">查询类请求遇到网络错误、超时、限流和服务器错误时自动重试，每次重试的等待时间翻倍；领取、注册等操作不会重试</div>
+          </div>
+          <div className="setting-action" style={{ gap: '8px' }}>
+            <select
+              className="setting-select"
+              value={currentSettings.http_retry_count ?? 2}
+              onChange={(e) =>
+                updateSettings(
+                  { http_retry_count: Number(e.target.value) },
+                  "已更新重试次数"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={0}>不重试</option>
+              <option value={1}>1 次</option>
+              <option value={2}>2 次</option>
+              <option value={3}>3 次</option>
+              <option value={5}>5 次</option>
+            </select>
+            <select
+              className="setting-select"
+              value={currentSettings.http_retry_backoff_ms ?? 500}
+              onChange={(e) =>
+                updateSettings(
+                  { http_retry_backoff_ms: Number(e.target.value) },
+                  "已更新重试等待时间"
+                )
+              }
+              disabled={settingsDisabled || (currentSettings.http_retry_count ?? 2) === 0}
+            >
+              <option value={200}>等待 0.2 秒起</option>
+              <option value={500}>等待 0.5 秒起</option>
+              <option value={1000}>等待 1 秒起</option>
+              <option value={3000}>等待 3 秒起</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">自定义 CA 证书</div>
+            <div className="setting-desc">
+              {currentSettings.extra_ca_path
+                ? `已信任: ${currentSettings.extra_ca_path}`
+                : "企业代理替换了 HTTPS 证书时，选择代理的根证书（PEM）即可正常访问 Trae API"}
+            </div>
+          </div>
+          <div className="setting-action" style={{ gap: '8px' }}>
+            {currentSettings.extra_ca_path && (
+              <button
+                className="setting-btn"
+                onClick={() => updateSettings({ extra_ca_path: "" }, "已移除自定义 CA 证书")}
+                disabled={settingsDisabled}
+              >
+                移除
+              </button>
+            )}
+            <button className="setting-btn" onClick={handleSelectCaFile} disabled={settingsDisabled}>
+              选择证书
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">证书固定</div>
+            <div className="setting-desc">
+              {currentSettings.pinned_certs?.length
+                ? `已固定 ${currentSettings.pinned_certs.length} 个证书，证书不匹配时拒绝使用 Trae API 的响应`
+                : "固定 Trae API 当前的服务器证书，防止被中间人劫持（服务器更换证书后需重新固定）"}
+            </div>
+          </div>
+          <div className="setting-action">
+            <button
+              className="setting-btn"
+              onClick={handleTogglePinning}
+              disabled={settingsDisabled || pinning}
+            >
+              {pinning ? "获取中..." : currentSettings.pinned_certs?.length ? "取消固定" : "固定当前证书"}
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">连接检测</div>
+            <div className="setting-desc">
+              检测 Trae 登录、使用量接口和临时邮箱服务能否访问及延迟，用于判断是账号问题还是服务故障
+            </div>
+            {healthReport && (
+              <div className="setting-desc" style={{ marginTop: '6px' }}>
+                {healthReport.endpoints.map((endpoint) => (
+                  <div key={endpoint.url} title={endpoint.error || endpoint.url}>
+                    <span style={{ color: endpoint.reachable ? 'var(--success)' : 'var(--danger)' }}>
+                      {endpoint.reachable ? "✓" : "✗"}
+                    </span>{" "}
+                    {endpoint.name} · {endpoint.reachable ? `${endpoint.latency_ms} ms` : endpoint.error || "无法访问"}
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+          <div className="setting-action">
+            <button className="setting-btn" onClick={handleCheckApiHealth} disabled={healthChecking}>
+              {healthChecking ? "检测中..." : "开始检测"}
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">调试日志</div>
+            <div className="setting-desc">
+              记录 Trae API 和临时邮箱请求的地址、状态码和耗时（隐藏 Token 等敏感参数，不记录请求内容），用于排查接口失败
+            </div>
+          </div>
+          <div className="setting-action" style={{ gap: '8px' }}>
+            <button className="setting-btn" onClick={handleRevealDebugLog}>
+              打开日志
+            </button>
+            <button className="setting-btn" onClick={handleClearDebugLog}>
+              清空
+            </button>
+            <button
+              type="button"
+              className={`pill-toggle ${currentSettings.api_debug_log ? "on" : ""}`}
+              onClick={() =>
+                updateSettings(
+                  { api_debug_log: !currentSettings.api_debug_log },
+                  currentSettings.api_debug_log ? "已关闭调试日志" : "已开启调试日志"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={Boolean(currentSettings.api_debug_log)}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">窗口控制台日志</div>
+            <div className="setting-desc">
+              记录登录、注册窗口的控制台输出和页面状态（地址、输入框是否已填写、按钮和错误提示，不记录输入内容），用于排查隐藏窗口注册卡住的问题
+            </div>
+          </div>
+          <div className="setting-action" style={{ gap: '8px' }}>
+            <button className="setting-btn" onClick={handleRevealConsoleLog}>
+              打开日志
+            </button>
+            <button className="setting-btn" onClick={handleClearConsoleLog}>
+              清空
+            </button>
+            <button
+              type="button"
+              className={`pill-toggle ${currentSettings.webview_console_log ? "on" : ""}`}
+              onClick={() =>
+                updateSettings(
+                  { webview_console_log: !currentSettings.webview_console_log },
+                  currentSettings.webview_console_log ? "已关闭窗口控制台日志" : "已开启窗口控制台日志"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={Boolean(currentSettings.webview_console_log)}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+      </div>
+
+      {/* 工作区映射 */}
+      <div className="settings-section">
+        <h3>工作区映射</h3>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">监听 Trae 工作区</div>
+            <div className="setting-desc">Trae 打开已映射的目录（含子目录）时，提示或自动切换到对应账号</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${currentSettings.workspace_watch_enabled ?? true ? "on" : ""}`}
+              onClick={() =>
+                updateSettings(
+                  { workspace_watch_enabled: !(currentSettings.workspace_watch_enabled ?? true) },
+                  "已更新工作区监听设置"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={currentSettings.workspace_watch_enabled ?? true}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        {mappings.map((mapping) => (
+          <div className="setting-item" key={mapping.id}>
+            <div className="setting-info" style={{ flex: 1, overflow: 'hidden' }}>
+              <div className="setting-label" style={{ wordBreak: 'break-all' }}>{mapping.folder}</div>
+              <div className="setting-desc">→ {accountLabel(mapping.account_id)}</div>
+            </div>
+            <div className="setting-action" style={{ gap: '8px' }}>
+              <select
+                className="setting-select"
+                value={mapping.auto_switch ? "auto" : "suggest"}
+                onChange={(e) => handleSaveMapping(mapping.folder, mapping.account_id, e.target.value === "auto")}
+              >
+                <option value="suggest">提示切换</option>
+                <option value="auto">自动切换</option>
+              </select>
+              <button className="setting-btn" onClick={() => handleRemoveMapping(mapping.id)}>
+                删除
+              </button>
+            </div>
+          </div>
+        ))}
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1, overflow: 'hidden' }}>
+            <div className="setting-label">添加映射</div>
+            <div className="setting-desc" style={{ wordBreak: 'break-all' }}>
+              {newMappingFolder || "选择目录，或使用 Trae 当前打开的工作区"}
+            </div>
+          </div>
+          <div className="setting-action" style={{ gap: '8px' }}>
+            <button className="setting-btn" onClick={handleUseCurrentWorkspace}>
+              当前工作区
+            </button>
+            <button className="setting-btn" onClick={handlePickMappingFolder}>
+              选择目录
+            </button>
+            <select
+              className="setting-select"
+              value={newMappingAccountId}
+              onChange={(e) => setNewMappingAccountId(e.target.value)}
+            >
+              <option value="">选择账号</option>
+              {mappingAccounts.map((account) => (
+                <option key={account.id} value={account.id}>
+                  {account.email || account.name}
+                </option>
+              ))}
+            </select>
+            <button
+              className="setting-btn"
+              onClick={() => handleSaveMapping(newMappingFolder, newMappingAccountId, false)}
+              disabled={!newMappingFolder || !newMappingAccountId}
+            >
+              添加
+            </button>
+          </div>
+        </div>
+      </div>
+
+      {/* 账号轮换 */}
+      <div className="settings-section">
+        <h3>账号轮换</h3>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">定时轮换</div>
+            <div className="setting-desc">
+              按时间表依次把 Trae IDE 切换到轮换池中的下一个账号
+              {rotationStatus?.enabled && rotationStatus.next_at
+                ? `，下次轮换：${new Date(rotationStatus.next_at * 1000).toLocaleString()}`
+                : ""}
+              {rotationStatus?.last_skipped ? `（上次跳过：${rotationStatus.last_skipped}）` : ""}
+            </div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${currentSettings.rotation?.enabled ? "on" : ""}`}
+              onClick={handleToggleRotation}
+              disabled={settingsDisabled || rotationBusy}
+              role="switch"
+              aria-checked={!!currentSettings.rotation?.enabled}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">轮换时间</div>
+            <div className="setting-desc">整点模式按本地时间对齐，如每 6 小时在 0、6、12、18 点切换</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={scheduleValue((currentSettings.rotation ?? DEFAULT_ROTATION).schedule)}
+              onChange={(e) => {
+                const option = ROTATION_SCHEDULES.find((o) => o.value === e.target.value);
+                if (option) updateRotation({ schedule: option.schedule }, "已更新轮换时间");
+              }}
+              disabled={settingsDisabled}
+            >
+              {ROTATION_SCHEDULES.map((option) => (
+                <option key={option.value} value={option.value}>{option.label}</option>
+              ))}
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">IDE 使用中时跳过</div>
+            <div className="setting-desc">Trae IDE 在最近几分钟内有操作时跳过本次轮换，避免打断正在进行的工作</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${(currentSettings.rotation ?? DEFAULT_ROTATION).skip_if_busy ? "on" : ""}`}
+              onClick={() =>
+                updateRotation(
+                  { skip_if_busy: !(currentSettings.rotation ?? DEFAULT_ROTATION).skip_if_busy },
+                  "已更新轮换设置"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={(currentSettings.rotation ?? DEFAULT_ROTATION).skip_if_busy}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">轮换池</div>
+            <div className="setting-desc">勾选参与轮换的账号，按列表顺序依次切换（至少 2 个）</div>
+            {mappingAccounts.map((account) => {
+              const pool = (currentSettings.rotation ?? DEFAULT_ROTATION).account_ids;
+              return (
+                <label key={account.id} className="setting-desc" style={{ display: 'flex', alignItems: 'center', gap: '6px', marginTop: '4px' }}>
+                  <input
+                    type="checkbox"
+                    checked={pool.includes(account.id)}
+                    onChange={() =>
+                      updateRotation(
+                        {
+                          account_ids: pool.includes(account.id)
+                            ? pool.filter((id) => id !== account.id)
+                            : mappingAccounts.map((a) => a.id).filter((id) => id === account.id || pool.includes(id)),
+                        },
+                        "已更新轮换池"
+                      )
+                    }
+                    disabled={settingsDisabled}
+                  />
+                  {account.name || account.email}
+                </label>
+              );
+            })}
+          </div>
+        </div>
+      </div>
+
+      {/* 账号池自动补充 */}
+      <div className="settings-section">
+        <h3>账号池自动补充</h3>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">自动补充注册</div>
+            <div className="setting-desc">可用账号（Token 有效且额度未用完）少于最低数量时，自动向注册队列添加任务直到达到目标数量</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${poolTopUp.enabled ? "on" : ""}`}
+              onClick={() => updatePoolTopUp({ enabled: !poolTopUp.enabled }, "已更新账号池自动补充设置")}
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={poolTopUp.enabled}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">最低 / 目标数量</div>
+            <div className="setting-desc">排队中的注册任务也计入目标数量，每次最多添加 10 个任务</div>
+          </div>
+          <div className="setting-action" style={{ display: 'flex', gap: '8px', alignItems: 'center' }}>
+            <input
+              type="number"
+              className="setting-input"
+              style={{ width: '72px' }}
+              min={1}
+              value={poolTopUp.min_available}
+              onChange={(e) =>
+                updatePoolTopUp({ min_available: Math.max(1, Number(e.target.value) || 1) }, "已更新账号池最低数量")
+              }
+              disabled={settingsDisabled}
+            />
+            <span className="setting-desc">/</span>
+            <input
+              type="number"
+              className="setting-input"
+              style={{ width: '72px' }}
+              min={1}
+              value={poolTopUp.target_available}
+              onChange={(e) =>
+                updatePoolTopUp({ target_available: Math.max(1, Number(e.target.value) || 1) }, "已更新账号池目标数量")
+              }
+              disabled={settingsDisabled}
+            />
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">检查间隔</div>
+            <div className="setting-desc">注册队列暂停时不会添加任务</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={poolTopUp.check_interval_minutes}
+              onChange={(e) =>
+                updatePoolTopUp({ check_interval_minutes: Number(e.target.value) }, "已更新账号池检查间隔")
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={10}>每 10 分钟</option>
+              <option value={30}>每 30 分钟</option>
+              <option value={60}>每 1 小时</option>
+              <option value={360}>每 6 小时</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">补充时显示浏览器窗口</div>
+            <div className="setting-desc">关闭后在后台完成注册</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${poolTopUp.show_window ? "on" : ""}`}
+              onClick={() => updatePoolTopUp({ show_window: !poolTopUp.show_window }, "已更新账号池自动补充设置")}
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={poolTopUp.show_window}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+      </div>
+
+      {/* 额度用完自动清理 */}
+      <div className="settings-section">
+        <h3>额度用完自动清理</h3>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">自动清理免费账号</div>
+            <div className="setting-desc">定期查找快速请求额度已用完的免费账号（以最近一次查询的使用量为准），Trae IDE 正在使用的账号不会被清理</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${retirement.enabled ? "on" : ""}`}
+              onClick={() => updateRetirement({ enabled: !retirement.enabled }, "已更新自动清理设置")}
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={retirement.enabled}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">处理方式</div>
+            <div className="setting-desc">归档的账号保留在列表中但不参与自动切换，额度恢复后自动取消归档；删除后无法恢复</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={retirement.action}
+              onChange={(e) => updateRetirement({ action: e.target.value as RetirementAction }, "已更新自动清理处理方式")}
+              disabled={settingsDisabled}
+            >
+              <option value="archive">归档</option>
+              <option value="delete">删除</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">检查间隔</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={retirement.check_interval_minutes}
+              onChange={(e) =>
+                updateRetirement({ check_interval_minutes: Number(e.target.value) }, "已更新自动清理检查间隔")
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={30}>每 30 分钟</option>
+              <option value={60}>每 1 小时</option>
+              <option value={360}>每 6 小时</option>
+              <option value={1440}>每天</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">补充注册</div>
+            <div className="setting-desc">为清理掉的账号向注册队列添加同样数量的任务（队列暂停时不添加）</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${retirement.replace ? "on" : ""}`}
+              onClick={() => updateRetirement({ replace: !retirement.replace }, "已更新自动清理设置")}
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={retirement.replace}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        {retirement.replace && (
+          <div className="setting-item">
+            <div className="setting-info">
+              <div className="setting-label">补充时显示浏览器窗口</div>
+              <div className="setting-desc">关闭后在后台完成注册</div>
+            </div>
+            <div className="setting-action">
+              <button
+                type="button"
+                className={`pill-toggle ${retirement.show_window ? "on" : ""}`}
+                onClick={() => updateRetirement({ show_window: !retirement.show_window }, "已更新自动清理设置")}
+                disabled={settingsDisabled}
+                role="switch"
+                aria-checked={retirement.show_window}
+              >
+                <span className="pill-track"></span>
+                <span className="pill-thumb"></span>
+              </button>
+            </div>
+          </div>
+        )}
+      </div>
+
+      {/* 数据备份 */}
+      <div className="settings-section">
+        <h3>数据备份</h3>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">自动备份</div>
+            <div className="setting-desc">定时备份账号数据和设置到数据目录下的 backups 文件夹</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.backup_interval_hours ?? 24}
+              onChange={(e) =>
+                updateSettings(
+                  { backup_interval_hours: Number(e.target.value) },
+                  "已更新自动备份设置"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={0}>关闭</option>
+              <option value={6}>每 6 小时</option>
+              <option value={24}>每天</option>
+              <option value={168}>每周</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">保留备份数量</div>
+            <div className="setting-desc">超出数量时自动删除最旧的备份</div>
+          </div>
+          <div className="setting-action">
+            <select
+              className="setting-select"
+              value={currentSettings.backup_retention ?? 10}
+              onChange={(e) =>
+                updateSettings(
+                  { backup_retention: Number(e.target.value) },
+                  "已更新备份保留数量"
+                )
+              }
+              disabled={settingsDisabled}
+            >
+              <option value={5}>5 份</option>
+              <option value={10}>10 份</option>
+              <option value={30}>30 份</option>
+            </select>
+          </div>
+        </div>
+
+        <div className="setting-item" style={{ alignItems: 'flex-start' }}>
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">备份列表</div>
+            {backups.length === 0 ? (
+              <div className="setting-desc">暂无备份</div>
+            ) : (
+              backups.map((backup) => (
+                <div
+                  key={backup.id}
+                  className="setting-desc"
+                  style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', gap: '8px' }}
+                >
+                  <span>
+                    {new Date(backup.created_at * 1000).toLocaleString()} · {backup.account_count} 个账号
+                    {backup.has_settings ? " · 含设置" : ""}
+                    {!backup.valid && <span style={{ color: 'var(--danger)' }}> · 校验失败</span>}
+                  </span>
+                  <button
+                    className="setting-btn"
+                    onClick={() => handleRestoreBackup(backup)}
+                    disabled={!backup.valid}
+                  >
+                    恢复
+                  </button>
+                </div>
+              ))
+            )}
+          </div>
+          <div className="setting-action">
+            <button className="setting-btn" onClick={handleCreateBackup} disabled={backingUp}>
+              {backingUp ? "备份中..." : "立即备份"}
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item" style={{ alignItems: 'flex-start' }}>
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">云端备份</div>
+            <div className="setting-desc">定时备份后自动上传到 WebDAV、S3 兼容存储或私有 GitHub Gist（Gist ID 留空时自动创建）</div>
+            {cloudDraft.kind !== "disabled" && (
+              <div style={{ display: 'flex', flexDirection: 'column', gap: '6px', marginTop: '8px' }}>
+                {cloudDraft.kind === "webdav" && [
+                  cloudField("url", "WebDAV 目录地址，如 https://dav.jianguoyun.com/dav/trae"),
+                  cloudField("username", "用户名"),
+                  cloudField("password", "密码 / 应用密码", true),
+                ]}
+                {cloudDraft.kind === "s3" && [
+                  cloudField("endpoint", "服务地址，如 https://s3.amazonaws.com"),
+                  cloudField("region", "区域（默认 us-east-1，R2 填 auto）"),
+                  cloudField("bucket", "存储桶"),
+                  cloudField("prefix", "对象前缀（可选）"),
+                  cloudField("access_key_id", "Access Key ID"),
+                  cloudField("secret_access_key", "Secret Access Key", true),
+                ]}
+                {cloudDraft.kind === "gist" && [
+                  cloudField("token", "GitHub Token（需要 gist 权限）", true),
+                  cloudField("gist_id", "Gist ID（可选）"),
+                ]}
+                <input
+                  className="setting-input"
+                  type="password"
+                  placeholder="加密密码（强烈建议设置，留空则上传明文）"
+                  value={cloudPassphrase}
+                  onChange={(e) => setCloudPassphrase(e.target.value)}
+                />
+              </div>
+            )}
+          </div>
+          <div className="setting-action" style={{ flexDirection: 'column', gap: '8px' }}>
+            <select
+              className="setting-select"
+              value={cloudDraft.kind}
+              onChange={(e) => setCloudDraft(EMPTY_CLOUD_BACKUP[e.target.value as CloudBackupConfig["kind"]])}
+              disabled={settingsDisabled}
+            >
+              <option value="disabled">关闭</option>
+              <option value="webdav">WebDAV</option>
+              <option value="s3">S3 兼容存储</option>
+              <option value="gist">GitHub Gist</option>
+            </select>
+            <button className="setting-btn" onClick={handleSaveCloudBackup} disabled={settingsDisabled}>
+              保存
+            </button>
+            <button
+              className="setting-btn"
+              onClick={handleUploadBackup}
+              disabled={uploading || (currentSettings.cloud_backup?.kind ?? "disabled") === "disabled"}
+            >
+              {uploading ? "上传中..." : "立即上传"}
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">清理过期数据</div>
+            <div className="setting-desc">删除旧的更新安装包、残留临时文件、已删除账号的浏览器数据、超出保留数量的备份和过期日志（启动时也会自动执行）</div>
+          </div>
+          <div className="setting-action">
+            <button className="setting-btn" onClick={handleCleanup} disabled={cleaning}>
+              {cleaning ? "清理中..." : "立即清理"}
+            </button>
+          </div>
+        </div>
+      </div>
+
+      {/* 周报 */}
+      <div className="settings-section">
+        <h3>周报</h3>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">每周自动生成周报</div>
+            <div className="setting-desc">汇总使用量变化、新增账号、失败和账号切换，保存为 Markdown 到数据目录下的 reports 文件夹</div>
+          </div>
+          <div className="setting-action">
+            <button
+              type="button"
+              className={`pill-toggle ${currentSettings.weekly_report_enabled ? "on" : ""}`}
+              onClick={() =>
+                updateSettings(
+                  { weekly_report_enabled: !currentSettings.weekly_report_enabled },
+                  "已更新周报设置"
+                )
+              }
+              disabled={settingsDisabled}
+              role="switch"
+              aria-checked={!!currentSettings.weekly_report_enabled}
+            >
+              <span className="pill-track"></span>
+              <span className="pill-thumb"></span>
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info">
+            <div className="setting-label">立即生成周报</div>
+            <div className="setting-desc">生成后按通知规则推送（默认发送到 Webhook）</div>
+          </div>
+          <div className="setting-action">
+            <button className="setting-btn" onClick={handleGenerateReport} disabled={generatingReport}>
+              {generatingReport ? "生成中..." : "立即生成"}
+            </button>
+          </div>
+        </div>
+      </div>
+
+      {/* 通知 */}
+      <div className="settings-section">
+        <h3>通知</h3>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">Webhook</div>
+            <div className="setting-desc">以 JSON（event、title、text 字段）POST 到该地址</div>
+            <input
+              className="setting-input"
+              style={{ marginTop: '8px' }}
+              placeholder="https://..."
+              value={notifyDraft.webhook_url}
+              onChange={(e) => setNotifyDraft((prev) => ({ ...prev, webhook_url: e.target.value }))}
+              onBlur={saveNotifyDraft}
+              disabled={settingsDisabled}
+            />
+          </div>
+          <div className="setting-action">
+            <button
+              className="setting-btn"
+              onClick={() => handleTestNotification("webhook")}
+              disabled={testingSink !== null || !currentSettings.notify?.webhook_url}
+            >
+              {testingSink === "webhook" ? "发送中..." : "测试"}
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">Telegram</div>
+            <div className="setting-desc">通过 Bot 发送消息，需要 Bot Token 和 Chat ID</div>
+            <input
+              className="setting-input"
+              type="password"
+              style={{ marginTop: '8px' }}
+              placeholder="Bot Token"
+              value={notifyDraft.telegram_bot_token}
+              onChange={(e) => setNotifyDraft((prev) => ({ ...prev, telegram_bot_token: e.target.value }))}
+              onBlur={saveNotifyDraft}
+              disabled={settingsDisabled}
+            />
+            <input
+              className="setting-input"
+              style={{ marginTop: '8px' }}
+              placeholder="Chat ID"
+              value={notifyDraft.telegram_chat_id}
+              onChange={(e) => setNotifyDraft((prev) => ({ ...prev, telegram_chat_id: e.target.value }))}
+              onBlur={saveNotifyDraft}
+              disabled={settingsDisabled}
+            />
+          </div>
+          <div className="setting-action">
+            <button
+              className="setting-btn"
+              onClick={() => handleTestNotification("telegram")}
+              disabled={
+                testingSink !== null ||
+                !currentSettings.notify?.telegram_bot_token ||
+                !currentSettings.notify?.telegram_chat_id
+              }
+            >
+              {testingSink === "telegram" ? "发送中..." : "测试"}
+            </button>
+          </div>
+        </div>
+
+        <div className="setting-item">
+          <div className="setting-info" style={{ flex: 1 }}>
+            <div className="setting-label">通知规则</div>
+            <div className="setting-desc">选择每类事件发送到哪些渠道，未配置的渠道会被跳过</div>
+            <table className="notify-routes">
+              <thead>
+                <tr>
+                  <th></th>
+                  {NOTIFY_SINKS.map(({ sink, label }) => (
+                    <th key={sink}>{label}</th>
+                  ))}
+                </tr>
+              </thead>
+              <tbody>
+                {NOTIFY_EVENTS.map(({ event, label }) => (
+                  <tr key={event}>
+                    <td>{label}</td>
+                    {NOTIFY_SINKS.map(({ sink }) => (
+                      <td key={sink}>
+                        <input
+                          type="checkbox"
+                          checked={(currentSettings.notify?.routes[event] ?? []).includes(sink)}
+                          onChange={() => toggleNotifyRoute(event, sink)}
+                          disabled={settingsDisabled}
+                        />
+                      </td>
+                    ))}
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+        </div>
       </div>
     </div>
   );
@@ -1,7 +1,8 @@
 import { useEffect, useState } from "react";
 import * as api from "../api";
-import type { UsageSummary, UserStatisticData } from "../types";
+import type { StatisticPoint, UsageSummary, UserStatisticData } from "../types";
 import { DashboardWidgets } from "../components/DashboardWidgets";
+import { UsageAggregate } from "../components/UsageAggregate";
 
 interface StatsProps {
   accounts: Array<{
@@ -18,6 +19,8 @@ export function Stats({ accounts, hasLoaded = true }: StatsProps) {
   const [userStats, setUserStats] = useState<UserStatisticData | null>(null);
   const [loadingStats, setLoadingStats] = useState(false);
   const [statsError, setStatsError] = useState<string | null>(null);
+  // 今天相对上一次采样的变化量（所有账号合计）
+  const [todayDelta, setTodayDelta] = useState<{ aiRequests: number; completions: number } | null>(null);
 
   const statsCacheKey = (accountId: string) => `trae_user_stats_${accountId}`;
   const loadStatsCache = (accountId: string) => {
@@ -99,10 +102,28 @@ export function Stats({ accounts, hasLoaded = true }: StatsProps) {
     }
   };
 
+  const loadTodayDelta = async () => {
+    const now = new Date();
+    const today = `${now.getFullYear()}-${String(now.getMonth() + 1).padStart(2, "0")}-${String(now.getDate()).padStart(2, "0")}`;
+    const histories = await Promise.allSettled(accounts.map((account) => api.getStatisticsHistory(account.id, "week")));
+    const points = histories
+      .map((res) => (res.status === "fulfilled" ? res.value.find((p) => p.date === today) : undefined))
+      .filter(Boolean) as StatisticPoint[];
+    setTodayDelta(
+      points.length > 0
+        ? {
+            aiRequests: points.reduce((sum, p) => sum + p.ai_requests_delta, 0),
+            completions: points.reduce((sum, p) => sum + p.completions_delta, 0),
+          }
+        : null
+    );
+  };
+
   useEffect(() => {
     let cancelled = false;
     if (!accounts.length) {
       setUserStats(null);
+      setTodayDelta(null);
       setLoadingStats(false);
       setStatsError(null);
       return;
@@ -139,6 +160,7 @@ export function Stats({ accounts, hasLoaded = true }: StatsProps) {
 
     if (accountsToFetch.length === 0) {
       setLoadingStats(false);
+      loadTodayDelta();
       return; // All fresh
     }
 
@@ -174,6 +196,7 @@ export function Stats({ accounts, hasLoaded = true }: StatsProps) {
         if (finalStatsList.length > 0) {
           setUserStats(aggregateStats(finalStatsList));
           setStatsError(null);
+          loadTodayDelta();
         } else {
           // Only show error if we still have no data
           if (!userStats) {
@@ -220,8 +243,16 @@ export function Stats({ accounts, hasLoaded = true }: StatsProps) {
         </div>
       )}
 
+      {accounts.length > 0 && <UsageAggregate accountKey={accounts.map(a => a.id).join("|")} />}
+
       {userStats && (
         <div className="dashboard-widgets-section" style={{ marginBottom: "24px" }}>
+          {todayDelta && (todayDelta.aiRequests !== 0 || todayDelta.completions !== 0) && (
+            <p style={{ color: "var(--text-muted)", fontSize: "13px", margin: "0 0 12px" }}>
+              较上次记录 {todayDelta.aiRequests >= 0 ? "+" : ""}{todayDelta.aiRequests} 次 AI 请求，
+              近 7 天补全 {todayDelta.completions >= 0 ? "+" : ""}{todayDelta.completions} 次
+            </p>
+          )}
           <DashboardWidgets data={userStats} />
         </div>
       )}
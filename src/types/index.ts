@@ -9,6 +9,55 @@ export interface AccountBrief {
   created_at: number;
   machine_id: string | null;
   is_current: boolean; // 是否是当前 Trae IDE 正在使用的账号
+  last_switched_at?: number | null;
+  switch_count?: number;
+  last_usage_check_at?: number | null;
+  unverified?: boolean;
+  last_error?: AccountError | null;
+  tags?: string[];
+  notes?: string | null;
+  token_expires_at?: number | null;
+  token_expiring?: boolean;
+  // 本地显示名称，设置后 name 即为别名
+  alias?: string | null;
+  // 付费套餐到期时间（查询订阅信息后更新）
+  plan_expires_at?: number | null;
+  // 付费套餐 7 天内到期且未开启自动续费
+  plan_expiring?: boolean;
+  // 添加方式和注册信息
+  registration?: AccountRegistration;
+  // 额度用完后被自动归档的时间
+  archived_at?: number | null;
+}
+
+// 账号的添加方式
+export type AccountSource =
+  | "unknown"
+  | "quick_register"
+  | "browser_login"
+  | "trae_ide"
+  | "browser_cookies"
+  | "token"
+  | "email_login"
+  | "import";
+
+// 账号的添加方式和注册信息
+export interface AccountRegistration {
+  source: AccountSource;
+  // 注册使用的临时邮箱服务（仅快速注册）
+  mail_provider: string | null;
+  mail_domain: string | null;
+  // 注册时间（仅快速注册）
+  registered_at: number | null;
+  // 注册窗口使用的 User-Agent（仅快速注册）
+  user_agent?: string | null;
+}
+
+// 账号最近一次错误
+export interface AccountError {
+  message: string;
+  code: ErrorCode;
+  occurred_at: number;
 }
 
 // 完整账号信息
@@ -29,6 +78,136 @@ export interface Account {
   updated_at: number;
   is_active: boolean;
   machine_id: string | null;
+  last_switched_at?: number | null;
+  switch_count?: number;
+  last_usage_check_at?: number | null;
+  unverified?: boolean;
+  region_override?: string | null;
+  api_host?: string | null;
+  profile_refreshed_at?: number | null;
+  last_error?: AccountError | null;
+  tags?: string[];
+  notes?: string | null;
+  // 切换到该账号时写入 Trae 的界面语言（如 zh-cn / en）
+  locale?: string | null;
+  // 本地显示名称（不会同步到服务器）
+  alias?: string | null;
+  // 添加方式和注册信息
+  registration?: AccountRegistration;
+  // 两步验证密钥（Base32）
+  totp_secret?: string | null;
+}
+
+// 当前的两步验证码
+export interface TotpCode {
+  code: string;
+  remaining_secs: number;
+}
+
+// 批量校验未验证账号结果
+export interface ValidationReport {
+  validated: number;
+  failed: { account_id: string; email: string; error: string }[];
+}
+
+// 批量刷新账号资料结果
+export interface ProfileRefreshReport {
+  refreshed: number;
+  failed: { account_id: string; email: string; error: string }[];
+}
+
+// Cookies 字符串中的单个条目
+export interface CookieEntry {
+  name: string;
+  value: string;
+}
+
+// 批量操作类型
+export type BatchAction = "refresh" | "bind_machine_id" | "claim_gift" | "remove";
+
+// 批量操作中单个账号的结果
+export interface BatchActionResult {
+  account_id: string;
+  success: boolean;
+  error: string | null;
+}
+
+// 一键领取礼包中单个账号的处理结果
+export type GiftClaimStatus = "claimed" | "already_claimed" | "failed";
+
+export interface GiftClaimResult {
+  account_id: string;
+  email: string;
+  status: GiftClaimStatus;
+  error: string | null;
+}
+
+// 一键领取所有账号礼包的结果
+export interface GiftClaimReport {
+  claimed: number;
+  skipped: number;
+  failed: number;
+  results: GiftClaimResult[];
+}
+
+// 维护步骤
+export type MaintenanceStep =
+  | "refresh_tokens"
+  | "refresh_profiles"
+  | "fetch_usage"
+  | "claim_gifts"
+  | "cleanup"
+  | "rotate_logs";
+
+// 维护进度（maintenance_progress 事件）
+export interface MaintenanceProgress {
+  step: MaintenanceStep;
+  current: number;
+  total: number;
+  account_id: string | null;
+  email: string | null;
+}
+
+// 维护结果汇总
+export interface MaintenanceReport {
+  started_at: number;
+  finished_at: number;
+  tokens_refreshed: number;
+  profiles_refreshed: number;
+  usage_fetched: number;
+  gifts_claimed: number;
+  cleaned_items: number;
+  reclaimed_bytes: number;
+  logs_rotated: number;
+  failures: { step: MaintenanceStep; account_id: string; email: string; error: string }[];
+}
+
+// 账号数据完整性问题
+export interface IntegrityIssue {
+  path: string;
+  reason: string;
+  detected_at: number;
+  latest_valid_backup: string | null;
+  // 文件可以正常解析（仅签名不匹配），可信任后重新签名
+  trustable?: boolean;
+}
+
+// 导入预览
+export type ImportPreviewStatus = "add" | "duplicate" | "failed";
+
+export interface ImportPreviewEntry {
+  index: number;
+  email: string;
+  user_id: string | null;
+  status: ImportPreviewStatus;
+  reason: string | null;
+}
+
+export interface ImportPreview {
+  entries: ImportPreviewEntry[];
+  to_add: number;
+  duplicates: number;
+  failed: number;
 }
 
 // 使用量汇总
@@ -88,9 +267,25 @@ export interface UsageEventsResponse {
   user_usage_group_by_sessions: UsageEvent[];
 }
 
+// 错误分类
+export type ErrorCode =
+  | "auth_expired"
+  | "forbidden"
+  | "not_found"
+  | "rate_limited"
+  | "timeout"
+  | "network"
+  | "trae_not_installed"
+  | "permission_denied"
+  | "invalid_input"
+  | "read_only"
+  | "cancelled"
+  | "unknown";
+
 // API 错误
 export interface ApiError {
   message: string;
+  code: ErrorCode;
 }
 
 export interface AppSettings {
@@ -99,6 +294,255 @@ export interface AppSettings {
   privacy_auto_enable: boolean;
   auto_update_check: boolean;
   auto_start_enabled: boolean;
+  import_concurrency?: number;
+  import_delay_ms?: number;
+  import_jitter_ms?: number;
+  import_defer_validation?: boolean;
+  profile_refresh_interval_hours?: number;
+  maintenance_interval_hours?: number;
+  backup_interval_hours?: number;
+  backup_retention?: number;
+  workspace_watch_enabled?: boolean;
+  proxy_mode?: ProxyMode;
+  // 手动代理地址（HTTP / HTTPS / SOCKS5，可包含用户名和密码）
+  proxy_url?: string;
+  // DNS-over-HTTPS 服务地址，空字符串表示使用系统 DNS
+  doh_url?: string;
+  // 额外信任的 CA 证书文件（PEM）
+  extra_ca_path?: string;
+  // Trae API 证书固定（SHA-256 指纹），为空时不启用
+  pinned_certs?: string[];
+  // 云端备份目标
+  cloud_backup?: CloudBackupConfig;
+  // 云端备份加密密码，为空时上传明文
+  cloud_backup_passphrase?: string;
+  // 每周自动生成账号周报
+  weekly_report_enabled?: boolean;
+  entitlement_mode?: EntitlementMode;
+  token_expiry_warning_hours?: number;
+  // 快速注册进度通知的详细程度
+  quick_register_notice_level?: NoticeLevel;
+  // 快速注册成功后为账号生成并绑定新的机器码
+  quick_register_new_machine_id?: boolean;
+  // 快速注册成功后立即切换到新账号
+  quick_register_auto_switch?: boolean;
+  // 每次快速注册随机使用不同的 User-Agent、语言和窗口大小
+  quick_register_random_fingerprint?: boolean;
+  // 浏览器登录等待完成的时长（秒）
+  browser_login_timeout_secs?: number;
+  // 浏览器登录窗口的起始页，为空时打开 Trae 海外版登录页
+  browser_login_url?: string;
+  // 快速注册使用的临时邮箱服务（不可用时自动切换到其他服务）
+  mail_provider?: MailProviderKind;
+  // 自定义收件域名（如自有的 catch-all 域名），为空时使用临时邮箱服务自带的域名
+  mail_domains?: string[];
+  // 验证码识别规则
+  verification_code?: CodeExtraction;
+  // 快速注册遇到人机验证时使用的打码服务
+  captcha_solver?: CaptchaSolverConfig;
+  // 快速注册使用的代理池（注册窗口和临时邮箱请求）
+  register_proxy?: RegisterProxyConfig;
+  // 使用量缓存有效期（秒），0 表示不缓存
+  usage_cache_ttl_secs?: number;
+  // 通知渠道和各事件的路由规则
+  notify?: NotifyConfig;
+  // 账号 ID -> 剩余快速请求提醒阈值
+  quota_thresholds?: Record<string, number>;
+  // 当前账号额度用完时自动切换到下一个可用账号
+  auto_switch_on_exhausted?: boolean;
+  // 账号轮换池和时间表
+  rotation?: RotationConfig;
+  // 可用账号不足时自动添加注册任务
+  pool_topup?: PoolTopUpConfig;
+  // 自动归档或删除额度用完的免费账号
+  retirement?: RetirementConfig;
+  // 网络请求超时（秒）
+  http_timeout_secs?: number;
+  // 网络错误、超时、429 和 5xx 响应的重试次数
+  http_retry_count?: number;
+  // 重试等待的基数（毫秒），每次重试翻倍
+  http_retry_backoff_ms?: number;
+  // 自定义 Trae API 地址（镜像/中转），为空时使用内置端点
+  api_base_url?: string;
+  // 将请求地址、状态码和耗时写入调试日志
+  api_debug_log?: boolean;
+  // 将登录、注册窗口的控制台输出和页面状态写入窗口控制台日志
+  webview_console_log?: boolean;
+}
+
+// 完整导入（合并）结果
+export interface ImportMergeReport {
+  added: number;
+  merged: number;
+  skipped: number;
+}
+
+// GitHub Release 附件
+export interface ReleaseAsset {
+  name: string;
+  browser_download_url: string;
+}
+
+// 最新版本信息（installer 为适用于当前系统架构的安装包）
+export interface LatestRelease {
+  version: string;
+  notes: string;
+  installer: ReleaseAsset | null;
+  platform: string;
+}
+
+// 清理过期数据的结果
+export interface CleanupReport {
+  items: { path: string; size: number; reason: string }[];
+  reclaimed_bytes: number;
+}
+
+// 账号周报
+export interface WeeklyReport {
+  path: string;
+  period_start: number;
+  period_end: number;
+  content: string;
+}
+
+// 云端备份目标配置
+export type CloudBackupConfig =
+  | { kind: "disabled" }
+  | { kind: "webdav"; url: string; username: string; password: string }
+  | {
+      kind: "s3";
+      endpoint: string;
+      region: string;
+      bucket: string;
+      prefix: string;
+      access_key_id: string;
+      secret_access_key: string;
+    }
+  | { kind: "gist"; token: string; gist_id: string };
+
+// 打码服务配置
+export type CaptchaSolverConfig =
+  | { kind: "disabled" }
+  | { kind: "two_captcha"; api_key: string }
+  | { kind: "anti_captcha"; api_key: string };
+
+// 代理模式：default 读取环境变量，system 使用系统代理，direct 直连
+export type ProxyMode = "default" | "system" | "direct" | "manual";
+
+// 切换账号时写入 Trae 权益信息的方式
+export type EntitlementMode = "from_plan" | "free" | "skip";
+
+// 检测到的系统代理
+export interface SystemProxy {
+  http: string | null;
+  https: string | null;
+  bypass: string | null;
+  pac_url: string | null;
+}
+
+// 支持读取 Cookie 的浏览器
+export type Browser = "chrome" | "edge" | "firefox";
+
+export interface BrowserCookieError {
+  browser: Browser;
+  profile: string;
+  error: string;
+}
+
+// 从浏览器导入账号的结果
+export interface BrowserImportReport {
+  imported: Account[];
+  skipped: BrowserCookieError[];
+  failed: BrowserCookieError[];
+}
+
+// Trae IDE 当前登录信息快照（敏感字段已打码）
+export interface TraeAuthSnapshot {
+  storage_path: string;
+  modified_at: number | null;
+  auth_info: unknown | null;
+  entitlement_info: unknown | null;
+}
+
+// 账号数据备份
+export interface BackupEntry {
+  id: string;
+  path: string;
+  created_at: number;
+  size: number;
+  account_count: number;
+  valid: boolean;
+  has_settings: boolean;
+}
+
+// 工作区映射：Trae 打开该目录（或子目录）时使用指定账号
+export interface WorkspaceMapping {
+  id: string;
+  folder: string;
+  account_id: string;
+  auto_switch: boolean;
+  created_at: number;
+}
+
+// 工作区匹配到映射账号时的事件
+export interface WorkspaceSwitchEvent {
+  folder: string;
+  mapping_id: string;
+  account_id: string;
+  email: string;
+}
+
+// accounts.json 被外部修改并重新加载（accounts_changed 事件）
+export interface AccountsChangedEvent {
+  account_count: number;
+  integrity_issue: IntegrityIssue | null;
+}
+
+// Token 即将过期（token_expiring 事件）
+export interface TokenExpiringEvent {
+  account_id: string;
+  email: string;
+  expires_at: number;
+}
+
+// 账号需要处理的原因
+export type AttentionReason = "no_token" | "token_expired" | "token_expiring" | "cookies_stale";
+
+export interface AccountAttention {
+  account_id: string;
+  email: string;
+  reasons: AttentionReason[];
+  token_expires_at: number | null;
+  can_renew: boolean;
+}
+
+// 启动时本地检查 Token 和 Cookies 的结果（token_sweep 事件负载）
+export interface TokenSweepSummary {
+  checked: number;
+  accounts: AccountAttention[];
+}
+
+// 单个端点的健康检查结果
+export interface EndpointHealth {
+  name: string;
+  url: string;
+  reachable: boolean;
+  status: number | null;
+  latency_ms: number;
+  error: string | null;
+}
+
+// API 健康检查结果
+export interface HealthReport {
+  checked_at: number;
+  endpoints: EndpointHealth[];
+  healthy: boolean;
+}
+
+// 服务器证书指纹
+export interface CertificateFingerprint {
+  host: string;
+  sha256: string;
 }
 
 // 用户统计数据
@@ -115,3 +559,441 @@ export interface UserStatisticData {
   DataDate: string;
   IsIde: boolean;
 }
+
+// Trae IDE 安装状态
+export interface TraeInstallStatus {
+  supported: boolean;
+  installed: boolean;
+  data_dir: string | null;
+  executable: string | null;
+  running: boolean;
+  reason: string | null;
+}
+
+// 批量查询中单个账号的使用量结果（同时作为 account_usage 事件负载）
+export interface AccountUsageResult {
+  account_id: string;
+  usage: UsageSummary | null;
+  error: string | null;
+}
+
+// 快速注册进度通知级别：不通知 / 关键节点 / 每个步骤及耗时
+export type NoticeLevel = "silent" | "milestones" | "verbose";
+
+// 临时邮箱服务
+export type MailProviderKind = "mail_cx" | "mail_tm" | "one_sec_mail" | "guerrilla_mail";
+
+// 临时邮箱中的一封邮件
+export interface MailSummary {
+  id: string;
+  from: string;
+  subject: string;
+}
+
+// 邮件详情
+export interface MailMessageDetail {
+  id: string;
+  content: { text: string; html: string };
+  // 纯文本正文（HTML 已转换）
+  plain_text: string;
+  // 按当前规则识别出的验证码
+  code: string | null;
+}
+
+// 验证码识别规则：长度恰好为 length 的连续字符，优先选择关键词之后的匹配
+export interface CodeExtraction {
+  length: number;
+  pattern: "digits" | "alphanumeric";
+  keywords: string[];
+}
+
+// 快速注册进度阶段
+export type RegisterStage =
+  | "mail_ready"
+  | "init"
+  | "page_opened"
+  | "code_sent"
+  | "captcha"
+  | "code_received"
+  | "submitted"
+  | "token_captured"
+  | "login_ok"
+  | "account_added"
+  | "retrying"
+  | "done"
+  | "failed";
+
+// 快速注册进度（quick_register_progress 事件负载）
+export interface RegisterProgress {
+  id: string;
+  stage: RegisterStage;
+  // 重试或失败时对应的注册步骤
+  step: RegisterStep | null;
+  percent: number;
+  // 后端的中文说明
+  message: string;
+  error: string | null;
+  email: string | null;
+  batch_index: number | null;
+  batch_total: number | null;
+  elapsed_ms: number;
+  step_ms: number;
+  milestone: boolean;
+  // 按通知级别是否应弹出提示
+  toast: boolean;
+}
+
+// 批量快速注册中单个账号完成（quick_register_batch_progress 事件负载）
+export interface BatchRegisterProgress {
+  index: number;
+  total: number;
+  completed: number;
+  success: boolean;
+  email: string | null;
+  error: string | null;
+}
+
+// 批量快速注册结果
+export interface BatchRegisterReport {
+  requested: number;
+  accounts: Account[];
+  failures: string[];
+}
+
+// 注册队列任务状态
+export type RegisterJobStatus = "queued" | "running" | "succeeded" | "failed" | "cancelled";
+
+// 注册队列任务
+export interface RegisterJob {
+  id: number;
+  status: RegisterJobStatus;
+  show_window: boolean;
+  account_id: string | null;
+  email: string | null;
+  error: string | null;
+  created_at: number;
+  started_at: number | null;
+  finished_at: number | null;
+}
+
+// 注册队列状态（register_queue_updated 事件负载）
+export interface RegisterQueueStatus {
+  paused: boolean;
+  running: boolean;
+  jobs: RegisterJob[];
+}
+
+// 快速注册步骤
+export type RegisterStep = "open_form" | "send_code" | "await_code" | "submit" | "await_token";
+
+// 快速注册步骤自动重试用尽（quick_register_step_failed 事件负载）
+export interface StepFailedEvent {
+  window_label: string;
+  step: RegisterStep;
+  step_label: string;
+  message: string;
+  resume_secs: number;
+}
+
+// 自有邮箱注册等待提交验证码（verification_code_required 事件负载）
+export interface CodeRequiredEvent {
+  window_label: string;
+  email: string;
+  timeout_secs: number;
+}
+
+// 代理池的选择方式
+export type ProxyRotation = "round_robin" | "random";
+
+// 快速注册代理设置
+export interface RegisterProxyConfig {
+  enabled: boolean;
+  // http:// 或 socks5:// 代理地址（不支持认证）
+  urls: string[];
+  rotation: ProxyRotation;
+}
+
+// 注册凭据记录的结果
+export type CredentialStatus = "signed_up" | "added" | "failed";
+
+// 注册凭据记录
+export interface CredentialRecord {
+  at: number;
+  email: string;
+  password: string;
+  status: CredentialStatus;
+  account_id: string | null;
+  mail_provider: string | null;
+  // 失败原因
+  reason: string | null;
+}
+
+// 临时邮箱会话
+export interface MailSession {
+  provider: MailProviderKind;
+  email: string;
+  credential: string | null;
+}
+
+// 注册窗口的浏览器特征
+export interface BrowserFingerprint {
+  user_agent: string;
+  languages: string[];
+  width: number;
+  height: number;
+}
+
+// 中断的注册（应用中途退出时保存）
+export interface RegisterSession {
+  id: string;
+  email: string;
+  password: string;
+  // 中断时所在的步骤
+  step: RegisterStep;
+  // 临时邮箱会话，使用自有邮箱注册时为 null
+  mail: MailSession | null;
+  // 注册使用的代理
+  proxy?: string | null;
+  // 注册窗口的浏览器特征
+  fingerprint?: BrowserFingerprint | null;
+  started_at: number;
+  updated_at: number;
+}
+
+// 快速注册页面出现人机验证（captcha_required 事件负载）
+export interface CaptchaEvent {
+  window_label: string;
+  step: RegisterStep;
+  step_label: string;
+  wait_secs: number;
+}
+
+// 环境自检单项结果
+export interface SelfTestCheck {
+  name: "register_page" | "mail_provider" | "ide_write";
+  passed: boolean;
+  duration_ms: number;
+  // 成功时为说明，失败时为错误信息
+  detail: string;
+}
+
+// 通知事件类型
+export type NotifyEvent = "quota_alert" | "token_expiring" | "registration" | "weekly_report";
+
+// 通知渠道（desktop 为应用内通知）
+export type SinkKind = "desktop" | "webhook" | "telegram";
+
+// 通知设置
+export interface NotifyConfig {
+  webhook_url: string;
+  telegram_bot_token: string;
+  telegram_chat_id: string;
+  // 事件类型 -> 发送渠道，未配置的事件不发送
+  routes: Partial<Record<NotifyEvent, SinkKind[]>>;
+}
+
+// 剩余快速请求低于阈值（quota_low 事件负载）
+export interface QuotaLowEvent {
+  account_id: string;
+  email: string;
+  left: number;
+  threshold: number;
+}
+
+// 额度用完自动切换账号（account_auto_switched 事件负载）
+export interface AutoSwitchEvent {
+  from_account_id: string;
+  from_email: string;
+  account_id: string;
+  email: string;
+}
+
+// 账号轮换时间表
+export type RotationSchedule =
+  | { kind: "interval"; minutes: number }
+  | { kind: "every_hours"; hours: number };
+
+// 账号轮换设置
+export interface RotationConfig {
+  enabled: boolean;
+  account_ids: string[];
+  schedule: RotationSchedule;
+  skip_if_busy: boolean;
+}
+
+// 账号轮换运行状态
+export interface RotationStatus {
+  enabled: boolean;
+  next_at: number | null;
+  last_switched_at: number | null;
+  last_account_id: string | null;
+  // 最近一次跳过或失败的原因
+  last_skipped: string | null;
+}
+
+// 账号池自动补充设置
+export interface PoolTopUpConfig {
+  enabled: boolean;
+  // 可用账号少于该数量时开始补充
+  min_available: number;
+  // 补充到的目标数量
+  target_available: number;
+  check_interval_minutes: number;
+  show_window: boolean;
+}
+
+// 自动补充账号池（pool_topup_enqueued 事件负载）
+export interface PoolTopUpEvent {
+  available: number;
+  pending: number;
+  enqueued: number;
+  target: number;
+}
+
+// 额度用完的账号的处理方式
+export type RetirementAction = "archive" | "delete";
+
+// 自动清理额度用完的免费账号设置
+export interface RetirementConfig {
+  enabled: boolean;
+  action: RetirementAction;
+  // 为清理掉的账号添加同样数量的注册任务
+  replace: boolean;
+  show_window: boolean;
+  check_interval_minutes: number;
+}
+
+// 自动清理结果（accounts_retired 事件负载）
+export interface RetirementEvent {
+  action: RetirementAction;
+  accounts: { id: string; email: string }[];
+  enqueued: number;
+}
+
+// 浏览器登录的阶段
+export type BrowserLoginStage =
+  | "window_opened"
+  | "page_navigated"
+  | "credentials_captured"
+  | "token_received"
+  | "cookies_collected";
+
+// 浏览器登录进度（browser_login_progress 事件负载）
+export interface BrowserLoginProgress {
+  stage: BrowserLoginStage;
+  message: string;
+  url: string | null;
+  email: string | null;
+}
+
+// 浏览器登录等待中（browser_login_waiting 事件负载）
+export interface BrowserLoginWaiting {
+  remaining_secs: number;
+  timeout_secs: number;
+  // 登录窗口当前打开的页面
+  url: string | null;
+}
+
+// 轮换切换账号（rotation_switched 事件负载）
+export interface RotationSwitchEvent {
+  account_id: string;
+  email: string;
+}
+
+// 一组账号的快速请求汇总（含额外礼包）
+export interface UsageTotals {
+  plan_type: string;
+  accounts: number;
+  used: number;
+  limit: number;
+  left: number;
+}
+
+// 全部账号的使用量汇总
+export interface UsageAggregate {
+  total: UsageTotals;
+  by_plan: UsageTotals[];
+  // 本次查询失败、使用了上次缓存的账号数
+  stale: number;
+  // 没有任何使用量数据的账号数
+  missing: number;
+}
+
+// 付款记录（取自权益包的计费信息）
+export interface PaymentRecord {
+  entitlement_id: string;
+  product_type: number;
+  // 金额（最小货币单位）
+  amount: number;
+  currency: number;
+  start_time: number;
+  end_time: number;
+}
+
+// 订阅信息
+export interface SubscriptionInfo {
+  plan_type: string;
+  renews_at: number | null;
+  expires_at: number | null;
+  auto_renew: boolean;
+  payments: PaymentRecord[];
+}
+
+// 可领取的活动礼包
+export interface Promotion {
+  id: string;
+  name: string;
+  description: string;
+  claimed: boolean;
+  expires_at: number | null;
+}
+
+// 应用内通知（app_notification 事件负载）
+export interface AppNotification {
+  event: NotifyEvent;
+  title: string;
+  body: string;
+}
+
+// 账号最近的 API 请求记录
+export interface RequestLogEntry {
+  at: number;
+  method: string;
+  host: string;
+  path: string;
+  status: number | null;
+  latency_ms: number;
+  error: string | null;
+}
+
+// 使用量趋势范围
+export type TrendRange = "week" | "month" | "quarter";
+
+// 每日使用量（按本地日期）
+export interface UsageTrendPoint {
+  date: string;
+  used: number;
+  delta: number;
+  left: number;
+}
+
+// 额度耗尽预测
+export interface QuotaForecast {
+  daily_burn: number;
+  days_sampled: number;
+  left: number;
+  exhausts_at: number | null;
+  resets_at: number | null;
+  exhausts_before_reset: boolean;
+}
+
+// 每日统计数据采样（delta 为与上一个有采样的日期相比的变化量）
+export interface StatisticPoint {
+  date: string;
+  ai_requests: number;
+  completions: number;
+  accepts: number;
+  ai_requests_delta: number;
+  completions_delta: number;
+  accepts_delta: number;
+}